@@ -7,8 +7,11 @@ use juniper::{
     graphql_object, graphql_subscription, graphql_value, EmptyMutation, FieldError, GraphQLEnum,
     RootNode,
 };
-use juniper_graphql_ws::ConnectionConfig;
-use juniper_warp::{playground_filter, subscriptions::serve_graphql_ws};
+use juniper_graphql_ws::{ConnectionConfig, Protocol};
+use juniper_warp::{
+    playground_filter,
+    subscriptions::{make_graphql_sse_filter, serve_ws},
+};
 use warp::{http::Response, Filter};
 
 #[derive(Clone)]
@@ -158,26 +161,38 @@ async fn main() {
 
     let root_node = Arc::new(schema());
 
+    let sse_filter = make_graphql_sse_filter(
+        Arc::new(juniper_subscriptions::Coordinator::new(schema())),
+        warp::any().map(|| Context).boxed(),
+    );
+
     log::info!("Listening on 127.0.0.1:8080");
 
     let routes = (warp::path("subscriptions")
         .and(warp::ws())
-        .map(move |ws: warp::ws::Ws| {
+        .and(warp::header::optional::<String>("sec-websocket-protocol"))
+        .map(move |ws: warp::ws::Ws, protocol: Option<String>| {
+            let protocol = Protocol::negotiate(protocol.as_deref());
             let root_node = root_node.clone();
-            ws.on_upgrade(move |websocket| async move {
-                serve_graphql_ws(websocket, root_node, ConnectionConfig::new(Context))
-                    .map(|r| {
-                        if let Err(e) = r {
-                            println!("Websocket error: {e}");
-                        }
-                    })
-                    .await
-            })
+            let reply = ws.on_upgrade(move |websocket| async move {
+                serve_ws(
+                    websocket,
+                    protocol,
+                    root_node,
+                    ConnectionConfig::new(Context),
+                )
+                .map(|r| {
+                    if let Err(e) = r {
+                        println!("Websocket error: {e}");
+                    }
+                })
+                .await
+            });
+            warp::reply::with_header(reply, "sec-websocket-protocol", protocol.as_str())
         }))
-    .map(|reply| {
-        // TODO#584: remove this workaround
-        warp::reply::with_header(reply, "Sec-WebSocket-Protocol", "graphql-ws")
-    })
+    .or(warp::path("graphql")
+        .and(warp::path("stream"))
+        .and(sse_filter))
     .or(warp::post()
         .and(warp::path("graphql"))
         .and(qm_graphql_filter))
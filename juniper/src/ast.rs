@@ -5,6 +5,7 @@ use indexmap::IndexMap;
 use crate::{
     executor::Variables,
     parser::Spanning,
+    schema::model::DirectiveLocation,
     value::{DefaultScalarValue, ScalarValue},
 };
 
@@ -139,11 +140,183 @@ pub struct Fragment<'a, S> {
     pub selection_set: Vec<Selection<'a, S>>,
 }
 
+/// Type system [schema definition][0] or [extension][1].
+///
+/// Schema extensions are represented by the same node, having no
+/// [`description`](SchemaDefinition::description) and possibly no
+/// [`operation_types`](SchemaDefinition::operation_types).
+///
+/// [0]: https://spec.graphql.org/October2021#SchemaDefinition
+/// [1]: https://spec.graphql.org/October2021#SchemaExtension
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct SchemaDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub operation_types: Vec<Spanning<RootOperationTypeDefinition<'a>>>,
+}
+
+/// [Root operation type][0] of a [`SchemaDefinition`], like `query: Query`.
+///
+/// [0]: https://spec.graphql.org/October2021#RootOperationTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct RootOperationTypeDefinition<'a> {
+    pub operation_type: Spanning<OperationType>,
+    pub named_type: Spanning<&'a str>,
+}
+
+/// Type system [type definition][0] or [extension][1].
+///
+/// Type extensions are represented by the same node, having no `description`.
+///
+/// [0]: https://spec.graphql.org/October2021#TypeDefinition
+/// [1]: https://spec.graphql.org/October2021#TypeExtension
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub enum TypeDefinition<'a, S> {
+    Scalar(ScalarTypeDefinition<'a, S>),
+    Object(ObjectTypeDefinition<'a, S>),
+    Interface(InterfaceTypeDefinition<'a, S>),
+    Union(UnionTypeDefinition<'a, S>),
+    Enum(EnumTypeDefinition<'a, S>),
+    InputObject(InputObjectTypeDefinition<'a, S>),
+}
+
+/// [Scalar type definition][0], like `scalar DateTime`.
+///
+/// [0]: https://spec.graphql.org/October2021#ScalarTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScalarTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+}
+
+/// [Object type definition][0], like `type User implements Node { id: ID! }`.
+///
+/// [0]: https://spec.graphql.org/October2021#ObjectTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ObjectTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub interfaces: Vec<Spanning<&'a str>>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub fields: Vec<Spanning<FieldDefinition<'a, S>>>,
+}
+
+/// [Interface type definition][0], like `interface Node { id: ID! }`.
+///
+/// [0]: https://spec.graphql.org/October2021#InterfaceTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct InterfaceTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub interfaces: Vec<Spanning<&'a str>>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub fields: Vec<Spanning<FieldDefinition<'a, S>>>,
+}
+
+/// [Union type definition][0], like `union SearchResult = User | Post`.
+///
+/// [0]: https://spec.graphql.org/October2021#UnionTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct UnionTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub members: Vec<Spanning<&'a str>>,
+}
+
+/// [Enum type definition][0], like `enum Episode { NEW_HOPE EMPIRE JEDI }`.
+///
+/// [0]: https://spec.graphql.org/October2021#EnumTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnumTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub values: Vec<Spanning<EnumValueDefinition<'a, S>>>,
+}
+
+/// [Input object type definition][0], like `input Point { x: Int! y: Int! }`.
+///
+/// [0]: https://spec.graphql.org/October2021#InputObjectTypeDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputObjectTypeDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    pub fields: Vec<Spanning<InputValueDefinition<'a, S>>>,
+}
+
+/// [Field definition][0] of an object or interface type.
+///
+/// [0]: https://spec.graphql.org/October2021#FieldDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct FieldDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub arguments: Option<Spanning<Vec<Spanning<InputValueDefinition<'a, S>>>>>,
+    pub field_type: Spanning<Type<'a>>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+}
+
+/// [Input value definition][0]: either an argument definition or an input
+/// object field definition.
+///
+/// [0]: https://spec.graphql.org/October2021#InputValueDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct InputValueDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub value_type: Spanning<Type<'a>>,
+    pub default_value: Option<Spanning<InputValue<S>>>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+}
+
+/// [Enum value definition][0] of an enum type.
+///
+/// [0]: https://spec.graphql.org/October2021#EnumValueDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnumValueDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+}
+
+/// [Directive definition][0], like `directive @auth(role: String) on FIELD_DEFINITION`.
+///
+/// [0]: https://spec.graphql.org/October2021#DirectiveDefinition
+#[allow(missing_docs)]
+#[derive(Clone, PartialEq, Debug)]
+pub struct DirectiveDefinition<'a, S> {
+    pub description: Option<Spanning<Cow<'a, str>>>,
+    pub name: Spanning<&'a str>,
+    pub arguments: Option<Spanning<Vec<Spanning<InputValueDefinition<'a, S>>>>>,
+    pub is_repeatable: bool,
+    pub locations: Vec<Spanning<DirectiveLocation>>,
+}
+
 #[doc(hidden)]
 #[derive(Clone, PartialEq, Debug)]
 pub enum Definition<'a, S> {
     Operation(Spanning<Operation<'a, S>>),
     Fragment(Spanning<Fragment<'a, S>>),
+    Schema(Spanning<SchemaDefinition<'a, S>>),
+    Type(Spanning<TypeDefinition<'a, S>>),
+    Directive(Spanning<DirectiveDefinition<'a, S>>),
+    SchemaExtension(Spanning<SchemaDefinition<'a, S>>),
+    TypeExtension(Spanning<TypeDefinition<'a, S>>),
 }
 
 #[doc(hidden)]
@@ -194,6 +367,20 @@ pub trait ToInputValue<S = DefaultScalarValue>: Sized {
     fn to_input_value(&self) -> InputValue<S>;
 }
 
+impl<'a, S> TypeDefinition<'a, S> {
+    /// Returns the name of the type this [`TypeDefinition`] defines.
+    pub fn name(&self) -> &'a str {
+        match self {
+            Self::Scalar(d) => d.name.item,
+            Self::Object(d) => d.name.item,
+            Self::Interface(d) => d.name.item,
+            Self::Union(d) => d.name.item,
+            Self::Enum(d) => d.name.item,
+            Self::InputObject(d) => d.name.item,
+        }
+    }
+}
+
 impl<'a> Type<'a> {
     /// Get the name of a named type.
     ///
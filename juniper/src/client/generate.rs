@@ -0,0 +1,1237 @@
+//! Generation of Rust types for GraphQL operations.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    ast::{Definition, Directive, Fragment, Operation, OperationType, Selection, Type},
+    parser::{parse_document_source, ParseError, Spanning},
+    printer::print_document,
+    schema::{meta::MetaType, model::SchemaType},
+    validation::{visit_all_rules, RuleError, ValidatorContext},
+    value::ScalarValue,
+};
+
+/// Rust types of the scalars built into GraphQL.
+const BUILTIN_SCALARS: &[(&str, &str)] = &[
+    ("Int", "i32"),
+    ("Float", "f64"),
+    ("String", "String"),
+    ("Boolean", "bool"),
+    ("ID", "String"),
+];
+
+/// Names reserved in every generated operation module.
+const RESERVED_NAMES: &[&str] = &["Operation", "Variables", "ResponseData"];
+
+/// Generator of Rust types for the GraphQL operations used by a client.
+///
+/// Every named operation of a document becomes a module (named after it in
+/// `snake_case`) containing:
+/// - an `Operation` type implementing [`GraphQLOperation`];
+/// - a `Variables` struct with the operation variables;
+/// - a `ResponseData` struct with the selected fields, and a struct for each
+///   nested selection set, named after the path to it.
+///
+/// Enums and input objects are generated once, next to the modules, for all
+/// the operations.
+///
+/// Aliases and fragments are resolved into the fields of the structs.
+/// Selections on interfaces or unions containing type-specific fragments
+/// become enums, [internally tagged][0] by `__typename`, with a variant for
+/// each possible type, so `__typename` must be selected in them.
+///
+/// The document is validated against the schema before generating anything.
+///
+/// The generated code uses [`serde`] re-exported by `juniper`, so requires no
+/// additional dependencies. Custom scalars should be mapped to Rust types with
+/// [`ClientGenerator::scalar()`].
+///
+/// ```rust
+/// # use juniper::{client::ClientGenerator, DefaultScalarValue, SchemaType};
+/// let schema = SchemaType::<DefaultScalarValue>::from_sdl(
+///     "type Query { hero: Hero } type Hero { name: String! born: Date }
+///      scalar Date",
+/// )
+/// .unwrap();
+///
+/// let code = ClientGenerator::new(&schema)
+///     .scalar("Date", "::chrono::NaiveDate")
+///     .derive("PartialEq")
+///     .generate("query HeroName { hero { name born } }")
+///     .unwrap();
+///
+/// assert!(code.contains("pub mod hero_name {"));
+/// assert!(code.contains("pub born: Option<::chrono::NaiveDate>,"));
+/// ```
+///
+/// [`GraphQLOperation`]: super::GraphQLOperation
+/// [0]: https://serde.rs/enum-representations.html#internally-tagged
+#[derive(Debug)]
+pub struct ClientGenerator<'a, S> {
+    schema: &'a SchemaType<'a, S>,
+    scalars: HashMap<String, String>,
+    derives: Vec<String>,
+}
+
+impl<'a, S> ClientGenerator<'a, S>
+where
+    S: ScalarValue,
+{
+    /// Creates a new [`ClientGenerator`] for operations executed against the
+    /// provided `schema`.
+    pub fn new(schema: &'a SchemaType<'a, S>) -> Self {
+        Self {
+            schema,
+            scalars: HashMap::new(),
+            derives: Vec::new(),
+        }
+    }
+
+    /// Maps the GraphQL scalar with the provided `name` to the provided
+    /// `rust_type`, which must implement [`serde::Serialize`] and
+    /// [`serde::Deserialize`].
+    ///
+    /// The `rust_type` should be a full path, as it's used from within the
+    /// generated modules.
+    ///
+    /// Built-in scalars are mapped to the usual Rust types by default, but may
+    /// be remapped too (e.g. `ID` to a newtype).
+    #[must_use]
+    pub fn scalar(mut self, name: impl Into<String>, rust_type: impl Into<String>) -> Self {
+        self.scalars.insert(name.into(), rust_type.into());
+        self
+    }
+
+    /// Adds the provided `derive` (like `PartialEq`) to all the generated
+    /// types.
+    ///
+    /// All of them derive [`Clone`] and [`Debug`] already.
+    #[must_use]
+    pub fn derive(mut self, derive: impl Into<String>) -> Self {
+        self.derives.push(derive.into());
+        self
+    }
+
+    /// Generates Rust code for all the operations of the provided GraphQL
+    /// `document`.
+    ///
+    /// # Errors
+    ///
+    /// If the `document` cannot be parsed, fails validation against the
+    /// schema, or the code cannot be generated for it.
+    pub fn generate(&self, document: &str) -> Result<String, GenerateError> {
+        let document =
+            parse_document_source(document, self.schema).map_err(GenerateError::Parse)?;
+
+        let mut ctx = ValidatorContext::new(self.schema, &document);
+        visit_all_rules(&mut ctx, &document);
+        let errors = ctx.into_errors();
+        if !errors.is_empty() {
+            return Err(GenerateError::Validation(errors));
+        }
+
+        let mut gen = Generation {
+            generator: self,
+            fragments: document
+                .iter()
+                .filter_map(|def| match def {
+                    Definition::Fragment(f) => Some((f.item.name.item, &f.item)),
+                    _ => None,
+                })
+                .collect(),
+            enums: BTreeSet::new(),
+            inputs: BTreeSet::new(),
+        };
+
+        let mut modules = Vec::new();
+        for def in &document {
+            if let Definition::Operation(op) = def {
+                modules.push(gen.operation(op, &document)?);
+            }
+        }
+
+        let mut inputs = BTreeMap::new();
+        loop {
+            let pending = gen
+                .inputs
+                .iter()
+                .filter(|name| !inputs.contains_key(*name))
+                .cloned()
+                .collect::<Vec<_>>();
+            if pending.is_empty() {
+                break;
+            }
+            for name in pending {
+                let item = gen.input_object(&name)?;
+                inputs.insert(name, item);
+            }
+        }
+
+        let mut out =
+            String::from("// This file is @generated by `juniper::client::ClientGenerator`.\n");
+        for name in &gen.enums {
+            out.push('\n');
+            out.push_str(&gen.enum_item(name));
+        }
+        for item in inputs.values() {
+            out.push('\n');
+            out.push_str(item);
+        }
+        for module in modules {
+            out.push('\n');
+            out.push_str(&module);
+        }
+        Ok(out)
+    }
+}
+
+/// Error of [generating][0] Rust code for GraphQL operations.
+///
+/// [0]: ClientGenerator::generate
+#[derive(Debug, PartialEq)]
+pub enum GenerateError {
+    /// Document cannot be parsed.
+    Parse(Spanning<ParseError>),
+
+    /// Document doesn't pass validation against the schema.
+    Validation(Vec<RuleError>),
+
+    /// Document contains an anonymous operation, so there is no name for its
+    /// module.
+    AnonymousOperation,
+
+    /// Rust type of a custom scalar is not [specified][0].
+    ///
+    /// [0]: ClientGenerator::scalar
+    UnknownScalar(String),
+
+    /// Selection on an abstract type has type-specific fragments, but doesn't
+    /// select `__typename` to distinguish them.
+    MissingTypename {
+        /// Path to the selection, starting with the operation name.
+        path: String,
+
+        /// Name of the selected abstract type.
+        type_name: String,
+    },
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Validation(errs) => {
+                for (i, e) in errs.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    e.fmt(f)?;
+                }
+                Ok(())
+            }
+            Self::AnonymousOperation => {
+                f.write_str("Cannot generate code for an anonymous operation")
+            }
+            Self::UnknownScalar(name) => {
+                write!(f, "Rust type of scalar \"{name}\" is not specified")
+            }
+            Self::MissingTypename { path, type_name } => write!(
+                f,
+                "Selection \"{path}\" of abstract type \"{type_name}\" must include \"__typename\"",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// State of a [`ClientGenerator::generate()`] call.
+struct Generation<'g, 'a, 'd, S> {
+    generator: &'g ClientGenerator<'a, S>,
+    fragments: HashMap<&'d str, &'d Fragment<'d, S>>,
+
+    /// Names of the GraphQL enums to generate.
+    enums: BTreeSet<String>,
+
+    /// Names of the GraphQL input objects to generate.
+    inputs: BTreeSet<String>,
+}
+
+/// Generated operation module.
+struct Module {
+    names: HashSet<String>,
+    items: Vec<String>,
+}
+
+impl Module {
+    /// Returns the provided `name`, suffixed if it's already used.
+    fn unique_name(&mut self, name: String) -> String {
+        let mut unique = name.clone();
+        let mut i = 1;
+        while !self.names.insert(unique.clone()) {
+            i += 1;
+            unique = format!("{name}{i}");
+        }
+        unique
+    }
+
+    /// Reserves a place for an item to be rendered once all its nested items
+    /// are, so they follow it.
+    fn reserve(&mut self) -> usize {
+        self.items.push(String::new());
+        self.items.len() - 1
+    }
+}
+
+/// Field of a selection set with all its occurrences merged.
+struct CollectedField<'d, S> {
+    key: &'d str,
+    name: &'d str,
+    selection_sets: Vec<&'d [Selection<'d, S>]>,
+
+    /// Whether this field may be excluded by `@skip` or `@include`.
+    conditional: bool,
+}
+
+/// Member (field or variant) of a generated item.
+struct Member {
+    doc: Option<String>,
+    serde: Vec<String>,
+    decl: String,
+}
+
+impl<'g, 'a, 'd, S> Generation<'g, 'a, 'd, S>
+where
+    S: ScalarValue,
+{
+    fn schema(&self) -> &'a SchemaType<'a, S> {
+        self.generator.schema
+    }
+
+    fn meta(&self, type_name: &str) -> &'a MetaType<'a, S> {
+        self.schema()
+            .concrete_type_by_name(type_name)
+            .expect("validated document references only existing types")
+    }
+
+    fn operation(
+        &mut self,
+        op: &'d Spanning<Operation<'d, S>>,
+        document: &'d [Definition<'d, S>],
+    ) -> Result<String, GenerateError> {
+        let name = op
+            .item
+            .name
+            .as_ref()
+            .ok_or(GenerateError::AnonymousOperation)?
+            .item;
+        let root = match op.item.operation_type {
+            OperationType::Query => Some(self.schema().concrete_query_type()),
+            OperationType::Mutation => self.schema().concrete_mutation_type(),
+            OperationType::Subscription => self.schema().concrete_subscription_type(),
+        }
+        .ok_or_else(|| {
+            let kind = match op.item.operation_type {
+                OperationType::Query => "queries",
+                OperationType::Mutation => "mutations",
+                OperationType::Subscription => "subscriptions",
+            };
+            GenerateError::Validation(vec![RuleError::new(
+                &format!("Schema is not configured for {kind}"),
+                &[op.start],
+            )])
+        })?;
+
+        let mut module = Module {
+            names: RESERVED_NAMES.iter().map(|&n| n.into()).collect(),
+            items: Vec::new(),
+        };
+
+        let variables = op
+            .item
+            .variable_definitions
+            .as_ref()
+            .map(|defs| {
+                defs.item
+                    .items
+                    .iter()
+                    .map(|(var, def)| {
+                        let ty = &def.var_type.item;
+                        let mut rust_ty = wrap_type(ty, &self.input_type(ty.innermost_name())?);
+                        let optional = !ty.is_non_null() || def.default_value.is_some();
+                        if ty.is_non_null() && optional {
+                            rust_ty = format!("Option<{rust_ty}>");
+                        }
+                        Ok(field_member(None, var.item, rust_ty, optional, false))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        module.items.push(self.render(
+            ItemKind::Input,
+            None,
+            None,
+            "struct",
+            "Variables",
+            &variables,
+        ));
+
+        let root_name = root.name().expect("root types are named");
+        let mut data = Vec::new();
+        self.collect(root_name, &op.item.selection_set, false, &mut data);
+        self.struct_item(
+            &mut module,
+            "ResponseData".into(),
+            root_name,
+            data,
+            name,
+            false,
+        )?;
+
+        let module_name = ident(&snake_case(name));
+        let document = print_document(&self.used_definitions(&op.item, document));
+
+        let mut out = format!(
+            "pub mod {module_name} {{\n    \
+                pub struct Operation;\n\n    \
+                impl ::juniper::client::GraphQLOperation for Operation {{\n        \
+                    type Variables = Variables;\n        \
+                    type ResponseData = ResponseData;\n\n        \
+                    const OPERATION_NAME: &'static str = {name:?};\n        \
+                    const DOCUMENT: &'static str = {document:?};\n    \
+                }}\n",
+        );
+        for item in module.items {
+            out.push('\n');
+            for line in item.lines() {
+                if !line.is_empty() {
+                    out.push_str("    ");
+                }
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
+    /// Returns the provided operation along with all the fragments it uses,
+    /// in their order in the `document`.
+    fn used_definitions(
+        &self,
+        op: &'d Operation<'d, S>,
+        document: &'d [Definition<'d, S>],
+    ) -> Vec<Definition<'d, S>> {
+        let mut used = HashSet::new();
+        let mut pending = vec![op.selection_set.as_slice()];
+        while let Some(selection_set) = pending.pop() {
+            for selection in selection_set {
+                match selection {
+                    Selection::Field(f) => {
+                        if let Some(set) = &f.item.selection_set {
+                            pending.push(set);
+                        }
+                    }
+                    Selection::InlineFragment(f) => pending.push(&f.item.selection_set),
+                    Selection::FragmentSpread(s) => {
+                        if used.insert(s.item.name.item) {
+                            pending.push(&self.fragments[s.item.name.item].selection_set);
+                        }
+                    }
+                }
+            }
+        }
+
+        document
+            .iter()
+            .filter(|def| match def {
+                Definition::Operation(o) => std::ptr::eq(&o.item, op),
+                Definition::Fragment(f) => used.contains(f.item.name.item),
+                _ => false,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Collects the fields of the provided `selection_set` applying to the
+    /// type with the provided `type_name`, merging them by their response
+    /// keys.
+    fn collect(
+        &self,
+        type_name: &str,
+        selection_set: &'d [Selection<'d, S>],
+        conditional: bool,
+        out: &mut Vec<CollectedField<'d, S>>,
+    ) {
+        for selection in selection_set {
+            match selection {
+                Selection::Field(f) => {
+                    let f = &f.item;
+                    let key = f.alias.as_ref().unwrap_or(&f.name).item;
+                    let conditional = conditional || is_conditional(&f.directives);
+                    let field = match out.iter_mut().find(|c| c.key == key) {
+                        Some(field) => {
+                            field.conditional &= conditional;
+                            field
+                        }
+                        None => {
+                            out.push(CollectedField {
+                                key,
+                                name: f.name.item,
+                                selection_sets: Vec::new(),
+                                conditional,
+                            });
+                            out.last_mut().unwrap()
+                        }
+                    };
+                    if let Some(set) = &f.selection_set {
+                        field.selection_sets.push(set);
+                    }
+                }
+                Selection::InlineFragment(f) => {
+                    let applies = f
+                        .item
+                        .type_condition
+                        .as_ref()
+                        .map_or(true, |cond| self.applies(type_name, cond.item));
+                    if applies {
+                        let conditional = conditional || is_conditional(&f.item.directives);
+                        self.collect(type_name, &f.item.selection_set, conditional, out);
+                    }
+                }
+                Selection::FragmentSpread(s) => {
+                    let fragment = self.fragments[s.item.name.item];
+                    if self.applies(type_name, fragment.type_condition.item) {
+                        let conditional = conditional || is_conditional(&s.item.directives);
+                        self.collect(type_name, &fragment.selection_set, conditional, out);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Indicates whether a fragment with the provided `type_condition` applies
+    /// to every value of the type with the provided `type_name`.
+    fn applies(&self, type_name: &str, type_condition: &str) -> bool {
+        self.schema().is_named_subtype(type_name, type_condition)
+            || matches!(
+                self.meta(type_name),
+                MetaType::Interface(i) if i.interface_names.iter().any(|n| n == type_condition),
+            )
+    }
+
+    /// Generates the items for the selection of the provided composite type
+    /// with the provided `name`.
+    fn composite_item(
+        &mut self,
+        module: &mut Module,
+        name: String,
+        type_name: &str,
+        selection_sets: &[&'d [Selection<'d, S>]],
+        path: &str,
+    ) -> Result<(), GenerateError> {
+        let mut fields = Vec::new();
+        for set in selection_sets {
+            self.collect(type_name, set, false, &mut fields);
+        }
+
+        let meta = self.meta(type_name);
+        if meta.is_abstract() {
+            let mut possible_types = self.schema().possible_types(meta);
+            // Sorted to not depend on the way the schema has been built.
+            possible_types.sort_by_key(|t| t.name());
+            let variants = possible_types
+                .into_iter()
+                .map(|t| {
+                    let name = t.name().expect("possible types are named");
+                    let mut fields = Vec::new();
+                    for set in selection_sets {
+                        self.collect(name, set, false, &mut fields);
+                    }
+                    (name, fields)
+                })
+                .collect::<Vec<_>>();
+            if variants.iter().any(|(_, f)| !same_shape(f, &fields)) {
+                return self.tagged_enum_item(module, name, type_name, variants, path);
+            }
+        }
+
+        self.struct_item(module, name, type_name, fields, path, false)
+    }
+
+    fn struct_item(
+        &mut self,
+        module: &mut Module,
+        name: String,
+        type_name: &str,
+        fields: Vec<CollectedField<'d, S>>,
+        path: &str,
+        skip_typename: bool,
+    ) -> Result<(), GenerateError> {
+        let index = module.reserve();
+        let prefix = if name == "ResponseData" { "" } else { &name };
+        let meta = self.meta(type_name);
+
+        let mut members = Vec::new();
+        for f in fields {
+            if skip_typename && f.key == "__typename" {
+                continue;
+            }
+
+            let (field_type, description) = if f.name == "__typename" {
+                (Type::NonNullNamed("String".into()), None)
+            } else {
+                let field = meta
+                    .field_by_name(f.name)
+                    .expect("validated document selects only existing fields");
+                (field.field_type.clone(), field.description.clone())
+            };
+
+            let inner_name = field_type.innermost_name();
+            let inner = match self.meta(inner_name) {
+                MetaType::Scalar(_) => self.scalar(inner_name)?,
+                MetaType::Enum(_) => {
+                    self.enums.insert(inner_name.into());
+                    format!("super::{}", pascal_case(inner_name))
+                }
+                _ => {
+                    let nested = module.unique_name(format!("{prefix}{}", pascal_case(f.key)));
+                    let path = format!("{path}.{}", f.key);
+                    self.composite_item(
+                        module,
+                        nested.clone(),
+                        inner_name,
+                        &f.selection_sets,
+                        &path,
+                    )?;
+                    nested
+                }
+            };
+
+            let mut rust_ty = wrap_type(&field_type, &inner);
+            if f.conditional && field_type.is_non_null() {
+                rust_ty = format!("Option<{rust_ty}>");
+            }
+            members.push(field_member(
+                description,
+                f.key,
+                rust_ty,
+                false,
+                f.conditional,
+            ));
+        }
+
+        module.items[index] = self.render(ItemKind::Output, None, None, "struct", &name, &members);
+        Ok(())
+    }
+
+    fn tagged_enum_item(
+        &mut self,
+        module: &mut Module,
+        name: String,
+        type_name: &str,
+        variants: Vec<(&str, Vec<CollectedField<'d, S>>)>,
+        path: &str,
+    ) -> Result<(), GenerateError> {
+        let has_typename = |fields: &[CollectedField<'d, S>]| {
+            fields
+                .iter()
+                .any(|f| f.key == "__typename" && f.name == "__typename")
+        };
+        if !variants.iter().all(|(_, fields)| has_typename(fields)) {
+            return Err(GenerateError::MissingTypename {
+                path: path.into(),
+                type_name: type_name.into(),
+            });
+        }
+
+        let index = module.reserve();
+        let mut members = Vec::new();
+        for (variant_type, fields) in variants {
+            let variant = ident(&pascal_case(variant_type));
+            let payload = module.unique_name(format!("{name}On{variant}"));
+            self.struct_item(module, payload.clone(), variant_type, fields, path, true)?;
+            members.push(Member {
+                doc: None,
+                serde: rename_attr(&variant, variant_type).into_iter().collect(),
+                decl: format!("{variant}({payload})"),
+            });
+        }
+
+        module.items[index] = self.render(
+            ItemKind::Output,
+            None,
+            Some("tag = \"__typename\""),
+            "enum",
+            &name,
+            &members,
+        );
+        Ok(())
+    }
+
+    /// Returns the Rust type of the scalar with the provided `name`.
+    fn scalar(&self, name: &str) -> Result<String, GenerateError> {
+        self.generator
+            .scalars
+            .get(name)
+            .cloned()
+            .or_else(|| {
+                BUILTIN_SCALARS
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, ty)| (*ty).into())
+            })
+            .ok_or_else(|| GenerateError::UnknownScalar(name.into()))
+    }
+
+    /// Returns the Rust type of the input type with the provided `name`, used
+    /// from within an operation module.
+    fn input_type(&mut self, name: &str) -> Result<String, GenerateError> {
+        match self.meta(name) {
+            MetaType::Scalar(_) => self.scalar(name),
+            MetaType::Enum(_) => {
+                self.enums.insert(name.into());
+                Ok(format!("super::{}", pascal_case(name)))
+            }
+            _ => {
+                self.inputs.insert(name.into());
+                Ok(format!("super::{}", pascal_case(name)))
+            }
+        }
+    }
+
+    fn input_object(&mut self, name: &str) -> Result<String, GenerateError> {
+        let meta = match self.meta(name) {
+            MetaType::InputObject(meta) => meta,
+            _ => unreachable!("only input objects are collected as inputs"),
+        };
+
+        let mut members = Vec::new();
+        for field in &meta.input_fields {
+            let ty = &field.arg_type;
+            let inner_name = ty.innermost_name();
+            let mut inner = self.input_type(inner_name)?.replacen("super::", "", 1);
+            // Recursive input objects are boxed to have a finite size.
+            if matches!(ty, Type::Named(_) | Type::NonNullNamed(_))
+                && self.reaches(inner_name, name, &mut HashSet::new())
+            {
+                inner = format!("Box<{inner}>");
+            }
+            members.push(field_member(
+                field.description.clone(),
+                &field.name,
+                wrap_type(ty, &inner),
+                !ty.is_non_null(),
+                false,
+            ));
+        }
+
+        Ok(self.render(
+            ItemKind::Input,
+            meta.description.as_deref(),
+            None,
+            "struct",
+            &pascal_case(name),
+            &members,
+        ))
+    }
+
+    /// Indicates whether the input object with the provided `to` name is
+    /// reachable from the `from` one via fields of non-list types.
+    fn reaches<'n>(&'n self, from: &'n str, to: &str, seen: &mut HashSet<&'n str>) -> bool {
+        if from == to {
+            return true;
+        }
+        if !seen.insert(from) {
+            return false;
+        }
+        match self.meta(from) {
+            MetaType::InputObject(meta) => meta.input_fields.iter().any(|f| {
+                matches!(f.arg_type, Type::Named(_) | Type::NonNullNamed(_))
+                    && self.reaches(f.arg_type.innermost_name(), to, seen)
+            }),
+            _ => false,
+        }
+    }
+
+    fn enum_item(&self, name: &str) -> String {
+        let meta = match self.meta(name) {
+            MetaType::Enum(meta) => meta,
+            _ => unreachable!("only enums are collected as enums"),
+        };
+
+        let members = meta
+            .values
+            .iter()
+            .map(|v| {
+                let variant = ident(&pascal_case(&v.name));
+                Member {
+                    doc: v.description.clone(),
+                    serde: rename_attr(&variant, &v.name).into_iter().collect(),
+                    decl: variant,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.render(
+            ItemKind::Enum,
+            meta.description.as_deref(),
+            None,
+            "enum",
+            &pascal_case(name),
+            &members,
+        )
+    }
+
+    /// Renders a generated item of the provided `kind` (`struct` or `enum`).
+    fn render(
+        &self,
+        item: ItemKind,
+        doc: Option<&str>,
+        serde: Option<&str>,
+        kind: &str,
+        name: &str,
+        members: &[Member],
+    ) -> String {
+        let (derives, serde_traits): (&[&str], &[&str]) = match item {
+            ItemKind::Enum => (
+                &["Clone", "Copy", "Debug", "Eq", "Hash", "PartialEq"],
+                &["Deserialize", "Serialize"],
+            ),
+            ItemKind::Input => (&["Clone", "Debug"], &["Serialize"]),
+            ItemKind::Output => (&["Clone", "Debug"], &["Deserialize"]),
+        };
+        let mut derives = derives.iter().map(|&d| d.to_owned()).collect::<Vec<_>>();
+        for d in &self.generator.derives {
+            if !derives.contains(d) {
+                derives.push(d.clone());
+            }
+        }
+        derives.extend(
+            serde_traits
+                .iter()
+                .map(|t| format!("::juniper::serde::{t}")),
+        );
+
+        let mut out = String::new();
+        write_doc(&mut out, "", doc);
+        out.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+        out.push_str("#[serde(crate = \"::juniper::serde\"");
+        if let Some(serde) = serde {
+            out.push_str(", ");
+            out.push_str(serde);
+        }
+        out.push_str(")]\n");
+        out.push_str(&format!("pub {kind} {name} {{"));
+        if members.is_empty() {
+            out.push_str("}\n");
+            return out;
+        }
+        out.push('\n');
+        for m in members {
+            write_doc(&mut out, "    ", m.doc.as_deref());
+            if !m.serde.is_empty() {
+                out.push_str(&format!("    #[serde({})]\n", m.serde.join(", ")));
+            }
+            out.push_str(&format!("    {},\n", m.decl));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Kind of a generated item, determining its derives.
+#[derive(Clone, Copy)]
+enum ItemKind {
+    Enum,
+    Input,
+    Output,
+}
+
+/// Builds a struct field [`Member`] for the provided GraphQL `key`.
+fn field_member(
+    doc: Option<String>,
+    key: &str,
+    rust_ty: String,
+    skip_none: bool,
+    default: bool,
+) -> Member {
+    let ident = ident(&snake_case(key.trim_start_matches('_')));
+    let mut serde = rename_attr(&ident, key).into_iter().collect::<Vec<_>>();
+    if default {
+        serde.push("default".into());
+    }
+    if skip_none {
+        serde.push("skip_serializing_if = \"Option::is_none\"".into());
+    }
+    Member {
+        doc,
+        serde,
+        decl: format!("pub {ident}: {rust_ty}"),
+    }
+}
+
+/// Returns a `rename` attribute argument, if the provided Rust `ident` differs
+/// from the GraphQL `name`.
+fn rename_attr(ident: &str, name: &str) -> Option<String> {
+    (ident.trim_start_matches("r#") != name).then(|| format!("rename = {name:?}"))
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    for line in doc.into_iter().flat_map(str::lines) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            out.push_str(&format!("{indent}///\n"));
+        } else {
+            out.push_str(&format!("{indent}/// {line}\n"));
+        }
+    }
+}
+
+/// Wraps the provided Rust `inner` type according to the provided GraphQL
+/// type modifiers.
+fn wrap_type(ty: &Type, inner: &str) -> String {
+    match ty {
+        Type::Named(_) => format!("Option<{inner}>"),
+        Type::NonNullNamed(_) => inner.into(),
+        Type::List(ty, _) => format!("Option<Vec<{}>>", wrap_type(ty, inner)),
+        Type::NonNullList(ty, _) => format!("Vec<{}>", wrap_type(ty, inner)),
+    }
+}
+
+/// Indicates whether the provided `directives` may exclude their selection.
+fn is_conditional<S>(directives: &Option<Vec<Spanning<Directive<'_, S>>>>) -> bool {
+    directives.iter().flatten().any(|d| {
+        let name = d.item.name.item;
+        name == "skip" || name == "include"
+    })
+}
+
+/// Indicates whether the provided collected fields have the same keys with
+/// the same sub-selections.
+fn same_shape<S>(a: &[CollectedField<'_, S>], b: &[CollectedField<'_, S>]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.key == b.key && a.selection_sets.len() == b.selection_sets.len())
+}
+
+/// Converts the provided GraphQL name into `snake_case`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(name.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let boundary = i > 0 && {
+                let prev = chars[i - 1];
+                let next = chars.get(i + 1);
+                prev.is_ascii_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_ascii_uppercase() && next.map_or(false, char::is_ascii_lowercase))
+            };
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Converts the provided GraphQL name into `PascalCase`.
+fn pascal_case(name: &str) -> String {
+    snake_case(name)
+        .split('_')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Makes the provided name a valid Rust identifier.
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ];
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else if matches!(name, "crate" | "self" | "Self" | "super") {
+        format!("{name}_")
+    } else if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schema::model::SchemaType, DefaultScalarValue};
+
+    use super::{ident, pascal_case, snake_case, ClientGenerator, GenerateError};
+
+    fn schema() -> SchemaType<'static, DefaultScalarValue> {
+        SchemaType::from_sdl(
+            r#"
+            type Query {
+                hero(episode: Episode): Character
+                search(filter: Filter!): [SearchResult!]!
+            }
+            type Mutation { rename(id: ID!, name: String!): Human }
+            "A character of the trilogy."
+            interface Character { id: ID! name: String! friends: [Character!]! }
+            type Human implements Character {
+                id: ID! name: String! friends: [Character!]! homePlanet: String
+            }
+            type Droid implements Character {
+                id: ID! name: String! friends: [Character!]! primaryFunction: String
+            }
+            type Starship { name: String! length: Float born: Date }
+            union SearchResult = Human | Droid | Starship
+            enum Episode { NEW_HOPE EMPIRE JEDI }
+            input Filter { text: String episodes: [Episode!] and: Filter }
+            scalar Date
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn generates_structs_and_shared_types() {
+        let schema = schema();
+        let code = ClientGenerator::new(&schema)
+            .derive("PartialEq")
+            .generate(
+                r#"
+                query HeroName($episode: Episode) {
+                    hero(episode: $episode) {
+                        id
+                        displayName: name
+                        ...Friends
+                        name @include(if: true)
+                    }
+                }
+                fragment Friends on Character { friends { name } }
+                "#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            code,
+            r#"// This file is @generated by `juniper::client::ClientGenerator`.
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ::juniper::serde::Deserialize, ::juniper::serde::Serialize)]
+#[serde(crate = "::juniper::serde")]
+pub enum Episode {
+    #[serde(rename = "NEW_HOPE")]
+    NewHope,
+    #[serde(rename = "EMPIRE")]
+    Empire,
+    #[serde(rename = "JEDI")]
+    Jedi,
+}
+
+pub mod hero_name {
+    pub struct Operation;
+
+    impl ::juniper::client::GraphQLOperation for Operation {
+        type Variables = Variables;
+        type ResponseData = ResponseData;
+
+        const OPERATION_NAME: &'static str = "HeroName";
+        const DOCUMENT: &'static str = "query HeroName($episode: Episode) {\n  hero(episode: $episode) {\n    id\n    displayName: name\n    ...Friends\n    name @include(if: true)\n  }\n}\n\nfragment Friends on Character {\n  friends {\n    name\n  }\n}";
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Serialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct Variables {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub episode: Option<super::Episode>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct ResponseData {
+        pub hero: Option<Hero>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct Hero {
+        pub id: String,
+        #[serde(rename = "displayName")]
+        pub display_name: String,
+        pub friends: Vec<HeroFriends>,
+        #[serde(default)]
+        pub name: Option<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct HeroFriends {
+        pub name: String,
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generates_tagged_enums_for_type_specific_fragments() {
+        let schema = schema();
+        let code = ClientGenerator::new(&schema)
+            .scalar("Date", "::std::string::String")
+            .generate(
+                r#"
+                query Search($filter: Filter!) {
+                    search(filter: $filter) {
+                        __typename
+                        ... on Character { name }
+                        ... on Human { homePlanet }
+                        ... on Starship { length born }
+                    }
+                }
+                mutation Rename { rename(id: "1", name: "Luke") { name } }
+                "#,
+            )
+            .unwrap();
+
+        assert!(
+            code.contains(
+                "#[derive(Clone, Debug, ::juniper::serde::Serialize)]\n\
+             #[serde(crate = \"::juniper::serde\")]\n\
+             pub struct Filter {\n    \
+                 #[serde(skip_serializing_if = \"Option::is_none\")]\n    \
+                 pub text: Option<String>,\n    \
+                 #[serde(skip_serializing_if = \"Option::is_none\")]\n    \
+                 pub episodes: Option<Vec<Episode>>,\n    \
+                 #[serde(skip_serializing_if = \"Option::is_none\")]\n    \
+                 pub and: Option<Box<Filter>>,\n\
+             }\n",
+            ),
+            "{code}"
+        );
+        assert!(code.contains("pub enum Episode {"), "{code}");
+        assert!(code.contains("pub filter: super::Filter,"), "{code}");
+        assert!(
+            code.contains(
+                "    #[serde(crate = \"::juniper::serde\", tag = \"__typename\")]\n    \
+             pub enum Search {\n        \
+                 Droid(SearchOnDroid),\n        \
+                 Human(SearchOnHuman),\n        \
+                 Starship(SearchOnStarship),\n    \
+             }\n",
+            ),
+            "{code}"
+        );
+        assert!(
+            code.contains(
+                "    pub struct SearchOnHuman {\n        \
+                 pub name: String,\n        \
+                 #[serde(rename = \"homePlanet\")]\n        \
+                 pub home_planet: Option<String>,\n    \
+             }\n",
+            ),
+            "{code}"
+        );
+        assert!(
+            code.contains(
+                "    pub struct SearchOnDroid {\n        \
+                 pub name: String,\n    \
+             }\n",
+            ),
+            "{code}"
+        );
+        assert!(
+            code.contains(
+                "    pub struct SearchOnStarship {\n        \
+                 pub length: Option<f64>,\n        \
+                 pub born: Option<::std::string::String>,\n    \
+             }\n",
+            ),
+            "{code}"
+        );
+        assert!(code.contains("pub mod rename {"), "{code}");
+        assert!(code.contains("pub rename: Option<Rename>,"), "{code}");
+    }
+
+    #[test]
+    fn uses_plain_structs_for_common_abstract_selections() {
+        let schema = schema();
+        let code = ClientGenerator::new(&schema)
+            .generate("query Hero { hero { ... on Character { name } } }")
+            .unwrap();
+
+        assert!(code.contains(
+            "    pub struct Hero {\n        \
+                 pub name: String,\n    \
+             }\n",
+        ));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let schema = schema();
+        let gen = ClientGenerator::new(&schema);
+
+        assert!(matches!(
+            gen.generate("query {"),
+            Err(GenerateError::Parse(_))
+        ));
+        assert_eq!(
+            gen.generate("query Q { hero { age } }")
+                .unwrap_err()
+                .to_string(),
+            "Unknown field \"age\" on type \"Character\". At 0:17",
+        );
+        assert_eq!(
+            gen.generate("{ hero { name } }"),
+            Err(GenerateError::AnonymousOperation),
+        );
+        assert_eq!(
+            gen.generate("query Q { search(filter: {}) { __typename ... on Starship { born } } }"),
+            Err(GenerateError::UnknownScalar("Date".into())),
+        );
+        assert_eq!(
+            gen.generate("query Q { hero { ... on Human { homePlanet } } }"),
+            Err(GenerateError::MissingTypename {
+                path: "Q.hero".into(),
+                type_name: "Character".into(),
+            }),
+        );
+    }
+
+    #[test]
+    fn converts_names() {
+        for (name, snake, pascal) in [
+            ("heroName", "hero_name", "HeroName"),
+            ("NEW_HOPE", "new_hope", "NewHope"),
+            ("HTTPStatus", "http_status", "HttpStatus"),
+            ("__Type", "__type", "Type"),
+            ("episode2", "episode2", "Episode2"),
+        ] {
+            assert_eq!(snake_case(name), snake, "{name}");
+            assert_eq!(pascal_case(name), pascal, "{name}");
+        }
+        assert_eq!(ident("type"), "r#type");
+        assert_eq!(ident("self"), "self_");
+    }
+}
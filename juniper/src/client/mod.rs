@@ -0,0 +1,146 @@
+//! Typed GraphQL clients of `juniper` (or any other) GraphQL services.
+//!
+//! Rust types for the operations a client uses are [generated][0] out of the
+//! GraphQL documents containing them, checked against the schema of the
+//! called service, which may be taken from its [`RootNode`] directly, or built
+//! out of its [SDL][1] or [introspection][2].
+//!
+//! Usually, the generation happens in a build script:
+//! ```rust,no_run
+//! # use std::{env, fs, path::Path};
+//! # use juniper::{client::ClientGenerator, DefaultScalarValue, SchemaType};
+//! // build.rs
+//! let schema = SchemaType::<DefaultScalarValue>::from_sdl(
+//!     &fs::read_to_string("schema.graphql").unwrap(),
+//! )
+//! .unwrap();
+//! let code = ClientGenerator::new(&schema)
+//!     .generate(&fs::read_to_string("operations.graphql").unwrap())
+//!     .unwrap_or_else(|e| panic!("invalid operations: {e}"));
+//! fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("operations.rs"), code).unwrap();
+//! println!("cargo:rerun-if-changed=schema.graphql");
+//! println!("cargo:rerun-if-changed=operations.graphql");
+//! ```
+//! And the generated code is included into the crate:
+//! ```rust,ignore
+//! mod operations {
+//!     include!(concat!(env!("OUT_DIR"), "/operations.rs"));
+//! }
+//!
+//! use juniper::client::{GraphQLOperation as _, OperationResponse};
+//!
+//! let body = operations::hero_name::Operation::build_request(
+//!     operations::hero_name::Variables { episode: None },
+//! );
+//! // ... send `body` as JSON and receive the `response` ...
+//! let response: OperationResponse<operations::hero_name::ResponseData> =
+//!     serde_json::from_str(&response).unwrap();
+//! ```
+//!
+//! [`RootNode`]: crate::RootNode
+//! [0]: ClientGenerator
+//! [1]: crate::SchemaType::from_sdl
+//! [2]: crate::SchemaType::from_introspection
+
+mod generate;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub use self::generate::{ClientGenerator, GenerateError};
+
+/// GraphQL operation with its typed [`Variables`] and [`ResponseData`].
+///
+/// Implemented by the code generated with a [`ClientGenerator`].
+///
+/// [`ResponseData`]: GraphQLOperation::ResponseData
+/// [`Variables`]: GraphQLOperation::Variables
+pub trait GraphQLOperation {
+    /// Type of the variables accepted by this operation.
+    type Variables: Serialize;
+
+    /// Type of the `data` returned by this operation.
+    type ResponseData: DeserializeOwned;
+
+    /// Name of this operation.
+    const OPERATION_NAME: &'static str;
+
+    /// GraphQL document containing this operation along with all the fragments
+    /// it uses.
+    const DOCUMENT: &'static str;
+
+    /// Builds an [`OperationRequest`] executing this operation with the
+    /// provided `variables`.
+    fn build_request(variables: Self::Variables) -> OperationRequest<Self::Variables> {
+        OperationRequest {
+            query: Self::DOCUMENT,
+            operation_name: Self::OPERATION_NAME,
+            variables,
+        }
+    }
+}
+
+/// Request executing a [`GraphQLOperation`], serializable as a
+/// [GraphQL over HTTP][0] JSON body.
+///
+/// [0]: https://github.com/graphql/graphql-over-http
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationRequest<V> {
+    /// GraphQL document containing the executed operation.
+    pub query: &'static str,
+
+    /// Name of the executed operation.
+    pub operation_name: &'static str,
+
+    /// Variables of the executed operation.
+    pub variables: V,
+}
+
+/// Response to an [`OperationRequest`], deserializable from its JSON
+/// representation.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(bound(deserialize = "D: DeserializeOwned"))]
+pub struct OperationResponse<D> {
+    /// Result of the operation, if it has been executed.
+    pub data: Option<D>,
+
+    /// Errors that occurred while executing the operation, if any.
+    #[serde(default)]
+    pub errors: Vec<ResponseError>,
+}
+
+/// Error returned in an [`OperationResponse`].
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ResponseError {
+    /// Description of this error.
+    pub message: String,
+
+    /// Locations in the GraphQL document this error relates to.
+    #[serde(default)]
+    pub locations: Vec<ResponseErrorLocation>,
+
+    /// Path to the response field this error occurred in, if any.
+    #[serde(default)]
+    pub path: Vec<ResponsePathSegment>,
+}
+
+/// Location in a GraphQL document a [`ResponseError`] relates to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct ResponseErrorLocation {
+    /// One-based line number.
+    pub line: usize,
+
+    /// One-based column number.
+    pub column: usize,
+}
+
+/// Segment of a [`ResponseError::path`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum ResponsePathSegment {
+    /// Response key of a field.
+    Field(String),
+
+    /// Index of a list item.
+    Index(usize),
+}
@@ -0,0 +1,169 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+/// Empty [`Extensions`] used when none are provided for an execution.
+pub(crate) static NO_EXTENSIONS: Extensions = Extensions::new();
+
+/// Type-keyed storage of arbitrary data attached to a single execution.
+///
+/// Allows middleware-like code to pass data (a request ID, a tracing span, a
+/// loader cache, etc.) down to resolvers without changing their context type.
+/// Holds at most one value of each type, accessible via
+/// [`Executor::extensions()`] during the whole execution, including
+/// subscription streams.
+///
+/// Values are shared immutably, so should use interior mutability if they
+/// need to be modified during execution.
+///
+/// ```rust
+/// # use juniper::{http::GraphQLRequest, Extensions};
+/// struct RequestId(u64);
+///
+/// let mut request = GraphQLRequest::<juniper::DefaultScalarValue>::new(
+///     "{ apiVersion }".into(),
+///     None,
+///     None,
+/// );
+/// request.extensions.insert(RequestId(42));
+///
+/// assert_eq!(request.extensions.get::<RequestId>().map(|id| id.0), Some(42));
+/// ```
+///
+/// [`Executor::extensions()`]: crate::Executor::extensions
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: Option<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+}
+
+impl Extensions {
+    /// Creates new empty [`Extensions`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { map: None }
+    }
+
+    /// Inserts the provided `value` into these [`Extensions`], returning the
+    /// previously stored value of the same type, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<Arc<T>> {
+        self.map
+            .get_or_insert_with(HashMap::new)
+            .insert(TypeId::of::<T>(), Arc::new(value))
+            .and_then(|prev| prev.downcast().ok())
+    }
+
+    /// Returns these [`Extensions`] with the provided `value` inserted.
+    #[must_use]
+    pub fn with<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Returns a reference to the stored value of type `T`, if any.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.map.as_ref()?.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Returns a shared pointer to the stored value of type `T`, if any.
+    ///
+    /// Useful for moving the value into futures or streams outliving the
+    /// [`Extensions`].
+    pub fn get_arc<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.map
+            .as_ref()?
+            .get(&TypeId::of::<T>())?
+            .clone()
+            .downcast()
+            .ok()
+    }
+
+    /// Indicates whether a value of type `T` is stored in these
+    /// [`Extensions`].
+    pub fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.map
+            .as_ref()
+            .map_or(false, |m| m.contains_key(&TypeId::of::<T>()))
+    }
+
+    /// Removes the stored value of type `T`, returning it, if any.
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<Arc<T>> {
+        self.map
+            .as_mut()?
+            .remove(&TypeId::of::<T>())?
+            .downcast()
+            .ok()
+    }
+
+    /// Returns number of values stored in these [`Extensions`].
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map_or(0, HashMap::len)
+    }
+
+    /// Indicates whether these [`Extensions`] store no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Two [`Extensions`] are equal if they store the very same values (not just
+/// equal ones).
+impl PartialEq for Extensions {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.map.iter().flatten().all(|(id, v)| {
+                other
+                    .map
+                    .as_ref()
+                    .and_then(|m| m.get(id))
+                    .map_or(false, |o| Arc::ptr_eq(v, o))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::Extensions;
+
+    #[derive(Debug, PartialEq)]
+    struct RequestId(u64);
+
+    #[test]
+    fn stores_single_value_per_type() {
+        let mut ext = Extensions::new().with(RequestId(1)).with("span");
+
+        assert_eq!(ext.len(), 2);
+        assert_eq!(ext.get::<RequestId>(), Some(&RequestId(1)));
+        assert_eq!(ext.get::<&str>(), Some(&"span"));
+        assert_eq!(ext.get::<u64>(), None);
+
+        assert_eq!(ext.insert(RequestId(2)), Some(Arc::new(RequestId(1))));
+        assert_eq!(ext.get_arc::<RequestId>(), Some(Arc::new(RequestId(2))));
+        assert_eq!(ext.len(), 2);
+
+        assert_eq!(ext.remove::<RequestId>(), Some(Arc::new(RequestId(2))));
+        assert!(!ext.contains::<RequestId>());
+        assert!(ext.contains::<&str>());
+    }
+
+    #[test]
+    fn compares_by_identity() {
+        let ext = Extensions::new().with(RequestId(1));
+
+        assert_eq!(ext, ext.clone());
+        assert_ne!(ext, Extensions::new().with(RequestId(1)));
+        assert_eq!(Extensions::new(), Extensions::default());
+    }
+}
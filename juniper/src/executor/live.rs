@@ -0,0 +1,60 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
+/// Name of the directive marking a query operation as a live query.
+///
+/// See [`RootNode::enable_live_queries()`] for details.
+///
+/// [`RootNode::enable_live_queries()`]: crate::RootNode::enable_live_queries
+pub const LIVE_DIRECTIVE: &str = "live";
+
+/// Invalidation keys touched by resolvers during a single execution, collected via
+/// [`Executor::track_invalidation_key()`].
+///
+/// Should be provided via [`Extensions`] of an execution to enable the tracking, which is usually
+/// done by a live queries implementation.
+///
+/// ```rust
+/// # use juniper::{Extensions, InvalidationKeys};
+/// let extensions = Extensions::new().with(InvalidationKeys::new());
+///
+/// let keys = extensions.get::<InvalidationKeys>().unwrap();
+/// keys.insert("user:1");
+/// keys.insert("user:2");
+/// keys.insert("user:1");
+///
+/// assert_eq!(keys.take().into_iter().collect::<Vec<_>>(), ["user:1", "user:2"]);
+/// assert!(keys.is_empty());
+/// ```
+///
+/// [`Executor::track_invalidation_key()`]: crate::Executor::track_invalidation_key
+/// [`Extensions`]: crate::Extensions
+#[derive(Debug, Default)]
+pub struct InvalidationKeys(Mutex<BTreeSet<String>>);
+
+impl InvalidationKeys {
+    /// Creates new empty [`InvalidationKeys`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the provided `key` into these [`InvalidationKeys`].
+    pub fn insert(&self, key: impl Into<String>) {
+        self.lock().insert(key.into());
+    }
+
+    /// Takes all the keys out of these [`InvalidationKeys`], leaving them empty.
+    pub fn take(&self) -> BTreeSet<String> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Indicates whether these [`InvalidationKeys`] contain no keys.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeSet<String>> {
+        // The set is never left half-modified, so poisoning may be safely ignored.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use crate::{
-    ast::{Directive, Fragment, InputValue, Selection},
-    parser::Spanning,
+    ast::{Directive, Fragment, InputValue, Selection, Type},
+    parser::{SourcePosition, Spanning},
+    schema::{meta::Field, model::SchemaType},
     value::ScalarValue,
 };
 
@@ -96,6 +97,51 @@ where
     }
 }
 
+/// A directive applied to a selection
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookAheadDirective<'a, S: 'a> {
+    name: &'a str,
+    arguments: Vec<LookAheadArgument<'a, S>>,
+}
+
+impl<'a, S> LookAheadDirective<'a, S>
+where
+    S: ScalarValue,
+{
+    fn new(directive: &'a Spanning<Directive<'a, S>>, vars: &'a Variables<S>) -> Self {
+        LookAheadDirective {
+            name: directive.item.name.item,
+            arguments: directive
+                .item
+                .arguments
+                .as_ref()
+                .map(|a| {
+                    a.item
+                        .items
+                        .iter()
+                        .map(|p| LookAheadArgument::new(p, vars))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The directive's name
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The arguments of the directive
+    pub fn arguments(&self) -> &[LookAheadArgument<'a, S>] {
+        &self.arguments
+    }
+
+    /// The argument of the directive with a given name
+    pub fn argument(&self, name: &str) -> Option<&LookAheadArgument<'a, S>> {
+        self.arguments.iter().find(|a| a.name == name)
+    }
+}
+
 #[doc(hidden)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChildSelection<'a, S: 'a> {
@@ -104,12 +150,21 @@ pub struct ChildSelection<'a, S: 'a> {
 }
 
 /// A selection performed by a query
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Selections obtained via [`Executor::look_ahead()`] also carry the schema
+/// metadata of the selected fields (see [`LookAheadMethods::meta()`]).
+///
+/// [`Executor::look_ahead()`]: super::Executor::look_ahead
+#[derive(Clone)]
 pub struct LookAheadSelection<'a, S: 'a> {
     pub(super) name: &'a str,
     pub(super) alias: Option<&'a str>,
     pub(super) arguments: Vec<LookAheadArgument<'a, S>>,
+    pub(super) directives: Vec<LookAheadDirective<'a, S>>,
     pub(super) children: Vec<ChildSelection<'a, S>>,
+    pub(super) span: (SourcePosition, SourcePosition),
+    pub(super) meta: Option<&'a Field<'a, S>>,
+    pub(super) schema: Option<&'a SchemaType<'a, S>>,
 }
 
 // Implemented manually to omit redundant `S: Default` trait bound, imposed by
@@ -120,11 +175,40 @@ impl<'a, S: 'a> Default for LookAheadSelection<'a, S> {
             name: "",
             alias: None,
             arguments: vec![],
+            directives: vec![],
             children: vec![],
+            span: (SourcePosition::new_origin(), SourcePosition::new_origin()),
+            meta: None,
+            schema: None,
         }
     }
 }
 
+// Implemented manually to omit the whole schema.
+impl<'a, S: fmt::Debug> fmt::Debug for LookAheadSelection<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LookAheadSelection")
+            .field("name", &self.name)
+            .field("alias", &self.alias)
+            .field("arguments", &self.arguments)
+            .field("directives", &self.directives)
+            .field("children", &self.children)
+            .field("span", &self.span)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compares what is selected only, ignoring source spans and schema metadata.
+impl<'a, S: PartialEq> PartialEq for LookAheadSelection<'a, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.alias == other.alias
+            && self.arguments == other.arguments
+            && self.directives == other.directives
+            && self.children == other.children
+    }
+}
+
 impl<'a, S> LookAheadSelection<'a, S>
 where
     S: ScalarValue,
@@ -198,8 +282,8 @@ where
     ) -> Option<LookAheadSelection<'a, S>> {
         let empty: &[Selection<S>] = &[];
         match *s {
-            Selection::Field(ref field) => {
-                let field = &field.item;
+            Selection::Field(ref spanned) => {
+                let field = &spanned.item;
                 let include = Self::should_include(field.directives.as_ref(), vars);
                 if !include {
                     return None;
@@ -217,11 +301,21 @@ where
                             .collect()
                     })
                     .unwrap_or_else(Vec::new);
+                let directives = field
+                    .directives
+                    .iter()
+                    .flatten()
+                    .map(|d| LookAheadDirective::new(d, vars))
+                    .collect();
                 let mut ret = LookAheadSelection {
                     name,
                     alias,
                     arguments,
+                    directives,
                     children: Vec::new(),
+                    span: (spanned.start, spanned.end),
+                    meta: None,
+                    schema: None,
                 };
                 for c in field
                     .selection_set
@@ -280,6 +374,7 @@ where
                     return None;
                 }
                 let parent = parent.unwrap();
+                let first_child = parent.children.len();
                 for c in inline.item.selection_set.iter() {
                     let s = LookAheadSelection::build_from_selection_with_parent(
                         c,
@@ -288,8 +383,12 @@ where
                         fragments,
                     );
                     assert!(s.is_none());
-                    if let Some(c) = inline.item.type_condition.as_ref().map(|t| t.item) {
-                        if let Some(p) = parent.children.last_mut() {
+                }
+                if let Some(c) = inline.item.type_condition.as_ref().map(|t| t.item) {
+                    // Children of nested inline fragments keep their more
+                    // specific type condition.
+                    for p in &mut parent.children[first_child..] {
+                        if p.applies_for == Applies::All {
                             p.applies_for = Applies::OnlyType(c);
                         }
                     }
@@ -300,6 +399,49 @@ where
         }
     }
 
+    /// Fills in the schema metadata of this selection, being a field with the
+    /// provided `meta`, and of all its children.
+    pub(super) fn with_schema(
+        mut self,
+        schema: &'a SchemaType<'a, S>,
+        meta: Option<&'a Field<'a, S>>,
+    ) -> Self {
+        self.schema = Some(schema);
+        self.meta = meta;
+        let field_type =
+            meta.and_then(|m| schema.concrete_type_by_name(m.field_type.innermost_name()));
+        self.children = self
+            .children
+            .into_iter()
+            .map(|c| {
+                let on_type = match c.applies_for {
+                    Applies::All => field_type,
+                    Applies::OnlyType(t) => schema.concrete_type_by_name(t),
+                };
+                let name = c.inner.name;
+                let meta = on_type.and_then(|t| {
+                    t.field_by_name(name).or_else(|| {
+                        // Fields of fragment spreads on subtypes are not
+                        // distinguished, so are looked up in possible types.
+                        t.is_abstract()
+                            .then(|| {
+                                schema
+                                    .possible_types(t)
+                                    .into_iter()
+                                    .find_map(|t| t.field_by_name(name))
+                            })
+                            .flatten()
+                    })
+                });
+                ChildSelection {
+                    inner: c.inner.with_schema(schema, meta),
+                    applies_for: c.applies_for,
+                }
+            })
+            .collect();
+        self
+    }
+
     /// Convert a eventually type independent selection into one for a concrete type
     pub fn for_explicit_type(&self, type_name: &str) -> ConcreteLookAheadSelection<'a, S> {
         ConcreteLookAheadSelection {
@@ -307,7 +449,7 @@ where
                 .children
                 .iter()
                 .filter_map(|c| match c.applies_for {
-                    Applies::OnlyType(t) if t == type_name => {
+                    Applies::OnlyType(t) if self.is_applicable(t, type_name) => {
                         Some(c.inner.for_explicit_type(type_name))
                     }
                     Applies::All => Some(c.inner.for_explicit_type(type_name)),
@@ -317,17 +459,120 @@ where
             name: self.name,
             alias: self.alias,
             arguments: self.arguments.clone(),
+            directives: self.directives.clone(),
+            span: self.span,
+            meta: self.meta,
+        }
+    }
+
+    /// Checks whether the provided type condition applies to the type with the
+    /// provided `type_name`.
+    ///
+    /// Without schema metadata only the exact type name matches.
+    fn is_applicable(&self, type_condition: &str, type_name: &str) -> bool {
+        type_condition == type_name
+            || self
+                .schema
+                .map_or(false, |s| s.is_named_subtype(type_name, type_condition))
+    }
+
+    /// Get the children for the current selection grouped by the type
+    /// condition (of an inline fragment) they're selected with, in the order
+    /// of their first appearance
+    pub fn children_by_type(&self) -> Vec<(Applies<'a>, Vec<&Self>)> {
+        let mut groups: Vec<(Applies<'a>, Vec<&Self>)> = Vec::new();
+        for c in &self.children {
+            match groups.iter_mut().find(|(a, _)| *a == c.applies_for) {
+                Some((_, children)) => children.push(&c.inner),
+                None => groups.push((c.applies_for, vec![&c.inner])),
+            }
         }
+        groups
+    }
+
+    /// Get the names of the concrete (object) types the children selected with
+    /// the provided type condition apply to
+    ///
+    /// Returns [`None`] if the schema metadata is not available (the selection
+    /// has not been obtained via [`Executor::look_ahead()`]).
+    ///
+    /// [`Executor::look_ahead()`]: super::Executor::look_ahead
+    pub fn concrete_types(&self, applies: Applies<'_>) -> Option<Vec<&'a str>> {
+        let schema = self.schema?;
+        let field_type = schema.concrete_type_by_name(self.meta?.field_type.innermost_name())?;
+        let condition = match applies {
+            Applies::All => field_type,
+            Applies::OnlyType(t) => schema.concrete_type_by_name(t)?,
+        };
+        let candidates = if field_type.is_abstract() {
+            schema.possible_types(field_type)
+        } else {
+            vec![field_type]
+        };
+        Some(
+            candidates
+                .into_iter()
+                .filter(|t| {
+                    std::ptr::eq(*t, condition)
+                        || (condition.is_abstract() && schema.is_possible_type(condition, t))
+                })
+                .filter_map(|t| t.name())
+                .collect(),
+        )
     }
 }
 
 /// A selection performed by a query on a concrete type
-#[derive(Debug, PartialEq)]
+#[derive(Clone)]
 pub struct ConcreteLookAheadSelection<'a, S: 'a> {
     name: &'a str,
     alias: Option<&'a str>,
     arguments: Vec<LookAheadArgument<'a, S>>,
+    directives: Vec<LookAheadDirective<'a, S>>,
     children: Vec<ConcreteLookAheadSelection<'a, S>>,
+    span: (SourcePosition, SourcePosition),
+    meta: Option<&'a Field<'a, S>>,
+}
+
+// Implemented manually to omit redundant `S: Default` trait bound, imposed by
+// `#[derive(Default)]`.
+impl<'a, S: 'a> Default for ConcreteLookAheadSelection<'a, S> {
+    fn default() -> Self {
+        Self {
+            name: "",
+            alias: None,
+            arguments: vec![],
+            directives: vec![],
+            children: vec![],
+            span: (SourcePosition::new_origin(), SourcePosition::new_origin()),
+            meta: None,
+        }
+    }
+}
+
+// Implemented manually for consistency with `LookAheadSelection`.
+impl<'a, S: fmt::Debug> fmt::Debug for ConcreteLookAheadSelection<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcreteLookAheadSelection")
+            .field("name", &self.name)
+            .field("alias", &self.alias)
+            .field("arguments", &self.arguments)
+            .field("directives", &self.directives)
+            .field("children", &self.children)
+            .field("span", &self.span)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compares what is selected only, ignoring source spans and schema metadata.
+impl<'a, S: PartialEq> PartialEq for ConcreteLookAheadSelection<'a, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.alias == other.alias
+            && self.arguments == other.arguments
+            && self.directives == other.directives
+            && self.children == other.children
+    }
 }
 
 /// Set of common methods for `ConcreteLookAheadSelection` and `LookAheadSelection`.
@@ -338,6 +583,46 @@ pub trait LookAheadMethods<'sel, S> {
     /// Get the (potentially aliased) name of the field represented by the current selection
     fn field_name(&self) -> &'sel str;
 
+    /// Get the name of the field represented by the current selection, as
+    /// defined in the schema (never aliased)
+    fn name(&self) -> &'sel str;
+
+    /// Get the alias of the field represented by the current selection, if any
+    fn alias(&self) -> Option<&'sel str>;
+
+    /// Get the schema metadata of the field represented by the current selection
+    ///
+    /// Available for selections obtained via [`Executor::look_ahead()`] only.
+    ///
+    /// [`Executor::look_ahead()`]: super::Executor::look_ahead
+    fn meta(&self) -> Option<&'sel Field<'sel, S>>;
+
+    /// Get the GraphQL type of the field represented by the current selection
+    ///
+    /// Available for selections obtained via [`Executor::look_ahead()`] only.
+    ///
+    /// [`Executor::look_ahead()`]: super::Executor::look_ahead
+    fn field_type(&self) -> Option<&'sel Type<'sel>>
+    where
+        S: 'sel,
+    {
+        self.meta().map(|m| &m.field_type)
+    }
+
+    /// Check if the field represented by the current selection is of a list
+    /// type (either nullable or not)
+    ///
+    /// Always `false` if the schema metadata is not available.
+    fn is_list(&self) -> bool
+    where
+        S: 'sel,
+    {
+        matches!(
+            self.field_type(),
+            Some(Type::List(..) | Type::NonNullList(..)),
+        )
+    }
+
     /// Get the the child selection for a given field
     /// If a child has an alias, it will only match if the alias matches `name`
     fn select_child(&self, name: &str) -> Option<&Self>;
@@ -362,6 +647,14 @@ pub trait LookAheadMethods<'sel, S> {
         self.arguments().iter().find(|a| a.name == name)
     }
 
+    /// Get the directives applied to the current selection (including the
+    /// `@skip` and `@include` ones)
+    fn directives(&self) -> &[LookAheadDirective<'_, S>];
+
+    /// Get the start and end positions of the current selection in the query
+    /// source
+    fn span(&self) -> (SourcePosition, SourcePosition);
+
     /// Get the (possibly aliased) names of the top level children for the current selection
     fn child_names(&self) -> Vec<&'sel str>;
 
@@ -374,6 +667,18 @@ impl<'a, S> LookAheadMethods<'a, S> for ConcreteLookAheadSelection<'a, S> {
         self.alias.unwrap_or(self.name)
     }
 
+    fn name(&self) -> &'a str {
+        self.name
+    }
+
+    fn alias(&self) -> Option<&'a str> {
+        self.alias
+    }
+
+    fn meta(&self) -> Option<&'a Field<'a, S>> {
+        self.meta
+    }
+
     fn select_child(&self, name: &str) -> Option<&Self> {
         self.children.iter().find(|c| c.field_name() == name)
     }
@@ -382,6 +687,14 @@ impl<'a, S> LookAheadMethods<'a, S> for ConcreteLookAheadSelection<'a, S> {
         &self.arguments
     }
 
+    fn directives(&self) -> &[LookAheadDirective<'_, S>] {
+        &self.directives
+    }
+
+    fn span(&self) -> (SourcePosition, SourcePosition) {
+        self.span
+    }
+
     fn child_names(&self) -> Vec<&'a str> {
         self.children.iter().map(|c| c.field_name()).collect()
     }
@@ -404,6 +717,18 @@ impl<'a, S> LookAheadMethods<'a, S> for LookAheadSelection<'a, S> {
         self.alias.unwrap_or(self.name)
     }
 
+    fn name(&self) -> &'a str {
+        self.name
+    }
+
+    fn alias(&self) -> Option<&'a str> {
+        self.alias
+    }
+
+    fn meta(&self) -> Option<&'a Field<'a, S>> {
+        self.meta
+    }
+
     fn select_child(&self, name: &str) -> Option<&Self> {
         self.children
             .iter()
@@ -415,6 +740,14 @@ impl<'a, S> LookAheadMethods<'a, S> for LookAheadSelection<'a, S> {
         &self.arguments
     }
 
+    fn directives(&self) -> &[LookAheadDirective<'_, S>] {
+        &self.directives
+    }
+
+    fn span(&self) -> (SourcePosition, SourcePosition) {
+        self.span
+    }
+
     fn child_names(&self) -> Vec<&'a str> {
         self.children.iter().map(|c| c.inner.field_name()).collect()
     }
@@ -454,7 +787,7 @@ mod tests {
     where
         S: ScalarValue,
     {
-        crate::parse_document_source(
+        crate::parser::parse_document_source(
             q,
             &SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &()),
         )
@@ -508,6 +841,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -517,10 +851,12 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -562,6 +898,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -571,10 +908,12 @@ query Hero {
                             alias: Some("my_name"),
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -620,6 +959,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -629,6 +969,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -644,6 +985,7 @@ query Hero {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::All,
                                 },
@@ -653,14 +995,17 @@ query Hero {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::All,
                                 },
                             ],
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -705,6 +1050,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -717,10 +1063,12 @@ query Hero {
                                 value: LookAheadValue::Scalar(&DefaultScalarValue::Boolean(true)),
                             }],
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -765,6 +1113,7 @@ query Hero($episode: Episode) {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -774,10 +1123,12 @@ query Hero($episode: Episode) {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -820,9 +1171,11 @@ query Hero($episode: Episode) {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 }],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -868,6 +1221,7 @@ fragment commonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -877,6 +1231,7 @@ fragment commonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -886,10 +1241,12 @@ fragment commonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -931,7 +1288,17 @@ query Hero {
                             name: "id",
                             alias: None,
                             arguments: Vec::new(),
+                            directives: vec![LookAheadDirective {
+                                name: "include",
+                                arguments: vec![LookAheadArgument {
+                                    name: "if",
+                                    value: LookAheadValue::Scalar(&DefaultScalarValue::Boolean(
+                                        true,
+                                    )),
+                                }],
+                            }],
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -940,11 +1307,22 @@ query Hero {
                             name: "height",
                             alias: None,
                             arguments: Vec::new(),
+                            directives: vec![LookAheadDirective {
+                                name: "skip",
+                                arguments: vec![LookAheadArgument {
+                                    name: "if",
+                                    value: LookAheadValue::Scalar(&DefaultScalarValue::Boolean(
+                                        false,
+                                    )),
+                                }],
+                            }],
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -990,6 +1368,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -999,6 +1378,7 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::OnlyType("Droid"),
                     },
@@ -1008,10 +1388,12 @@ query Hero {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::OnlyType("Human"),
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -1054,9 +1436,11 @@ query HeroAndHuman {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 }],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
 
@@ -1076,9 +1460,11 @@ query HeroAndHuman {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 }],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -1137,6 +1523,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -1146,6 +1533,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -1155,6 +1543,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -1164,6 +1553,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
@@ -1173,6 +1563,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::OnlyType("Droid"),
                     },
@@ -1182,6 +1573,7 @@ fragment comparisonFields on Character {
                             alias: None,
                             arguments: Vec::new(),
                             children: Vec::new(),
+                            ..Default::default()
                         },
                         applies_for: Applies::OnlyType("Human"),
                     },
@@ -1190,6 +1582,15 @@ fragment comparisonFields on Character {
                             name: "friends",
                             alias: None,
                             arguments: Vec::new(),
+                            directives: vec![LookAheadDirective {
+                                name: "include",
+                                arguments: vec![LookAheadArgument {
+                                    name: "if",
+                                    value: LookAheadValue::Scalar(&DefaultScalarValue::Boolean(
+                                        true,
+                                    )),
+                                }],
+                            }],
                             children: vec![
                                 ChildSelection {
                                     inner: LookAheadSelection {
@@ -1197,6 +1598,7 @@ fragment comparisonFields on Character {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::All,
                                 },
@@ -1206,6 +1608,7 @@ fragment comparisonFields on Character {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::All,
                                 },
@@ -1215,6 +1618,7 @@ fragment comparisonFields on Character {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::All,
                                 },
@@ -1224,6 +1628,7 @@ fragment comparisonFields on Character {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::OnlyType("Droid"),
                                 },
@@ -1233,14 +1638,17 @@ fragment comparisonFields on Character {
                                         alias: None,
                                         arguments: Vec::new(),
                                         children: Vec::new(),
+                                        ..Default::default()
                                     },
                                     applies_for: Applies::OnlyType("Human"),
                                 },
                             ],
+                            ..Default::default()
                         },
                         applies_for: Applies::All,
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -1286,14 +1694,17 @@ query Hero {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     ConcreteLookAheadSelection {
                         name: "height",
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -1314,6 +1725,7 @@ query Hero {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 },
@@ -1329,6 +1741,7 @@ query Hero {
                                     alias: None,
                                     arguments: Vec::new(),
                                     children: Vec::new(),
+                                    ..Default::default()
                                 },
                                 applies_for: Applies::All,
                             },
@@ -1338,14 +1751,17 @@ query Hero {
                                     alias: None,
                                     arguments: Vec::new(),
                                     children: Vec::new(),
+                                    ..Default::default()
                                 },
                                 applies_for: Applies::All,
                             },
                         ],
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 },
             ],
+            ..Default::default()
         };
         let concret_query = lookahead.for_explicit_type("does not matter");
 
@@ -1356,6 +1772,7 @@ query Hero {
             alias: None,
             arguments: Vec::new(),
             children: Vec::new(),
+            ..Default::default()
         };
         assert_eq!(id, Some(&expected));
         assert_eq!(
@@ -1376,6 +1793,7 @@ query Hero {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 },
@@ -1385,10 +1803,12 @@ query Hero {
                         alias: None,
                         arguments: Vec::new(),
                         children: Vec::new(),
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 },
             ],
+            ..Default::default()
         };
         assert_eq!(friends, Some(&expected));
         assert_eq!(
@@ -1439,12 +1859,15 @@ fragment heroFriendNames on Hero {
                                 alias: None,
                                 arguments: Vec::new(),
                                 children: Vec::new(),
+                                ..Default::default()
                             },
                             applies_for: Applies::All,
                         }],
+                        ..Default::default()
                     },
                     applies_for: Applies::All,
                 }],
+                ..Default::default()
             };
             assert_eq!(look_ahead, expected);
         } else {
@@ -1541,4 +1964,99 @@ query Hero {
             panic!("No Operation found");
         }
     }
+
+    #[test]
+    fn check_schema_metadata() {
+        let schema = SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &());
+        let docs = parse_document_source::<DefaultScalarValue>(
+            "
+query Hero {
+    owner: human {
+        fullName: name(surname: true)
+        pets @include(if: true) {
+            name
+            ... on Canine {
+                barkVolume
+            }
+            ... on Cat {
+                meows
+            }
+        }
+    }
+}
+",
+        )
+        .unwrap();
+        let fragments = extract_fragments(&docs);
+
+        if let crate::ast::Definition::Operation(ref op) = docs[0] {
+            let vars = graphql_vars! {};
+            let look_ahead = LookAheadSelection::build_from_selection(
+                &op.item.selection_set[0],
+                &vars,
+                &fragments,
+            )
+            .unwrap()
+            .with_schema(&schema, schema.concrete_query_type().field_by_name("human"));
+
+            assert_eq!(look_ahead.name(), "human");
+            assert_eq!(look_ahead.alias(), Some("owner"));
+            assert_eq!(look_ahead.field_type(), Some(&Type::Named("Human".into())));
+            assert!(!look_ahead.is_list());
+            assert_eq!(look_ahead.span().0, SourcePosition::new(18, 2, 4));
+
+            let full_name = look_ahead.select_child("fullName").unwrap();
+            assert_eq!(full_name.name(), "name");
+            assert_eq!(full_name.meta().unwrap().name, "name");
+            assert!(full_name.meta().unwrap().arguments.is_some());
+
+            let pets = look_ahead.select_child("pets").unwrap();
+            assert!(pets.is_list());
+            assert_eq!(pets.directives().len(), 1);
+            assert_eq!(pets.directives()[0].name(), "include");
+            assert_eq!(pets.directives()[0].arguments().len(), 1);
+            assert_eq!(
+                pets.directives()[0].argument("if").unwrap().value(),
+                &LookAheadValue::Scalar(&DefaultScalarValue::Boolean(true)),
+            );
+
+            let groups = pets
+                .children_by_type()
+                .into_iter()
+                .map(|(applies, children)| {
+                    (
+                        applies,
+                        children.into_iter().map(|c| c.name()).collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                groups,
+                vec![
+                    (Applies::All, vec!["name"]),
+                    (Applies::OnlyType("Canine"), vec!["barkVolume"]),
+                    (Applies::OnlyType("Cat"), vec!["meows"]),
+                ],
+            );
+
+            let bark_volume = pets.select_child("barkVolume").unwrap();
+            assert_eq!(bark_volume.field_type(), Some(&Type::Named("Int".into())),);
+
+            let mut all = pets.concrete_types(Applies::All).unwrap();
+            all.sort_unstable();
+            assert_eq!(all, vec!["Cat", "Dog"]);
+            assert_eq!(
+                pets.concrete_types(Applies::OnlyType("Canine")),
+                Some(vec!["Dog"]),
+            );
+
+            let dog = pets.for_explicit_type("Dog");
+            assert_eq!(dog.child_names(), vec!["name", "barkVolume"]);
+            assert_eq!(dog.children()[1].meta().unwrap().name, "barkVolume");
+            let cat = pets.for_explicit_type("Cat");
+            assert_eq!(cat.child_names(), vec!["name", "meows"]);
+        } else {
+            panic!("No Operation found");
+        }
+    }
 }
@@ -1,14 +1,16 @@
 //! Resolve the document to values
 
 use std::{
+    any,
     borrow::Cow,
     cmp::Ordering,
     collections::HashMap,
     fmt::{Debug, Display},
+    mem,
     sync::{Arc, RwLock},
 };
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 use futures::Stream;
 
 use crate::{
@@ -24,6 +26,7 @@ use crate::{
             ScalarMeta, UnionMeta,
         },
         model::{RootNode, SchemaType, TypeType},
+        validation::{type_shape, SchemaError},
     },
     types::{
         async_await::{GraphQLTypeAsync, GraphQLValueAsync},
@@ -35,14 +38,20 @@ use crate::{
     GraphQLError,
 };
 
+pub(crate) use self::extensions::NO_EXTENSIONS;
+
 pub use self::{
+    extensions::Extensions,
+    live::{InvalidationKeys, LIVE_DIRECTIVE},
     look_ahead::{
-        Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadMethods,
-        LookAheadSelection, LookAheadValue,
+        Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadDirective,
+        LookAheadMethods, LookAheadSelection, LookAheadValue,
     },
     owned_executor::OwnedExecutor,
 };
 
+mod extensions;
+mod live;
 mod look_ahead;
 mod owned_executor;
 
@@ -54,6 +63,19 @@ mod owned_executor;
 pub struct Registry<'r, S = DefaultScalarValue> {
     /// Currently registered types
     pub types: FnvHashMap<Name, MetaType<'r, S>>,
+
+    /// Errors of the same type names being registered with different
+    /// definitions.
+    pub(crate) errors: Vec<SchemaError>,
+
+    /// Rust types (by their [`any::type_name()`]) already seen registering
+    /// each type name, so the definitions of different Rust types registering
+    /// the same name are compared only once.
+    registrants: FnvHashSet<(Name, &'static str)>,
+
+    /// Shapes of definitions to be compared with the ones of the types still
+    /// being constructed, once they are.
+    pending_checks: Vec<(Name, String)>,
 }
 
 #[allow(missing_docs)]
@@ -77,8 +99,10 @@ where
     current_selection_set: Option<&'r [Selection<'a, S>]>,
     parent_selection_set: Option<&'r [Selection<'a, S>]>,
     current_type: TypeType<'a, S>,
+    current_field: Option<&'a Field<'a, S>>,
     schema: &'a SchemaType<'a, S>,
     context: &'a CtxT,
+    extensions: &'a Extensions,
     errors: &'r RwLock<Vec<ExecutionError<S>>>,
     field_path: Arc<FieldPath<'a>>,
 }
@@ -87,7 +111,7 @@ where
 ///
 /// All execution errors contain the source position in the query of the field
 /// that failed to resolve. It also contains the field stack.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ExecutionError<S> {
     location: SourcePosition,
     path: Vec<String>,
@@ -561,8 +585,10 @@ where
             current_selection_set: self.current_selection_set,
             parent_selection_set: self.parent_selection_set,
             current_type: self.current_type.clone(),
+            current_field: self.current_field,
             schema: self.schema,
             context: ctx,
+            extensions: self.extensions,
             errors: self.errors,
             field_path: self.field_path.clone(),
         }
@@ -576,21 +602,21 @@ where
         location: SourcePosition,
         selection_set: Option<&'s [Selection<'a, S>]>,
     ) -> Executor<'s, 'a, CtxT, S> {
+        let field = self
+            .current_type
+            .innermost_concrete()
+            .field_by_name(field_name)
+            .expect("Field not found on inner type");
         Executor {
             fragments: self.fragments,
             variables: self.variables,
             current_selection_set: selection_set,
             parent_selection_set: self.current_selection_set,
-            current_type: self.schema.make_type(
-                &self
-                    .current_type
-                    .innermost_concrete()
-                    .field_by_name(field_name)
-                    .expect("Field not found on inner type")
-                    .field_type,
-            ),
+            current_type: self.schema.make_type(&field.field_type),
+            current_field: Some(field),
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: self.errors,
             field_path: Arc::new(FieldPath::Field(
                 field_alias,
@@ -615,8 +641,10 @@ where
                 Some(type_name) => self.schema.type_by_name(type_name).expect("Type not found"),
                 None => self.current_type.clone(),
             },
+            current_field: self.current_field,
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: self.errors,
             field_path: self.field_path.clone(),
         }
@@ -635,6 +663,28 @@ where
         self.context
     }
 
+    /// Access the [`Extensions`] of the current execution
+    ///
+    /// They are provided before execution, usually via
+    /// [`GraphQLRequest::extensions`].
+    ///
+    /// [`GraphQLRequest::extensions`]: crate::http::GraphQLRequest::extensions
+    pub fn extensions(&self) -> &'a Extensions {
+        self.extensions
+    }
+
+    /// Tracks the provided invalidation `key` as touched by the current
+    /// execution, so a live query is re-executed once the `key` is
+    /// invalidated.
+    ///
+    /// Does nothing unless [`InvalidationKeys`] are provided via the
+    /// [`Extensions`] of the current execution.
+    pub fn track_invalidation_key(&self, key: impl Into<String>) {
+        if let Some(keys) = self.extensions.get::<InvalidationKeys>() {
+            keys.insert(key);
+        }
+    }
+
     /// The currently executing schema
     pub fn schema(&self) -> &'a SchemaType<S> {
         self.schema
@@ -695,6 +745,9 @@ where
     ///
     /// This allows seeing the whole selection and perform operations
     /// affecting the children.
+    ///
+    /// The returned selection carries the schema metadata of all the selected
+    /// fields, allowing to inspect their types (see [`LookAheadMethods`]).
     pub fn look_ahead(&'a self) -> LookAheadSelection<'a, S> {
         let field_name = match *self.field_path {
             FieldPath::Field(x, ..) => x,
@@ -725,10 +778,13 @@ where
                 // We didn't find a field in the parent's selection matching
                 // this field, which means we're inside a FragmentSpread
                 let mut ret = LookAheadSelection {
-                    name: field_name,
-                    alias: None,
-                    arguments: Vec::new(),
-                    children: Vec::new(),
+                    name: self.current_field.map_or(field_name, |f| f.name.as_str()),
+                    alias: self
+                        .current_field
+                        .map_or(false, |f| f.name != field_name)
+                        .then_some(field_name),
+                    span: (*self.location(), *self.location()),
+                    ..LookAheadSelection::default()
                 };
 
                 // Add in all the children - this will mutate `ret`
@@ -744,6 +800,7 @@ where
                 }
                 ret
             })
+            .with_schema(self.schema, self.current_field)
     }
 
     /// Create new `OwnedExecutor` and clone all current data
@@ -760,8 +817,10 @@ where
             current_selection_set: self.current_selection_set.map(|x| x.to_vec()),
             parent_selection_set: self.parent_selection_set.map(|x| x.to_vec()),
             current_type: self.current_type.clone(),
+            current_field: self.current_field,
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: RwLock::new(vec![]),
             field_path: Arc::clone(&self.field_path),
         }
@@ -820,6 +879,7 @@ pub fn execute_validated_query<'b, QueryT, MutationT, SubscriptionT, S>(
     root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &QueryT::Context,
+    extensions: &Extensions,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>
 where
     S: ScalarValue,
@@ -885,8 +945,10 @@ where
             current_selection_set: Some(&operation.item.selection_set[..]),
             parent_selection_set: None,
             current_type: root_type,
+            current_field: None,
             schema: &root_node.schema,
             context,
+            extensions,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
         };
@@ -914,6 +976,7 @@ pub async fn execute_validated_query_async<'a, 'b, QueryT, MutationT, Subscripti
     root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &QueryT::Context,
+    extensions: &Extensions,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>
 where
     QueryT: GraphQLTypeAsync<S>,
@@ -983,8 +1046,10 @@ where
             current_selection_set: Some(&operation.item.selection_set[..]),
             parent_selection_set: None,
             current_type: root_type,
+            current_field: None,
             schema: &root_node.schema,
             context,
+            extensions,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
         };
@@ -1058,6 +1123,7 @@ pub async fn resolve_validated_subscription<
     root_node: &'r RootNode<'r, QueryT, MutationT, SubscriptionT, S>,
     variables: &Variables<S>,
     context: &'r QueryT::Context,
+    extensions: &'r Extensions,
 ) -> Result<(Value<ValuesStream<'r, S>>, Vec<ExecutionError<S>>), GraphQLError>
 where
     'r: 'exec_ref,
@@ -1129,8 +1195,10 @@ where
             current_selection_set: Some(&operation.item.selection_set[..]),
             parent_selection_set: None,
             current_type: root_type,
+            current_field: None,
             schema: &root_node.schema,
             context,
+            extensions,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
         };
@@ -1154,7 +1222,12 @@ where
 impl<'r, S: 'r> Registry<'r, S> {
     /// Constructs a new [`Registry`] out of the given `types`.
     pub fn new(types: FnvHashMap<Name, MetaType<'r, S>>) -> Self {
-        Self { types }
+        Self {
+            types,
+            errors: Vec::new(),
+            registrants: FnvHashSet::default(),
+            pending_checks: Vec::new(),
+        }
     }
 
     /// Returns a [`Type`] instance for the given [`GraphQLType`], registered in
@@ -1169,13 +1242,37 @@ impl<'r, S: 'r> Registry<'r, S> {
     {
         if let Some(name) = T::name(info) {
             let validated_name = name.parse::<Name>().unwrap();
+            let is_new_registrant = self
+                .registrants
+                .insert((validated_name.clone(), any::type_name::<T>()));
             if !self.types.contains_key(name) {
                 self.insert_placeholder(
                     validated_name.clone(),
                     Type::NonNullNamed(Cow::Owned(name.into())),
                 );
                 let meta = T::meta(info, self);
-                self.types.insert(validated_name, meta);
+                self.types.insert(validated_name.clone(), meta);
+
+                if !self.pending_checks.is_empty() {
+                    let mut pending = mem::take(&mut self.pending_checks);
+                    pending.retain(|(n, shape)| {
+                        let is_ready = *n == validated_name;
+                        if is_ready {
+                            self.check_type_shape(name, shape);
+                        }
+                        !is_ready
+                    });
+                    self.pending_checks = pending;
+                }
+            } else if is_new_registrant {
+                // The same name may be registered by a different Rust type, so
+                // make sure it doesn't silently shadow a different definition.
+                let shape = type_shape(&T::meta(info, self));
+                if matches!(self.types[name], MetaType::Placeholder(_)) {
+                    self.pending_checks.push((validated_name, shape));
+                } else {
+                    self.check_type_shape(name, &shape);
+                }
             }
             self.types[name].as_type()
         } else {
@@ -1183,6 +1280,22 @@ impl<'r, S: 'r> Registry<'r, S> {
         }
     }
 
+    /// Checks whether the provided `shape` of a definition matches the one of
+    /// the already registered type with the given `name`, reporting an error
+    /// otherwise.
+    fn check_type_shape(&mut self, name: &str, shape: &str) {
+        let registered = type_shape(&self.types[name]);
+        if registered != shape {
+            let err = SchemaError::new(format!(
+                "Type \"{name}\" is registered multiple times with different \
+                 definitions: `{registered}` and `{shape}`",
+            ));
+            if !self.errors.contains(&err) {
+                self.errors.push(err);
+            }
+        }
+    }
+
     /// Creates a [`Field`] with the provided `name`.
     pub fn field<T>(&mut self, name: &str, info: &T::TypeInfo) -> Field<'r, S>
     where
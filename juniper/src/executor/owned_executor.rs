@@ -5,9 +5,12 @@ use std::{
 
 use crate::{
     ast::Fragment,
-    executor::FieldPath,
+    executor::{Extensions, FieldPath},
     parser::SourcePosition,
-    schema::model::{SchemaType, TypeType},
+    schema::{
+        meta::Field,
+        model::{SchemaType, TypeType},
+    },
     ExecutionError, Executor, Selection, Variables,
 };
 
@@ -19,8 +22,10 @@ pub struct OwnedExecutor<'a, CtxT, S> {
     pub(super) current_selection_set: Option<Vec<Selection<'a, S>>>,
     pub(super) parent_selection_set: Option<Vec<Selection<'a, S>>>,
     pub(super) current_type: TypeType<'a, S>,
+    pub(super) current_field: Option<&'a Field<'a, S>>,
     pub(super) schema: &'a SchemaType<'a, S>,
     pub(super) context: &'a CtxT,
+    pub(super) extensions: &'a Extensions,
     pub(super) errors: RwLock<Vec<ExecutionError<S>>>,
     pub(super) field_path: Arc<FieldPath<'a>>,
 }
@@ -36,8 +41,10 @@ where
             current_selection_set: self.current_selection_set.clone(),
             parent_selection_set: self.parent_selection_set.clone(),
             current_type: self.current_type.clone(),
+            current_field: self.current_field,
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
         }
@@ -64,8 +71,10 @@ where
                 Some(type_name) => self.schema.type_by_name(type_name).expect("Type not found"),
                 None => self.current_type.clone(),
             },
+            current_field: self.current_field,
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
         }
@@ -85,21 +94,21 @@ where
         location: SourcePosition,
         selection_set: Option<Vec<Selection<'a, S>>>,
     ) -> OwnedExecutor<'a, CtxT, S> {
+        let field = self
+            .current_type
+            .innermost_concrete()
+            .field_by_name(field_name)
+            .expect("Field not found on inner type");
         OwnedExecutor {
             fragments: self.fragments.clone(),
             variables: self.variables.clone(),
             current_selection_set: selection_set,
             parent_selection_set: self.current_selection_set.clone(),
-            current_type: self.schema.make_type(
-                &self
-                    .current_type
-                    .innermost_concrete()
-                    .field_by_name(field_name)
-                    .expect("Field not found on inner type")
-                    .field_type,
-            ),
+            current_type: self.schema.make_type(&field.field_type),
+            current_field: Some(field),
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: RwLock::new(vec![]),
             field_path: Arc::new(FieldPath::Field(
                 field_alias,
@@ -117,8 +126,10 @@ where
             current_selection_set: self.current_selection_set.as_deref(),
             parent_selection_set: self.parent_selection_set.as_deref(),
             current_type: self.current_type.clone(),
+            current_field: self.current_field,
             schema: self.schema,
             context: self.context,
+            extensions: self.extensions,
             errors: &self.errors,
             field_path: Arc::clone(&self.field_path),
         }
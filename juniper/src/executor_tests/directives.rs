@@ -246,3 +246,92 @@ async fn scalar_include_false_skip_false() {
     })
     .await;
 }
+
+mod live {
+    use crate::{
+        graphql_object, http::GraphQLRequest, EmptyMutation, EmptySubscription, Executor,
+        InvalidationKeys, RootNode, ScalarValue,
+    };
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn user<S: ScalarValue>(executor: &Executor<(), S>, id: i32) -> String {
+            executor.track_invalidation_key(format!("user:{id}"));
+            format!("user {id}")
+        }
+    }
+
+    fn schema() -> RootNode<'static, Query, EmptyMutation, EmptySubscription> {
+        RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    #[tokio::test]
+    async fn is_rejected_unless_enabled() {
+        let schema = schema();
+        let query = "query @live { user(id: 1) }";
+
+        assert!(!schema.is_live_query(query, None));
+
+        let err = GraphQLRequest::new(query.into(), None, None)
+            .execute(&schema, &())
+            .await
+            .into_result()
+            .expect_err("`@live` directive should be unknown");
+        assert!(
+            err.to_string().contains(r#"Unknown directive "live""#),
+            "{err}",
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_live_queries() {
+        let schema = schema().enable_live_queries();
+
+        assert!(schema.is_live_query("query @live { user(id: 1) }", None));
+        assert!(schema.is_live_query(
+            "query A { user(id: 1) } query B @live { user(id: 2) }",
+            Some("B"),
+        ));
+        assert!(!schema.is_live_query(
+            "query A { user(id: 1) } query B @live { user(id: 2) }",
+            Some("A"),
+        ));
+        assert!(!schema.is_live_query("{ user(id: 1) }", None));
+        assert!(!schema.is_live_query("query @live {", None));
+    }
+
+    #[tokio::test]
+    async fn tracks_invalidation_keys() {
+        let schema = schema().enable_live_queries();
+
+        let mut req = GraphQLRequest::new(
+            "query @live { a: user(id: 1) b: user(id: 2) c: user(id: 1) }".into(),
+            None,
+            None,
+        );
+        req.extensions.insert(InvalidationKeys::new());
+
+        let (res, errs) = req
+            .execute(&schema, &())
+            .await
+            .into_result()
+            .expect("execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            res,
+            graphql_value!({"a": "user 1", "b": "user 2", "c": "user 1"}),
+        );
+        assert_eq!(
+            req.extensions
+                .get::<InvalidationKeys>()
+                .unwrap()
+                .take()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["user:1", "user:2"],
+        );
+    }
+}
@@ -1101,3 +1101,59 @@ mod named_operations {
         assert_eq!(err, GraphQLError::UnknownOperationName);
     }
 }
+
+mod look_ahead_metadata {
+    use crate::{
+        executor::{Executor, LookAheadMethods as _},
+        graphql_object, graphql_value, graphql_vars,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::ScalarValue,
+    };
+
+    struct Schema;
+
+    #[graphql_object]
+    impl Schema {
+        fn describe<S: ScalarValue>(executor: &Executor<'_, '_, (), S>) -> String {
+            let look_ahead = executor.look_ahead();
+            format!(
+                "{}{}: {}",
+                look_ahead.name(),
+                look_ahead
+                    .alias()
+                    .map(|a| format!(" as {a}"))
+                    .unwrap_or_default(),
+                look_ahead.field_type().unwrap(),
+            )
+        }
+
+        fn nested() -> Vec<Schema> {
+            vec![Schema]
+        }
+    }
+
+    #[tokio::test]
+    async fn exposes_field_metadata() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = r"{ info: describe, nested { describe } }";
+        let vars = graphql_vars! {};
+
+        let (res, errs) = crate::execute(doc, None, &schema, &vars, &())
+            .await
+            .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            res,
+            graphql_value!({
+                "info": "describe as info: String!",
+                "nested": [{"describe": "describe: String!"}],
+            }),
+        );
+    }
+}
@@ -7,9 +7,11 @@ use self::input_object::{NamedPublic, NamedPublicWithDescription};
 
 use crate::{
     graphql_interface, graphql_object, graphql_value, graphql_vars,
+    parser::SourcePosition,
     schema::model::RootNode,
     types::scalars::{EmptyMutation, EmptySubscription},
-    GraphQLEnum, GraphQLScalar,
+    validation::RuleError,
+    GraphQLEnum, GraphQLError, GraphQLScalar, IntrospectionFormat,
 };
 
 #[derive(GraphQLEnum)]
@@ -521,3 +523,84 @@ async fn scalar_introspection() {
         }),
     );
 }
+
+#[tokio::test]
+async fn disabled_introspection() {
+    let schema = RootNode::new(
+        Root,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .disable_introspection();
+
+    let doc = r#"{ __typename sampleEnum }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &()).await,
+        Ok((
+            graphql_value!({"__typename": "Root", "sampleEnum": "ONE"}),
+            vec![],
+        )),
+    );
+
+    let doc = r#"{ __schema { queryType { name } } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &()).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "GraphQL introspection is not allowed, but the query contained \"__schema\"",
+            &[SourcePosition::new(2, 0, 2)],
+        )])),
+    );
+    assert!(crate::introspect(&schema, &(), IntrospectionFormat::All).is_err());
+}
+
+#[tokio::test]
+async fn restricted_introspection() {
+    struct Context {
+        is_staff: bool,
+    }
+
+    impl crate::Context for Context {}
+
+    struct Query;
+
+    #[graphql_object(context = Context)]
+    impl Query {
+        fn hello() -> &'static str {
+            "world"
+        }
+    }
+
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Context>::new(),
+        EmptySubscription::<Context>::new(),
+    )
+    .restrict_introspection(|ctx: &Context| ctx.is_staff);
+
+    let doc = r#"{ __type(name: "Query") { name } }"#;
+    assert_eq!(
+        crate::execute(
+            doc,
+            None,
+            &schema,
+            &graphql_vars! {},
+            &Context { is_staff: true }
+        )
+        .await,
+        Ok((graphql_value!({"__type": {"name": "Query"}}), vec![])),
+    );
+    assert_eq!(
+        crate::execute(
+            doc,
+            None,
+            &schema,
+            &graphql_vars! {},
+            &Context { is_staff: false }
+        )
+        .await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "GraphQL introspection is not allowed, but the query contained \"__type\"",
+            &[SourcePosition::new(2, 0, 2)],
+        )])),
+    );
+}
@@ -1,7 +1,10 @@
 //! Utilities for building HTTP endpoints in a library-agnostic manner
 
 pub mod graphiql;
+#[cfg(feature = "multipart")]
+pub mod multipart;
 pub mod playground;
+pub mod policy;
 
 use serde::{
     de,
@@ -11,8 +14,9 @@ use serde::{
 
 use crate::{
     ast::InputValue,
-    executor::{ExecutionError, ValuesStream},
-    value::{DefaultScalarValue, ScalarValue},
+    executor::{ExecutionError, Extensions, ValuesStream},
+    usage::OperationUsage,
+    value::{DefaultScalarValue, Object, ScalarValue},
     FieldError, GraphQLError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, RootNode,
     Value, Variables,
 };
@@ -42,6 +46,16 @@ where
         serialize = "InputValue<S>: Serialize",
     ))]
     pub variables: Option<InputValue<S>>,
+
+    /// [`Extensions`] to execute the GraphQL operation with, accessible via
+    /// [`Executor::extensions()`].
+    ///
+    /// Never (de)serialized, so is expected to be populated by the server code
+    /// before executing this request.
+    ///
+    /// [`Executor::extensions()`]: crate::Executor::extensions
+    #[serde(skip)]
+    pub extensions: Extensions,
 }
 
 impl<S> GraphQLRequest<S>
@@ -76,6 +90,7 @@ where
             query,
             operation_name,
             variables,
+            extensions: Extensions::new(),
         }
     }
 
@@ -94,12 +109,13 @@ where
         MutationT: GraphQLType<S, Context = QueryT::Context>,
         SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
     {
-        GraphQLResponse(crate::execute_sync(
+        GraphQLResponse::from_result_with_usage(crate::execute_sync_with_usage(
             &self.query,
             self.operation_name.as_deref(),
             root_node,
             &self.variables(),
             context,
+            &self.extensions,
         ))
     }
 
@@ -124,8 +140,9 @@ where
     {
         let op = self.operation_name.as_deref();
         let vars = &self.variables();
-        let res = crate::execute(&self.query, op, root_node, vars, context).await;
-        GraphQLResponse(res)
+        let ext = &self.extensions;
+        let res = crate::execute_with_usage(&self.query, op, root_node, vars, context, ext).await;
+        GraphQLResponse::from_result_with_usage(res)
     }
 }
 
@@ -154,7 +171,15 @@ where
     let op = req.operation_name.as_deref();
     let vars = req.variables();
 
-    crate::resolve_into_stream(&req.query, op, root_node, &vars, context).await
+    crate::resolve_into_stream_with_extensions(
+        &req.query,
+        op,
+        root_node,
+        &vars,
+        context,
+        &req.extensions,
+    )
+    .await
 }
 
 /// Simple wrapper around the result from executing a GraphQL query
@@ -163,9 +188,10 @@ where
 /// to JSON and send it over the wire. Use the `is_ok` method to determine
 /// whether to send a 200 or 400 HTTP status code.
 #[derive(Debug)]
-pub struct GraphQLResponse<S = DefaultScalarValue>(
-    Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>,
-);
+pub struct GraphQLResponse<S = DefaultScalarValue> {
+    result: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>,
+    extensions: Object<S>,
+}
 
 impl<S> GraphQLResponse<S>
 where
@@ -173,12 +199,50 @@ where
 {
     /// Constructs new `GraphQLResponse` using the given result
     pub fn from_result(r: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError>) -> Self {
-        Self(r)
+        Self {
+            result: r,
+            extensions: Object::with_capacity(0),
+        }
+    }
+
+    /// Constructs new `GraphQLResponse` using the given result, reporting the
+    /// deprecated schema members used by the executed operation (if any) in
+    /// the `deprecations` extension.
+    fn from_result_with_usage(r: Result<crate::ExecutionOutputWithUsage<S>, GraphQLError>) -> Self {
+        match r {
+            Ok((res, usage)) => {
+                let resp = Self::from_result(Ok(res));
+                match usage.as_ref().map(deprecations_extension) {
+                    Some(Some(deprecations)) => resp.with_extension("deprecations", deprecations),
+                    _ => resp,
+                }
+            }
+            Err(e) => Self::from_result(Err(e)),
+        }
     }
 
     /// Constructs an error response outside of the normal execution flow
     pub fn error(error: FieldError<S>) -> Self {
-        GraphQLResponse(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+        Self::from_result(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+    }
+
+    /// Adds the provided `value` to the `extensions` of this response under
+    /// the provided `name`, replacing the previous one (if any).
+    #[must_use]
+    pub fn with_extension(mut self, name: impl AsRef<str>, value: Value<S>) -> Self {
+        self.extensions.add_field(name.as_ref(), value);
+        self
+    }
+
+    /// Returns the `extensions` of this response.
+    pub fn extensions(&self) -> &Object<S> {
+        &self.extensions
+    }
+
+    /// Converts this response into its execution result, dropping its
+    /// `extensions`.
+    pub fn into_result(self) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError> {
+        self.result
     }
 
     /// Was the request successful or not?
@@ -186,10 +250,47 @@ where
     /// Note that there still might be errors in the response even though it's
     /// considered OK. This is by design in GraphQL.
     pub fn is_ok(&self) -> bool {
-        self.0.is_ok()
+        self.result.is_ok()
+    }
+
+    /// Returns the HTTP status code of this response, according to the
+    /// [GraphQL-over-HTTP] spec.
+    ///
+    /// Once the execution has started, the response is considered successful,
+    /// even if it contains field errors. Otherwise, the status code is chosen by
+    /// [`policy::error_status_code()`].
+    ///
+    /// [GraphQL-over-HTTP]: https://graphql.github.io/graphql-over-http/draft
+    pub fn status_code(&self) -> u16 {
+        match &self.result {
+            Ok(_) => 200,
+            Err(e) => policy::error_status_code(e),
+        }
     }
 }
 
+/// Builds the `deprecations` extension value out of the provided
+/// [`OperationUsage`], if it uses any deprecated schema members.
+fn deprecations_extension<S: ScalarValue>(usage: &OperationUsage) -> Option<Value<S>> {
+    let deprecations = usage
+        .deprecated()
+        .map(|(coordinate, reason)| {
+            Value::object(
+                [
+                    ("coordinate", Value::scalar(coordinate.to_string())),
+                    (
+                        "reason",
+                        reason.map_or_else(Value::null, |r| Value::scalar(r.to_owned())),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+    (!deprecations.is_empty()).then(|| Value::list(deprecations))
+}
+
 impl<T> Serialize for GraphQLResponse<T>
 where
     T: Serialize + ScalarValue,
@@ -200,7 +301,7 @@ where
     where
         S: ser::Serializer,
     {
-        match self.0 {
+        let mut map = match self.result {
             Ok((ref res, ref err)) => {
                 let mut map = serializer.serialize_map(None)?;
 
@@ -212,15 +313,22 @@ where
                     map.serialize_value(err)?;
                 }
 
-                map.end()
+                map
             }
             Err(ref err) => {
-                let mut map = serializer.serialize_map(Some(1))?;
+                let mut map = serializer.serialize_map(None)?;
                 map.serialize_key("errors")?;
                 map.serialize_value(err)?;
-                map.end()
+                map
             }
+        };
+
+        if self.extensions.field_count() > 0 {
+            map.serialize_key("extensions")?;
+            map.serialize_value(&self.extensions)?;
         }
+
+        map.end()
     }
 }
 
@@ -355,6 +463,21 @@ impl<S: ScalarValue> GraphQLBatchResponse<S> {
             Self::Batch(resps) => resps.iter().all(GraphQLResponse::is_ok),
         }
     }
+
+    /// Returns the HTTP status code of this response: the one of the first
+    /// failed [`GraphQLResponse`], if any, or `200 OK` otherwise.
+    ///
+    /// See [`GraphQLResponse::status_code()`] for details.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Single(resp) => resp.status_code(),
+            Self::Batch(resps) => resps
+                .iter()
+                .map(GraphQLResponse::status_code)
+                .find(|code| *code != 200)
+                .unwrap_or(200),
+        }
+    }
 }
 
 #[cfg(feature = "expose-test-schema")]
@@ -385,6 +508,14 @@ pub mod tests {
         /// Sends POST HTTP request to this integration with the provided raw GraphQL query as
         /// `body`, and returns response returned by this integration.
         fn post_graphql(&self, url: &str, body: &str) -> TestResponse;
+
+        /// Sends GET HTTP request to this integration with the provided `url` parameters string
+        /// and `Accept` header, and returns response returned by this integration.
+        fn get_with_accept(&self, url: &str, accept: &str) -> TestResponse;
+
+        /// Sends POST HTTP request to this integration with the provided JSON-encoded `body` and
+        /// `Accept` header, and returns response returned by this integration.
+        fn post_json_with_accept(&self, url: &str, body: &str, accept: &str) -> TestResponse;
     }
 
     #[allow(missing_docs)]
@@ -423,6 +554,24 @@ pub mod tests {
 
         println!("  - test_invalid_graphql_post");
         test_invalid_graphql_post(integration);
+
+        println!("  - test_get_mutation");
+        test_get_mutation(integration);
+
+        println!("  - test_get_subscription");
+        test_get_subscription(integration);
+
+        println!("  - test_accept_graphql_response_json");
+        test_accept_graphql_response_json(integration);
+
+        println!("  - test_accept_json");
+        test_accept_json(integration);
+
+        println!("  - test_not_acceptable");
+        test_not_acceptable(integration);
+
+        println!("  - test_invalid_field_graphql_response_json");
+        test_invalid_field_graphql_response_json(integration);
     }
 
     fn unwrap_json_response(response: &TestResponse) -> Json {
@@ -589,6 +738,84 @@ pub mod tests {
         assert_eq!(resp.status_code, 400);
     }
 
+    fn test_get_mutation<T: HttpIntegration>(integration: &T) {
+        // mutation { __typename }
+        let resp = integration.get("/?query=mutation%20%7B%20__typename%20%7D");
+
+        assert_eq!(resp.status_code, 405);
+    }
+
+    fn test_get_subscription<T: HttpIntegration>(integration: &T) {
+        // subscription { __typename }
+        let resp = integration.get("/?query=subscription%20%7B%20__typename%20%7D");
+
+        assert_eq!(resp.status_code, 405);
+    }
+
+    fn test_accept_graphql_response_json<T: HttpIntegration>(integration: &T) {
+        const ACCEPT: &str = "application/graphql-response+json, application/json;q=0.9";
+
+        // {hero{name}}
+        let resp = integration.get_with_accept("/?query=%7Bhero%7Bname%7D%7D", ACCEPT);
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(resp.content_type, "application/graphql-response+json");
+        assert_eq!(
+            unwrap_json_response(&resp),
+            serde_json::from_str::<Json>(r#"{"data": {"hero": {"name": "R2-D2"}}}"#)
+                .expect("Invalid JSON constant in test"),
+        );
+
+        let resp = integration.post_json_with_accept("/", r#"{"query": "{hero{name}}"}"#, ACCEPT);
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(resp.content_type, "application/graphql-response+json");
+        assert_eq!(
+            unwrap_json_response(&resp),
+            serde_json::from_str::<Json>(r#"{"data": {"hero": {"name": "R2-D2"}}}"#)
+                .expect("Invalid JSON constant in test"),
+        );
+    }
+
+    fn test_accept_json<T: HttpIntegration>(integration: &T) {
+        for accept in ["application/json", "*/*"] {
+            // {hero{name}}
+            let resp = integration.get_with_accept("/?query=%7Bhero%7Bname%7D%7D", accept);
+
+            assert_eq!(resp.status_code, 200, "Accept: {accept}");
+            assert_eq!(resp.content_type, "application/json", "Accept: {accept}");
+
+            let resp =
+                integration.post_json_with_accept("/", r#"{"query": "{hero{name}}"}"#, accept);
+
+            assert_eq!(resp.status_code, 200, "Accept: {accept}");
+            assert_eq!(resp.content_type, "application/json", "Accept: {accept}");
+        }
+    }
+
+    fn test_not_acceptable<T: HttpIntegration>(integration: &T) {
+        // {hero{name}}
+        let resp = integration.get_with_accept("/?query=%7Bhero%7Bname%7D%7D", "text/html");
+
+        assert_eq!(resp.status_code, 406);
+
+        let resp =
+            integration.post_json_with_accept("/", r#"{"query": "{hero{name}}"}"#, "text/html");
+
+        assert_eq!(resp.status_code, 406);
+    }
+
+    fn test_invalid_field_graphql_response_json<T: HttpIntegration>(integration: &T) {
+        let resp = integration.post_json_with_accept(
+            "/",
+            r#"{"query": "{hero{blah}}"}"#,
+            "application/graphql-response+json",
+        );
+
+        assert_eq!(resp.status_code, 400);
+        assert_eq!(resp.content_type, "application/graphql-response+json");
+    }
+
     /// Normalized way to make requests to the WebSocket framework integration we are testing.
     pub trait WsIntegration {
         /// Runs a test with the given messages
@@ -625,6 +852,26 @@ pub mod tests {
         test_ws_invalid_query(integration).await;
     }
 
+    /// Runs the test suite of the [graphql-transport-ws][1] protocol against the given
+    /// [`WsIntegration`], which must serve this protocol.
+    ///
+    /// [1]: https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md
+    pub async fn run_graphql_transport_ws_test_suite<T: WsIntegration>(integration: &T) {
+        println!("Running graphql-transport-ws Test suite for integration");
+
+        println!("  - test_graphql_transport_ws_simple_subscription");
+        test_graphql_transport_ws_simple_subscription(integration).await;
+
+        println!("  - test_graphql_transport_ws_query");
+        test_graphql_transport_ws_query(integration).await;
+
+        println!("  - test_graphql_transport_ws_ping");
+        test_graphql_transport_ws_ping(integration).await;
+
+        println!("  - test_graphql_transport_ws_invalid_query");
+        test_graphql_transport_ws_invalid_query(integration).await;
+    }
+
     async fn test_ws_simple_subscription<T: WsIntegration>(integration: &T) {
         let messages = vec![
             WsIntegrationMessage::Send(
@@ -756,4 +1003,163 @@ pub mod tests {
 
         integration.run(messages).await.unwrap();
     }
+
+    fn graphql_transport_ws_init() -> Vec<WsIntegrationMessage> {
+        vec![
+            WsIntegrationMessage::Send(
+                r#"{
+                    "type":"connection_init",
+                    "payload":{}
+                }"#
+                .into(),
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"connection_ack"
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+        ]
+    }
+
+    async fn test_graphql_transport_ws_simple_subscription<T: WsIntegration>(integration: &T) {
+        let mut messages = graphql_transport_ws_init();
+        messages.extend([
+            WsIntegrationMessage::Send(
+                r#"{
+                    "id":"1",
+                    "type":"subscribe",
+                    "payload":{
+                        "variables":{},
+                        "extensions":{},
+                        "operationName":null,
+                        "query":"subscription { asyncHuman { id, name, homePlanet } }"
+                    }
+                }"#
+                .into(),
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"next",
+                    "id":"1",
+                    "payload":{
+                        "data":{
+                            "asyncHuman":{
+                                "id":"1000",
+                                "name":"Luke Skywalker",
+                                "homePlanet":"Tatooine"
+                            }
+                        }
+                    }
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"complete",
+                    "id":"1"
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+        ]);
+
+        integration.run(messages).await.unwrap();
+    }
+
+    async fn test_graphql_transport_ws_query<T: WsIntegration>(integration: &T) {
+        let mut messages = graphql_transport_ws_init();
+        messages.extend([
+            WsIntegrationMessage::Send(
+                r#"{
+                    "id":"1",
+                    "type":"subscribe",
+                    "payload":{
+                        "query":"{ hero { name } }"
+                    }
+                }"#
+                .into(),
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"next",
+                    "id":"1",
+                    "payload":{
+                        "data":{
+                            "hero":{
+                                "name":"R2-D2"
+                            }
+                        }
+                    }
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"complete",
+                    "id":"1"
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+        ]);
+
+        integration.run(messages).await.unwrap();
+    }
+
+    async fn test_graphql_transport_ws_ping<T: WsIntegration>(integration: &T) {
+        let messages = vec![
+            WsIntegrationMessage::Send(
+                r#"{
+                    "type":"ping"
+                }"#
+                .into(),
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"pong"
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+        ];
+
+        integration.run(messages).await.unwrap();
+    }
+
+    async fn test_graphql_transport_ws_invalid_query<T: WsIntegration>(integration: &T) {
+        let mut messages = graphql_transport_ws_init();
+        messages.extend([
+            WsIntegrationMessage::Send(
+                r#"{
+                    "id":"1",
+                    "type":"subscribe",
+                    "payload":{
+                        "query":"subscription { asyncHuman }"
+                    }
+                }"#
+                .into(),
+            ),
+            WsIntegrationMessage::Expect(
+                r#"{
+                    "type":"error",
+                    "id":"1",
+                    "payload":[{
+                        "message":"Field \"asyncHuman\" of type \"Human!\" must have a selection of subfields. Did you mean \"asyncHuman { ... }\"?",
+                        "locations":[{
+                            "line":1,
+                            "column":16
+                        }]
+                    }]
+                }"#
+                .into(),
+                WS_INTEGRATION_EXPECT_DEFAULT_TIMEOUT,
+            ),
+        ]);
+
+        integration.run(messages).await.unwrap();
+    }
 }
@@ -0,0 +1,802 @@
+//! Support of [GraphQL multipart requests][0] for uploading files.
+//!
+//! A `multipart/form-data` request consists of:
+//! - `operations` field, containing a JSON-encoded [`GraphQLBatchRequest`] with `null`s in place
+//!   of the uploaded files;
+//! - `map` field, containing a JSON object mapping file fields to the paths of variables they
+//!   should be injected into (like `{"0": ["variables.file"]}`, or
+//!   `{"0": ["0.variables.files.0"]}` for a batch);
+//! - file fields, named as the keys of the `map`.
+//!
+//! Files are streamed into temporary storage (see [`MultipartOptions`]) and injected into the
+//! variables as [`Upload`] scalars, resolvable into [`UploadedFile`]s via the [`Extensions`] of
+//! the request:
+//!
+//! ```rust
+//! # use std::io::Read as _;
+//! # use juniper::{graphql_object, http::multipart::Upload, Executor, FieldResult, ScalarValue};
+//! #
+//! struct Mutation;
+//!
+//! #[graphql_object]
+//! impl Mutation {
+//!     fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> FieldResult<String> {
+//!         let file = file
+//!             .file(executor.extensions())
+//!             .ok_or("file is not uploaded")?;
+//!
+//!         let mut contents = String::new();
+//!         file.open()?.read_to_string(&mut contents)?;
+//!         Ok(contents)
+//!     }
+//! }
+//! ```
+//!
+//! [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use futures::Stream;
+use multer::{Constraints, Multipart, SizeLimit};
+use tempfile::NamedTempFile;
+use tokio::{io::AsyncWriteExt as _, task};
+
+use crate::{ast::InputValue, executor::Extensions, value::ScalarValue, GraphQLScalar, Value};
+
+use super::GraphQLBatchRequest;
+
+/// Name of the field containing the GraphQL operations of a multipart request.
+const OPERATIONS_FIELD: &str = "operations";
+
+/// Name of the field mapping the files of a multipart request to the variables.
+const MAP_FIELD: &str = "map";
+
+/// Uploaded file, as referred by a variable of a [GraphQL multipart request][0].
+///
+/// Represented as the name of the file field of the request, and resolved into an
+/// [`UploadedFile`] via [`Upload::file()`].
+///
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[derive(Clone, Debug, Eq, GraphQLScalar, PartialEq)]
+#[graphql(
+    parse_token(String),
+    specified_by_url = "https://github.com/jaydenseric/graphql-multipart-request-spec"
+)]
+pub struct Upload(String);
+
+impl Upload {
+    /// Returns the name of the file field of the request this [`Upload`] refers to.
+    pub fn field_name(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the [`UploadedFile`] this [`Upload`] refers to, if it's present in the
+    /// [`Uploads`] of the provided request `extensions`.
+    pub fn file<'e>(&self, extensions: &'e Extensions) -> Option<&'e UploadedFile> {
+        extensions.get::<Uploads>()?.get(&self.0)
+    }
+
+    fn to_output<S: ScalarValue>(&self) -> Value<S> {
+        Value::scalar(self.0.clone())
+    }
+
+    fn from_input<S: ScalarValue>(v: &InputValue<S>) -> Result<Self, String> {
+        v.as_string_value()
+            .map(|s| Self(s.into()))
+            .ok_or_else(|| format!("Expected `String`, found: {v}"))
+    }
+}
+
+/// File uploaded with a [GraphQL multipart request][0], stored in a temporary file.
+///
+/// The temporary file is removed once this [`UploadedFile`] is dropped (along with the request it
+/// belongs to), so should be copied elsewhere to persist.
+///
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[derive(Debug)]
+pub struct UploadedFile {
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size: u64,
+    file: NamedTempFile,
+}
+
+impl UploadedFile {
+    /// Returns the file name provided by the client, if any.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Returns the content type provided by the client, if any.
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns the size of this [`UploadedFile`] in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the path of the temporary file storing this [`UploadedFile`].
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Opens this [`UploadedFile`] for reading from its beginning.
+    ///
+    /// # Errors
+    ///
+    /// If the temporary file cannot be opened.
+    pub fn open(&self) -> io::Result<File> {
+        self.file.reopen()
+    }
+}
+
+/// [`UploadedFile`]s of a [GraphQL multipart request][0], by the names of their fields.
+///
+/// Provided via the [`GraphQLRequest::extensions`] by [`parse_multipart_request()`].
+///
+/// [`GraphQLRequest::extensions`]: super::GraphQLRequest::extensions
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[derive(Clone, Debug, Default)]
+pub struct Uploads(HashMap<String, Arc<UploadedFile>>);
+
+impl Uploads {
+    /// Returns the [`UploadedFile`] of the provided `field_name`, if any.
+    pub fn get(&self, field_name: &str) -> Option<&UploadedFile> {
+        self.0.get(field_name).map(Arc::as_ref)
+    }
+
+    /// Returns the number of [`UploadedFile`]s.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Indicates whether there are no [`UploadedFile`]s.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Options of parsing [GraphQL multipart requests][0].
+///
+/// By default, files are stored in the [`std::env::temp_dir()`], and no limits are imposed.
+///
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[derive(Clone, Debug, Default)]
+pub struct MultipartOptions {
+    temp_dir: Option<PathBuf>,
+    max_file_size: Option<u64>,
+    max_files: Option<usize>,
+    max_request_size: Option<u64>,
+}
+
+impl MultipartOptions {
+    /// Creates new default [`MultipartOptions`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the directory to store the uploaded files in.
+    #[must_use]
+    pub fn with_temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(dir.into());
+        self
+    }
+
+    /// Specifies the maximum size of a single uploaded file in bytes.
+    #[must_use]
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Specifies the maximum number of files uploaded with a single request.
+    #[must_use]
+    pub fn with_max_files(mut self, max: usize) -> Self {
+        self.max_files = Some(max);
+        self
+    }
+
+    /// Specifies the maximum size of the whole request body in bytes.
+    #[must_use]
+    pub fn with_max_request_size(mut self, bytes: u64) -> Self {
+        self.max_request_size = Some(bytes);
+        self
+    }
+
+    /// Creates a new temporary file to store an upload in.
+    fn temp_file(&self) -> io::Result<NamedTempFile> {
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("juniper-upload-");
+        match &self.temp_dir {
+            Some(dir) => builder.tempfile_in(dir),
+            None => builder.tempfile(),
+        }
+    }
+
+    fn constraints(&self) -> Constraints {
+        let mut limit = SizeLimit::new();
+        if let Some(bytes) = self.max_request_size {
+            limit = limit.whole_stream(bytes);
+        }
+        if let Some(bytes) = self.max_file_size {
+            // Only the files are limited, while `operations` and `map` fields are restricted by
+            // the whole request size only.
+            limit = limit
+                .per_field(bytes)
+                .for_field(OPERATIONS_FIELD, u64::MAX)
+                .for_field(MAP_FIELD, u64::MAX);
+        }
+        Constraints::new().size_limit(limit)
+    }
+}
+
+/// Error of parsing a [GraphQL multipart request][0].
+///
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[derive(Debug)]
+pub enum MultipartError {
+    /// `Content-Type` of the request is not `multipart/form-data` with a boundary.
+    InvalidContentType,
+
+    /// Required field is missing, or is not in its place.
+    MissingField(&'static str),
+
+    /// `operations` or `map` field is not a valid JSON of the expected shape.
+    InvalidField {
+        /// Name of the invalid field.
+        field: &'static str,
+
+        /// Error of deserializing the field.
+        error: serde_json::Error,
+    },
+
+    /// Path in the `map` field doesn't point to a variable.
+    InvalidPath(String),
+
+    /// File mentioned in the `map` field is not present in the request.
+    MissingFile(String),
+
+    /// Request contains more files than allowed.
+    TooManyFiles {
+        /// Maximum allowed number of files.
+        limit: usize,
+    },
+
+    /// Uploaded file is larger than allowed.
+    FileTooLarge {
+        /// Name of the file field.
+        field: Option<String>,
+
+        /// Maximum allowed size in bytes.
+        limit: u64,
+    },
+
+    /// Request body is larger than allowed.
+    RequestTooLarge {
+        /// Maximum allowed size in bytes.
+        limit: u64,
+    },
+
+    /// Uploaded file cannot be stored.
+    Storage(io::Error),
+
+    /// Request body is malformed or cannot be read.
+    Body(Box<dyn Error + Send + Sync>),
+}
+
+impl MultipartError {
+    /// Indicates whether this [`MultipartError`] is caused by exceeding the size limits, so
+    /// should be reported with `413 Payload Too Large` HTTP status.
+    pub fn is_too_large(&self) -> bool {
+        matches!(
+            self,
+            Self::TooManyFiles { .. } | Self::FileTooLarge { .. } | Self::RequestTooLarge { .. },
+        )
+    }
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidContentType => write!(f, "expected `multipart/form-data` request"),
+            Self::MissingField(field) => write!(f, "missing `{field}` field"),
+            Self::InvalidField { field, error } => write!(f, "invalid `{field}` field: {error}"),
+            Self::InvalidPath(path) => write!(f, "invalid variable path in `map` field: {path}"),
+            Self::MissingFile(field) => write!(f, "missing `{field}` file"),
+            Self::TooManyFiles { limit } => write!(f, "too many files, at most {limit} allowed"),
+            Self::FileTooLarge { field, limit } => match field {
+                Some(field) => write!(f, "`{field}` file exceeds {limit} bytes"),
+                None => write!(f, "file exceeds {limit} bytes"),
+            },
+            Self::RequestTooLarge { limit } => write!(f, "request exceeds {limit} bytes"),
+            Self::Storage(e) => write!(f, "cannot store file: {e}"),
+            Self::Body(e) => write!(f, "malformed request: {e}"),
+        }
+    }
+}
+
+impl Error for MultipartError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidField { error, .. } => Some(error),
+            Self::Storage(e) => Some(e),
+            Self::Body(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<multer::Error> for MultipartError {
+    fn from(e: multer::Error) -> Self {
+        match e {
+            multer::Error::FieldSizeExceeded { limit, field_name } => Self::FileTooLarge {
+                field: field_name,
+                limit,
+            },
+            multer::Error::StreamSizeExceeded { limit } => Self::RequestTooLarge { limit },
+            multer::Error::NoMultipart | multer::Error::NoBoundary => Self::InvalidContentType,
+            e => Self::Body(e.into()),
+        }
+    }
+}
+
+/// Indicates whether the provided `content_type` is the one of a [GraphQL multipart request][0].
+///
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+pub fn is_multipart(content_type: &str) -> bool {
+    content_type.split(';').next().map_or(false, |mime| {
+        mime.trim().eq_ignore_ascii_case("multipart/form-data")
+    })
+}
+
+/// Parses a [GraphQL multipart request][0] with the provided `content_type` out of the given
+/// `body` stream, storing the uploaded files according to the provided [`MultipartOptions`].
+///
+/// The uploaded files are injected into the variables as [`Upload`]s, while their
+/// [`UploadedFile`]s are provided via the [`GraphQLRequest::extensions`] as [`Uploads`].
+///
+/// Files are written to the storage chunk by chunk as they arrive, on the blocking thread pool of
+/// the [`tokio`] runtime, so this function must be called within one.
+///
+/// # Errors
+///
+/// If the request is malformed, exceeds the limits of the [`MultipartOptions`], or an uploaded
+/// file cannot be stored.
+///
+/// [`GraphQLRequest::extensions`]: super::GraphQLRequest::extensions
+/// [0]: https://github.com/jaydenseric/graphql-multipart-request-spec
+pub async fn parse_multipart_request<'r, S, B, O, E>(
+    content_type: &str,
+    body: B,
+    options: &MultipartOptions,
+) -> Result<GraphQLBatchRequest<S>, MultipartError>
+where
+    S: ScalarValue,
+    B: Stream<Item = Result<O, E>> + Send + 'r,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn Error + Send + Sync>> + 'r,
+{
+    let boundary = multer::parse_boundary(content_type)?;
+    let mut multipart = Multipart::with_constraints(body, boundary, options.constraints());
+
+    let operations = match multipart.next_field().await? {
+        Some(field) if field.name() == Some(OPERATIONS_FIELD) => field.text().await?,
+        _ => return Err(MultipartError::MissingField(OPERATIONS_FIELD)),
+    };
+    let mut request = serde_json::from_str::<GraphQLBatchRequest<S>>(&operations).map_err(|e| {
+        MultipartError::InvalidField {
+            field: OPERATIONS_FIELD,
+            error: e,
+        }
+    })?;
+
+    let map = match multipart.next_field().await? {
+        Some(field) if field.name() == Some(MAP_FIELD) => field.text().await?,
+        _ => return Err(MultipartError::MissingField(MAP_FIELD)),
+    };
+    let mut map = serde_json::from_str::<HashMap<String, Vec<String>>>(&map).map_err(|e| {
+        MultipartError::InvalidField {
+            field: MAP_FIELD,
+            error: e,
+        }
+    })?;
+    if let Some(limit) = options.max_files {
+        if map.len() > limit {
+            return Err(MultipartError::TooManyFiles { limit });
+        }
+    }
+    for (field, paths) in &map {
+        for path in paths {
+            inject_upload(&mut request, path, field)?;
+        }
+    }
+
+    let mut uploads = HashMap::with_capacity(map.len());
+    while let Some(mut field) = multipart.next_field().await? {
+        // Fields not mentioned in the `map` are ignored.
+        let name = match field.name().and_then(|n| map.remove_entry(n)) {
+            Some((name, _)) => name,
+            None => continue,
+        };
+
+        let opts = options.clone();
+        let file = task::spawn_blocking(move || opts.temp_file())
+            .await
+            .map_err(io::Error::from)
+            .and_then(|res| res)
+            .map_err(MultipartError::Storage)?;
+        let mut writer = file
+            .as_file()
+            .try_clone()
+            .map(tokio::fs::File::from_std)
+            .map_err(MultipartError::Storage)?;
+        let mut size = 0;
+        while let Some(chunk) = field.chunk().await? {
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(MultipartError::Storage)?;
+            size += chunk.len() as u64;
+        }
+        writer.flush().await.map_err(MultipartError::Storage)?;
+
+        let upload = UploadedFile {
+            file_name: field.file_name().map(Into::into),
+            content_type: field.content_type().map(ToString::to_string),
+            size,
+            file,
+        };
+        uploads.insert(name, Arc::new(upload));
+    }
+    if let Some(field) = map.into_keys().next() {
+        return Err(MultipartError::MissingFile(field));
+    }
+
+    let uploads = Uploads(uploads);
+    match &mut request {
+        GraphQLBatchRequest::Single(req) => {
+            req.extensions.insert(uploads);
+        }
+        GraphQLBatchRequest::Batch(reqs) => {
+            for req in reqs {
+                req.extensions.insert(uploads.clone());
+            }
+        }
+    }
+    Ok(request)
+}
+
+/// Injects the [`Upload`] of the provided `field` into the variable of the given `request`
+/// pointed by the provided `path`.
+fn inject_upload<S: ScalarValue>(
+    request: &mut GraphQLBatchRequest<S>,
+    path: &str,
+    field: &str,
+) -> Result<(), MultipartError> {
+    let invalid = || MultipartError::InvalidPath(path.into());
+
+    let mut segments = path.split('.');
+    let req = match request {
+        GraphQLBatchRequest::Single(req) => req,
+        GraphQLBatchRequest::Batch(reqs) => segments
+            .next()
+            .and_then(|i| i.parse::<usize>().ok())
+            .and_then(|i| reqs.get_mut(i))
+            .ok_or_else(invalid)?,
+    };
+    if segments.next() != Some("variables") {
+        return Err(invalid());
+    }
+
+    let mut value = req.variables.as_mut().ok_or_else(invalid)?;
+    for segment in segments {
+        value = match value {
+            InputValue::Object(fields) => fields
+                .iter_mut()
+                .find(|(name, _)| name.item == segment)
+                .map(|(_, v)| &mut v.item)
+                .ok_or_else(invalid)?,
+            InputValue::List(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .map(|v| &mut v.item)
+                .ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+    }
+    *value = InputValue::scalar(field.to_owned());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{convert::Infallible, io::Read as _};
+
+    use futures::stream;
+
+    use crate::{
+        graphql_object, http::GraphQLBatchRequest, DefaultScalarValue, EmptySubscription, Executor,
+        FieldResult, RootNode, ScalarValue,
+    };
+
+    use super::{is_multipart, parse_multipart_request, MultipartError, MultipartOptions, Upload};
+
+    const BOUNDARY: &str = "------------------------boundary";
+
+    fn content_type() -> String {
+        format!("multipart/form-data; boundary={BOUNDARY}")
+    }
+
+    /// Builds a multipart body out of the provided `(name, file_name, contents)` fields, split
+    /// into small chunks.
+    fn body(fields: &[(&str, Option<&str>, &str)]) -> Vec<Result<Vec<u8>, Infallible>> {
+        let mut body = String::new();
+        for (name, file_name, contents) in fields {
+            body.push_str(&format!("--{BOUNDARY}\r\n"));
+            match file_name {
+                Some(file_name) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\n\
+                     Content-Type: text/plain\r\n\r\n",
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{name}\"\r\n\r\n",
+                )),
+            }
+            body.push_str(contents);
+            body.push_str("\r\n");
+        }
+        body.push_str(&format!("--{BOUNDARY}--\r\n"));
+        body.as_bytes().chunks(7).map(|c| Ok(c.to_vec())).collect()
+    }
+
+    async fn parse(
+        fields: &[(&str, Option<&str>, &str)],
+        options: &MultipartOptions,
+    ) -> Result<GraphQLBatchRequest<DefaultScalarValue>, MultipartError> {
+        parse_multipart_request(&content_type(), stream::iter(body(fields)), options).await
+    }
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Mutation;
+
+    #[graphql_object]
+    impl Mutation {
+        fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> FieldResult<String> {
+            let file = file
+                .file(executor.extensions())
+                .ok_or("file is not uploaded")?;
+            let mut contents = String::new();
+            file.open()?.read_to_string(&mut contents)?;
+            Ok(format!(
+                "{}:{}:{}:{contents}",
+                file.file_name().unwrap_or_default(),
+                file.content_type().unwrap_or_default(),
+                file.size(),
+            ))
+        }
+
+        fn upload_many<S: ScalarValue>(
+            executor: &Executor<(), S>,
+            files: Vec<Upload>,
+        ) -> Vec<Option<i32>> {
+            files
+                .iter()
+                .map(|f| f.file(executor.extensions()).map(|f| f.size() as i32))
+                .collect()
+        }
+    }
+
+    fn schema() -> RootNode<'static, Query, Mutation, EmptySubscription> {
+        RootNode::new(Query, Mutation, EmptySubscription::new())
+    }
+
+    #[test]
+    fn detects_multipart() {
+        assert!(is_multipart("multipart/form-data; boundary=foo"));
+        assert!(is_multipart("Multipart/Form-Data"));
+        assert!(!is_multipart("application/json"));
+    }
+
+    #[tokio::test]
+    async fn injects_single_upload() {
+        let req = parse(
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+                ("0", Some("a.txt"), "Hello, world!"),
+            ],
+            &MultipartOptions::new(),
+        )
+        .await
+        .expect("failed to parse");
+
+        let res = req.execute(&schema(), &()).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            serde_json::to_value(&res).unwrap(),
+            serde_json::json!({"data": {"upload": "a.txt:text/plain:13:Hello, world!"}}),
+        );
+    }
+
+    #[tokio::test]
+    async fn injects_batched_uploads() {
+        let req = parse(
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"[
+                        {"query": "mutation($files: [Upload!]!) { uploadMany(files: $files) }", "variables": {"files": [null, null]}},
+                        {"query": "mutation($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}
+                    ]"#,
+                ),
+                (
+                    "map",
+                    None,
+                    r#"{"a": ["0.variables.files.0", "1.variables.file"], "b": ["0.variables.files.1"]}"#,
+                ),
+                ("a", Some("a.txt"), "a"),
+                ("unknown", Some("c.txt"), "ignored"),
+                ("b", Some("b.txt"), "bb"),
+            ],
+            &MultipartOptions::new(),
+        )
+        .await
+        .expect("failed to parse");
+
+        let res = req.execute(&schema(), &()).await;
+        assert_eq!(
+            serde_json::to_value(&res).unwrap(),
+            serde_json::json!([
+                {"data": {"uploadMany": [1, 2]}},
+                {"data": {"upload": "a.txt:text/plain:1:a"}},
+            ]),
+        );
+    }
+
+    #[tokio::test]
+    async fn removes_files_once_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let req = parse(
+            &[
+                (
+                    "operations",
+                    None,
+                    r#"{"query": "mutation($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}"#,
+                ),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+                ("0", Some("a.txt"), "contents"),
+            ],
+            &MultipartOptions::new().with_temp_dir(dir.path()),
+        )
+        .await
+        .expect("failed to parse");
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        drop(req);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let operations = r#"{"query": "mutation($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}"#;
+
+        let err = parse(
+            &[("map", None, r#"{"0": ["variables.file"]}"#)],
+            &MultipartOptions::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, MultipartError::MissingField("operations")),
+            "{err}"
+        );
+
+        let err = parse(
+            &[("operations", None, operations), ("map", None, "[]")],
+            &MultipartOptions::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, MultipartError::InvalidField { field: "map", .. }),
+            "{err}",
+        );
+
+        let err = parse(
+            &[
+                ("operations", None, operations),
+                ("map", None, r#"{"0": ["variables.unknown"]}"#),
+            ],
+            &MultipartOptions::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&err, MultipartError::InvalidPath(p) if p == "variables.unknown"),
+            "{err}",
+        );
+
+        let err = parse(
+            &[
+                ("operations", None, operations),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+            ],
+            &MultipartOptions::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(&err, MultipartError::MissingFile(f) if f == "0"),
+            "{err}"
+        );
+
+        let err = parse(
+            &[
+                ("operations", None, operations),
+                ("map", None, r#"{"0": ["variables.file"]}"#),
+                ("0", Some("a.txt"), "too large"),
+            ],
+            &MultipartOptions::new().with_max_file_size(3),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.is_too_large(), "{err}");
+        assert!(
+            matches!(err, MultipartError::FileTooLarge { limit: 3, .. }),
+            "{err}"
+        );
+
+        let err = parse(
+            &[
+                ("operations", None, operations),
+                (
+                    "map",
+                    None,
+                    r#"{"0": ["variables.file"], "1": ["variables.file"]}"#,
+                ),
+            ],
+            &MultipartOptions::new().with_max_files(1),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, MultipartError::TooManyFiles { limit: 1 }),
+            "{err}"
+        );
+
+        let err = parse_multipart_request::<DefaultScalarValue, _, Vec<u8>, Infallible>(
+            "application/json",
+            stream::empty(),
+            &MultipartOptions::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidContentType), "{err}");
+    }
+}
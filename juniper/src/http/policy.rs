@@ -0,0 +1,467 @@
+//! [GraphQL-over-HTTP] request and response policy, shared by web server integrations.
+//!
+//! Covers:
+//! - negotiation of the response media type out of the `Accept` header of a request
+//!   ([`MediaType::negotiate()`]);
+//! - rejection of non-query operations over GET requests ([`check_get_request()`]);
+//! - mapping of [`GraphQLError`]s to HTTP status codes ([`error_status_code()`]).
+//!
+//! [GraphQL-over-HTTP]: https://graphql.github.io/graphql-over-http/draft
+
+use std::fmt;
+
+use crate::{
+    ast::{OperationType, OwnedDocument},
+    executor::get_operation,
+    parser::parse_document_source_with_limits,
+    GraphQLError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, RootNode, ScalarValue,
+};
+
+use super::{GraphQLBatchRequest, GraphQLBatchResponse, GraphQLRequest, GraphQLResponse};
+
+/// `application/graphql-response+json` media type.
+pub const GRAPHQL_RESPONSE_JSON: &str = "application/graphql-response+json";
+
+/// `application/json` media type.
+pub const JSON: &str = "application/json";
+
+/// Media type of a response to a [GraphQL-over-HTTP] request.
+///
+/// [GraphQL-over-HTTP]: https://graphql.github.io/graphql-over-http/draft
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MediaType {
+    /// `application/graphql-response+json` media type.
+    GraphQLResponseJson,
+
+    /// Legacy `application/json` media type.
+    Json,
+}
+
+impl MediaType {
+    /// Negotiates the [`MediaType`] of a response out of the provided `Accept` header value of a
+    /// request.
+    ///
+    /// Prefers the media range with the highest quality, and the first listed one among equal
+    /// qualities. Wildcards are treated as the legacy [`MediaType::Json`], as well as the absent
+    /// or empty `Accept` header.
+    ///
+    /// Returns [`None`] if none of the supported media types is acceptable, so the request should
+    /// be rejected with [`PolicyError::NotAcceptable`].
+    pub fn negotiate(accept: Option<&str>) -> Option<Self> {
+        let accept = match accept.map(str::trim) {
+            None | Some("") => return Some(Self::Json),
+            Some(accept) => accept,
+        };
+
+        let mut negotiated: Option<(Self, f32)> = None;
+        for range in accept.split(',') {
+            let mut parts = range.split(';');
+            let media_type = match parts.next().map(str::trim) {
+                Some(mt) if mt.eq_ignore_ascii_case(GRAPHQL_RESPONSE_JSON) => {
+                    Self::GraphQLResponseJson
+                }
+                Some(mt)
+                    if mt.eq_ignore_ascii_case(JSON)
+                        || mt.eq_ignore_ascii_case("application/*")
+                        || mt == "*/*" =>
+                {
+                    Self::Json
+                }
+                _ => continue,
+            };
+            let quality = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && negotiated.map_or(true, |(_, q)| quality > q) {
+                negotiated = Some((media_type, quality));
+            }
+        }
+        negotiated.map(|(media_type, _)| media_type)
+    }
+
+    /// Returns the `Content-Type` header value of this [`MediaType`].
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::GraphQLResponseJson => GRAPHQL_RESPONSE_JSON,
+            Self::Json => JSON,
+        }
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Rejection of a [GraphQL-over-HTTP] request before its execution.
+///
+/// [GraphQL-over-HTTP]: https://graphql.github.io/graphql-over-http/draft
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PolicyError {
+    /// None of the supported [`MediaType`]s is acceptable by the request.
+    NotAcceptable,
+
+    /// An operation other than a query (a mutation or a subscription) is requested via GET
+    /// request.
+    ///
+    /// Response should contain `Allow: POST` header.
+    NonQueryOverGet,
+}
+
+impl PolicyError {
+    /// Returns the HTTP status code to reject the request with.
+    pub fn status_code(self) -> u16 {
+        match self {
+            Self::NotAcceptable => 406,
+            Self::NonQueryOverGet => 405,
+        }
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAcceptable => write!(
+                f,
+                "Neither `{GRAPHQL_RESPONSE_JSON}` nor `{JSON}` media type is acceptable",
+            ),
+            Self::NonQueryOverGet => write!(f, "Only queries can be executed via GET request"),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+/// Checks whether the provided [`GraphQLBatchRequest`] received via GET request can be executed
+/// with the given [`RootNode`].
+///
+/// Returns the [`CheckedGetRequest`] to be executed instead of the provided `request`, so its
+/// documents are not parsed once again.
+///
+/// # Errors
+///
+/// [`PolicyError::NonQueryOverGet`] if any of the requested operations is not a query.
+pub fn check_get_request<'r, QueryT, MutationT, SubscriptionT, S>(
+    request: &'r GraphQLBatchRequest<S>,
+    root_node: &RootNode<'_, QueryT, MutationT, SubscriptionT, S>,
+) -> Result<CheckedGetRequest<'r, S>, PolicyError>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    let mut documents = Vec::new();
+    for req in requests(request) {
+        // Unparsable documents and undeterminable operations are left for execution to report.
+        let document =
+            parse_document_source_with_limits(&req.query, &root_node.schema, root_node.limits)
+                .map_err(GraphQLError::ParseError);
+        if let Ok(document) = &document {
+            let is_query = get_operation(document, req.operation_name.as_deref())
+                .map_or(true, |op| op.item.operation_type == OperationType::Query);
+            if !is_query {
+                return Err(PolicyError::NonQueryOverGet);
+            }
+        }
+        documents.push(document);
+    }
+    Ok(CheckedGetRequest { request, documents })
+}
+
+/// [`GraphQLBatchRequest`] received via GET request and checked by [`check_get_request()`].
+///
+/// Keeps the documents parsed by the check, so executes them without parsing once again.
+pub struct CheckedGetRequest<'r, S: ScalarValue> {
+    request: &'r GraphQLBatchRequest<S>,
+
+    /// Parsed documents of the requests, in the same order.
+    documents: Vec<Result<OwnedDocument<'r, S>, GraphQLError>>,
+}
+
+impl<'r, S: ScalarValue> CheckedGetRequest<'r, S> {
+    /// Executes this request synchronously with the specified schema and context.
+    ///
+    /// Same as [`GraphQLBatchRequest::execute_sync()`], except parsing.
+    pub fn execute_sync<QueryT, MutationT, SubscriptionT>(
+        self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+        context: &QueryT::Context,
+    ) -> GraphQLBatchResponse<S>
+    where
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S, Context = QueryT::Context>,
+        SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+    {
+        let request = self.request;
+        let mut resps = self.requests().map(|(req, document)| {
+            let res = document.and_then(|document| {
+                crate::execute_document_sync_with_usage(
+                    &document,
+                    req.operation_name.as_deref(),
+                    root_node,
+                    &req.variables(),
+                    context,
+                    &req.extensions,
+                )
+            });
+            GraphQLResponse::from_result_with_usage(res)
+        });
+        match request {
+            GraphQLBatchRequest::Single(_) => {
+                GraphQLBatchResponse::Single(resps.next().expect("single document"))
+            }
+            GraphQLBatchRequest::Batch(_) => GraphQLBatchResponse::Batch(resps.collect()),
+        }
+    }
+
+    /// Executes this request with the specified schema and context.
+    ///
+    /// Same as [`GraphQLBatchRequest::execute()`], except parsing.
+    pub async fn execute<'a, QueryT, MutationT, SubscriptionT>(
+        self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a QueryT::Context,
+    ) -> GraphQLBatchResponse<S>
+    where
+        'r: 'a,
+        QueryT: GraphQLTypeAsync<S>,
+        QueryT::TypeInfo: Sync,
+        QueryT::Context: Sync,
+        MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+        MutationT::TypeInfo: Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = QueryT::Context>,
+        SubscriptionT::TypeInfo: Sync,
+        S: Send + Sync,
+    {
+        let request = self.request;
+        let mut resps = self.requests().map(|(req, document)| async move {
+            let res = match document {
+                Ok(document) => {
+                    crate::execute_document_with_usage(
+                        &document,
+                        req.operation_name.as_deref(),
+                        root_node,
+                        &req.variables(),
+                        context,
+                        &req.extensions,
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            GraphQLResponse::from_result_with_usage(res)
+        });
+        match request {
+            GraphQLBatchRequest::Single(_) => {
+                GraphQLBatchResponse::Single(resps.next().expect("single document").await)
+            }
+            GraphQLBatchRequest::Batch(_) => {
+                GraphQLBatchResponse::Batch(futures::future::join_all(resps).await)
+            }
+        }
+    }
+
+    /// Returns the requests along with their parsed documents.
+    fn requests(
+        self,
+    ) -> impl Iterator<
+        Item = (
+            &'r GraphQLRequest<S>,
+            Result<OwnedDocument<'r, S>, GraphQLError>,
+        ),
+    > {
+        requests(self.request).iter().zip(self.documents)
+    }
+}
+
+/// Returns the requests of the provided [`GraphQLBatchRequest`].
+fn requests<S: ScalarValue>(request: &GraphQLBatchRequest<S>) -> &[GraphQLRequest<S>] {
+    match request {
+        GraphQLBatchRequest::Single(req) => std::slice::from_ref(req),
+        GraphQLBatchRequest::Batch(reqs) => reqs,
+    }
+}
+
+impl<'r, S: ScalarValue> fmt::Debug for CheckedGetRequest<'r, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckedGetRequest")
+            .field("request", &self.request)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the HTTP status code of a response to a request failed with the provided
+/// [`GraphQLError`], before its execution has started.
+///
+/// Document parsing or validation failures, as well as inability to determine the operation to
+/// execute or to coerce its variables, are reported as `400 Bad Request`.
+///
+/// The same code is used regardless of the [`MediaType`] of the response. For the legacy
+/// [`MediaType::Json`] the spec recommends `200 OK` instead, but `400 Bad Request` is kept
+/// deliberately, as it's what the clients of the web server integrations have always received.
+pub fn error_status_code(error: &GraphQLError) -> u16 {
+    match error {
+        GraphQLError::ParseError(_)
+        | GraphQLError::ValidationError(_)
+        | GraphQLError::NoOperationProvided
+        | GraphQLError::MultipleOperationsProvided
+        | GraphQLError::UnknownOperationName
+        | GraphQLError::IsSubscription
+        | GraphQLError::NotSubscription => 400,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        graphql_object,
+        http::{GraphQLBatchRequest, GraphQLRequest},
+        tests::fixtures::starwars::schema::{Database, Query, Subscription},
+        DefaultScalarValue, EmptyMutation, EmptySubscription, RootNode,
+    };
+
+    use super::{check_get_request, MediaType, PolicyError};
+
+    #[test]
+    fn negotiates_media_type() {
+        for (accept, expected) in [
+            (None, Some(MediaType::Json)),
+            (Some(""), Some(MediaType::Json)),
+            (Some("*/*"), Some(MediaType::Json)),
+            (Some("application/*"), Some(MediaType::Json)),
+            (Some("application/json"), Some(MediaType::Json)),
+            (
+                Some("application/graphql-response+json"),
+                Some(MediaType::GraphQLResponseJson),
+            ),
+            (
+                Some("application/graphql-response+json, application/json;q=0.9"),
+                Some(MediaType::GraphQLResponseJson),
+            ),
+            (
+                Some("application/json, application/graphql-response+json"),
+                Some(MediaType::Json),
+            ),
+            (
+                Some("application/json;q=0.5, application/graphql-response+json"),
+                Some(MediaType::GraphQLResponseJson),
+            ),
+            (Some("text/html, application/json;q=0"), None),
+            (Some("text/html"), None),
+        ] {
+            assert_eq!(MediaType::negotiate(accept), expected, "Accept: {accept:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn executes_checked_get_requests() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let db = Database::new();
+        let req = |query: &str| GraphQLRequest::<DefaultScalarValue>::new(query.into(), None, None);
+
+        for request in [
+            GraphQLBatchRequest::Single(req("{ hero { name } }")),
+            GraphQLBatchRequest::Single(req("{ hero {")),
+            GraphQLBatchRequest::Batch(vec![
+                req("{ hero { name } }"),
+                req("{ hero {"),
+                req("{ hero { unknown } }"),
+            ]),
+        ] {
+            let expected = serde_json::to_value(request.execute_sync(&schema, &db)).unwrap();
+
+            let checked = check_get_request(&request, &schema).unwrap();
+            let resp = serde_json::to_value(checked.execute_sync(&schema, &db)).unwrap();
+            assert_eq!(resp, expected, "request: {request:?}");
+
+            let checked = check_get_request(&request, &schema).unwrap();
+            let resp = serde_json::to_value(checked.execute(&schema, &db).await).unwrap();
+            assert_eq!(resp, expected, "request: {request:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_non_queries_over_get() {
+        struct Mutation;
+
+        #[graphql_object(context = Database)]
+        impl Mutation {
+            fn noop() -> bool {
+                true
+            }
+        }
+
+        let schema = RootNode::new(Query, Mutation, Subscription);
+        let req = |query: &str, op: Option<&str>| {
+            GraphQLRequest::<DefaultScalarValue>::new(query.into(), op.map(Into::into), None)
+        };
+
+        for (request, expected) in [
+            (
+                GraphQLBatchRequest::Single(req("{ hero { name } }", None)),
+                Ok(()),
+            ),
+            (
+                GraphQLBatchRequest::Single(req("mutation { noop }", None)),
+                Err(PolicyError::NonQueryOverGet),
+            ),
+            (
+                GraphQLBatchRequest::Single(req("subscription { asyncHuman { name } }", None)),
+                Err(PolicyError::NonQueryOverGet),
+            ),
+            (
+                GraphQLBatchRequest::Single(req(
+                    "query Q { hero { name } } mutation M { noop }",
+                    Some("Q"),
+                )),
+                Ok(()),
+            ),
+            (
+                GraphQLBatchRequest::Single(req(
+                    "query Q { hero { name } } mutation M { noop }",
+                    Some("M"),
+                )),
+                Err(PolicyError::NonQueryOverGet),
+            ),
+            (
+                GraphQLBatchRequest::Single(req(
+                    "query Q { hero { name } } subscription S { asyncHuman { name } }",
+                    Some("S"),
+                )),
+                Err(PolicyError::NonQueryOverGet),
+            ),
+            (
+                GraphQLBatchRequest::Batch(vec![
+                    req("{ hero { name } }", None),
+                    req("mutation { noop }", None),
+                ]),
+                Err(PolicyError::NonQueryOverGet),
+            ),
+            // Unparsable documents are left for execution to report.
+            (GraphQLBatchRequest::Single(req("mutation {", None)), Ok(())),
+        ] {
+            assert_eq!(check_get_request(&request, &schema).map(|_| ()), expected);
+        }
+
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        for query in ["mutation { noop }", "subscription { asyncHuman { name } }"] {
+            assert_eq!(
+                check_get_request(&GraphQLBatchRequest::Single(req(query, None)), &schema)
+                    .map(|_| ()),
+                Err(PolicyError::NonQueryOverGet),
+                "query: {query}",
+            );
+        }
+    }
+}
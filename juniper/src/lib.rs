@@ -30,11 +30,14 @@ pub use juniper_codegen::{
 #[macro_use]
 pub mod macros;
 mod ast;
+pub mod client;
 pub mod executor;
 mod introspection;
 pub mod parser;
+mod printer;
 pub(crate) mod schema;
 mod types;
+pub mod usage;
 mod util;
 pub mod validation;
 mod value;
@@ -55,28 +58,43 @@ mod executor_tests;
 pub use crate::util::to_camel_case;
 
 use crate::{
-    executor::{execute_validated_query, get_operation},
+    executor::{execute_validated_query, get_operation, NO_EXTENSIONS},
     introspection::{INTROSPECTION_QUERY, INTROSPECTION_QUERY_WITHOUT_DESCRIPTIONS},
-    parser::parse_document_source,
-    validation::{validate_input_values, visit_all_rules, ValidatorContext},
+    parser::parse_document_source_with_limits,
+    usage::OperationUsage,
+    validation::{
+        validate_input_values, visit_all_rules_with, visit_introspection_rules, visit_limit_rules,
+        ValidatorContext,
+    },
 };
 
 pub use crate::{
     ast::{
-        Definition, Document, FromInputValue, InputValue, Operation, OperationType, Selection,
-        ToInputValue, Type,
+        Definition, Directive, DirectiveDefinition, Document, EnumTypeDefinition,
+        EnumValueDefinition, Field, FieldDefinition, Fragment, FragmentSpread, FromInputValue,
+        InlineFragment, InputObjectTypeDefinition, InputValue, InputValueDefinition,
+        InterfaceTypeDefinition, ObjectTypeDefinition, Operation, OperationType,
+        RootOperationTypeDefinition, ScalarTypeDefinition, SchemaDefinition, Selection,
+        ToInputValue, Type, TypeDefinition, UnionTypeDefinition, VariableDefinition,
     },
     executor::{
-        Applies, Context, ExecutionError, ExecutionResult, Executor, FieldError, FieldResult,
-        FromContext, IntoFieldError, IntoResolvable, LookAheadArgument, LookAheadMethods,
-        LookAheadSelection, LookAheadValue, OwnedExecutor, Registry, ValuesStream, Variables,
+        Applies, Context, ExecutionError, ExecutionResult, Executor, Extensions, FieldError,
+        FieldResult, FromContext, IntoFieldError, IntoResolvable, InvalidationKeys,
+        LookAheadArgument, LookAheadDirective, LookAheadMethods, LookAheadSelection,
+        LookAheadValue, OwnedExecutor, Registry, ValuesStream, Variables, LIVE_DIRECTIVE,
     },
     introspection::IntrospectionFormat,
     macros::helper::subscription::{ExtractTypeFromStream, IntoFieldResult},
-    parser::{ParseError, ScalarToken, Spanning},
+    parser::{
+        parse_type_system_document_source, DocumentLimit, DocumentLimits, ParseError, ScalarToken,
+        Spanning, StringLiteral,
+    },
+    printer::{normalize_document, operation_signature, print_document, print_document_minified},
     schema::{
+        build::SchemaBuildError,
         meta,
         model::{RootNode, SchemaType},
+        validation::SchemaError,
     },
     types::{
         async_await::{GraphQLTypeAsync, GraphQLValueAsync},
@@ -139,6 +157,11 @@ impl std::error::Error for GraphQLError {
     }
 }
 
+/// Output of executing an operation along with its collected
+/// [`OperationUsage`].
+pub(crate) type ExecutionOutputWithUsage<S> =
+    ((Value<S>, Vec<ExecutionError<S>>), Option<OperationUsage>);
+
 /// Execute a query synchronously in a provided schema
 pub fn execute_sync<'a, S, QueryT, MutationT, SubscriptionT>(
     document_source: &'a str,
@@ -153,19 +176,67 @@ where
     MutationT: GraphQLType<S, Context = QueryT::Context>,
     SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
+    execute_sync_with_usage(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &NO_EXTENSIONS,
+    )
+    .map(|(res, _)| res)
+}
 
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+/// Execute a query synchronously in a provided schema, additionally returning
+/// its [`OperationUsage`] if [deprecation warnings][0] are enabled.
+///
+/// [0]: RootNode::with_deprecation_warnings
+pub(crate) fn execute_sync_with_usage<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions,
+) -> Result<ExecutionOutputWithUsage<S>, GraphQLError>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+{
+    let document =
+        parse_document_source_with_limits(document_source, &root_node.schema, root_node.limits)?;
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    execute_document_sync_with_usage(
+        &document,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        extensions,
+    )
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Same as [`execute_sync_with_usage()`], but executes the already parsed
+/// `document`.
+pub(crate) fn execute_document_sync_with_usage<S, QueryT, MutationT, SubscriptionT>(
+    document: &Document<S>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions,
+) -> Result<ExecutionOutputWithUsage<S>, GraphQLError>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+{
+    validate_document(document, root_node, context)?;
+
+    let operation = get_operation(document, operation_name)?;
 
     {
         let errors = validate_input_values(variables, operation, &root_node.schema);
@@ -175,7 +246,12 @@ where
         }
     }
 
-    execute_validated_query(&document, operation, root_node, variables, context)
+    let usage = root_node.collect_usage(document, operation, variables, context);
+
+    execute_validated_query(
+        document, operation, root_node, variables, context, extensions,
+    )
+    .map(|res| (res, usage))
 }
 
 /// Execute a query in a provided schema
@@ -196,19 +272,77 @@ where
     SubscriptionT::TypeInfo: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
+    execute_with_usage(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &NO_EXTENSIONS,
+    )
+    .await
+    .map(|(res, _)| res)
+}
 
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+/// Execute a query in a provided schema, additionally returning its
+/// [`OperationUsage`] if [deprecation warnings][0] are enabled.
+///
+/// [0]: RootNode::with_deprecation_warnings
+pub(crate) async fn execute_with_usage<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions,
+) -> Result<ExecutionOutputWithUsage<S>, GraphQLError>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    let document =
+        parse_document_source_with_limits(document_source, &root_node.schema, root_node.limits)?;
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    execute_document_with_usage(
+        &document,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        extensions,
+    )
+    .await
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Same as [`execute_with_usage()`], but executes the already parsed
+/// `document`.
+pub(crate) async fn execute_document_with_usage<'a, S, QueryT, MutationT, SubscriptionT>(
+    document: &Document<'a, S>,
+    operation_name: Option<&str>,
+    root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &QueryT::Context,
+    extensions: &Extensions,
+) -> Result<ExecutionOutputWithUsage<S>, GraphQLError>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context> + Sync,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    validate_document(document, root_node, context)?;
+
+    let operation = get_operation(document, operation_name)?;
 
     {
         let errors = validate_input_values(variables, operation, &root_node.schema);
@@ -218,8 +352,13 @@ where
         }
     }
 
-    executor::execute_validated_query_async(&document, operation, root_node, variables, context)
-        .await
+    let usage = root_node.collect_usage(document, operation, variables, context);
+
+    executor::execute_validated_query_async(
+        document, operation, root_node, variables, context, extensions,
+    )
+    .await
+    .map(|res| (res, usage))
 }
 
 /// Resolve subscription into `ValuesStream`
@@ -230,6 +369,37 @@ pub async fn resolve_into_stream<'a, S, QueryT, MutationT, SubscriptionT>(
     variables: &Variables<S>,
     context: &'a QueryT::Context,
 ) -> Result<(Value<ValuesStream<'a, S>>, Vec<ExecutionError<S>>), GraphQLError>
+where
+    QueryT: GraphQLTypeAsync<S>,
+    QueryT::TypeInfo: Sync,
+    QueryT::Context: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = QueryT::Context>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = QueryT::Context>,
+    SubscriptionT::TypeInfo: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    resolve_into_stream_with_extensions(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+        &NO_EXTENSIONS,
+    )
+    .await
+}
+
+/// Resolve subscription into `ValuesStream` with the provided [`Extensions`]
+/// carried into it.
+pub(crate) async fn resolve_into_stream_with_extensions<'a, S, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &'a QueryT::Context,
+    extensions: &'a Extensions,
+) -> Result<(Value<ValuesStream<'a, S>>, Vec<ExecutionError<S>>), GraphQLError>
 where
     QueryT: GraphQLTypeAsync<S>,
     QueryT::TypeInfo: Sync,
@@ -241,30 +411,56 @@ where
     S: ScalarValue + Send + Sync,
 {
     let document: crate::ast::OwnedDocument<'a, S> =
-        parse_document_source(document_source, &root_node.schema)?;
+        parse_document_source_with_limits(document_source, &root_node.schema, root_node.limits)?;
+
+    validate_document(&document, root_node, context)?;
+
+    let operation = get_operation(&document, operation_name)?;
 
     {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+        let errors = validate_input_values(variables, operation, &root_node.schema);
 
-        let errors = ctx.into_errors();
         if !errors.is_empty() {
             return Err(GraphQLError::ValidationError(errors));
         }
     }
 
-    let operation = get_operation(&document, operation_name)?;
+    root_node.collect_usage(&document, operation, variables, context);
 
-    {
-        let errors = validate_input_values(variables, operation, &root_node.schema);
+    executor::resolve_validated_subscription(
+        &document, operation, root_node, variables, context, extensions,
+    )
+    .await
+}
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
+/// Validates the provided `document` against the [`RootNode`] it's going to be
+/// executed with, respecting its [`DocumentLimits`], introspection policy and
+/// additional validation rules.
+fn validate_document<S, QueryT, MutationT, SubscriptionT>(
+    document: &Document<S>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    context: &QueryT::Context,
+) -> Result<(), GraphQLError>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+{
+    let mut ctx = ValidatorContext::new(&root_node.schema, document);
+    visit_limit_rules(&mut ctx, document, root_node.limits);
+    if !ctx.has_errors() {
+        if !root_node.is_introspection_allowed(context) {
+            visit_introspection_rules(&mut ctx, document);
         }
+        visit_all_rules_with(&mut ctx, document, &root_node.validation_rules);
     }
 
-    executor::resolve_validated_subscription(&document, operation, root_node, variables, context)
-        .await
+    let errors = ctx.into_errors();
+    if !errors.is_empty() {
+        return Err(GraphQLError::ValidationError(errors));
+    }
+    Ok(())
 }
 
 /// Execute the reference introspection query in the provided schema
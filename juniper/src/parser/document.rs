@@ -8,8 +8,10 @@ use crate::ast::{
 
 use crate::{
     parser::{
-        value::parse_value_literal, Lexer, OptionParseResult, ParseError, ParseResult, Parser,
-        Spanning, Token, UnlocatedParseResult,
+        type_system::{is_type_system_definition_start, parse_type_system_definition},
+        value::parse_value_literal,
+        DocumentLimits, Lexer, OptionParseResult, ParseError, ParseResult, Parser, Spanning, Token,
+        UnlocatedParseResult,
     },
     schema::{
         meta::{Argument, Field as MetaField},
@@ -31,6 +33,54 @@ where
     parse_document(&mut parser, schema)
 }
 
+/// Parses the provided source as an executable GraphQL document, failing as soon
+/// as any of the provided [`DocumentLimits`] is exceeded.
+#[doc(hidden)]
+pub fn parse_document_source_with_limits<'a, 'b, S>(
+    s: &'a str,
+    schema: &'b SchemaType<'b, S>,
+    limits: DocumentLimits,
+) -> UnlocatedParseResult<OwnedDocument<'a, S>>
+where
+    S: ScalarValue,
+{
+    let mut lexer = Lexer::new(s);
+    let mut parser = Parser::new_with_limits(&mut lexer, limits)?;
+    parse_document(&mut parser, schema)
+}
+
+/// Parses the provided source as an executable GraphQL document, recovering from
+/// syntax errors instead of stopping at the first one.
+///
+/// Broken definitions and selections are skipped up to the next definition or
+/// selection boundary, so the returned partial [`OwnedDocument`] contains only
+/// the successfully parsed parts, while all the encountered errors are returned
+/// along.
+///
+/// Tokenization cannot be recovered from, so the source is parsed only up to the
+/// first [`LexerError`].
+///
+/// [`LexerError`]: crate::parser::LexerError
+pub fn parse_document_source_recovering<'a, 'b, S>(
+    s: &'a str,
+    schema: &'b SchemaType<'b, S>,
+) -> (OwnedDocument<'a, S>, Vec<Spanning<ParseError>>)
+where
+    S: ScalarValue,
+{
+    let mut lexer = Lexer::new(s);
+    let mut parser = Parser::new_recovering(&mut lexer);
+    // Recovering parser collects errors instead of returning them, but let's
+    // not panic just in case.
+    let doc = parse_document(&mut parser, schema);
+    let mut errors = parser.into_errors();
+    let doc = doc.unwrap_or_else(|e| {
+        errors.push(e);
+        Vec::new()
+    });
+    (doc, errors)
+}
+
 fn parse_document<'a, 'b, S>(
     parser: &mut Parser<'a>,
     schema: &'b SchemaType<'b, S>,
@@ -41,7 +91,11 @@ where
     let mut defs = Vec::new();
 
     loop {
-        defs.push(parse_definition(parser, schema)?);
+        let checkpoint = parser.checkpoint();
+        match parse_definition(parser, schema) {
+            Ok(def) => defs.push(def),
+            Err(e) => parser.recover(e, checkpoint, None, is_definition_boundary)?,
+        }
 
         if parser.peek().item == Token::EndOfFile {
             return Ok(defs);
@@ -49,6 +103,25 @@ where
     }
 }
 
+/// Checks whether the `current` [`Token`] (following the `previous` one and
+/// followed by the `next` one) starts a new definition.
+fn is_definition_boundary(previous: &Token, current: &Token, next: &Token) -> bool {
+    match current {
+        Token::Name("query" | "mutation" | "subscription" | "fragment") => true,
+        // Type system keywords are common field and type names, so they're
+        // recognizable only right after the end of the previous block, and only
+        // if followed by what a type system definition continues with. Strings
+        // are never considered, as may be anything (descriptions, arguments).
+        Token::Name(_) if is_type_system_definition_start(current) => {
+            *previous == Token::CurlyClose && matches!(next, Token::Name(_) | Token::CurlyOpen)
+        }
+        // Anonymous operation is recognizable only right after the end of the
+        // previous block, as otherwise the braces may belong to the skipped one.
+        Token::CurlyOpen => *previous == Token::CurlyClose,
+        _ => false,
+    }
+}
+
 fn parse_definition<'a, 'b, S>(
     parser: &mut Parser<'a>,
     schema: &'b SchemaType<'b, S>,
@@ -66,6 +139,9 @@ where
         Token::Name("fragment") => Ok(Definition::Fragment(parse_fragment_definition(
             parser, schema,
         )?)),
+        // Type system definitions are not executable, but are parsed anyway to be
+        // reported by validation in a more meaningful way.
+        ref t if is_type_system_definition_start(t) => parse_type_system_definition(parser),
         _ => Err(parser.next_token()?.map(ParseError::unexpected_token)),
     }
 }
@@ -193,11 +269,36 @@ fn parse_selection_set<'a, 'b, S>(
 where
     S: ScalarValue,
 {
-    parser.unlocated_delimited_nonempty_list(
-        &Token::CurlyOpen,
-        |p| parse_selection(p, schema, fields),
-        &Token::CurlyClose,
-    )
+    parser.nested(|parser| {
+        parser
+            .unlocated_delimited_nonempty_list(
+                &Token::CurlyOpen,
+                |p| {
+                    let checkpoint = p.checkpoint();
+                    match parse_selection(p, schema, fields) {
+                        Ok(sel) => Ok(Some(sel)),
+                        Err(e) => p
+                            .recover(
+                                e,
+                                checkpoint,
+                                Some(&Token::CurlyClose),
+                                is_selection_boundary,
+                            )
+                            .map(|()| None),
+                    }
+                },
+                &Token::CurlyClose,
+            )
+            .map(|s| s.map(|sels| sels.into_iter().flatten().collect()))
+    })
+}
+
+/// Checks whether the `current` [`Token`] (following the `previous` one) starts a
+/// new selection.
+fn is_selection_boundary(previous: &Token, current: &Token, _: &Token) -> bool {
+    // Names following `...` or `:` are parts of fragments and aliased fields.
+    matches!(current, Token::Name(_) | Token::Ellipsis)
+        && !matches!(previous, Token::Ellipsis | Token::Colon)
 }
 
 fn parse_selection<'a, 'b, S>(
@@ -311,6 +412,9 @@ where
     } else {
         alias.take().unwrap()
     };
+    if let Some(alias) = &alias {
+        parser.count_alias(alias)?;
+    }
 
     let field = fields.and_then(|f| f.iter().find(|f| f.name == name.item));
     let args = field
@@ -386,7 +490,7 @@ where
         .and_then(|arg| schema.lookup_type(&arg.arg_type));
 
     parser.expect(&Token::Colon)?;
-    let value = parse_value_literal(parser, false, schema, tpe)?;
+    let value = parse_value_literal(parser, false, Some(schema), tpe)?;
 
     Ok(Spanning::start_end(
         &name.start.clone(),
@@ -446,7 +550,7 @@ where
     let tpe = schema.lookup_type(&var_type.item);
 
     let default_value = if parser.skip(&Token::Equals)?.is_some() {
-        Some(parse_value_literal(parser, true, schema, tpe)?)
+        Some(parse_value_literal(parser, true, Some(schema), tpe)?)
     } else {
         None
     };
@@ -482,7 +586,9 @@ where
     } else {
         let mut items = Vec::new();
         while parser.peek().item == Token::At {
-            items.push(parse_directive(parser, schema)?);
+            let directive = parse_directive(parser, schema)?;
+            parser.check_directives_count(items.len() + 1, &directive)?;
+            items.push(directive);
         }
 
         Ok(Spanning::spanning(items))
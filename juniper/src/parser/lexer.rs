@@ -1,11 +1,12 @@
 use std::{
+    borrow::Cow,
     char, fmt,
     iter::{Iterator, Peekable},
     result::Result,
-    str::CharIndices,
+    str::{CharIndices, Chars},
 };
 
-use crate::parser::{SourcePosition, Spanning};
+use crate::parser::{ParseError, SourcePosition, Spanning};
 
 #[doc(hidden)]
 #[derive(Debug)]
@@ -23,11 +24,183 @@ pub struct Lexer<'a> {
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ScalarToken<'a> {
-    String(&'a str),
+    String(StringLiteral<'a>),
     Float(&'a str),
     Int(&'a str),
 }
 
+/// Representation of a [String literal][0] as it appears in the input source.
+///
+/// Holds the raw source contents between the quotes, so the escape sequences
+/// (and the indentation of a block string) are still there. Use
+/// [`StringLiteral::parse()`] to get the actual [String value][1].
+///
+/// [0]: https://spec.graphql.org/October2021#StringValue
+/// [1]: https://spec.graphql.org/October2021#sec-String-Value.Semantics
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StringLiteral<'a> {
+    /// [Quoted][0] string literal, like `"foo\nbar"`.
+    ///
+    /// [0]: https://spec.graphql.org/October2021#StringCharacter
+    Quoted(&'a str),
+
+    /// [Block][0] string literal, like `"""foo"""`.
+    ///
+    /// [0]: https://spec.graphql.org/October2021#BlockStringCharacter
+    Block(&'a str),
+}
+
+impl<'a> StringLiteral<'a> {
+    /// Parses this [`StringLiteral`] into its [String value][0], resolving all
+    /// the escape sequences and, for a block string, stripping its common
+    /// indentation and leading/trailing blank lines.
+    ///
+    /// # Errors
+    ///
+    /// If this [`StringLiteral`] contains an invalid escape sequence. Literals
+    /// produced by the [`Lexer`] are already validated, so this may happen
+    /// only for manually constructed ones.
+    ///
+    /// [0]: https://spec.graphql.org/October2021#sec-String-Value.Semantics
+    pub fn parse(self) -> Result<Cow<'a, str>, ParseError> {
+        match self {
+            Self::Quoted(lit) => {
+                if !lit.contains('\\') {
+                    return Ok(lit.into());
+                }
+
+                let mut unescaped = String::with_capacity(lit.len());
+                let mut char_iter = lit.chars();
+                while let Some(ch) = char_iter.next() {
+                    match ch {
+                        '\\' => match char_iter.next() {
+                            Some('"') => unescaped.push('"'),
+                            Some('/') => unescaped.push('/'),
+                            Some('n') => unescaped.push('\n'),
+                            Some('r') => unescaped.push('\r'),
+                            Some('t') => unescaped.push('\t'),
+                            Some('\\') => unescaped.push('\\'),
+                            Some('f') => unescaped.push('\u{000c}'),
+                            Some('b') => unescaped.push('\u{0008}'),
+                            Some('u') => unescaped.push(parse_unicode_codepoint(&mut char_iter)?),
+                            Some(s) => {
+                                return Err(ParseError::LexerError(
+                                    LexerError::UnknownEscapeSequence(format!("\\{s}")),
+                                ))
+                            }
+                            None => {
+                                return Err(ParseError::LexerError(LexerError::UnterminatedString))
+                            }
+                        },
+                        ch => unescaped.push(ch),
+                    }
+                }
+                Ok(unescaped.into())
+            }
+            Self::Block(lit) => Ok(block_string_value(&lit.replace("\\\"\"\"", "\"\"\"")).into()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for StringLiteral<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Quoted(lit) => {
+                write!(f, "\"{}\"", lit.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Self::Block(lit) => write!(f, "\"\"\"{lit}\"\"\""),
+        }
+    }
+}
+
+/// Parses the rest of a Unicode escape sequence, the `\u` part of which has
+/// been consumed already, from the provided `char_iter`.
+fn parse_unicode_codepoint(char_iter: &mut Chars<'_>) -> Result<char, ParseError> {
+    let unknown_escape =
+        |s: &str| ParseError::LexerError(LexerError::UnknownEscapeSequence(format!("\\u{s}")));
+
+    let rest = char_iter.as_str();
+    let (escape, code_point) = if let Some(rest) = rest.strip_prefix('{') {
+        let end = rest.find('}').ok_or_else(|| unknown_escape(rest))?;
+        let escape = &char_iter.as_str()[..end + 2];
+        let code_point = Some(&rest[..end])
+            .filter(|e| !e.is_empty() && e.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|e| u32::from_str_radix(e, 16).ok())
+            .ok_or_else(|| unknown_escape(escape))?;
+        (escape, code_point)
+    } else {
+        let escape = &rest[..rest.char_indices().nth(4).map_or(rest.len(), |(i, _)| i)];
+        let code_point = Some(escape)
+            .filter(|e| e.len() == 4 && e.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|e| u32::from_str_radix(e, 16).ok())
+            .ok_or_else(|| unknown_escape(escape))?;
+        (escape, code_point)
+    };
+    for _ in escape.chars() {
+        char_iter.next();
+    }
+
+    if !escape.starts_with('{') && is_leading_surrogate(code_point) {
+        let trailing = char_iter
+            .as_str()
+            .strip_prefix("\\u")
+            .and_then(|r| r.get(..4))
+            .filter(|r| r.chars().all(|c| c.is_ascii_hexdigit()))
+            .and_then(|r| u32::from_str_radix(r, 16).ok())
+            .filter(|&c| is_trailing_surrogate(c))
+            .ok_or_else(|| unknown_escape(escape))?;
+        for _ in 0..6 {
+            char_iter.next();
+        }
+        let code_point = 0x10000 + ((code_point - 0xD800) << 10) + (trailing - 0xDC00);
+        return char::from_u32(code_point).ok_or_else(|| unknown_escape(escape));
+    }
+
+    char::from_u32(code_point).ok_or_else(|| unknown_escape(escape))
+}
+
+/// Computes the [String value][0] of the provided raw block string contents
+/// (with `\"""` escapes already replaced).
+///
+/// [0]: https://spec.graphql.org/October2021#BlockStringValue()
+fn block_string_value(raw: &str) -> String {
+    let lines = raw
+        .split("\r\n")
+        .flat_map(|l| l.split(['\n', '\r']))
+        .collect::<Vec<_>>();
+
+    let is_whitespace = |c: char| c == ' ' || c == '\t';
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|l| {
+            let indent = l.len() - l.trim_start_matches(is_whitespace).len();
+            (indent < l.len()).then_some(indent)
+        })
+        .min();
+
+    let mut lines = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, l)| match common_indent {
+            Some(indent) if i > 0 => l.get(indent..).unwrap_or_default(),
+            _ => l,
+        })
+        .collect::<Vec<_>>();
+
+    let is_blank = |l: &&str| l.chars().all(is_whitespace);
+    let first = lines.iter().position(|l| !is_blank(l));
+    let last = lines.iter().rposition(|l| !is_blank(l));
+    match (first, last) {
+        (Some(first), Some(last)) => {
+            lines.truncate(last + 1);
+            lines.drain(..first);
+            lines.join("\n")
+        }
+        _ => String::new(),
+    }
+}
+
 /// A single token in the input source
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -47,6 +220,7 @@ pub enum Token<'a> {
     Equals,
     At,
     Pipe,
+    Amp,
     EndOfFile,
 }
 
@@ -122,7 +296,8 @@ impl<'a> Lexer<'a> {
         let next = self.iterator.next();
 
         if let Some((_, ch)) = next {
-            if ch == '\n' {
+            // `\r\n`, `\n` and `\r` each terminate a line.
+            if ch == '\n' || (ch == '\r' && self.iterator.peek().map(|&(_, c)| c) != Some('\n')) {
                 self.position.advance_line();
             } else {
                 self.position.advance_col();
@@ -247,7 +422,9 @@ impl<'a> Lexer<'a> {
                     return Ok(Spanning::start_end(
                         &start_pos,
                         &self.position,
-                        Token::Scalar(ScalarToken::String(&self.source[start_idx + 1..idx])),
+                        Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                            &self.source[start_idx + 1..idx],
+                        ))),
                     ));
                 }
                 '\n' | '\r' => {
@@ -273,6 +450,54 @@ impl<'a> Lexer<'a> {
         ))
     }
 
+    fn scan_block_string(&mut self) -> LexerResult<'a> {
+        let start_pos = self.position;
+        let (start_idx, _) = self
+            .peek_char()
+            .ok_or_else(|| Spanning::zero_width(&self.position, LexerError::UnexpectedEndOfFile))?;
+        for _ in 0..3 {
+            self.next_char();
+        }
+
+        while let Some((idx, ch)) = self.peek_char() {
+            let rest = &self.source[idx..];
+            if rest.starts_with("\"\"\"") {
+                for _ in 0..3 {
+                    self.next_char();
+                }
+                return Ok(Spanning::start_end(
+                    &start_pos,
+                    &self.position,
+                    Token::Scalar(ScalarToken::String(StringLiteral::Block(
+                        &self.source[start_idx + 3..idx],
+                    ))),
+                ));
+            } else if rest.starts_with("\\\"\"\"") {
+                for _ in 0..4 {
+                    self.next_char();
+                }
+            } else if !is_source_char(ch) {
+                return Err(Spanning::zero_width(
+                    &self.position,
+                    LexerError::UnknownCharacterInString(ch),
+                ));
+            } else {
+                self.next_char();
+            }
+        }
+
+        Err(Spanning::zero_width(
+            &self.position,
+            LexerError::UnterminatedString,
+        ))
+    }
+
+    /// Scans over a Unicode escape sequence, the `\u` part of which has been
+    /// consumed already.
+    ///
+    /// Both fixed-width (`\u0041`) and variable-width (`\u{1F600}`) forms
+    /// are supported. A fixed-width leading surrogate must be immediately
+    /// followed by a fixed-width trailing surrogate, forming a surrogate pair.
     fn scan_escaped_unicode(
         &mut self,
         start_pos: &SourcePosition,
@@ -280,47 +505,74 @@ impl<'a> Lexer<'a> {
         let (start_idx, _) = self
             .peek_char()
             .ok_or_else(|| Spanning::zero_width(&self.position, LexerError::UnterminatedString))?;
-        let mut end_idx = start_idx;
-        let mut len = 0;
 
-        for _ in 0..4 {
-            let (idx, ch) = self.next_char().ok_or_else(|| {
+        let mut len = 0;
+        let mut is_variable_width = false;
+        let end_idx = loop {
+            let (idx, ch) = self.peek_char().ok_or_else(|| {
                 Spanning::zero_width(&self.position, LexerError::UnterminatedString)
             })?;
 
-            if !ch.is_alphanumeric() {
-                break;
+            if len == 0 && ch == '{' {
+                is_variable_width = true;
+            } else if is_variable_width && ch == '}' {
+                self.next_char();
+                break idx + 1;
+            } else if (is_variable_width && !ch.is_ascii_hexdigit()) || !ch.is_alphanumeric() {
+                break idx;
             }
-
-            end_idx = idx;
+            self.next_char();
             len += 1;
-        }
-
-        // Make sure we are on a valid char boundary.
-        let escape = self
-            .source
-            .get(start_idx..=end_idx)
-            .ok_or_else(|| Spanning::zero_width(&self.position, LexerError::UnterminatedString))?;
 
-        if len != 4 {
-            return Err(Spanning::zero_width(
-                start_pos,
-                LexerError::UnknownEscapeSequence(format!("\\u{escape}")),
-            ));
+            if !is_variable_width && len == 4 {
+                break idx + ch.len_utf8();
+            }
+        };
+        let escape = &self.source[start_idx..end_idx];
+
+        let code_point = if is_variable_width {
+            escape
+                .strip_prefix('{')
+                .and_then(|e| e.strip_suffix('}'))
+                .filter(|e| !e.is_empty())
+                .and_then(|e| u32::from_str_radix(e, 16).ok())
+        } else if len == 4 {
+            u32::from_str_radix(escape, 16).ok()
+        } else {
+            None
         }
-
-        let code_point = u32::from_str_radix(escape, 16).map_err(|_| {
+        .ok_or_else(|| {
             Spanning::zero_width(
                 start_pos,
                 LexerError::UnknownEscapeSequence(format!("\\u{escape}")),
             )
         })?;
 
+        if !is_variable_width && is_leading_surrogate(code_point) {
+            let rest = &self.source[end_idx..];
+            let trailing = rest
+                .strip_prefix("\\u")
+                .and_then(|r| r.get(..4))
+                .filter(|r| r.chars().all(|c| c.is_ascii_hexdigit()))
+                .and_then(|r| u32::from_str_radix(r, 16).ok())
+                .filter(|&c| is_trailing_surrogate(c));
+            if trailing.is_none() {
+                return Err(Spanning::zero_width(
+                    start_pos,
+                    LexerError::UnknownEscapeSequence(format!("\\u{escape}")),
+                ));
+            }
+            for _ in 0..6 {
+                self.next_char();
+            }
+            return Ok(());
+        }
+
         char::from_u32(code_point)
             .ok_or_else(|| {
                 Spanning::zero_width(
                     start_pos,
-                    LexerError::UnknownEscapeSequence("\\u".to_owned() + escape),
+                    LexerError::UnknownEscapeSequence(format!("\\u{escape}")),
                 )
             })
             .map(|_| ())
@@ -445,7 +697,10 @@ impl<'a> Iterator for Lexer<'a> {
 
         self.scan_over_whitespace();
 
-        let ch = self.iterator.peek().map(|&(_, ch)| ch);
+        let (idx, ch) = match self.iterator.peek() {
+            Some(&(idx, ch)) => (idx, Some(ch)),
+            None => (self.length, None),
+        };
 
         Some(match ch {
             Some('!') => Ok(self.emit_single_char(Token::ExclamationMark)),
@@ -460,7 +715,9 @@ impl<'a> Iterator for Lexer<'a> {
             Some('=') => Ok(self.emit_single_char(Token::Equals)),
             Some('@') => Ok(self.emit_single_char(Token::At)),
             Some('|') => Ok(self.emit_single_char(Token::Pipe)),
+            Some('&') => Ok(self.emit_single_char(Token::Amp)),
             Some('.') => self.scan_ellipsis(),
+            Some('"') if self.source[idx..].starts_with("\"\"\"") => self.scan_block_string(),
             Some('"') => self.scan_string(),
             Some(ch) => {
                 if is_number_start(ch) {
@@ -489,9 +746,7 @@ impl<'a> fmt::Display for Token<'a> {
             Token::Scalar(ScalarToken::Int(s)) | Token::Scalar(ScalarToken::Float(s)) => {
                 write!(f, "{s}")
             }
-            Token::Scalar(ScalarToken::String(s)) => {
-                write!(f, "\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
-            }
+            Token::Scalar(ScalarToken::String(lit)) => write!(f, "{lit}"),
             Token::ExclamationMark => write!(f, "!"),
             Token::Dollar => write!(f, "$"),
             Token::ParenOpen => write!(f, "("),
@@ -505,6 +760,7 @@ impl<'a> fmt::Display for Token<'a> {
             Token::Equals => write!(f, "="),
             Token::At => write!(f, "@"),
             Token::Pipe => write!(f, "|"),
+            Token::Amp => write!(f, "&"),
             Token::EndOfFile => write!(f, "End of file"),
         }
     }
@@ -526,6 +782,14 @@ fn is_number_start(c: char) -> bool {
     c == '-' || ('0'..='9').contains(&c)
 }
 
+fn is_leading_surrogate(c: u32) -> bool {
+    (0xD800..=0xDBFF).contains(&c)
+}
+
+fn is_trailing_surrogate(c: u32) -> bool {
+    (0xDC00..=0xDFFF).contains(&c)
+}
+
 impl fmt::Display for LexerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Limits of a GraphQL document, protecting from abusively large or deeply
+/// nested queries.
+///
+/// Every limit is disabled (`None`) by default. Exceeding any of them fails the
+/// document as early as possible: tokens, nesting, aliases and directives are
+/// checked while lexing and parsing, while root fields are checked during
+/// validation (as fragments should be resolved to count them).
+///
+/// ```rust
+/// # use juniper::DocumentLimits;
+/// let limits = DocumentLimits {
+///     max_tokens: Some(10_000),
+///     max_depth: Some(15),
+///     ..DocumentLimits::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DocumentLimits {
+    /// Maximum number of tokens in the whole document.
+    pub max_tokens: Option<usize>,
+
+    /// Maximum nesting depth of selection sets.
+    pub max_depth: Option<usize>,
+
+    /// Maximum number of aliased fields in the whole document.
+    pub max_aliases: Option<usize>,
+
+    /// Maximum number of directives applied to a single location (field,
+    /// fragment, operation, etc).
+    pub max_directives_per_location: Option<usize>,
+
+    /// Maximum number of fields selected on the root type of an operation,
+    /// including the ones selected via fragments.
+    pub max_root_fields: Option<usize>,
+}
+
+/// Kind of a [`DocumentLimits`] being exceeded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DocumentLimit {
+    /// [`DocumentLimits::max_tokens`] is exceeded.
+    Tokens,
+
+    /// [`DocumentLimits::max_depth`] is exceeded.
+    Depth,
+
+    /// [`DocumentLimits::max_aliases`] is exceeded.
+    Aliases,
+
+    /// [`DocumentLimits::max_directives_per_location`] is exceeded.
+    DirectivesPerLocation,
+
+    /// [`DocumentLimits::max_root_fields`] is exceeded.
+    RootFields,
+}
+
+impl fmt::Display for DocumentLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Tokens => "tokens",
+            Self::Depth => "selection nesting levels",
+            Self::Aliases => "aliases",
+            Self::DirectivesPerLocation => "directives per location",
+            Self::RootFields => "root fields",
+        })
+    }
+}
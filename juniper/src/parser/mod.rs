@@ -3,17 +3,27 @@
 
 mod document;
 mod lexer;
+mod limits;
 mod parser;
+mod type_system;
 mod utils;
 mod value;
 
 #[cfg(test)]
 mod tests;
 
-pub use self::document::parse_document_source;
+pub use self::{
+    document::{
+        parse_document_source, parse_document_source_recovering, parse_document_source_with_limits,
+    },
+    type_system::parse_type_system_document_source,
+};
+
+pub(crate) use self::value::parse_value_literal;
 
 pub use self::{
-    lexer::{Lexer, LexerError, ScalarToken, Token},
+    lexer::{Lexer, LexerError, ScalarToken, StringLiteral, Token},
+    limits::{DocumentLimit, DocumentLimits},
     parser::{OptionParseResult, ParseError, ParseResult, Parser, UnlocatedParseResult},
     utils::{SourcePosition, Spanning},
 };
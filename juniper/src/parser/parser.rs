@@ -2,7 +2,7 @@ use std::{error::Error, fmt, result::Result};
 
 use smartstring::alias::String;
 
-use crate::parser::{Lexer, LexerError, Spanning, Token};
+use crate::parser::{DocumentLimit, DocumentLimits, Lexer, LexerError, Spanning, Token};
 
 /// Error while parsing a GraphQL query
 #[derive(Debug, Eq, PartialEq)]
@@ -20,6 +20,9 @@ pub enum ParseError {
 
     /// A scalar of unexpected type occurred in the source
     ExpectedScalarError(&'static str),
+
+    /// The source exceeds the configured [`DocumentLimits`]
+    LimitExceeded(DocumentLimit, usize),
 }
 
 impl fmt::Display for ParseError {
@@ -29,6 +32,9 @@ impl fmt::Display for ParseError {
             Self::UnexpectedEndOfFile => write!(f, "Unexpected end of input"),
             Self::LexerError(e) => e.fmt(f),
             Self::ExpectedScalarError(e) => e.fmt(f),
+            Self::LimitExceeded(limit, max) => {
+                write!(f, "Document exceeds the limit of {max} {limit}")
+            }
         }
     }
 }
@@ -37,9 +43,10 @@ impl Error for ParseError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::LexerError(e) => Some(e),
-            Self::ExpectedScalarError(_) | Self::UnexpectedToken(_) | Self::UnexpectedEndOfFile => {
-                None
-            }
+            Self::ExpectedScalarError(_)
+            | Self::UnexpectedToken(_)
+            | Self::UnexpectedEndOfFile
+            | Self::LimitExceeded(..) => None,
         }
     }
 }
@@ -72,6 +79,21 @@ pub type OptionParseResult<T> = Result<Option<Spanning<T>>, Spanning<ParseError>
 #[derive(Debug)]
 pub struct Parser<'a> {
     tokens: Vec<Spanning<Token<'a>>>,
+    position: usize,
+    recovered: Option<RecoveredErrors>,
+    limits: DocumentLimits,
+    depth: usize,
+    aliases: usize,
+}
+
+/// Errors collected by a [`Parser`] running in recovering mode.
+#[derive(Debug, Default)]
+struct RecoveredErrors {
+    errors: Vec<Spanning<ParseError>>,
+
+    /// Indicator whether the tokens were cut short by a [`LexerError`], so
+    /// [`ParseError::UnexpectedEndOfFile`] is just its consequence.
+    truncated: bool,
 }
 
 impl<'a> Parser<'a> {
@@ -86,24 +108,171 @@ impl<'a> Parser<'a> {
             }
         }
 
-        Ok(Parser { tokens })
+        Ok(Parser {
+            tokens,
+            position: 0,
+            recovered: None,
+            limits: DocumentLimits::default(),
+            depth: 0,
+            aliases: 0,
+        })
+    }
+
+    /// Creates a new [`Parser`] enforcing the provided [`DocumentLimits`].
+    ///
+    /// [`DocumentLimits::max_tokens`] is checked right while lexing, so the
+    /// source is not tokenized any further once it's exceeded.
+    #[doc(hidden)]
+    pub fn new_with_limits(
+        lexer: &mut Lexer<'a>,
+        limits: DocumentLimits,
+    ) -> Result<Parser<'a>, Spanning<ParseError>> {
+        let mut tokens = Vec::new();
+
+        for res in lexer {
+            let token = res.map_err(|e| e.map(ParseError::LexerError))?;
+            if let Some(max) = limits.max_tokens {
+                if tokens.len() >= max && token.item != Token::EndOfFile {
+                    return Err(
+                        token.map(|_| ParseError::LimitExceeded(DocumentLimit::Tokens, max))
+                    );
+                }
+            }
+            tokens.push(token);
+        }
+
+        Ok(Parser {
+            tokens,
+            position: 0,
+            recovered: None,
+            limits,
+            depth: 0,
+            aliases: 0,
+        })
+    }
+
+    /// Creates a new [`Parser`] in recovering mode, which collects errors via
+    /// [`Parser::recover()`] instead of failing on them.
+    ///
+    /// [`LexerError`] doesn't fail the creation: it's collected, and the
+    /// tokens preceding it are parsed as if the input ended there.
+    #[doc(hidden)]
+    pub fn new_recovering(lexer: &mut Lexer<'a>) -> Parser<'a> {
+        let mut tokens = Vec::new();
+        let mut recovered = RecoveredErrors::default();
+
+        for res in lexer {
+            match res {
+                Ok(s) => tokens.push(s),
+                Err(e) => {
+                    tokens.push(Spanning::zero_width(&e.start, Token::EndOfFile));
+                    recovered.errors.push(e.map(ParseError::LexerError));
+                    recovered.truncated = true;
+                    break;
+                }
+            }
+        }
+
+        Parser {
+            tokens,
+            position: 0,
+            recovered: Some(recovered),
+            limits: DocumentLimits::default(),
+            depth: 0,
+            aliases: 0,
+        }
+    }
+
+    /// Returns all the errors collected by this [`Parser`] in recovering mode.
+    #[doc(hidden)]
+    pub fn into_errors(self) -> Vec<Spanning<ParseError>> {
+        self.recovered.map(|r| r.errors).unwrap_or_default()
     }
 
     #[doc(hidden)]
     pub fn peek(&self) -> &Spanning<Token<'a>> {
-        &self.tokens[0]
+        &self.tokens[self.position]
     }
 
     #[doc(hidden)]
     pub fn next_token(&mut self) -> ParseResult<Token<'a>> {
-        if self.tokens.len() == 1 {
+        if self.position + 1 >= self.tokens.len() {
             Err(Spanning::start_end(
                 &self.peek().start,
                 &self.peek().end,
                 ParseError::UnexpectedEndOfFile,
             ))
         } else {
-            Ok(self.tokens.remove(0))
+            self.position += 1;
+            Ok(self.tokens[self.position - 1])
+        }
+    }
+
+    /// Returns the current position of this [`Parser`] to [`Parser::recover()`]
+    /// from later.
+    #[doc(hidden)]
+    pub fn checkpoint(&self) -> usize {
+        self.position
+    }
+
+    /// Recovers from the provided `error` occurred while parsing an item started
+    /// at the `checkpoint`.
+    ///
+    /// If this [`Parser`] is not in recovering mode, just returns the `error`
+    /// back. Otherwise, collects the `error` and skips the tokens of the broken
+    /// item, stopping before the next token on the same nesting level, which is
+    /// either the `closing` one of the enclosing list or satisfies the
+    /// `is_boundary` predicate (accepting the previous, the current and the next
+    /// tokens).
+    #[doc(hidden)]
+    pub fn recover<F>(
+        &mut self,
+        error: Spanning<ParseError>,
+        checkpoint: usize,
+        closing: Option<&Token>,
+        is_boundary: F,
+    ) -> UnlocatedParseResult<()>
+    where
+        F: Fn(&Token<'a>, &Token<'a>, &Token<'a>) -> bool,
+    {
+        let recovered = match &mut self.recovered {
+            Some(r) => r,
+            None => return Err(error),
+        };
+        if !(recovered.truncated && error.item == ParseError::UnexpectedEndOfFile) {
+            recovered.errors.push(error);
+        }
+
+        self.position = checkpoint;
+        let mut depth = 0_usize;
+        let mut previous = None;
+        loop {
+            let current = self.peek().item;
+            if current == Token::EndOfFile {
+                return Ok(());
+            }
+            if depth == 0 {
+                if Some(&current) == closing {
+                    return Ok(());
+                }
+                let next = self
+                    .tokens
+                    .get(self.position + 1)
+                    .map_or(Token::EndOfFile, |t| t.item);
+                if matches!(previous, Some(p) if is_boundary(&p, &current, &next)) {
+                    return Ok(());
+                }
+            }
+
+            match current {
+                Token::ParenOpen | Token::BracketOpen | Token::CurlyOpen => depth += 1,
+                Token::ParenClose | Token::BracketClose | Token::CurlyClose => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            previous = Some(current);
+            self.position += 1;
         }
     }
 
@@ -208,6 +377,59 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Runs the provided `parser` one selection set nesting level deeper,
+    /// checking the [`DocumentLimits::max_depth`].
+    #[doc(hidden)]
+    pub fn nested<T, F>(&mut self, parser: F) -> UnlocatedParseResult<T>
+    where
+        F: FnOnce(&mut Parser<'a>) -> UnlocatedParseResult<T>,
+    {
+        match self.limits.max_depth {
+            Some(max) if self.depth >= max => {
+                return Err(self
+                    .peek()
+                    .map(|_| ParseError::LimitExceeded(DocumentLimit::Depth, max)));
+            }
+            _ => {}
+        }
+
+        self.depth += 1;
+        let res = parser(self);
+        self.depth -= 1;
+        res
+    }
+
+    /// Accounts the provided `alias`, checking the
+    /// [`DocumentLimits::max_aliases`].
+    #[doc(hidden)]
+    pub fn count_alias(&mut self, alias: &Spanning<&'a str>) -> UnlocatedParseResult<()> {
+        self.aliases += 1;
+        match self.limits.max_aliases {
+            Some(max) if self.aliases > max => {
+                Err(alias.map(|_| ParseError::LimitExceeded(DocumentLimit::Aliases, max)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the [`DocumentLimits::max_directives_per_location`] for the
+    /// `directive` being the `count`th one applied to a single location.
+    #[doc(hidden)]
+    pub fn check_directives_count<T>(
+        &self,
+        count: usize,
+        directive: &Spanning<T>,
+    ) -> UnlocatedParseResult<()> {
+        match self.limits.max_directives_per_location {
+            Some(max) if count > max => Err(Spanning::start_end(
+                &directive.start,
+                &directive.end,
+                ParseError::LimitExceeded(DocumentLimit::DirectivesPerLocation, max),
+            )),
+            _ => Ok(()),
+        }
+    }
+
     #[doc(hidden)]
     pub fn expect_name(&mut self) -> ParseResult<&'a str> {
         match *self.peek() {
@@ -1,7 +1,13 @@
 use crate::{
     ast::{Arguments, Definition, Field, Operation, OperationType, OwnedDocument, Selection},
     graphql_input_value,
-    parser::{document::parse_document_source, ParseError, SourcePosition, Spanning, Token},
+    parser::{
+        document::{
+            parse_document_source, parse_document_source_recovering,
+            parse_document_source_with_limits,
+        },
+        DocumentLimit, DocumentLimits, LexerError, ParseError, SourcePosition, Spanning, Token,
+    },
     schema::model::SchemaType,
     types::scalars::{EmptyMutation, EmptySubscription},
     validation::test_harness::{MutationRoot, QueryRoot, SubscriptionRoot},
@@ -29,6 +35,32 @@ fn parse_document_error<S: ScalarValue>(s: &str) -> Spanning<ParseError> {
     }
 }
 
+fn parse_document_recovering<S: ScalarValue>(
+    s: &str,
+) -> (OwnedDocument<S>, Vec<Spanning<ParseError>>) {
+    parse_document_source_recovering(
+        s,
+        &SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &()),
+    )
+}
+
+fn selected_field_names<'a, S: ScalarValue>(doc: &OwnedDocument<'a, S>) -> Vec<Vec<&'a str>> {
+    doc.iter()
+        .map(|def| match def {
+            Definition::Operation(op) => op
+                .item
+                .selection_set
+                .iter()
+                .map(|sel| match sel {
+                    Selection::Field(f) => f.item.name.item,
+                    sel => panic!("Expected field selection, found: {sel:#?}"),
+                })
+                .collect(),
+            def => panic!("Expected operation definition, found: {def:#?}"),
+        })
+        .collect()
+}
+
 #[test]
 fn simple_ast() {
     assert_eq!(
@@ -147,6 +179,184 @@ fn errors() {
     );
 }
 
+#[test]
+fn recovers_from_errors() {
+    let (doc, errors) =
+        parse_document_recovering::<DefaultScalarValue>("{ a 1 b } query Q($x: ) { c } { d ) e }");
+    assert_eq!(selected_field_names(&doc), [vec!["a", "b"], vec!["d", "e"]]);
+    assert_eq!(
+        errors,
+        [
+            Spanning::start_end(
+                &SourcePosition::new(4, 0, 4),
+                &SourcePosition::new(5, 0, 5),
+                ParseError::UnexpectedToken("1".into()),
+            ),
+            Spanning::single_width(
+                &SourcePosition::new(22, 0, 22),
+                ParseError::unexpected_token(Token::ParenClose),
+            ),
+            Spanning::single_width(
+                &SourcePosition::new(34, 0, 34),
+                ParseError::unexpected_token(Token::ParenClose),
+            ),
+        ],
+    );
+
+    let (doc, errors) = parse_document_recovering::<DefaultScalarValue>(
+        "fragment on on T { a }\nquery { b { } c ...on }\n{ d }",
+    );
+    assert_eq!(doc.len(), 2);
+    assert_eq!(selected_field_names(&doc), [vec!["b", "c"], vec!["d"]]);
+    assert_eq!(
+        errors,
+        [
+            Spanning::start_end(
+                &SourcePosition::new(9, 0, 9),
+                &SourcePosition::new(11, 0, 11),
+                ParseError::UnexpectedToken("on".into()),
+            ),
+            Spanning::single_width(
+                &SourcePosition::new(35, 1, 12),
+                ParseError::unexpected_token(Token::CurlyClose),
+            ),
+            Spanning::single_width(
+                &SourcePosition::new(45, 1, 22),
+                ParseError::unexpected_token(Token::CurlyClose),
+            ),
+        ],
+    );
+
+    let (doc, errors) = parse_document_recovering::<DefaultScalarValue>("{ a } { b ?");
+    assert_eq!(selected_field_names(&doc), [vec!["a"]]);
+    assert_eq!(
+        errors,
+        [Spanning::zero_width(
+            &SourcePosition::new(10, 0, 10),
+            ParseError::LexerError(LexerError::UnknownCharacter('?')),
+        )],
+    );
+
+    let (doc, errors) = parse_document_recovering::<DefaultScalarValue>("");
+    assert!(doc.is_empty());
+    assert_eq!(
+        errors,
+        [Spanning::zero_width(
+            &SourcePosition::new(0, 0, 0),
+            ParseError::UnexpectedEndOfFile,
+        )],
+    );
+}
+
+#[test]
+fn recovers_only_at_type_system_definition_boundaries() {
+    // Neither `type` name, nor string is a boundary inside a skipped definition.
+    let (doc, errors) = parse_document_recovering::<DefaultScalarValue>(
+        "fragment F type { a }\nquery Q($x: ) \"desc\" { b }\nquery { c }",
+    );
+    assert_eq!(selected_field_names(&doc), [vec!["c"]]);
+    assert_eq!(
+        errors,
+        [
+            Spanning::start_end(
+                &SourcePosition::new(11, 0, 11),
+                &SourcePosition::new(15, 0, 15),
+                ParseError::UnexpectedToken("type".into()),
+            ),
+            Spanning::single_width(
+                &SourcePosition::new(34, 1, 12),
+                ParseError::unexpected_token(Token::ParenClose),
+            ),
+        ],
+    );
+
+    // While a type system definition following a skipped block is.
+    let (doc, errors) =
+        parse_document_recovering::<DefaultScalarValue>("fragment F T { a }\ntype T { b: Int }");
+    assert_eq!(doc.len(), 1, "{errors:?}");
+    assert!(matches!(doc[0], Definition::Type(_)), "{:?}", doc[0]);
+    assert_eq!(
+        errors,
+        [Spanning::start_end(
+            &SourcePosition::new(11, 0, 11),
+            &SourcePosition::new(12, 0, 12),
+            ParseError::UnexpectedToken("T".into()),
+        )],
+    );
+}
+
+#[test]
+fn recovering_matches_non_recovering_on_valid_input() {
+    let source = "query Q($id: Int = 4) { node(id: $id) { ...F } }\nfragment F on Node { id }";
+    let (doc, errors) = parse_document_recovering::<DefaultScalarValue>(source);
+    assert!(errors.is_empty(), "Unexpected errors: {errors:#?}");
+    assert_eq!(doc, parse_document::<DefaultScalarValue>(source));
+}
+
+#[test]
+fn limits() {
+    let parse = |s, limits| {
+        parse_document_source_with_limits::<DefaultScalarValue>(
+            s,
+            &SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &()),
+            limits,
+        )
+    };
+
+    let limits = DocumentLimits {
+        max_tokens: Some(4),
+        ..DocumentLimits::default()
+    };
+    assert!(parse("{ a b }", limits).is_ok());
+    assert_eq!(
+        parse("{ a b c }", limits).unwrap_err(),
+        Spanning::single_width(
+            &SourcePosition::new(8, 0, 8),
+            ParseError::LimitExceeded(DocumentLimit::Tokens, 4),
+        ),
+    );
+
+    let limits = DocumentLimits {
+        max_depth: Some(2),
+        ..DocumentLimits::default()
+    };
+    assert!(parse("{ a { b } } { c { d } }", limits).is_ok());
+    assert_eq!(
+        parse("{ a { b { c } } }", limits).unwrap_err(),
+        Spanning::single_width(
+            &SourcePosition::new(8, 0, 8),
+            ParseError::LimitExceeded(DocumentLimit::Depth, 2),
+        ),
+    );
+
+    let limits = DocumentLimits {
+        max_aliases: Some(2),
+        ..DocumentLimits::default()
+    };
+    assert!(parse("{ x: a y: b c }", limits).is_ok());
+    assert_eq!(
+        parse("{ x: a } { y: b z: c }", limits).unwrap_err(),
+        Spanning::single_width(
+            &SourcePosition::new(16, 0, 16),
+            ParseError::LimitExceeded(DocumentLimit::Aliases, 2),
+        ),
+    );
+
+    let limits = DocumentLimits {
+        max_directives_per_location: Some(1),
+        ..DocumentLimits::default()
+    };
+    assert!(parse("{ a @skip(if: false) b @skip(if: false) }", limits).is_ok());
+    assert_eq!(
+        parse("{ a @skip(if: false) @skip(if: false) }", limits).unwrap_err(),
+        Spanning::start_end(
+            &SourcePosition::new(21, 0, 21),
+            &SourcePosition::new(37, 0, 37),
+            ParseError::LimitExceeded(DocumentLimit::DirectivesPerLocation, 1),
+        ),
+    );
+}
+
 #[test]
 fn issue_427_panic_is_not_expected() {
     struct QueryWithoutFloat;
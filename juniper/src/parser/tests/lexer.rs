@@ -1,4 +1,6 @@
-use crate::parser::{Lexer, LexerError, ScalarToken, SourcePosition, Spanning, Token};
+use crate::parser::{
+    Lexer, LexerError, ScalarToken, SourcePosition, Spanning, StringLiteral, Token,
+};
 
 fn tokenize_to_vec<'a>(s: &'a str) -> Vec<Spanning<Token<'a>>> {
     let mut tokens = Vec::new();
@@ -91,6 +93,26 @@ fn skip_whitespace() {
     );
 }
 
+#[test]
+fn count_line_terminators() {
+    assert_eq!(
+        tokenize_to_vec("\r\n\rfoo#comment\r\nbar\n"),
+        vec![
+            Spanning::start_end(
+                &SourcePosition::new(3, 2, 0),
+                &SourcePosition::new(6, 2, 3),
+                Token::Name("foo"),
+            ),
+            Spanning::start_end(
+                &SourcePosition::new(16, 3, 0),
+                &SourcePosition::new(19, 3, 3),
+                Token::Name("bar"),
+            ),
+            Spanning::zero_width(&SourcePosition::new(20, 4, 0), Token::EndOfFile),
+        ]
+    );
+}
+
 #[test]
 fn skip_comments() {
     assert_eq!(
@@ -151,7 +173,7 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(8, 0, 8),
-            Token::Scalar(ScalarToken::String("simple"))
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted("simple")))
         )
     );
 
@@ -160,7 +182,7 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(15, 0, 15),
-            Token::Scalar(ScalarToken::String(" white space "))
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(" white space ")))
         )
     );
 
@@ -169,7 +191,7 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(10, 0, 10),
-            Token::Scalar(ScalarToken::String(r#"quote \""#))
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(r#"quote \""#)))
         )
     );
 
@@ -178,7 +200,9 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(20, 0, 20),
-            Token::Scalar(ScalarToken::String(r#"escaped \n\r\b\t\f"#))
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                r#"escaped \n\r\b\t\f"#
+            )))
         )
     );
 
@@ -187,7 +211,9 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(15, 0, 15),
-            Token::Scalar(ScalarToken::String(r#"slashes \\ \/"#))
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                r#"slashes \\ \/"#
+            )))
         )
     );
 
@@ -196,11 +222,170 @@ fn strings() {
         Spanning::start_end(
             &SourcePosition::new(0, 0, 0),
             &SourcePosition::new(34, 0, 34),
-            Token::Scalar(ScalarToken::String(r#"unicode \u1234\u5678\u90AB\uCDEF"#)),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                r#"unicode \u1234\u5678\u90AB\uCDEF"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""variable \u{1F600}\u{41}""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(26, 0, 26),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                r#"variable \u{1F600}\u{41}"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""surrogate \uD83D\uDE00""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(24, 0, 24),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                r#"surrogate \uD83D\uDE00"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(2, 0, 2),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(""))),
         )
     );
 }
 
+#[test]
+fn block_strings() {
+    assert_eq!(
+        tokenize_single(r#""""""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(6, 0, 6),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(""))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""simple""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(12, 0, 12),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block("simple"))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#"""" white space """"#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(19, 0, 19),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(" white space "))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""contains " quote""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(22, 0, 22),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(
+                r#"contains " quote"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""contains \""" triple quote""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(32, 0, 32),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(
+                r#"contains \""" triple quote"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single(r#""""unescaped \n\r\b\t\f\u1234""""#),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(32, 0, 32),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(
+                r#"unescaped \n\r\b\t\f\u1234"#
+            ))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_single("\"\"\"multi\nline\"\"\""),
+        Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(16, 1, 7),
+            Token::Scalar(ScalarToken::String(StringLiteral::Block("multi\nline"))),
+        )
+    );
+
+    assert_eq!(
+        tokenize_to_vec("\"\"\"\n  first\n\"\"\" after"),
+        vec![
+            Spanning::start_end(
+                &SourcePosition::new(0, 0, 0),
+                &SourcePosition::new(15, 2, 3),
+                Token::Scalar(ScalarToken::String(StringLiteral::Block("\n  first\n"))),
+            ),
+            Spanning::start_end(
+                &SourcePosition::new(16, 2, 4),
+                &SourcePosition::new(21, 2, 9),
+                Token::Name("after"),
+            ),
+            Spanning::zero_width(&SourcePosition::new(21, 2, 9), Token::EndOfFile),
+        ]
+    );
+}
+
+#[test]
+fn block_string_values() {
+    fn block_string_value(s: &str) -> String {
+        match tokenize_single(s).item {
+            Token::Scalar(ScalarToken::String(lit)) => lit.parse().unwrap().into_owned(),
+            t => panic!("Expected block string, found: {t:#?}"),
+        }
+    }
+
+    assert_eq!(block_string_value(r#""""simple""""#), "simple");
+    assert_eq!(
+        block_string_value(r#""""contains \""" triple quote""""#),
+        r#"contains """ triple quote"#,
+    );
+    assert_eq!(
+        block_string_value(r#""""unescaped \n\u1234""""#),
+        r#"unescaped \n\u1234"#,
+    );
+    assert_eq!(
+        block_string_value(
+            r#""""
+                Hello,
+                  World!
+
+                Yours,
+                  GraphQL.
+            """"#,
+        ),
+        "Hello,\n  World!\n\nYours,\n  GraphQL.",
+    );
+    assert_eq!(
+        block_string_value("\"\"\"  first\r\n      second\r    third\"\"\""),
+        "  first\n  second\nthird",
+    );
+    assert_eq!(block_string_value("\"\"\"\n  \t\n\n   \"\"\""), "",);
+}
+
 #[test]
 fn string_errors() {
     assert_eq!(
@@ -327,8 +512,72 @@ fn string_errors() {
     assert_eq!(
         tokenize_error(r#""\uɠ^A"#),
         Spanning::zero_width(
-            &SourcePosition::new(5, 0, 5),
-            LexerError::UnterminatedString
+            &SourcePosition::new(2, 0, 2),
+            LexerError::UnknownEscapeSequence("\\uɠ".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""bad \u{} esc""#),
+        Spanning::zero_width(
+            &SourcePosition::new(6, 0, 6),
+            LexerError::UnknownEscapeSequence("\\u{}".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""bad \u{1F60X} esc""#),
+        Spanning::zero_width(
+            &SourcePosition::new(6, 0, 6),
+            LexerError::UnknownEscapeSequence("\\u{1F60".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""bad \u{110000} esc""#),
+        Spanning::zero_width(
+            &SourcePosition::new(6, 0, 6),
+            LexerError::UnknownEscapeSequence("\\u{110000}".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""bad \u{D800} esc""#),
+        Spanning::zero_width(
+            &SourcePosition::new(6, 0, 6),
+            LexerError::UnknownEscapeSequence("\\u{D800}".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""lone leading \uD83D surrogate""#),
+        Spanning::zero_width(
+            &SourcePosition::new(15, 0, 15),
+            LexerError::UnknownEscapeSequence("\\uD83D".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""reversed \uDE00\uD83D surrogates""#),
+        Spanning::zero_width(
+            &SourcePosition::new(11, 0, 11),
+            LexerError::UnknownEscapeSequence("\\uDE00".into()),
+        )
+    );
+
+    assert_eq!(
+        tokenize_error(r#""""no end quote"#),
+        Spanning::zero_width(
+            &SourcePosition::new(15, 0, 15),
+            LexerError::UnterminatedString,
+        )
+    );
+
+    assert_eq!(
+        tokenize_error("\"\"\"contains \u{0007} control char\"\"\""),
+        Spanning::zero_width(
+            &SourcePosition::new(12, 0, 12),
+            LexerError::UnknownCharacterInString('\u{0007}'),
         )
     );
 }
@@ -621,6 +870,11 @@ fn punctuation() {
         tokenize_single("|"),
         Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::Pipe)
     );
+
+    assert_eq!(
+        tokenize_single("&"),
+        Spanning::single_width(&SourcePosition::new(0, 0, 0), Token::Amp)
+    );
 }
 
 #[test]
@@ -665,13 +919,21 @@ fn display() {
         (Token::Scalar(ScalarToken::Int("123")), "123"),
         (Token::Scalar(ScalarToken::Float("4.5")), "4.5"),
         (
-            Token::Scalar(ScalarToken::String("some string")),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted("some string"))),
             "\"some string\"",
         ),
         (
-            Token::Scalar(ScalarToken::String("string with \\ escape and \" quote")),
+            Token::Scalar(ScalarToken::String(StringLiteral::Quoted(
+                "string with \\ escape and \" quote",
+            ))),
             "\"string with \\\\ escape and \\\" quote\"",
         ),
+        (
+            Token::Scalar(ScalarToken::String(StringLiteral::Block(
+                "block string with \" quote",
+            ))),
+            "\"\"\"block string with \" quote\"\"\"",
+        ),
         (Token::ExclamationMark, "!"),
         (Token::Dollar, "$"),
         (Token::ParenOpen, "("),
@@ -685,6 +947,7 @@ fn display() {
         (Token::Equals, "="),
         (Token::At, "@"),
         (Token::Pipe, "|"),
+        (Token::Amp, "&"),
     ] {
         assert_eq!(input.to_string(), expected);
     }
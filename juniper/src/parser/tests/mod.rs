@@ -1,3 +1,4 @@
 mod document;
 mod lexer;
+mod type_system;
 mod value;
@@ -0,0 +1,412 @@
+use std::borrow::Cow;
+
+use crate::{
+    ast::{
+        Definition, DirectiveDefinition, EnumTypeDefinition, InputObjectTypeDefinition,
+        ObjectTypeDefinition, OperationType, OwnedDocument, ScalarTypeDefinition, SchemaDefinition,
+        Type, TypeDefinition, UnionTypeDefinition,
+    },
+    graphql_input_value,
+    parser::{
+        type_system::parse_type_system_document_source, ParseError, SourcePosition, Spanning, Token,
+    },
+    schema::model::DirectiveLocation,
+    value::{DefaultScalarValue, ScalarValue},
+};
+
+fn parse_document<S>(s: &str) -> OwnedDocument<S>
+where
+    S: ScalarValue,
+{
+    parse_type_system_document_source(s).expect(&format!("Parse error on input {s:#?}"))
+}
+
+fn parse_document_error<S: ScalarValue>(s: &str) -> Spanning<ParseError> {
+    match parse_type_system_document_source::<S>(s) {
+        Ok(doc) => panic!("*No* parse error on input {s:#?} =>\n{doc:#?}"),
+        Err(err) => err,
+    }
+}
+
+fn single_type_definition<'a>(
+    doc: &'a OwnedDocument<'a, DefaultScalarValue>,
+) -> &'a TypeDefinition<'a, DefaultScalarValue> {
+    assert_eq!(doc.len(), 1, "Expected single definition in {doc:#?}");
+    match &doc[0] {
+        Definition::Type(def) => &def.item,
+        def => panic!("Expected type definition, found: {def:#?}"),
+    }
+}
+
+#[test]
+fn scalar_ast() {
+    assert_eq!(
+        parse_document::<DefaultScalarValue>(
+            r#""Date and time" scalar DateTime @specifiedBy(url: "https://example.com")"#
+        ),
+        vec![Definition::Type(Spanning::start_end(
+            &SourcePosition::new(0, 0, 0),
+            &SourcePosition::new(72, 0, 72),
+            TypeDefinition::Scalar(ScalarTypeDefinition {
+                description: Some(Spanning::start_end(
+                    &SourcePosition::new(0, 0, 0),
+                    &SourcePosition::new(15, 0, 15),
+                    Cow::Borrowed("Date and time"),
+                )),
+                name: Spanning::start_end(
+                    &SourcePosition::new(23, 0, 23),
+                    &SourcePosition::new(31, 0, 31),
+                    "DateTime",
+                ),
+                directives: Some(vec![Spanning::start_end(
+                    &SourcePosition::new(32, 0, 32),
+                    &SourcePosition::new(72, 0, 72),
+                    crate::ast::Directive {
+                        name: Spanning::start_end(
+                            &SourcePosition::new(33, 0, 33),
+                            &SourcePosition::new(44, 0, 44),
+                            "specifiedBy",
+                        ),
+                        arguments: Some(Spanning::start_end(
+                            &SourcePosition::new(44, 0, 44),
+                            &SourcePosition::new(72, 0, 72),
+                            crate::ast::Arguments {
+                                items: vec![(
+                                    Spanning::start_end(
+                                        &SourcePosition::new(45, 0, 45),
+                                        &SourcePosition::new(48, 0, 48),
+                                        "url",
+                                    ),
+                                    Spanning::start_end(
+                                        &SourcePosition::new(50, 0, 50),
+                                        &SourcePosition::new(71, 0, 71),
+                                        graphql_input_value!("https://example.com"),
+                                    ),
+                                )],
+                            },
+                        )),
+                    },
+                )]),
+            }),
+        ))],
+    );
+}
+
+#[test]
+fn object_and_interface_types() {
+    let doc = parse_document::<DefaultScalarValue>(
+        r#"
+        """
+        A user of the system.
+        """
+        type User implements & Node & Entity @key(fields: "id") {
+            id: ID!
+            "Friends of the user."
+            friends(first: Int = 10, after: String @deprecated): [User!]! @auth
+        }
+        "#,
+    );
+
+    let TypeDefinition::Object(ObjectTypeDefinition {
+        description,
+        name,
+        interfaces,
+        directives,
+        fields,
+    }) = single_type_definition(&doc)
+    else {
+        panic!("Expected object type definition in {doc:#?}");
+    };
+    assert_eq!(description.as_ref().unwrap().item, "A user of the system.");
+    assert_eq!(name.item, "User");
+    assert_eq!(
+        interfaces.iter().map(|i| i.item).collect::<Vec<_>>(),
+        ["Node", "Entity"],
+    );
+    assert_eq!(directives.as_ref().unwrap()[0].item.name.item, "key");
+    assert_eq!(fields.len(), 2);
+
+    assert_eq!(fields[0].item.name.item, "id");
+    assert_eq!(
+        fields[0].item.field_type.item,
+        Type::NonNullNamed("ID".into())
+    );
+    assert!(fields[0].item.arguments.is_none());
+
+    let friends = &fields[1].item;
+    assert_eq!(
+        friends.description.as_ref().unwrap().item,
+        "Friends of the user."
+    );
+    assert_eq!(
+        friends.field_type.item,
+        Type::NonNullList(Box::new(Type::NonNullNamed("User".into())), None),
+    );
+    let args = &friends.arguments.as_ref().unwrap().item;
+    assert_eq!(args.len(), 2);
+    assert_eq!(args[0].item.name.item, "first");
+    assert_eq!(args[0].item.value_type.item, Type::Named("Int".into()));
+    assert_eq!(
+        args[0].item.default_value.as_ref().unwrap().item,
+        graphql_input_value!(10),
+    );
+    assert_eq!(
+        args[1].item.directives.as_ref().unwrap()[0].item.name.item,
+        "deprecated",
+    );
+    assert_eq!(
+        friends.directives.as_ref().unwrap()[0].item.name.item,
+        "auth"
+    );
+    assert_eq!(fields[1].start, SourcePosition::new(153, 6, 12));
+    assert_eq!(fields[1].end, SourcePosition::new(255, 7, 79));
+
+    let doc = parse_document::<DefaultScalarValue>("interface Node implements Entity { id: ID! }");
+    let TypeDefinition::Interface(def) = single_type_definition(&doc) else {
+        panic!("Expected interface type definition in {doc:#?}");
+    };
+    assert_eq!(def.name.item, "Node");
+    assert_eq!(def.interfaces[0].item, "Entity");
+    assert_eq!(def.fields[0].item.name.item, "id");
+}
+
+#[test]
+fn union_enum_and_input_types() {
+    let doc = parse_document::<DefaultScalarValue>("union SearchResult = | User | Post");
+    let TypeDefinition::Union(UnionTypeDefinition { name, members, .. }) =
+        single_type_definition(&doc)
+    else {
+        panic!("Expected union type definition in {doc:#?}");
+    };
+    assert_eq!(name.item, "SearchResult");
+    assert_eq!(
+        members.iter().map(|m| m.item).collect::<Vec<_>>(),
+        ["User", "Post"],
+    );
+    assert_eq!(members[1].end, SourcePosition::new(34, 0, 34));
+
+    let doc = parse_document::<DefaultScalarValue>(
+        r#"enum Episode { "Released in 1977." NEW_HOPE EMPIRE @deprecated JEDI }"#,
+    );
+    let TypeDefinition::Enum(EnumTypeDefinition { values, .. }) = single_type_definition(&doc)
+    else {
+        panic!("Expected enum type definition in {doc:#?}");
+    };
+    assert_eq!(
+        values.iter().map(|v| v.item.name.item).collect::<Vec<_>>(),
+        ["NEW_HOPE", "EMPIRE", "JEDI"],
+    );
+    assert_eq!(
+        values[0].item.description.as_ref().unwrap().item,
+        "Released in 1977.",
+    );
+    assert!(values[1].item.directives.is_some());
+
+    let doc = parse_document::<DefaultScalarValue>(
+        "input Point { x: Int! = 0, y: Int! tags: [String] = [\"a\", \"b\"] }",
+    );
+    let TypeDefinition::InputObject(InputObjectTypeDefinition { fields, .. }) =
+        single_type_definition(&doc)
+    else {
+        panic!("Expected input object type definition in {doc:#?}");
+    };
+    assert_eq!(
+        fields.iter().map(|f| f.item.name.item).collect::<Vec<_>>(),
+        ["x", "y", "tags"],
+    );
+    assert_eq!(
+        fields[2]
+            .item
+            .default_value
+            .as_ref()
+            .unwrap()
+            .item
+            .to_string(),
+        r#"["a", "b"]"#,
+    );
+}
+
+#[test]
+fn schema_and_directive_definitions() {
+    let doc = parse_document::<DefaultScalarValue>(
+        r#"
+        schema @link(url: "https://example.com") {
+            query: Query
+            subscription: Subscription
+        }
+
+        "Restricts access."
+        directive @auth(role: String = "admin") repeatable on | FIELD_DEFINITION | OBJECT
+        "#,
+    );
+    assert_eq!(doc.len(), 2);
+
+    let Definition::Schema(Spanning {
+        item:
+            SchemaDefinition {
+                directives,
+                operation_types,
+                ..
+            },
+        ..
+    }) = &doc[0]
+    else {
+        panic!("Expected schema definition in {doc:#?}");
+    };
+    assert_eq!(directives.as_ref().unwrap()[0].item.name.item, "link");
+    assert_eq!(
+        operation_types
+            .iter()
+            .map(|o| (o.item.operation_type.item, o.item.named_type.item))
+            .collect::<Vec<_>>(),
+        [
+            (OperationType::Query, "Query"),
+            (OperationType::Subscription, "Subscription"),
+        ],
+    );
+
+    let Definition::Directive(Spanning {
+        item:
+            DirectiveDefinition {
+                description,
+                name,
+                arguments,
+                is_repeatable,
+                locations,
+            },
+        start,
+        end,
+    }) = &doc[1]
+    else {
+        panic!("Expected directive definition in {doc:#?}");
+    };
+    assert_eq!(description.as_ref().unwrap().item, "Restricts access.");
+    assert_eq!(name.item, "auth");
+    assert_eq!(arguments.as_ref().unwrap().item[0].item.name.item, "role");
+    assert!(is_repeatable);
+    assert_eq!(
+        locations.iter().map(|l| l.item.clone()).collect::<Vec<_>>(),
+        [
+            DirectiveLocation::FieldDefinition,
+            DirectiveLocation::Object
+        ],
+    );
+    assert_eq!(*start, SourcePosition::new(135, 6, 8));
+    assert_eq!(*end, SourcePosition::new(244, 7, 89));
+}
+
+#[test]
+fn extensions() {
+    let doc = parse_document::<DefaultScalarValue>(
+        r#"
+        extend schema @link(url: "https://example.com")
+        extend scalar DateTime @specifiedBy(url: "https://example.com")
+        extend type User implements Node
+        extend interface Node @key(fields: "id")
+        extend union SearchResult = Comment
+        extend enum Episode { PHANTOM_MENACE }
+        extend input Point { z: Int }
+        "#,
+    );
+    assert_eq!(doc.len(), 7);
+
+    assert!(matches!(
+        &doc[0],
+        Definition::SchemaExtension(Spanning {
+            item: SchemaDefinition { operation_types, description: None, .. },
+            ..
+        }) if operation_types.is_empty(),
+    ));
+    let names = doc[1..]
+        .iter()
+        .map(|d| match d {
+            Definition::TypeExtension(def) => def.item.name(),
+            def => panic!("Expected type extension, found: {def:#?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "DateTime",
+            "User",
+            "Node",
+            "SearchResult",
+            "Episode",
+            "Point"
+        ],
+    );
+    if let Definition::TypeExtension(def) = &doc[2] {
+        assert_eq!(def.start, SourcePosition::new(137, 3, 8));
+        assert_eq!(def.end, SourcePosition::new(169, 3, 40));
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>(""),
+        Spanning::zero_width(
+            &SourcePosition::new(0, 0, 0),
+            ParseError::UnexpectedEndOfFile,
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("{ field }"),
+        Spanning::single_width(
+            &SourcePosition::new(0, 0, 0),
+            ParseError::unexpected_token(Token::CurlyOpen),
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("type Empty {}"),
+        Spanning::single_width(
+            &SourcePosition::new(12, 0, 12),
+            ParseError::unexpected_token(Token::CurlyClose),
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("extend type User"),
+        Spanning::zero_width(
+            &SourcePosition::new(16, 0, 16),
+            ParseError::UnexpectedEndOfFile,
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("\"description\" extend type User @key"),
+        Spanning::start_end(
+            &SourcePosition::new(14, 0, 14),
+            &SourcePosition::new(20, 0, 20),
+            ParseError::UnexpectedToken("extend".into()),
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("enum Bool { true false }"),
+        Spanning::start_end(
+            &SourcePosition::new(12, 0, 12),
+            &SourcePosition::new(16, 0, 16),
+            ParseError::UnexpectedToken("true".into()),
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("directive @foo on FIELD | SOMEWHERE"),
+        Spanning::start_end(
+            &SourcePosition::new(26, 0, 26),
+            &SourcePosition::new(35, 0, 35),
+            ParseError::UnexpectedToken("SOMEWHERE".into()),
+        ),
+    );
+
+    assert_eq!(
+        parse_document_error::<DefaultScalarValue>("input Point { x: Int = $var }"),
+        Spanning::single_width(
+            &SourcePosition::new(23, 0, 23),
+            ParseError::unexpected_token(Token::Dollar),
+        ),
+    );
+}
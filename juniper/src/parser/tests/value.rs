@@ -68,7 +68,7 @@ where
     let mut parser = Parser::new(&mut lexer).expect(&format!("Lexer error on input {s:#?}"));
     let schema = SchemaType::new::<Query, EmptyMutation<()>, EmptySubscription<()>>(&(), &(), &());
 
-    parse_value_literal(&mut parser, false, &schema, Some(meta))
+    parse_value_literal(&mut parser, false, Some(&schema), Some(meta))
         .expect(&format!("Parse error on input {s:#?}"))
 }
 
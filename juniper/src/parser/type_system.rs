@@ -0,0 +1,625 @@
+use std::borrow::Cow;
+
+use crate::{
+    ast::{
+        Arguments, Definition, Directive, DirectiveDefinition, EnumTypeDefinition,
+        EnumValueDefinition, FieldDefinition, InputObjectTypeDefinition, InputValue,
+        InputValueDefinition, InterfaceTypeDefinition, ObjectTypeDefinition, OperationType,
+        OwnedDocument, RootOperationTypeDefinition, ScalarTypeDefinition, SchemaDefinition,
+        TypeDefinition, UnionTypeDefinition,
+    },
+    parser::{
+        document::parse_type, value::parse_value_literal, Lexer, OptionParseResult, ParseError,
+        ParseResult, Parser, ScalarToken, SourcePosition, Spanning, Token, UnlocatedParseResult,
+    },
+    schema::model::DirectiveLocation,
+    value::ScalarValue,
+};
+
+/// Parses the provided [type system document][0] source: a GraphQL document
+/// consisting only of type system definitions and extensions (also known as
+/// SDL).
+///
+/// Unlike [`parse_document_source()`](super::parse_document_source), no schema
+/// is required, as literals are parsed with the built-in scalars only.
+///
+/// [0]: https://spec.graphql.org/October2021#TypeSystemDocument
+pub fn parse_type_system_document_source<'a, S>(
+    s: &'a str,
+) -> UnlocatedParseResult<OwnedDocument<'a, S>>
+where
+    S: ScalarValue,
+{
+    let mut lexer = Lexer::new(s);
+    let mut parser = Parser::new(&mut lexer).map_err(|s| s.map(ParseError::LexerError))?;
+    let mut defs = Vec::new();
+
+    loop {
+        defs.push(parse_type_system_definition(&mut parser)?);
+
+        if parser.peek().item == Token::EndOfFile {
+            return Ok(defs);
+        }
+    }
+}
+
+/// Checks whether the provided [`Token`] may start a type system definition or
+/// extension.
+pub(super) fn is_type_system_definition_start(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Scalar(ScalarToken::String(_))
+            | Token::Name(
+                "schema"
+                    | "scalar"
+                    | "type"
+                    | "interface"
+                    | "union"
+                    | "enum"
+                    | "input"
+                    | "directive"
+                    | "extend"
+            ),
+    )
+}
+
+pub(super) fn parse_type_system_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> UnlocatedParseResult<Definition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description(parser)?;
+    let start_pos = description
+        .as_ref()
+        .map_or(parser.peek().start, |d| d.start);
+
+    match parser.peek().item {
+        Token::Name("schema") => Ok(Definition::Schema(parse_schema_definition(
+            parser,
+            start_pos,
+            description,
+            false,
+        )?)),
+        Token::Name("scalar" | "type" | "interface" | "union" | "enum" | "input") => {
+            Ok(Definition::Type(parse_type_definition(
+                parser,
+                start_pos,
+                description,
+                false,
+            )?))
+        }
+        Token::Name("directive") => Ok(Definition::Directive(parse_directive_definition(
+            parser,
+            start_pos,
+            description,
+        )?)),
+        Token::Name("extend") if description.is_none() => {
+            parser.next_token()?;
+            match parser.peek().item {
+                Token::Name("schema") => Ok(Definition::SchemaExtension(parse_schema_definition(
+                    parser, start_pos, None, true,
+                )?)),
+                Token::Name("scalar" | "type" | "interface" | "union" | "enum" | "input") => {
+                    Ok(Definition::TypeExtension(parse_type_definition(
+                        parser, start_pos, None, true,
+                    )?))
+                }
+                _ => Err(parser.next_token()?.map(ParseError::unexpected_token)),
+            }
+        }
+        _ => Err(parser.next_token()?.map(ParseError::unexpected_token)),
+    }
+}
+
+/// Skips the upcoming [`Token`] if it's the `expected` one, returning whether
+/// it has been skipped.
+///
+/// Unlike [`Parser::skip()`], doesn't fail on reaching the end of input, as
+/// type system definitions may end with an optional token.
+fn skip_optional(parser: &mut Parser<'_>, expected: &Token<'_>) -> UnlocatedParseResult<bool> {
+    if &parser.peek().item == expected {
+        parser.next_token()?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+fn parse_description<'a>(parser: &mut Parser<'a>) -> OptionParseResult<Cow<'a, str>> {
+    if !matches!(parser.peek().item, Token::Scalar(ScalarToken::String(_))) {
+        return Ok(None);
+    }
+
+    let Spanning { item, start, end } = parser.next_token()?;
+    if let Token::Scalar(ScalarToken::String(lit)) = item {
+        lit.parse()
+            .map(|s| Some(Spanning::start_end(&start, &end, s)))
+            .map_err(|e| Spanning::start_end(&start, &end, e))
+    } else {
+        unreachable!()
+    }
+}
+
+/// Parses a schema definition, or a schema extension (having no
+/// `description`), if `is_extension` is `true`.
+fn parse_schema_definition<'a, S>(
+    parser: &mut Parser<'a>,
+    start_pos: SourcePosition,
+    description: Option<Spanning<Cow<'a, str>>>,
+    is_extension: bool,
+) -> ParseResult<SchemaDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let Spanning { end: end_pos, .. } = parser.expect(&Token::Name("schema"))?;
+    let directives = parse_const_directives(parser)?;
+    let operation_types = if !is_extension || parser.peek().item == Token::CurlyOpen {
+        Some(parser.delimited_nonempty_list(
+            &Token::CurlyOpen,
+            parse_root_operation_type_definition,
+            &Token::CurlyClose,
+        )?)
+    } else if directives.is_none() {
+        return Err(parser.next_token()?.map(ParseError::unexpected_token));
+    } else {
+        None
+    };
+
+    let end_pos = operation_types
+        .as_ref()
+        .map(|s| s.end)
+        .or_else(|| directives.as_ref().map(|s| s.end))
+        .unwrap_or(end_pos);
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &end_pos,
+        SchemaDefinition {
+            description,
+            directives: directives.map(|s| s.item),
+            operation_types: operation_types.map(|s| s.item).unwrap_or_default(),
+        },
+    ))
+}
+
+fn parse_root_operation_type_definition<'a>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<RootOperationTypeDefinition<'a>> {
+    let operation_type = match parser.peek().item {
+        Token::Name("query") => parser.next_token()?.map(|_| OperationType::Query),
+        Token::Name("mutation") => parser.next_token()?.map(|_| OperationType::Mutation),
+        Token::Name("subscription") => parser.next_token()?.map(|_| OperationType::Subscription),
+        _ => return Err(parser.next_token()?.map(ParseError::unexpected_token)),
+    };
+    parser.expect(&Token::Colon)?;
+    let named_type = parser.expect_name()?;
+
+    Ok(Spanning::start_end(
+        &operation_type.start.clone(),
+        &named_type.end.clone(),
+        RootOperationTypeDefinition {
+            operation_type,
+            named_type,
+        },
+    ))
+}
+
+/// Parses a type definition, or a type extension (having no `description`),
+/// if `is_extension` is `true`.
+fn parse_type_definition<'a, S>(
+    parser: &mut Parser<'a>,
+    start_pos: SourcePosition,
+    description: Option<Spanning<Cow<'a, str>>>,
+    is_extension: bool,
+) -> ParseResult<TypeDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let keyword = parser.next_token()?;
+    let name = parser.expect_name()?;
+
+    let (def, end_pos) = match keyword.item {
+        Token::Name("scalar") => {
+            let directives = parse_const_directives(parser)?;
+            if is_extension && directives.is_none() {
+                return Err(parser.next_token()?.map(ParseError::unexpected_token));
+            }
+            let end_pos = directives.as_ref().map_or(name.end, |s| s.end);
+            let def = TypeDefinition::Scalar(ScalarTypeDefinition {
+                description,
+                name,
+                directives: directives.map(|s| s.item),
+            });
+            (def, end_pos)
+        }
+        Token::Name(kw @ ("type" | "interface")) => {
+            let interfaces = parse_implements_interfaces(parser)?;
+            let directives = parse_const_directives(parser)?;
+            let fields = parse_optional_definitions(parser, parse_field_definition)?;
+            if is_extension && interfaces.is_none() && directives.is_none() && fields.is_none() {
+                return Err(parser.next_token()?.map(ParseError::unexpected_token));
+            }
+            let end_pos = fields
+                .as_ref()
+                .map(|s| s.end)
+                .or_else(|| directives.as_ref().map(|s| s.end))
+                .or_else(|| interfaces.as_ref().map(|s| s.end))
+                .unwrap_or(name.end);
+            let interfaces = interfaces.map(|s| s.item).unwrap_or_default();
+            let directives = directives.map(|s| s.item);
+            let fields = fields.map(|s| s.item).unwrap_or_default();
+            let def = if kw == "type" {
+                TypeDefinition::Object(ObjectTypeDefinition {
+                    description,
+                    name,
+                    interfaces,
+                    directives,
+                    fields,
+                })
+            } else {
+                TypeDefinition::Interface(InterfaceTypeDefinition {
+                    description,
+                    name,
+                    interfaces,
+                    directives,
+                    fields,
+                })
+            };
+            (def, end_pos)
+        }
+        Token::Name("union") => {
+            let directives = parse_const_directives(parser)?;
+            let members = if parser.peek().item == Token::Equals {
+                Some(parse_union_member_types(parser)?)
+            } else {
+                None
+            };
+            if is_extension && directives.is_none() && members.is_none() {
+                return Err(parser.next_token()?.map(ParseError::unexpected_token));
+            }
+            let end_pos = members
+                .as_ref()
+                .map(|s| s.end)
+                .or_else(|| directives.as_ref().map(|s| s.end))
+                .unwrap_or(name.end);
+            let def = TypeDefinition::Union(UnionTypeDefinition {
+                description,
+                name,
+                directives: directives.map(|s| s.item),
+                members: members.map(|s| s.item).unwrap_or_default(),
+            });
+            (def, end_pos)
+        }
+        Token::Name("enum") => {
+            let directives = parse_const_directives(parser)?;
+            let values = parse_optional_definitions(parser, parse_enum_value_definition)?;
+            if is_extension && directives.is_none() && values.is_none() {
+                return Err(parser.next_token()?.map(ParseError::unexpected_token));
+            }
+            let end_pos = values
+                .as_ref()
+                .map(|s| s.end)
+                .or_else(|| directives.as_ref().map(|s| s.end))
+                .unwrap_or(name.end);
+            let def = TypeDefinition::Enum(EnumTypeDefinition {
+                description,
+                name,
+                directives: directives.map(|s| s.item),
+                values: values.map(|s| s.item).unwrap_or_default(),
+            });
+            (def, end_pos)
+        }
+        Token::Name("input") => {
+            let directives = parse_const_directives(parser)?;
+            let fields = parse_optional_definitions(parser, parse_input_value_definition)?;
+            if is_extension && directives.is_none() && fields.is_none() {
+                return Err(parser.next_token()?.map(ParseError::unexpected_token));
+            }
+            let end_pos = fields
+                .as_ref()
+                .map(|s| s.end)
+                .or_else(|| directives.as_ref().map(|s| s.end))
+                .unwrap_or(name.end);
+            let def = TypeDefinition::InputObject(InputObjectTypeDefinition {
+                description,
+                name,
+                directives: directives.map(|s| s.item),
+                fields: fields.map(|s| s.item).unwrap_or_default(),
+            });
+            (def, end_pos)
+        }
+        _ => return Err(keyword.map(ParseError::unexpected_token)),
+    };
+
+    Ok(Spanning::start_end(&start_pos, &end_pos, def))
+}
+
+fn parse_implements_interfaces<'a>(
+    parser: &mut Parser<'a>,
+) -> OptionParseResult<Vec<Spanning<&'a str>>> {
+    if parser.peek().item != Token::Name("implements") {
+        return Ok(None);
+    }
+
+    let Spanning {
+        start: start_pos, ..
+    } = parser.next_token()?;
+    skip_optional(parser, &Token::Amp)?;
+    let mut interfaces = vec![parser.expect_name()?];
+    while skip_optional(parser, &Token::Amp)? {
+        interfaces.push(parser.expect_name()?);
+    }
+
+    Ok(Some(Spanning::start_end(
+        &start_pos,
+        &interfaces.last().unwrap().end.clone(),
+        interfaces,
+    )))
+}
+
+fn parse_union_member_types<'a>(parser: &mut Parser<'a>) -> ParseResult<Vec<Spanning<&'a str>>> {
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::Equals)?;
+    skip_optional(parser, &Token::Pipe)?;
+    let mut members = vec![parser.expect_name()?];
+    while skip_optional(parser, &Token::Pipe)? {
+        members.push(parser.expect_name()?);
+    }
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &members.last().unwrap().end.clone(),
+        members,
+    ))
+}
+
+fn parse_optional_definitions<'a, T, F>(
+    parser: &mut Parser<'a>,
+    parse_definition: F,
+) -> OptionParseResult<Vec<Spanning<T>>>
+where
+    T: std::fmt::Debug,
+    F: Fn(&mut Parser<'a>) -> ParseResult<T>,
+{
+    if parser.peek().item != Token::CurlyOpen {
+        return Ok(None);
+    }
+
+    Ok(Some(parser.delimited_nonempty_list(
+        &Token::CurlyOpen,
+        parse_definition,
+        &Token::CurlyClose,
+    )?))
+}
+
+fn parse_field_definition<'a, S>(parser: &mut Parser<'a>) -> ParseResult<FieldDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description(parser)?;
+    let name = parser.expect_name()?;
+    let arguments = parse_arguments_definition(parser)?;
+    parser.expect(&Token::Colon)?;
+    let field_type = parse_type(parser)?;
+    let directives = parse_const_directives(parser)?;
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(name.start, |s| s.start),
+        &directives.as_ref().map_or(field_type.end, |s| s.end),
+        FieldDefinition {
+            description,
+            name,
+            arguments,
+            field_type,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_arguments_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> OptionParseResult<Vec<Spanning<InputValueDefinition<'a, S>>>>
+where
+    S: ScalarValue,
+{
+    if parser.peek().item != Token::ParenOpen {
+        return Ok(None);
+    }
+
+    Ok(Some(parser.delimited_nonempty_list(
+        &Token::ParenOpen,
+        parse_input_value_definition,
+        &Token::ParenClose,
+    )?))
+}
+
+fn parse_input_value_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<InputValueDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description(parser)?;
+    let name = parser.expect_name()?;
+    parser.expect(&Token::Colon)?;
+    let value_type = parse_type(parser)?;
+    let default_value = if skip_optional(parser, &Token::Equals)? {
+        Some(parse_value_literal(parser, true, None, None)?)
+    } else {
+        None
+    };
+    let directives = parse_const_directives(parser)?;
+
+    let start_pos = description.as_ref().map_or(name.start, |s| s.start);
+    let end_pos = directives
+        .as_ref()
+        .map(|s| s.end)
+        .or_else(|| default_value.as_ref().map(|s| s.end))
+        .unwrap_or(value_type.end);
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &end_pos,
+        InputValueDefinition {
+            description,
+            name,
+            value_type,
+            default_value,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_enum_value_definition<'a, S>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<EnumValueDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    let description = parse_description(parser)?;
+    if let Token::Name("true" | "false" | "null") = parser.peek().item {
+        return Err(parser.next_token()?.map(ParseError::unexpected_token));
+    }
+    let name = parser.expect_name()?;
+    let directives = parse_const_directives(parser)?;
+
+    Ok(Spanning::start_end(
+        &description.as_ref().map_or(name.start, |s| s.start),
+        &directives.as_ref().map_or(name.end, |s| s.end),
+        EnumValueDefinition {
+            description,
+            name,
+            directives: directives.map(|s| s.item),
+        },
+    ))
+}
+
+fn parse_directive_definition<'a, S>(
+    parser: &mut Parser<'a>,
+    start_pos: SourcePosition,
+    description: Option<Spanning<Cow<'a, str>>>,
+) -> ParseResult<DirectiveDefinition<'a, S>>
+where
+    S: ScalarValue,
+{
+    parser.expect(&Token::Name("directive"))?;
+    parser.expect(&Token::At)?;
+    let name = parser.expect_name()?;
+    let arguments = parse_arguments_definition(parser)?;
+    let is_repeatable = skip_optional(parser, &Token::Name("repeatable"))?;
+    parser.expect(&Token::Name("on"))?;
+    skip_optional(parser, &Token::Pipe)?;
+    let mut locations = vec![parse_directive_location(parser)?];
+    while skip_optional(parser, &Token::Pipe)? {
+        locations.push(parse_directive_location(parser)?);
+    }
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &locations.last().unwrap().end.clone(),
+        DirectiveDefinition {
+            description,
+            name,
+            arguments,
+            is_repeatable,
+            locations,
+        },
+    ))
+}
+
+fn parse_directive_location(parser: &mut Parser<'_>) -> ParseResult<DirectiveLocation> {
+    let name = parser.expect_name()?;
+    let location = match name.item {
+        "QUERY" => DirectiveLocation::Query,
+        "MUTATION" => DirectiveLocation::Mutation,
+        "SUBSCRIPTION" => DirectiveLocation::Subscription,
+        "FIELD" => DirectiveLocation::Field,
+        "FRAGMENT_DEFINITION" => DirectiveLocation::FragmentDefinition,
+        "FRAGMENT_SPREAD" => DirectiveLocation::FragmentSpread,
+        "INLINE_FRAGMENT" => DirectiveLocation::InlineFragment,
+        "VARIABLE_DEFINITION" => DirectiveLocation::VariableDefinition,
+        "SCHEMA" => DirectiveLocation::Schema,
+        "SCALAR" => DirectiveLocation::Scalar,
+        "OBJECT" => DirectiveLocation::Object,
+        "FIELD_DEFINITION" => DirectiveLocation::FieldDefinition,
+        "ARGUMENT_DEFINITION" => DirectiveLocation::ArgumentDefinition,
+        "INTERFACE" => DirectiveLocation::Interface,
+        "UNION" => DirectiveLocation::Union,
+        "ENUM" => DirectiveLocation::Enum,
+        "ENUM_VALUE" => DirectiveLocation::EnumValue,
+        "INPUT_OBJECT" => DirectiveLocation::InputObject,
+        "INPUT_FIELD_DEFINITION" => DirectiveLocation::InputFieldDefinition,
+        _ => return Err(name.map(|n| ParseError::UnexpectedToken(n.into()))),
+    };
+
+    Ok(Spanning::start_end(&name.start, &name.end, location))
+}
+
+fn parse_const_directives<'a, S>(
+    parser: &mut Parser<'a>,
+) -> OptionParseResult<Vec<Spanning<Directive<'a, S>>>>
+where
+    S: ScalarValue,
+{
+    if parser.peek().item != Token::At {
+        Ok(None)
+    } else {
+        let mut items = Vec::new();
+        while parser.peek().item == Token::At {
+            items.push(parse_const_directive(parser)?);
+        }
+
+        Ok(Spanning::spanning(items))
+    }
+}
+
+fn parse_const_directive<'a, S>(parser: &mut Parser<'a>) -> ParseResult<Directive<'a, S>>
+where
+    S: ScalarValue,
+{
+    let Spanning {
+        start: start_pos, ..
+    } = parser.expect(&Token::At)?;
+    let name = parser.expect_name()?;
+
+    let arguments = if parser.peek().item == Token::ParenOpen {
+        Some(
+            parser
+                .delimited_nonempty_list(
+                    &Token::ParenOpen,
+                    parse_const_argument,
+                    &Token::ParenClose,
+                )?
+                .map(|args| Arguments {
+                    items: args.into_iter().map(|s| s.item).collect(),
+                }),
+        )
+    } else {
+        None
+    };
+
+    Ok(Spanning::start_end(
+        &start_pos,
+        &arguments.as_ref().map_or(&name.end, |s| &s.end).clone(),
+        Directive { name, arguments },
+    ))
+}
+
+fn parse_const_argument<'a, S>(
+    parser: &mut Parser<'a>,
+) -> ParseResult<(Spanning<&'a str>, Spanning<InputValue<S>>)>
+where
+    S: ScalarValue,
+{
+    let name = parser.expect_name()?;
+    parser.expect(&Token::Colon)?;
+    let value = parse_value_literal(parser, true, None, None)?;
+
+    Ok(Spanning::start_end(
+        &name.start.clone(),
+        &value.end.clone(),
+        (name, value),
+    ))
+}
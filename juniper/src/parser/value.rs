@@ -6,13 +6,13 @@ use crate::{
         meta::{InputObjectMeta, MetaType},
         model::SchemaType,
     },
-    value::ScalarValue,
+    value::{ParseScalarValue, ScalarValue},
 };
 
 pub fn parse_value_literal<'b, S>(
     parser: &mut Parser<'_>,
     is_const: bool,
-    schema: &'b SchemaType<'b, S>,
+    schema: Option<&'b SchemaType<'b, S>>,
     tpe: Option<&MetaType<'b, S>>,
 ) -> ParseResult<InputValue<S>>
 where
@@ -120,7 +120,7 @@ where
 fn parse_list_literal<'b, S>(
     parser: &mut Parser<'_>,
     is_const: bool,
-    schema: &'b SchemaType<'b, S>,
+    schema: Option<&'b SchemaType<'b, S>>,
     tpe: Option<&MetaType<'b, S>>,
 ) -> ParseResult<InputValue<S>>
 where
@@ -138,7 +138,7 @@ where
 fn parse_object_literal<'b, S>(
     parser: &mut Parser<'_>,
     is_const: bool,
-    schema: &'b SchemaType<'b, S>,
+    schema: Option<&'b SchemaType<'b, S>>,
     object_tpe: Option<&InputObjectMeta<'b, S>>,
 ) -> ParseResult<InputValue<S>>
 where
@@ -156,7 +156,7 @@ where
 fn parse_object_field<'b, S>(
     parser: &mut Parser<'_>,
     is_const: bool,
-    schema: &'b SchemaType<'b, S>,
+    schema: Option<&'b SchemaType<'b, S>>,
     object_meta: Option<&InputObjectMeta<'b, S>>,
 ) -> ParseResult<(Spanning<String>, Spanning<InputValue<S>>)>
 where
@@ -166,7 +166,7 @@ where
 
     let tpe = object_meta
         .and_then(|o| o.input_fields.iter().find(|f| f.name == key.item))
-        .and_then(|f| schema.lookup_type(&f.arg_type));
+        .and_then(|f| schema?.lookup_type(&f.arg_type));
 
     parser.expect(&Token::Colon)?;
 
@@ -203,13 +203,13 @@ fn parse_scalar_literal_by_infered_type<'b, S>(
     token: ScalarToken<'_>,
     start: &SourcePosition,
     end: &SourcePosition,
-    schema: &'b SchemaType<'b, S>,
+    schema: Option<&'b SchemaType<'b, S>>,
 ) -> ParseResult<InputValue<S>>
 where
     S: ScalarValue,
 {
-    let result = match token {
-        ScalarToken::String(_) => {
+    let result = match (token, schema) {
+        (ScalarToken::String(_), Some(schema)) => {
             if let Some(&MetaType::Scalar(ref s)) = schema.concrete_type_by_name("String") {
                 (s.parse_fn)(token).map(InputValue::Scalar)
             } else {
@@ -218,7 +218,7 @@ where
                 ))
             }
         }
-        ScalarToken::Int(_) => {
+        (ScalarToken::Int(_), Some(schema)) => {
             if let Some(&MetaType::Scalar(ref s)) = schema.concrete_type_by_name("Int") {
                 (s.parse_fn)(token).map(InputValue::Scalar)
             } else {
@@ -227,7 +227,7 @@ where
                 ))
             }
         }
-        ScalarToken::Float(_) => {
+        (ScalarToken::Float(_), Some(schema)) => {
             if let Some(&MetaType::Scalar(ref s)) = schema.concrete_type_by_name("Float") {
                 (s.parse_fn)(token).map(InputValue::Scalar)
             } else {
@@ -236,6 +236,17 @@ where
                 ))
             }
         }
+        // Without a schema (e.g. in type system documents) literals are parsed
+        // with the built-in scalars directly.
+        (ScalarToken::String(_), None) => {
+            <String as ParseScalarValue<S>>::from_str(token).map(InputValue::Scalar)
+        }
+        (ScalarToken::Int(_), None) => {
+            <i32 as ParseScalarValue<S>>::from_str(token).map(InputValue::Scalar)
+        }
+        (ScalarToken::Float(_), None) => {
+            <f64 as ParseScalarValue<S>>::from_str(token).map(InputValue::Scalar)
+        }
     };
     result
         .map(|s| Spanning::start_end(start, end, s))
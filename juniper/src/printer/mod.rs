@@ -0,0 +1,409 @@
+//! Printing of executable GraphQL documents back into their source text.
+
+mod normalize;
+mod signature;
+
+use crate::{
+    ast::{
+        Arguments, Definition, Directive, Document, Fragment, InputValue, Operation, OperationType,
+        Selection, VariableDefinitions,
+    },
+    parser::Spanning,
+    value::ScalarValue,
+};
+
+pub use self::{normalize::normalize_document, signature::operation_signature};
+
+/// Prints the provided executable [`Document`] as a human-readable GraphQL
+/// source, indented with two spaces.
+///
+/// Only operations and fragments are printed, while type system definitions
+/// are ignored.
+///
+/// ```rust
+/// # use juniper::{parser::parse_document_source, print_document, EmptyMutation, EmptySubscription, RootNode};
+/// # struct Query;
+/// # #[juniper::graphql_object]
+/// # impl Query {
+/// #     fn hero(id: i32) -> i32 { id }
+/// # }
+/// # let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new());
+/// let doc = parse_document_source("query Hero{hero(id:1)}", &schema.schema).unwrap();
+///
+/// assert_eq!(print_document(&doc), "query Hero {\n  hero(id: 1)\n}");
+/// ```
+pub fn print_document<S: ScalarValue>(doc: &Document<S>) -> String {
+    Printer::new(false).document(doc)
+}
+
+/// Prints the provided executable [`Document`] as a GraphQL source on a single
+/// line, omitting all the insignificant whitespace.
+///
+/// Only operations and fragments are printed, while type system definitions
+/// are ignored.
+pub fn print_document_minified<S: ScalarValue>(doc: &Document<S>) -> String {
+    Printer::new(true).document(doc)
+}
+
+/// Printer of executable GraphQL documents.
+struct Printer {
+    /// Printed GraphQL source.
+    out: String,
+
+    /// Indicator whether the insignificant whitespace should be omitted.
+    minified: bool,
+
+    /// Current indentation level.
+    indent: usize,
+}
+
+impl Printer {
+    fn new(minified: bool) -> Self {
+        Self {
+            out: String::new(),
+            minified,
+            indent: 0,
+        }
+    }
+
+    fn document<S: ScalarValue>(mut self, doc: &Document<S>) -> String {
+        let mut is_first = true;
+        for def in doc {
+            if !matches!(def, Definition::Operation(_) | Definition::Fragment(_)) {
+                continue;
+            }
+            if !is_first {
+                self.out.push_str(if self.minified { " " } else { "\n\n" });
+            }
+            is_first = false;
+
+            match def {
+                Definition::Operation(op) => self.operation(&op.item),
+                Definition::Fragment(f) => self.fragment(&f.item),
+                _ => {}
+            }
+        }
+        self.out
+    }
+
+    fn operation<S: ScalarValue>(&mut self, op: &Operation<S>) {
+        let is_shorthand = op.operation_type == OperationType::Query
+            && op.name.is_none()
+            && op.variable_definitions.is_none()
+            && op.directives.is_none();
+        if !is_shorthand {
+            self.out.push_str(match op.operation_type {
+                OperationType::Query => "query",
+                OperationType::Mutation => "mutation",
+                OperationType::Subscription => "subscription",
+            });
+            if let Some(name) = &op.name {
+                self.out.push(' ');
+                self.out.push_str(name.item);
+            }
+            if let Some(vars) = &op.variable_definitions {
+                self.variable_definitions(&vars.item);
+            }
+            self.directives(op.directives.as_deref());
+            self.space();
+        }
+        self.selection_set(&op.selection_set);
+    }
+
+    fn fragment<S: ScalarValue>(&mut self, f: &Fragment<S>) {
+        self.out.push_str("fragment ");
+        self.out.push_str(f.name.item);
+        self.out.push_str(" on ");
+        self.out.push_str(f.type_condition.item);
+        self.directives(f.directives.as_deref());
+        self.space();
+        self.selection_set(&f.selection_set);
+    }
+
+    fn variable_definitions<S: ScalarValue>(&mut self, vars: &VariableDefinitions<S>) {
+        if vars.items.is_empty() {
+            return;
+        }
+
+        self.out.push('(');
+        for (i, (name, def)) in vars.items.iter().enumerate() {
+            if i > 0 {
+                self.separator();
+            }
+            self.out.push('$');
+            self.out.push_str(name.item);
+            self.colon();
+            self.out.push_str(&def.var_type.item.to_string());
+            if let Some(default) = &def.default_value {
+                self.out.push_str(if self.minified { "=" } else { " = " });
+                self.value(&default.item);
+            }
+            self.directives(def.directives.as_deref());
+        }
+        self.out.push(')');
+    }
+
+    fn selection_set<S: ScalarValue>(&mut self, selections: &[Selection<S>]) {
+        self.out.push('{');
+        self.indent += 1;
+        for (i, sel) in selections.iter().enumerate() {
+            if self.minified {
+                if i > 0 {
+                    self.out.push(' ');
+                }
+            } else {
+                self.newline();
+            }
+            self.selection(sel);
+        }
+        self.indent -= 1;
+        if !self.minified {
+            self.newline();
+        }
+        self.out.push('}');
+    }
+
+    fn selection<S: ScalarValue>(&mut self, sel: &Selection<S>) {
+        match sel {
+            Selection::Field(f) => {
+                let f = &f.item;
+                if let Some(alias) = &f.alias {
+                    self.out.push_str(alias.item);
+                    self.colon();
+                }
+                self.out.push_str(f.name.item);
+                self.arguments(f.arguments.as_ref().map(|a| &a.item));
+                self.directives(f.directives.as_deref());
+                if let Some(selection_set) = &f.selection_set {
+                    self.space();
+                    self.selection_set(selection_set);
+                }
+            }
+            Selection::FragmentSpread(s) => {
+                self.out.push_str("...");
+                self.out.push_str(s.item.name.item);
+                self.directives(s.item.directives.as_deref());
+            }
+            Selection::InlineFragment(f) => {
+                let f = &f.item;
+                self.out.push_str("...");
+                if let Some(cond) = &f.type_condition {
+                    self.space();
+                    self.out.push_str("on ");
+                    self.out.push_str(cond.item);
+                }
+                self.directives(f.directives.as_deref());
+                self.space();
+                self.selection_set(&f.selection_set);
+            }
+        }
+    }
+
+    fn directives<S: ScalarValue>(&mut self, directives: Option<&[Spanning<Directive<S>>]>) {
+        for d in directives.unwrap_or_default() {
+            self.space();
+            self.out.push('@');
+            self.out.push_str(d.item.name.item);
+            self.arguments(d.item.arguments.as_ref().map(|a| &a.item));
+        }
+    }
+
+    fn arguments<S: ScalarValue>(&mut self, args: Option<&Arguments<S>>) {
+        let args = match args {
+            Some(args) if !args.items.is_empty() => args,
+            _ => return,
+        };
+
+        self.out.push('(');
+        for (i, (name, value)) in args.items.iter().enumerate() {
+            if i > 0 {
+                self.separator();
+            }
+            self.out.push_str(name.item);
+            self.colon();
+            self.value(&value.item);
+        }
+        self.out.push(')');
+    }
+
+    fn value<S: ScalarValue>(&mut self, value: &InputValue<S>) {
+        match value {
+            InputValue::Null => self.out.push_str("null"),
+            InputValue::Scalar(s) => self.scalar(s),
+            InputValue::Enum(e) => self.out.push_str(e),
+            InputValue::Variable(v) => {
+                self.out.push('$');
+                self.out.push_str(v);
+            }
+            InputValue::List(items) => {
+                self.out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.separator();
+                    }
+                    self.value(&item.item);
+                }
+                self.out.push(']');
+            }
+            InputValue::Object(fields) => {
+                self.out.push('{');
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.separator();
+                    }
+                    self.out.push_str(&name.item);
+                    self.colon();
+                    self.value(&value.item);
+                }
+                self.out.push('}');
+            }
+        }
+    }
+
+    fn scalar<S: ScalarValue>(&mut self, s: &S) {
+        if let Some(s) = s.as_str() {
+            self.string(s);
+        } else if let Some(b) = s.as_bool() {
+            self.out.push_str(if b { "true" } else { "false" });
+        } else if let Some(i) = s.as_int() {
+            self.out.push_str(&i.to_string());
+        } else if let Some(f) = s.as_float() {
+            let f = f.to_string();
+            self.out.push_str(&f);
+            // Keep the value being a float literal when parsed back.
+            if f.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+                self.out.push_str(".0");
+            }
+        } else {
+            self.out.push_str(&s.to_string());
+        }
+    }
+
+    fn string(&mut self, s: &str) {
+        self.out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => self.out.push_str("\\\""),
+                '\\' => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{0008}' => self.out.push_str("\\b"),
+                '\u{000C}' => self.out.push_str("\\f"),
+                c if c < ' ' => self.out.push_str(&format!("\\u{:04X}", c as u32)),
+                c => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.indent {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn space(&mut self) {
+        if !self.minified {
+            self.out.push(' ');
+        }
+    }
+
+    fn colon(&mut self) {
+        self.out.push_str(if self.minified { ":" } else { ": " });
+    }
+
+    fn separator(&mut self) {
+        self.out.push_str(if self.minified { "," } else { ", " });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::parse_document_source,
+        schema::model::SchemaType,
+        validation::test_harness::{MutationRoot, QueryRoot, SubscriptionRoot},
+        value::DefaultScalarValue,
+    };
+
+    use super::{print_document, print_document_minified};
+
+    const SOURCE: &str = r#"
+        query Q($id: Int = 4, $flag: Boolean!) @dir {
+          a: dog(id: $id, name: "Rex \"the\" dog\n", obj: {x: [1, 2.5, RED, null]}) @skip(if: $flag) {
+            name
+            ...DogFields
+            ... on Dog @include(if: true) { barkVolume }
+            ... { nickname }
+          }
+        }
+
+        { human { name } }
+
+        fragment DogFields on Dog { isHousetrained(atOtherHomes: false) }
+    "#;
+
+    fn parse_and<R>(s: &str, f: impl FnOnce(&crate::ast::Document<DefaultScalarValue>) -> R) -> R {
+        let schema = SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &());
+        f(&parse_document_source(s, &schema).expect("Parse error"))
+    }
+
+    #[test]
+    fn prints_pretty() {
+        assert_eq!(
+            parse_and(SOURCE, |doc| print_document(doc)),
+            r#"query Q($id: Int = 4, $flag: Boolean!) @dir {
+  a: dog(id: $id, name: "Rex \"the\" dog\n", obj: {x: [1, 2.5, RED, null]}) @skip(if: $flag) {
+    name
+    ...DogFields
+    ... on Dog @include(if: true) {
+      barkVolume
+    }
+    ... {
+      nickname
+    }
+  }
+}
+
+{
+  human {
+    name
+  }
+}
+
+fragment DogFields on Dog {
+  isHousetrained(atOtherHomes: false)
+}"#,
+        );
+    }
+
+    #[test]
+    fn prints_minified() {
+        assert_eq!(
+            parse_and(SOURCE, |doc| print_document_minified(doc)),
+            "query Q($id:Int=4,$flag:Boolean!)@dir{\
+             a:dog(id:$id,name:\"Rex \\\"the\\\" dog\\n\",obj:{x:[1,2.5,RED,null]})@skip(if:$flag)\
+             {name ...DogFields ...on Dog@include(if:true){barkVolume} ...{nickname}}} \
+             {human{name}} \
+             fragment DogFields on Dog{isHousetrained(atOtherHomes:false)}",
+        );
+    }
+
+    #[test]
+    fn reparses_printed() {
+        parse_and(SOURCE, |doc| {
+            let pretty = print_document(doc);
+            let minified = print_document_minified(doc);
+
+            parse_and(&pretty, |reparsed| {
+                assert_eq!(print_document(reparsed), pretty);
+                assert_eq!(print_document_minified(reparsed), minified);
+            });
+            parse_and(&minified, |reparsed| {
+                assert_eq!(print_document(reparsed), pretty);
+            });
+        });
+    }
+}
@@ -0,0 +1,263 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use crate::{
+    ast::{Arguments, Definition, Directive, Document, InputValue, OwnedDocument, Selection},
+    parser::Spanning,
+    value::ScalarValue,
+};
+
+/// Normalizes the provided executable [`Document`], so semantically equal
+/// documents are printed the same way.
+///
+/// Normalization:
+/// - keeps operations in their original order;
+/// - keeps only fragments used by the operations (directly or transitively),
+///   ordered by their names;
+/// - sorts selections (fields by their response keys, then fragment spreads by
+///   their names, then inline fragments by their type conditions);
+/// - sorts arguments of fields and directives, and fields of input object
+///   literals by their names.
+///
+/// Type system definitions are stripped out.
+pub fn normalize_document<'a, S: ScalarValue>(doc: &Document<'a, S>) -> OwnedDocument<'a, S> {
+    let fragments = doc
+        .iter()
+        .filter_map(|def| match def {
+            Definition::Fragment(f) => Some(&f.item),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut used = HashSet::new();
+    let mut pending = Vec::new();
+    for def in doc {
+        if let Definition::Operation(op) = def {
+            collect_spreads(&op.item.selection_set, &mut pending);
+        }
+    }
+    while let Some(name) = pending.pop() {
+        if used.insert(name) {
+            if let Some(f) = fragments.iter().find(|f| f.name.item == name) {
+                collect_spreads(&f.selection_set, &mut pending);
+            }
+        }
+    }
+
+    let mut used_fragments = doc
+        .iter()
+        .filter(|def| matches!(def, Definition::Fragment(f) if used.contains(f.item.name.item)))
+        .cloned()
+        .collect::<Vec<_>>();
+    used_fragments.sort_by_key(|def| match def {
+        Definition::Fragment(f) => f.item.name.item,
+        _ => unreachable!("only fragments are retained"),
+    });
+
+    let mut normalized = doc
+        .iter()
+        .filter(|def| matches!(def, Definition::Operation(_)))
+        .cloned()
+        .chain(used_fragments)
+        .collect::<Vec<_>>();
+    for def in &mut normalized {
+        match def {
+            Definition::Operation(op) => {
+                sort_directives(op.item.directives.as_deref_mut());
+                sort_selection_set(&mut op.item.selection_set);
+            }
+            Definition::Fragment(f) => {
+                sort_directives(f.item.directives.as_deref_mut());
+                sort_selection_set(&mut f.item.selection_set);
+            }
+            _ => {}
+        }
+    }
+    normalized
+}
+
+/// Collects names of the fragments spread in the provided `selections`.
+fn collect_spreads<'a, S>(selections: &[Selection<'a, S>], names: &mut Vec<&'a str>) {
+    for sel in selections {
+        match sel {
+            Selection::Field(f) => {
+                if let Some(selection_set) = &f.item.selection_set {
+                    collect_spreads(selection_set, names);
+                }
+            }
+            Selection::FragmentSpread(s) => names.push(s.item.name.item),
+            Selection::InlineFragment(f) => collect_spreads(&f.item.selection_set, names),
+        }
+    }
+}
+
+fn sort_selection_set<S>(selections: &mut [Selection<S>]) {
+    for sel in selections.iter_mut() {
+        match sel {
+            Selection::Field(f) => {
+                sort_arguments(f.item.arguments.as_mut().map(|a| &mut a.item));
+                sort_directives(f.item.directives.as_deref_mut());
+                if let Some(selection_set) = &mut f.item.selection_set {
+                    sort_selection_set(selection_set);
+                }
+            }
+            Selection::FragmentSpread(s) => sort_directives(s.item.directives.as_deref_mut()),
+            Selection::InlineFragment(f) => {
+                sort_directives(f.item.directives.as_deref_mut());
+                sort_selection_set(&mut f.item.selection_set);
+            }
+        }
+    }
+    selections.sort_by(compare_selections);
+}
+
+fn compare_selections<S>(a: &Selection<S>, b: &Selection<S>) -> Ordering {
+    fn key<'a, S>(sel: &Selection<'a, S>) -> (u8, &'a str, &'a str) {
+        match sel {
+            Selection::Field(f) => (
+                0,
+                f.item.alias.as_ref().unwrap_or(&f.item.name).item,
+                f.item.name.item,
+            ),
+            Selection::FragmentSpread(s) => (1, s.item.name.item, ""),
+            Selection::InlineFragment(f) => {
+                (2, f.item.type_condition.map(|c| c.item).unwrap_or(""), "")
+            }
+        }
+    }
+
+    key(a).cmp(&key(b))
+}
+
+/// Sorts arguments of the provided `directives`, while retaining the order of
+/// the `directives` themselves, as it may be meaningful.
+fn sort_directives<S>(directives: Option<&mut [Spanning<Directive<S>>]>) {
+    for d in directives.unwrap_or_default() {
+        sort_arguments(d.item.arguments.as_mut().map(|a| &mut a.item));
+    }
+}
+
+fn sort_arguments<S>(args: Option<&mut Arguments<S>>) {
+    if let Some(args) = args {
+        for (_, value) in &mut args.items {
+            sort_input_value(&mut value.item);
+        }
+        args.items.sort_by(|(a, _), (b, _)| a.item.cmp(b.item));
+    }
+}
+
+fn sort_input_value<S>(value: &mut InputValue<S>) {
+    match value {
+        InputValue::List(items) => {
+            for item in items {
+                sort_input_value(&mut item.item);
+            }
+        }
+        InputValue::Object(fields) => {
+            for (_, value) in fields.iter_mut() {
+                sort_input_value(&mut value.item);
+            }
+            fields.sort_by(|(a, _), (b, _)| a.item.cmp(&b.item));
+        }
+        InputValue::Null
+        | InputValue::Scalar(_)
+        | InputValue::Enum(_)
+        | InputValue::Variable(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::parse_document_source,
+        printer::print_document,
+        schema::model::SchemaType,
+        validation::test_harness::{MutationRoot, QueryRoot, SubscriptionRoot},
+        value::DefaultScalarValue,
+    };
+
+    use super::normalize_document;
+
+    fn normalize(s: &str) -> String {
+        let schema = SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &());
+        let doc = parse_document_source::<DefaultScalarValue>(s, &schema).expect("Parse error");
+        print_document(&normalize_document(&doc))
+    }
+
+    #[test]
+    fn sorts_selections_and_arguments() {
+        assert_eq!(
+            normalize(
+                r#"
+                query Q @dir(b: 2, a: 1) {
+                    ... on Dog { name }
+                    zed: dog { name }
+                    ...B
+                    ...A
+                    cat(name: "x", age: {z: 1, y: [{d: 4, c: 3}]}) { name }
+                    alpha: human { name }
+                }
+                fragment B on QueryRoot { b }
+                fragment A on QueryRoot { a }
+                "#,
+            ),
+            r#"query Q @dir(a: 1, b: 2) {
+  alpha: human {
+    name
+  }
+  cat(age: {y: [{c: 3, d: 4}], z: 1}, name: "x") {
+    name
+  }
+  zed: dog {
+    name
+  }
+  ...A
+  ...B
+  ... on Dog {
+    name
+  }
+}
+
+fragment A on QueryRoot {
+  a
+}
+
+fragment B on QueryRoot {
+  b
+}"#,
+        );
+    }
+
+    #[test]
+    fn strips_unused_fragments() {
+        assert_eq!(
+            normalize(
+                r#"
+                fragment Unused on Dog { ...AlsoUsed name }
+                { dog { ...Used } }
+                fragment AlsoUsed on Dog { barkVolume }
+                fragment Used on Dog { ...AlsoUsed ...Used }
+                "#,
+            ),
+            r#"{
+  dog {
+    ...Used
+  }
+}
+
+fragment AlsoUsed on Dog {
+  barkVolume
+}
+
+fragment Used on Dog {
+  ...AlsoUsed
+  ...Used
+}"#,
+        );
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let once = normalize("{ b a(y: 1, x: 2) ...F } fragment F on QueryRoot { d c }");
+        assert_eq!(normalize(&once), once);
+    }
+}
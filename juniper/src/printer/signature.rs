@@ -0,0 +1,204 @@
+use crate::{
+    ast::{Arguments, Definition, Directive, Document, InputValue, Selection},
+    executor::get_operation,
+    parser::Spanning,
+    printer::{normalize_document, print_document_minified},
+    value::ScalarValue,
+    GraphQLError,
+};
+
+/// Computes a stable signature of the operation from the provided executable
+/// [`Document`], suitable for logging, metrics keys and persisted queries
+/// manifests.
+///
+/// The signature is the minified source of the [normalized] document containing
+/// only the requested operation and the fragments it uses, with all the literal
+/// values replaced by placeholders: numbers with `0`, strings with `""`, lists
+/// with `[]` and input objects with `{}` (booleans, enum values and `null`s are
+/// retained as they usually define the shape of the operation).
+///
+/// The operation is selected by its `operation_name` the same way as for its
+/// execution.
+///
+/// # Errors
+///
+/// If the operation cannot be selected from the provided [`Document`].
+///
+/// [normalized]: normalize_document
+pub fn operation_signature<S: ScalarValue>(
+    doc: &Document<S>,
+    operation_name: Option<&str>,
+) -> Result<String, GraphQLError> {
+    let operation = get_operation(doc, operation_name)?;
+
+    let doc = doc
+        .iter()
+        .filter(|def| match def {
+            Definition::Operation(op) => std::ptr::eq(op, operation),
+            Definition::Fragment(_) => true,
+            _ => false,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let mut doc = normalize_document(&doc);
+    for def in &mut doc {
+        match def {
+            Definition::Operation(op) => {
+                if let Some(vars) = &mut op.item.variable_definitions {
+                    for (_, var) in &mut vars.item.items {
+                        if let Some(default) = &mut var.default_value {
+                            hide_literals(&mut default.item);
+                        }
+                        hide_directives_literals(var.directives.as_deref_mut());
+                    }
+                }
+                hide_directives_literals(op.item.directives.as_deref_mut());
+                hide_selection_set_literals(&mut op.item.selection_set);
+            }
+            Definition::Fragment(f) => {
+                hide_directives_literals(f.item.directives.as_deref_mut());
+                hide_selection_set_literals(&mut f.item.selection_set);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(print_document_minified(&doc))
+}
+
+fn hide_selection_set_literals<S: ScalarValue>(selections: &mut [Selection<S>]) {
+    for sel in selections {
+        match sel {
+            Selection::Field(f) => {
+                hide_arguments_literals(f.item.arguments.as_mut().map(|a| &mut a.item));
+                hide_directives_literals(f.item.directives.as_deref_mut());
+                if let Some(selection_set) = &mut f.item.selection_set {
+                    hide_selection_set_literals(selection_set);
+                }
+            }
+            Selection::FragmentSpread(s) => {
+                hide_directives_literals(s.item.directives.as_deref_mut());
+            }
+            Selection::InlineFragment(f) => {
+                hide_directives_literals(f.item.directives.as_deref_mut());
+                hide_selection_set_literals(&mut f.item.selection_set);
+            }
+        }
+    }
+}
+
+fn hide_directives_literals<S: ScalarValue>(directives: Option<&mut [Spanning<Directive<S>>]>) {
+    for d in directives.unwrap_or_default() {
+        hide_arguments_literals(d.item.arguments.as_mut().map(|a| &mut a.item));
+    }
+}
+
+fn hide_arguments_literals<S: ScalarValue>(args: Option<&mut Arguments<S>>) {
+    for (_, value) in args.map(|a| &mut a.items[..]).unwrap_or_default() {
+        hide_literals(&mut value.item);
+    }
+}
+
+fn hide_literals<S: ScalarValue>(value: &mut InputValue<S>) {
+    match value {
+        InputValue::Scalar(s) => {
+            if s.as_str().is_some() {
+                *s = String::new().into();
+            } else if s.as_bool().is_none() {
+                *s = 0.into();
+            }
+        }
+        InputValue::List(items) => items.clear(),
+        InputValue::Object(fields) => fields.clear(),
+        InputValue::Null | InputValue::Enum(_) | InputValue::Variable(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::parse_document_source,
+        schema::model::SchemaType,
+        validation::test_harness::{MutationRoot, QueryRoot, SubscriptionRoot},
+        value::DefaultScalarValue,
+        GraphQLError,
+    };
+
+    use super::operation_signature;
+
+    fn signature(s: &str, operation_name: Option<&str>) -> Result<String, GraphQLError> {
+        let schema = SchemaType::new::<QueryRoot, MutationRoot, SubscriptionRoot>(&(), &(), &());
+        let doc = parse_document_source::<DefaultScalarValue>(s, &schema).expect("Parse error");
+        operation_signature(&doc, operation_name)
+    }
+
+    #[test]
+    fn replaces_literals() {
+        assert_eq!(
+            signature(
+                r#"
+                query Q($limit: Int = 10, $ids: [ID!] = ["1", "2"]) {
+                    dog(name: "Rex", age: 3, weight: 1.5, tags: ["a"], filter: {x: 1}) @include(if: true) {
+                        name(surname: false, color: RED, nickname: null, id: $id)
+                    }
+                }
+                "#,
+                None,
+            ),
+            Ok(
+                r#"query Q($limit:Int=0,$ids:[ID!]=[]){dog(age:0,filter:{},name:"",tags:[],weight:0)@include(if:true){name(color:RED,id:$id,nickname:null,surname:false)}}"#
+                    .into(),
+            ),
+        );
+    }
+
+    #[test]
+    fn is_stable_across_equivalent_documents() {
+        let first = signature(
+            r#"
+            query A { ...F human(id: 1) { name } }
+            query B { cat(name: "Tom") { name } }
+            fragment F on QueryRoot { dog(name: "Rex") { name } }
+            fragment Unused on QueryRoot { cat { name } }
+            "#,
+            Some("A"),
+        );
+        let second = signature(
+            r#"
+            fragment F on QueryRoot {
+                dog(name: "Rufus") {
+                    name
+                }
+            }
+            query A {
+                human(id: 42) {
+                    name
+                }
+                ...F
+            }
+            "#,
+            None,
+        );
+
+        assert_eq!(
+            first,
+            Ok(
+                "query A{human(id:0){name} ...F} fragment F on QueryRoot{dog(name:\"\"){name}}"
+                    .into()
+            ),
+        );
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn fails_on_unknown_operation() {
+        assert_eq!(
+            signature("query A { dog { name } }", Some("B")),
+            Err(GraphQLError::UnknownOperationName),
+        );
+        assert_eq!(
+            signature("query A { dog { name } } query B { dog { name } }", None),
+            Err(GraphQLError::MultipleOperationsProvided),
+        );
+    }
+}
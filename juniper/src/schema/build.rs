@@ -0,0 +1,1098 @@
+//! Building of a [`SchemaType`] out of a [type system document][0] (SDL) or an
+//! [introspection][1] result, rather than out of Rust types.
+//!
+//! Such a [`SchemaType`] cannot resolve anything, but is enough to validate
+//! operations against it, like the [`Linter`] does.
+//!
+//! [`Linter`]: crate::validation::Linter
+//! [0]: https://spec.graphql.org/October2021#TypeSystemDocument
+//! [1]: https://spec.graphql.org/October2021#sec-Introspection
+
+use std::{borrow::Cow, collections::hash_map::Entry, fmt};
+
+use fnv::FnvHashMap;
+use indexmap::IndexMap;
+
+use crate::{
+    ast::{
+        Definition, Directive, EnumValueDefinition, FieldDefinition, FromInputValue, InputValue,
+        InputValueDefinition, OperationType, SchemaDefinition, Type, TypeDefinition,
+    },
+    executor::{FieldError, Registry},
+    parser::{
+        parse_type_system_document_source, parse_value_literal, Lexer, ParseError, Parser,
+        ScalarToken, Spanning, Token,
+    },
+    schema::{
+        meta::{
+            Argument, DeprecationStatus, EnumMeta, EnumValue, Field, InputObjectMeta,
+            InterfaceMeta, MetaType, ObjectMeta, ScalarMeta, UnionMeta,
+        },
+        model::{DirectiveLocation, DirectiveType, SchemaType, TypeType},
+        validation::SchemaError,
+    },
+    types::{name::Name, scalars::ID},
+    value::ScalarValue,
+};
+
+/// Scalars built into GraphQL, always provided by `juniper` itself.
+const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
+
+/// Directives built into GraphQL, always provided by `juniper` itself.
+const BUILTIN_DIRECTIVES: &[&str] = &["skip", "include", "deprecated", "specifiedBy"];
+
+/// Error of building a [`SchemaType`] out of its [SDL][0] or
+/// [introspection][1] representation.
+///
+/// [0]: SchemaType::from_sdl
+/// [1]: SchemaType::from_introspection
+#[derive(Debug, PartialEq)]
+pub enum SchemaBuildError {
+    /// Schema definition document cannot be parsed.
+    Parse(Spanning<ParseError>),
+
+    /// Schema is malformed or violates the type system rules of the spec.
+    Invalid(Vec<SchemaError>),
+}
+
+impl fmt::Display for SchemaBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => e.fmt(f),
+            Self::Invalid(errs) => {
+                for (i, e) in errs.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    e.fmt(f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaBuildError {}
+
+impl From<SchemaError> for SchemaBuildError {
+    fn from(e: SchemaError) -> Self {
+        Self::Invalid(vec![e])
+    }
+}
+
+impl<'a, S> SchemaType<'a, S>
+where
+    S: ScalarValue + 'a,
+{
+    /// Builds a new [`SchemaType`] out of the provided [type system
+    /// document][0] source (SDL), like the one printed by
+    /// [`RootNode::as_schema_language()`].
+    ///
+    /// Type and schema extensions are applied. Root operation types default to
+    /// `Query`, `Mutation` and `Subscription` ones, unless a `schema`
+    /// definition is provided. Built-in scalars and directives are always
+    /// provided, so may be omitted.
+    ///
+    /// The built [`SchemaType`] is [validated](SchemaType::validate).
+    ///
+    /// ```rust
+    /// # use juniper::{DefaultScalarValue, SchemaType};
+    /// let schema = SchemaType::<DefaultScalarValue>::from_sdl(
+    ///     "type Query { hero(id: ID!): Hero } type Hero { name: String! }",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(schema.concrete_type_by_name("Hero").is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the document cannot be parsed, or describes an invalid schema.
+    ///
+    /// [`RootNode::as_schema_language()`]: crate::RootNode::as_schema_language
+    /// [0]: https://spec.graphql.org/October2021#TypeSystemDocument
+    pub fn from_sdl(source: &str) -> Result<Self, SchemaBuildError> {
+        let document =
+            parse_type_system_document_source::<S>(source).map_err(SchemaBuildError::Parse)?;
+
+        let mut builder = Builder::new();
+        let mut extensions = Vec::new();
+        for def in &document {
+            match def {
+                Definition::Schema(d) => builder.schema_definition(&d.item, false),
+                Definition::SchemaExtension(d) => builder.schema_definition(&d.item, true),
+                Definition::Type(d) => builder.add_type(sdl_type(&d.item)),
+                Definition::TypeExtension(d) => extensions.push(&d.item),
+                Definition::Directive(d) => builder.add_directive(DirectiveType {
+                    name: d.item.name.item.into(),
+                    description: sdl_description(&d.item.description),
+                    locations: d.item.locations.iter().map(|l| l.item.clone()).collect(),
+                    arguments: sdl_arguments(&d.item.arguments).unwrap_or_default(),
+                    is_repeatable: d.item.is_repeatable,
+                }),
+                // Not produced by the type system document parser.
+                Definition::Operation(_) | Definition::Fragment(_) => {}
+            }
+        }
+        for ext in extensions {
+            builder.extend_type(ext);
+        }
+
+        builder.finish()
+    }
+
+    /// Builds a new [`SchemaType`] out of the provided [introspection][0]
+    /// result, like the one returned by [`introspect()`].
+    ///
+    /// The result may be provided either as is, or wrapped into a `data`
+    /// field of a response. It should be queried with all the fields of
+    /// [`IntrospectionFormat::All`] (deprecated members and default values are
+    /// considered, if present).
+    ///
+    /// The built [`SchemaType`] is [validated](SchemaType::validate).
+    ///
+    /// ```rust
+    /// # use juniper::{DefaultScalarValue, InputValue, SchemaType};
+    /// let json = r#"{"__schema": {
+    ///     "queryType": {"name": "Query"},
+    ///     "types": [{
+    ///         "kind": "OBJECT",
+    ///         "name": "Query",
+    ///         "fields": [{
+    ///             "name": "answer",
+    ///             "args": [],
+    ///             "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "Int"}}
+    ///         }],
+    ///         "interfaces": []
+    ///     }],
+    ///     "directives": []
+    /// }}"#;
+    /// let result: InputValue = serde_json::from_str(json).unwrap();
+    ///
+    /// let schema = SchemaType::<DefaultScalarValue>::from_introspection(&result).unwrap();
+    ///
+    /// assert!(schema.concrete_query_type().field_by_name("answer").is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the introspection result is malformed, or describes an invalid
+    /// schema.
+    ///
+    /// [`introspect()`]: crate::introspect
+    /// [`IntrospectionFormat::All`]: crate::IntrospectionFormat::All
+    /// [0]: https://spec.graphql.org/October2021#sec-Introspection
+    pub fn from_introspection(result: &InputValue<S>) -> Result<Self, SchemaBuildError> {
+        introspected_schema(result)?.finish()
+    }
+}
+
+/// Builder of a [`SchemaType`] out of separate type definitions.
+struct Builder<'a, S> {
+    registry: Registry<'a, S>,
+    directives: FnvHashMap<String, DirectiveType<'a, S>>,
+    description: Option<String>,
+    query_type_name: Option<String>,
+    mutation_type_name: Option<String>,
+    subscription_type_name: Option<String>,
+    has_schema_definition: bool,
+    errors: Vec<SchemaError>,
+}
+
+impl<'a, S> Builder<'a, S>
+where
+    S: ScalarValue + 'a,
+{
+    fn new() -> Self {
+        let mut registry = Registry::new(FnvHashMap::default());
+
+        registry.get_type::<SchemaType<S>>(&());
+        registry.get_type::<i32>(&());
+        registry.get_type::<f64>(&());
+        registry.get_type::<String>(&());
+        registry.get_type::<bool>(&());
+        registry.get_type::<ID>(&());
+
+        let directives = [
+            DirectiveType::new_skip(&mut registry),
+            DirectiveType::new_include(&mut registry),
+            DirectiveType::new_deprecated(&mut registry),
+            DirectiveType::new_specified_by(&mut registry),
+        ]
+        .into_iter()
+        .map(|d| (d.name.clone(), d))
+        .collect();
+
+        Self {
+            registry,
+            directives,
+            description: None,
+            query_type_name: None,
+            mutation_type_name: None,
+            subscription_type_name: None,
+            has_schema_definition: false,
+            errors: Vec::new(),
+        }
+    }
+
+    fn schema_definition(&mut self, def: &SchemaDefinition<S>, is_extension: bool) {
+        if !is_extension {
+            if self.has_schema_definition {
+                self.errors
+                    .push(SchemaError::new("Schema must be defined only once"));
+            }
+            self.has_schema_definition = true;
+            self.description = sdl_description(&def.description);
+        }
+
+        for op in &def.operation_types {
+            let (kind, name) = match op.item.operation_type.item {
+                OperationType::Query => ("query", &mut self.query_type_name),
+                OperationType::Mutation => ("mutation", &mut self.mutation_type_name),
+                OperationType::Subscription => ("subscription", &mut self.subscription_type_name),
+            };
+            if name.is_some() {
+                self.errors.push(SchemaError::new(format!(
+                    "Root {kind} type must be defined only once",
+                )));
+            }
+            *name = Some(op.item.named_type.item.into());
+        }
+    }
+
+    fn add_type(&mut self, meta: MetaType<'a, S>) {
+        let name = match meta.name() {
+            Some(n) => n.to_owned(),
+            None => return,
+        };
+        // Built-in scalars may be declared explicitly, but are always provided
+        // by `juniper` itself.
+        if BUILTIN_SCALARS.contains(&name.as_str()) && matches!(meta, MetaType::Scalar(_)) {
+            return;
+        }
+
+        let key = match name.parse::<Name>() {
+            Ok(n) => n,
+            Err(e) => return self.errors.push(SchemaError::new(e.to_string())),
+        };
+        match self.registry.types.entry(key) {
+            Entry::Occupied(_) => self.errors.push(SchemaError::new(format!(
+                "Type \"{name}\" must be defined only once",
+            ))),
+            Entry::Vacant(e) => {
+                e.insert(meta);
+            }
+        }
+    }
+
+    fn add_directive(&mut self, directive: DirectiveType<'a, S>) {
+        // Built-in directives may be declared explicitly, but are always
+        // provided by `juniper` itself.
+        if BUILTIN_DIRECTIVES.contains(&directive.name.as_str()) {
+            return;
+        }
+
+        match self.directives.entry(directive.name.clone()) {
+            Entry::Occupied(e) => self.errors.push(SchemaError::new(format!(
+                "Directive \"@{}\" must be defined only once",
+                e.key(),
+            ))),
+            Entry::Vacant(e) => {
+                e.insert(directive);
+            }
+        }
+    }
+
+    fn extend_type(&mut self, ext: &TypeDefinition<S>) {
+        let name = sdl_type_name(ext);
+        let meta = match self.registry.types.get_mut(name) {
+            Some(m) => m,
+            None => {
+                return self.errors.push(SchemaError::new(format!(
+                    "Cannot extend undefined type \"{name}\"",
+                )))
+            }
+        };
+
+        match (meta, ext) {
+            (MetaType::Scalar(m), TypeDefinition::Scalar(d)) => {
+                if let Some(url) = sdl_directive_argument(&d.directives, "specifiedBy", "url") {
+                    m.specified_by_url = Some(url.to_owned().into());
+                }
+            }
+            (MetaType::Object(m), TypeDefinition::Object(d)) => {
+                m.fields.extend(d.fields.iter().map(|f| sdl_field(&f.item)));
+                m.interface_names
+                    .extend(d.interfaces.iter().map(|i| i.item.into()));
+            }
+            (MetaType::Interface(m), TypeDefinition::Interface(d)) => {
+                m.fields.extend(d.fields.iter().map(|f| sdl_field(&f.item)));
+                m.interface_names
+                    .extend(d.interfaces.iter().map(|i| i.item.into()));
+            }
+            (MetaType::Union(m), TypeDefinition::Union(d)) => {
+                m.of_type_names
+                    .extend(d.members.iter().map(|t| t.item.into()));
+            }
+            (MetaType::Enum(m), TypeDefinition::Enum(d)) => {
+                m.values
+                    .extend(d.values.iter().map(|v| sdl_enum_value(&v.item)));
+            }
+            (MetaType::InputObject(m), TypeDefinition::InputObject(d)) => {
+                m.input_fields
+                    .extend(d.fields.iter().map(|f| sdl_argument(&f.item)));
+            }
+            _ => self.errors.push(SchemaError::new(format!(
+                "Cannot extend type \"{name}\" with a different kind of type",
+            ))),
+        }
+    }
+
+    fn finish(mut self) -> Result<SchemaType<'a, S>, SchemaBuildError> {
+        if !self.has_schema_definition {
+            // https://spec.graphql.org/October2021#sec-Root-Operation-Types.Default-Root-Operation-Type-Names
+            for (name, type_name) in [
+                ("Query", &mut self.query_type_name),
+                ("Mutation", &mut self.mutation_type_name),
+                ("Subscription", &mut self.subscription_type_name),
+            ] {
+                if type_name.is_none() && self.registry.types.contains_key(name) {
+                    *type_name = Some(name.into());
+                }
+            }
+        }
+
+        let typename = self.registry.field::<String>("__typename", &());
+        for meta in self.registry.types.values_mut() {
+            match meta {
+                MetaType::Object(ObjectMeta { name, fields, .. })
+                | MetaType::Interface(InterfaceMeta { name, fields, .. })
+                    if !name.starts_with("__") =>
+                {
+                    fields.push(typename.clone());
+                }
+                _ => {}
+            }
+        }
+
+        let mut meta_fields = vec![
+            self.registry.field::<SchemaType<S>>("__schema", &()),
+            self.registry
+                .field::<TypeType<S>>("__type", &())
+                .argument(self.registry.arg::<String>("name", &())),
+        ];
+        let query_type_name = match self.query_type_name {
+            Some(name) => {
+                match self.registry.types.get_mut(name.as_str()) {
+                    Some(MetaType::Object(ObjectMeta { fields, .. })) => {
+                        fields.append(&mut meta_fields);
+                    }
+                    _ => self.errors.push(SchemaError::new(format!(
+                        "Root query type \"{name}\" must be an object type",
+                    ))),
+                }
+                name
+            }
+            None => {
+                self.errors
+                    .push(SchemaError::new("Root query type must be provided"));
+                String::new()
+            }
+        };
+
+        if !self.errors.is_empty() {
+            return Err(SchemaBuildError::Invalid(self.errors));
+        }
+
+        let schema = SchemaType {
+            description: self.description.map(Cow::Owned),
+            types: self.registry.types,
+            query_type_name,
+            mutation_type_name: self.mutation_type_name,
+            subscription_type_name: self.subscription_type_name,
+            directives: self.directives,
+            registration_errors: self.registry.errors,
+        };
+        schema.validate().map_err(SchemaBuildError::Invalid)?;
+        Ok(schema)
+    }
+}
+
+/// Accepts any input value of a custom scalar, as there is no Rust type to
+/// parse it into.
+fn try_parse_custom_scalar<S>(_: &InputValue<S>) -> Result<(), FieldError<S>> {
+    Ok(())
+}
+
+/// Fails to parse any literal of a custom scalar, so its type is inferred
+/// from the literal itself.
+fn parse_custom_scalar<S>(token: ScalarToken<'_>) -> Result<S, ParseError> {
+    Err(ParseError::unexpected_token(Token::Scalar(token)))
+}
+
+/// Accepts any enum value (or a string one, as provided via variables).
+///
+/// Whether the value is a member of the enum is checked separately.
+fn try_parse_enum<S: ScalarValue>(v: &InputValue<S>) -> Result<(), FieldError<S>> {
+    match v {
+        InputValue::Enum(_) => Ok(()),
+        InputValue::Scalar(s) if s.as_str().is_some() => Ok(()),
+        _ => Err(format!("Expected enum value, found: {v}").into()),
+    }
+}
+
+/// Accepts any input object value.
+///
+/// Its fields are checked separately.
+fn try_parse_input_object<S: ScalarValue>(v: &InputValue<S>) -> Result<(), FieldError<S>> {
+    match v {
+        InputValue::Object(_) => Ok(()),
+        _ => Err(format!("Expected input object value, found: {v}").into()),
+    }
+}
+
+fn custom_scalar<'a, S: ScalarValue>(
+    name: &str,
+    description: Option<String>,
+    specified_by_url: Option<&str>,
+) -> MetaType<'a, S> {
+    MetaType::Scalar(ScalarMeta {
+        name: name.to_owned().into(),
+        description,
+        specified_by_url: specified_by_url.map(|url| url.to_owned().into()),
+        try_parse_fn: try_parse_custom_scalar,
+        parse_fn: parse_custom_scalar,
+    })
+}
+
+fn enum_meta<'a, S: ScalarValue>(
+    name: &str,
+    description: Option<String>,
+    values: Vec<EnumValue>,
+) -> MetaType<'a, S> {
+    MetaType::Enum(EnumMeta {
+        name: name.to_owned().into(),
+        description,
+        values,
+        try_parse_fn: try_parse_enum,
+    })
+}
+
+fn input_object_meta<'a, S: ScalarValue>(
+    name: &str,
+    description: Option<String>,
+    input_fields: Vec<Argument<'a, S>>,
+) -> MetaType<'a, S> {
+    MetaType::InputObject(InputObjectMeta {
+        name: name.to_owned().into(),
+        description,
+        input_fields,
+        try_parse_fn: try_parse_input_object,
+    })
+}
+
+/// Converts the provided [`Type`] into the one not borrowing its names.
+fn owned_type(ty: &Type<'_>) -> Type<'static> {
+    match ty {
+        Type::Named(n) => Type::Named(n.to_string().into()),
+        Type::NonNullNamed(n) => Type::NonNullNamed(n.to_string().into()),
+        Type::List(t, size) => Type::List(Box::new(owned_type(t)), *size),
+        Type::NonNullList(t, size) => Type::NonNullList(Box::new(owned_type(t)), *size),
+    }
+}
+
+fn sdl_type_name<'d, S>(def: &TypeDefinition<'d, S>) -> &'d str {
+    match def {
+        TypeDefinition::Scalar(d) => d.name.item,
+        TypeDefinition::Object(d) => d.name.item,
+        TypeDefinition::Interface(d) => d.name.item,
+        TypeDefinition::Union(d) => d.name.item,
+        TypeDefinition::Enum(d) => d.name.item,
+        TypeDefinition::InputObject(d) => d.name.item,
+    }
+}
+
+fn sdl_type<'a, S: ScalarValue>(def: &TypeDefinition<S>) -> MetaType<'a, S> {
+    match def {
+        TypeDefinition::Scalar(d) => custom_scalar(
+            d.name.item,
+            sdl_description(&d.description),
+            sdl_directive_argument(&d.directives, "specifiedBy", "url"),
+        ),
+        TypeDefinition::Object(d) => MetaType::Object(ObjectMeta {
+            name: d.name.item.to_owned().into(),
+            description: sdl_description(&d.description),
+            fields: d.fields.iter().map(|f| sdl_field(&f.item)).collect(),
+            interface_names: d.interfaces.iter().map(|i| i.item.into()).collect(),
+        }),
+        TypeDefinition::Interface(d) => MetaType::Interface(InterfaceMeta {
+            name: d.name.item.to_owned().into(),
+            description: sdl_description(&d.description),
+            fields: d.fields.iter().map(|f| sdl_field(&f.item)).collect(),
+            interface_names: d.interfaces.iter().map(|i| i.item.into()).collect(),
+        }),
+        TypeDefinition::Union(d) => MetaType::Union(UnionMeta {
+            name: d.name.item.to_owned().into(),
+            description: sdl_description(&d.description),
+            of_type_names: d.members.iter().map(|t| t.item.into()).collect(),
+        }),
+        TypeDefinition::Enum(d) => enum_meta(
+            d.name.item,
+            sdl_description(&d.description),
+            d.values.iter().map(|v| sdl_enum_value(&v.item)).collect(),
+        ),
+        TypeDefinition::InputObject(d) => input_object_meta(
+            d.name.item,
+            sdl_description(&d.description),
+            d.fields.iter().map(|f| sdl_argument(&f.item)).collect(),
+        ),
+    }
+}
+
+fn sdl_description(description: &Option<Spanning<Cow<'_, str>>>) -> Option<String> {
+    description.as_ref().map(|d| d.item.to_string())
+}
+
+/// Returns the string value of the `argument` of the `directive`, if any.
+fn sdl_directive_argument<'d, S: ScalarValue>(
+    directives: &'d Option<Vec<Spanning<Directive<S>>>>,
+    directive: &str,
+    argument: &str,
+) -> Option<&'d str> {
+    directives
+        .iter()
+        .flatten()
+        .find(|d| d.item.name.item == directive)
+        .and_then(|d| d.item.arguments.as_ref())
+        .and_then(|args| args.item.get(argument))
+        .and_then(|v| v.item.as_string_value())
+}
+
+fn sdl_deprecation_status<S: ScalarValue>(
+    directives: &Option<Vec<Spanning<Directive<S>>>>,
+) -> DeprecationStatus {
+    let is_deprecated = directives
+        .iter()
+        .flatten()
+        .any(|d| d.item.name.item == "deprecated");
+    if is_deprecated {
+        let reason = sdl_directive_argument(directives, "deprecated", "reason");
+        DeprecationStatus::Deprecated(reason.map(Into::into))
+    } else {
+        DeprecationStatus::Current
+    }
+}
+
+fn sdl_field<'a, S: ScalarValue>(def: &FieldDefinition<S>) -> Field<'a, S> {
+    Field {
+        name: def.name.item.into(),
+        description: sdl_description(&def.description),
+        arguments: sdl_arguments(&def.arguments),
+        field_type: owned_type(&def.field_type.item),
+        deprecation_status: sdl_deprecation_status(&def.directives),
+    }
+}
+
+fn sdl_arguments<'a, S: ScalarValue>(
+    defs: &Option<Spanning<Vec<Spanning<InputValueDefinition<S>>>>>,
+) -> Option<Vec<Argument<'a, S>>> {
+    defs.as_ref()
+        .map(|args| args.item.iter().map(|a| sdl_argument(&a.item)).collect())
+}
+
+fn sdl_argument<'a, S: ScalarValue>(def: &InputValueDefinition<S>) -> Argument<'a, S> {
+    Argument {
+        name: def.name.item.into(),
+        description: sdl_description(&def.description),
+        arg_type: owned_type(&def.value_type.item),
+        default_value: def.default_value.as_ref().map(|v| v.item.clone()),
+        deprecation_status: sdl_deprecation_status(&def.directives),
+    }
+}
+
+fn sdl_enum_value<S: ScalarValue>(def: &EnumValueDefinition<S>) -> EnumValue {
+    EnumValue {
+        name: def.name.item.into(),
+        description: sdl_description(&def.description),
+        deprecation_status: sdl_deprecation_status(&def.directives),
+    }
+}
+
+/// Object of an introspection result.
+type Object<'v, S> = IndexMap<&'v str, &'v InputValue<S>>;
+
+fn introspection_error(message: impl fmt::Display) -> SchemaError {
+    SchemaError::new(format!("Invalid introspection result: {message}"))
+}
+
+fn object<S: ScalarValue>(v: &InputValue<S>) -> Result<Object<'_, S>, SchemaError> {
+    v.to_object_value()
+        .ok_or_else(|| introspection_error(format!("expected object, found: {v}")))
+}
+
+fn list<S: ScalarValue>(v: &InputValue<S>) -> Result<Vec<&InputValue<S>>, SchemaError> {
+    v.to_list_value()
+        .ok_or_else(|| introspection_error(format!("expected list, found: {v}")))
+}
+
+fn string<S: ScalarValue>(v: &InputValue<S>) -> Result<&str, SchemaError> {
+    v.as_string_value()
+        .ok_or_else(|| introspection_error(format!("expected string, found: {v}")))
+}
+
+/// Returns the non-`null` `name`d field of the provided introspection object.
+fn field<'v, S>(obj: &Object<'v, S>, name: &str) -> Result<&'v InputValue<S>, SchemaError> {
+    optional_field(obj, name).ok_or_else(|| introspection_error(format!("missing `{name}` field")))
+}
+
+/// Returns the `name`d field of the provided introspection object, if it's
+/// present and not `null`.
+fn optional_field<'v, S>(obj: &Object<'v, S>, name: &str) -> Option<&'v InputValue<S>> {
+    obj.get(name).copied().filter(|v| !v.is_null())
+}
+
+fn optional_string<'v, S: ScalarValue>(
+    obj: &Object<'v, S>,
+    name: &str,
+) -> Result<Option<&'v str>, SchemaError> {
+    optional_field(obj, name).map(string).transpose()
+}
+
+/// Returns the names of the types listed in the `name`d field of the provided
+/// introspection object.
+fn type_names<S: ScalarValue>(obj: &Object<'_, S>, name: &str) -> Result<Vec<String>, SchemaError> {
+    optional_field(obj, name)
+        .map(list)
+        .transpose()?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|t| string(field(&object(t)?, "name")?).map(Into::into))
+        .collect()
+}
+
+fn introspected_schema<'a, S: ScalarValue + 'a>(
+    result: &InputValue<S>,
+) -> Result<Builder<'a, S>, SchemaError> {
+    let result = object(result)?;
+    let result = match optional_field(&result, "data") {
+        Some(data) => object(data)?,
+        None => result,
+    };
+    let schema = object(field(&result, "__schema")?)?;
+
+    let mut builder = Builder::new();
+    builder.has_schema_definition = true;
+    builder.description = optional_string(&schema, "description")?.map(Into::into);
+    for (name, type_name) in [
+        ("queryType", &mut builder.query_type_name),
+        ("mutationType", &mut builder.mutation_type_name),
+        ("subscriptionType", &mut builder.subscription_type_name),
+    ] {
+        if let Some(t) = optional_field(&schema, name) {
+            *type_name = Some(string(field(&object(t)?, "name")?)?.into());
+        }
+    }
+
+    for t in list(field(&schema, "types")?)? {
+        if let Some(meta) = introspected_type(t)? {
+            builder.add_type(meta);
+        }
+    }
+    for d in list(field(&schema, "directives")?)? {
+        builder.add_directive(introspected_directive(d)?);
+    }
+
+    Ok(builder)
+}
+
+fn introspected_type<'a, S: ScalarValue>(
+    v: &InputValue<S>,
+) -> Result<Option<MetaType<'a, S>>, SchemaError> {
+    let obj = object(v)?;
+    let name = string(field(&obj, "name")?)?;
+    // Introspection types are provided by `juniper` itself.
+    if name.starts_with("__") {
+        return Ok(None);
+    }
+    let description = optional_string(&obj, "description")?.map(Into::into);
+
+    let fields = |name| -> Result<Vec<Field<'a, S>>, SchemaError> {
+        list(field(&obj, name)?)?
+            .into_iter()
+            .map(introspected_field)
+            .collect()
+    };
+
+    Ok(Some(match string(field(&obj, "kind")?)? {
+        "SCALAR" => custom_scalar(
+            name,
+            description,
+            match optional_string(&obj, "specifiedByURL")? {
+                Some(url) => Some(url),
+                None => optional_string(&obj, "specifiedByUrl")?,
+            },
+        ),
+        "OBJECT" => MetaType::Object(ObjectMeta {
+            name: name.to_owned().into(),
+            description,
+            fields: fields("fields")?,
+            interface_names: type_names(&obj, "interfaces")?,
+        }),
+        "INTERFACE" => MetaType::Interface(InterfaceMeta {
+            name: name.to_owned().into(),
+            description,
+            fields: fields("fields")?,
+            interface_names: type_names(&obj, "interfaces")?,
+        }),
+        "UNION" => MetaType::Union(UnionMeta {
+            name: name.to_owned().into(),
+            description,
+            of_type_names: type_names(&obj, "possibleTypes")?,
+        }),
+        "ENUM" => enum_meta(
+            name,
+            description,
+            list(field(&obj, "enumValues")?)?
+                .into_iter()
+                .map(|v| {
+                    let v = object(v)?;
+                    Ok(EnumValue {
+                        name: string(field(&v, "name")?)?.into(),
+                        description: optional_string(&v, "description")?.map(Into::into),
+                        deprecation_status: introspected_deprecation_status(&v)?,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        "INPUT_OBJECT" => input_object_meta(
+            name,
+            description,
+            introspected_arguments(field(&obj, "inputFields")?)?,
+        ),
+        kind => {
+            return Err(introspection_error(format!(
+                "unexpected `{kind}` kind of type \"{name}\"",
+            )))
+        }
+    }))
+}
+
+fn introspected_type_ref<S: ScalarValue>(v: &InputValue<S>) -> Result<Type<'static>, SchemaError> {
+    let obj = object(v)?;
+    Ok(match string(field(&obj, "kind")?)? {
+        "NON_NULL" => match introspected_type_ref(field(&obj, "ofType")?)? {
+            Type::Named(n) => Type::NonNullNamed(n),
+            Type::List(t, size) => Type::NonNullList(t, size),
+            t => {
+                return Err(introspection_error(format!(
+                    "non-null type wrapping non-null type `{t}`",
+                )))
+            }
+        },
+        "LIST" => Type::List(
+            Box::new(introspected_type_ref(field(&obj, "ofType")?)?),
+            None,
+        ),
+        _ => Type::Named(string(field(&obj, "name")?)?.to_owned().into()),
+    })
+}
+
+fn introspected_deprecation_status<S: ScalarValue>(
+    obj: &Object<'_, S>,
+) -> Result<DeprecationStatus, SchemaError> {
+    let is_deprecated = optional_field(obj, "isDeprecated")
+        .and_then(InputValue::as_scalar)
+        .and_then(ScalarValue::as_bool)
+        .unwrap_or_default();
+    Ok(if is_deprecated {
+        DeprecationStatus::Deprecated(optional_string(obj, "deprecationReason")?.map(Into::into))
+    } else {
+        DeprecationStatus::Current
+    })
+}
+
+fn introspected_field<'a, S: ScalarValue>(v: &InputValue<S>) -> Result<Field<'a, S>, SchemaError> {
+    let obj = object(v)?;
+    let arguments = introspected_arguments(field(&obj, "args")?)?;
+    Ok(Field {
+        name: string(field(&obj, "name")?)?.into(),
+        description: optional_string(&obj, "description")?.map(Into::into),
+        arguments: (!arguments.is_empty()).then_some(arguments),
+        field_type: introspected_type_ref(field(&obj, "type")?)?,
+        deprecation_status: introspected_deprecation_status(&obj)?,
+    })
+}
+
+fn introspected_arguments<'a, S: ScalarValue>(
+    v: &InputValue<S>,
+) -> Result<Vec<Argument<'a, S>>, SchemaError> {
+    list(v)?
+        .into_iter()
+        .map(|v| {
+            let obj = object(v)?;
+            Ok(Argument {
+                name: string(field(&obj, "name")?)?.into(),
+                description: optional_string(&obj, "description")?.map(Into::into),
+                arg_type: introspected_type_ref(field(&obj, "type")?)?,
+                default_value: optional_string(&obj, "defaultValue")?
+                    .map(parse_default_value)
+                    .transpose()?,
+                deprecation_status: introspected_deprecation_status(&obj)?,
+            })
+        })
+        .collect()
+}
+
+fn introspected_directive<'a, S: ScalarValue>(
+    v: &InputValue<S>,
+) -> Result<DirectiveType<'a, S>, SchemaError> {
+    let obj = object(v)?;
+    Ok(DirectiveType {
+        name: string(field(&obj, "name")?)?.into(),
+        description: optional_string(&obj, "description")?.map(Into::into),
+        locations: list(field(&obj, "locations")?)?
+            .into_iter()
+            .map(|l| {
+                let l = InputValue::<S>::enum_value(string(l).or_else(|_| {
+                    l.as_enum_value()
+                        .ok_or_else(|| introspection_error(format!("unexpected location: {l}")))
+                })?);
+                DirectiveLocation::from_input_value(&l)
+                    .map_err(|_| introspection_error(format!("unknown directive location: {l}")))
+            })
+            .collect::<Result<_, _>>()?,
+        arguments: introspected_arguments(field(&obj, "args")?)?,
+        is_repeatable: optional_field(&obj, "isRepeatable")
+            .and_then(InputValue::as_scalar)
+            .and_then(ScalarValue::as_bool)
+            .unwrap_or_default(),
+    })
+}
+
+/// Parses the provided `defaultValue` of an introspected argument or input
+/// object field.
+fn parse_default_value<S: ScalarValue>(source: &str) -> Result<InputValue<S>, SchemaError> {
+    let error = || introspection_error(format!("cannot parse default value `{source}`"));
+
+    let mut lexer = Lexer::new(source);
+    let mut parser = Parser::new(&mut lexer).map_err(|_| error())?;
+    let value = parse_value_literal::<S>(&mut parser, true, None, None).map_err(|_| error())?;
+    if parser.peek().item != Token::EndOfFile {
+        return Err(error());
+    }
+    Ok(value.item)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::{InputValue, Type},
+        schema::{
+            meta::{DeprecationStatus, MetaType},
+            model::{RootNode, SchemaType},
+        },
+        tests::fixtures::starwars::schema::{Database, Query},
+        types::scalars::{EmptyMutation, EmptySubscription},
+        DefaultScalarValue, IntrospectionFormat,
+    };
+
+    use super::{SchemaBuildError, BUILTIN_SCALARS};
+
+    type Schema = SchemaType<'static, DefaultScalarValue>;
+
+    const STARWARS_SDL: &str = include_str!("../tests/fixtures/starwars/starwars.graphql");
+
+    fn error_messages(res: Result<Schema, SchemaBuildError>) -> Vec<String> {
+        match res.expect_err("schema should be invalid") {
+            SchemaBuildError::Invalid(errs) => errs.iter().map(|e| e.message().into()).collect(),
+            SchemaBuildError::Parse(e) => panic!("unexpected parse error: {e}"),
+        }
+    }
+
+    /// Returns the signatures of all the non-built-in types, along with their
+    /// fields, arguments, enum values and union members.
+    fn signatures(schema: &Schema) -> Vec<String> {
+        let mut out = Vec::new();
+        for t in schema.concrete_type_list() {
+            let name = t.name().unwrap();
+            if t.is_builtin() || BUILTIN_SCALARS.contains(&name) {
+                continue;
+            }
+            out.push(name.to_owned());
+            match t {
+                MetaType::Object(_) | MetaType::Interface(_) => {
+                    for f in t.fields(schema).unwrap() {
+                        out.push(format!("{name}.{}: {}", f.name, f.field_type));
+                        for a in f.arguments.iter().flatten() {
+                            out.push(format!("{name}.{}({}: {})", f.name, a.name, a.arg_type));
+                        }
+                    }
+                }
+                MetaType::Enum(e) => {
+                    out.extend(e.values.iter().map(|v| format!("{name}.{}", v.name)));
+                }
+                MetaType::InputObject(o) => {
+                    out.extend(
+                        o.input_fields
+                            .iter()
+                            .map(|f| format!("{name}.{}: {}", f.name, f.arg_type)),
+                    );
+                }
+                MetaType::Union(u) => {
+                    out.extend(u.of_type_names.iter().map(|t| format!("{name} = {t}")));
+                }
+                _ => {}
+            }
+        }
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn builds_from_sdl() {
+        let schema = Schema::from_sdl(STARWARS_SDL).unwrap();
+        let root = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        assert_eq!(signatures(&schema), signatures(&root.schema));
+        assert_eq!(schema.concrete_query_type().name(), Some("Query"));
+        assert!(schema.concrete_mutation_type().is_none());
+        assert!(schema
+            .concrete_type_by_name("Droid")
+            .unwrap()
+            .field_by_name("__typename")
+            .is_some());
+    }
+
+    #[test]
+    fn builds_from_introspection() {
+        let database = Database::new();
+        let root = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let (result, errs) = crate::introspect(&root, &database, IntrospectionFormat::All).unwrap();
+        assert_eq!(errs, []);
+
+        // Mimic loading from a JSON file.
+        let json = serde_json::to_string(&serde_json::json!({ "data": result })).unwrap();
+        let result: InputValue = serde_json::from_str(&json).unwrap();
+
+        let schema = Schema::from_introspection(&result).unwrap();
+
+        assert_eq!(signatures(&schema), signatures(&root.schema));
+        assert_eq!(
+            schema
+                .concrete_type_by_name("Human")
+                .and_then(|t| t.description()),
+            Some("A humanoid creature in the Star Wars universe."),
+        );
+    }
+
+    #[test]
+    fn applies_schema_and_type_extensions() {
+        let schema = Schema::from_sdl(
+            r#"
+            schema { query: Root }
+            extend schema { mutation: Mut }
+
+            type Root { users(filter: Filter = {role: ADMIN}): [User!]! }
+            extend type Root { me: User @deprecated(reason: "Use `users`.") }
+
+            type Mut { noop: Boolean }
+
+            type User { id: ID! role: Role joinedAt: DateTime }
+
+            enum Role { USER }
+            extend enum Role { ADMIN @deprecated }
+
+            input Filter { role: Role }
+
+            scalar DateTime @specifiedBy(url: "https://tools.ietf.org/html/rfc3339")
+
+            directive @cached(ttl: Int = 60) repeatable on FIELD
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(schema.concrete_query_type().name(), Some("Root"));
+        assert_eq!(
+            schema.concrete_mutation_type().and_then(MetaType::name),
+            Some("Mut"),
+        );
+
+        let root = schema.concrete_query_type();
+        assert_eq!(
+            root.field_by_name("me").unwrap().deprecation_status,
+            DeprecationStatus::Deprecated(Some("Use `users`.".into())),
+        );
+        let users = root.field_by_name("users").unwrap();
+        assert_eq!(
+            users.field_type,
+            Type::NonNullList(Box::new(Type::NonNullNamed("User".into())), None),
+        );
+        assert!(users.arguments.as_ref().unwrap()[0].default_value.is_some());
+
+        match schema.concrete_type_by_name("Role") {
+            Some(MetaType::Enum(e)) => {
+                assert_eq!(e.values.len(), 2);
+                assert!(e.values[1].deprecation_status.is_deprecated());
+            }
+            t => panic!("unexpected type: {t:?}"),
+        }
+        match schema.concrete_type_by_name("DateTime") {
+            Some(MetaType::Scalar(s)) => assert_eq!(
+                s.specified_by_url.as_deref(),
+                Some("https://tools.ietf.org/html/rfc3339"),
+            ),
+            t => panic!("unexpected type: {t:?}"),
+        }
+
+        let cached = schema.directive_by_name("cached").unwrap();
+        assert!(cached.is_repeatable);
+        assert_eq!(cached.arguments[0].name, "ttl");
+    }
+
+    #[test]
+    fn rejects_invalid_sdl() {
+        assert!(matches!(
+            Schema::from_sdl("type Query {"),
+            Err(SchemaBuildError::Parse(_)),
+        ));
+
+        assert_eq!(
+            error_messages(Schema::from_sdl("type Foo { id: ID }")),
+            ["Root query type must be provided"],
+        );
+        assert_eq!(
+            error_messages(Schema::from_sdl(
+                "type Query { id: ID } type Query { name: String }",
+            )),
+            ["Type \"Query\" must be defined only once"],
+        );
+        assert_eq!(
+            error_messages(Schema::from_sdl(
+                "type Query { id: ID } extend type User { name: String }",
+            )),
+            ["Cannot extend undefined type \"User\""],
+        );
+        assert_eq!(
+            error_messages(Schema::from_sdl("type Query { user: User }")),
+            ["Field \"Query.user\" references type \"User\" missing in the schema"],
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_introspection() {
+        let result: InputValue = serde_json::from_str(r#"{"__schema": {"types": []}}"#).unwrap();
+
+        assert_eq!(
+            error_messages(Schema::from_introspection(&result)),
+            ["Invalid introspection result: missing `directives` field"],
+        );
+    }
+}
@@ -203,6 +203,8 @@ pub struct Argument<'a, S> {
     pub arg_type: Type<'a>,
     #[doc(hidden)]
     pub default_value: Option<InputValue<S>>,
+    #[doc(hidden)]
+    pub deprecation_status: DeprecationStatus,
 }
 
 impl<'a, S> Argument<'a, S> {
@@ -722,6 +724,7 @@ impl<'a, S> Argument<'a, S> {
             description: None,
             arg_type,
             default_value: None,
+            deprecation_status: DeprecationStatus::Current,
         }
     }
 
@@ -742,6 +745,15 @@ impl<'a, S> Argument<'a, S> {
         self.default_value = Some(val);
         self
     }
+
+    /// Sets this [`Argument`] as deprecated with an optional `reason`.
+    ///
+    /// Overwrites any previously set deprecation reason.
+    #[must_use]
+    pub fn deprecated(mut self, reason: Option<&str>) -> Self {
+        self.deprecation_status = DeprecationStatus::Deprecated(reason.map(Into::into));
+        self
+    }
 }
 
 impl EnumValue {
@@ -5,10 +5,16 @@ use fnv::FnvHashMap;
 use graphql_parser::schema::Document;
 
 use crate::{
-    ast::Type,
-    executor::{Context, Registry},
-    schema::meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
+    ast::{self, Operation, OperationType, Type},
+    executor::{get_operation, Context, Registry, Variables, LIVE_DIRECTIVE},
+    parser::{parse_document_source_with_limits, DocumentLimits, Spanning},
+    schema::{
+        meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
+        validation::SchemaError,
+    },
     types::{base::GraphQLType, name::Name},
+    usage::{self, OperationUsage, UsageCollector},
+    validation::ValidationRule,
     value::{DefaultScalarValue, ScalarValue},
     GraphQLEnum,
 };
@@ -44,6 +50,36 @@ pub struct RootNode<
     pub subscription_info: SubscriptionT::TypeInfo,
     #[doc(hidden)]
     pub schema: SchemaType<'a, S>,
+    #[doc(hidden)]
+    pub limits: DocumentLimits,
+    #[doc(hidden)]
+    pub validation_rules: Vec<Box<dyn ValidationRule<S>>>,
+    pub(crate) introspection: Introspection<QueryT::Context>,
+    pub(crate) usage_collectors: Vec<Box<dyn UsageCollector<QueryT::Context>>>,
+    pub(crate) deprecation_warnings: bool,
+}
+
+/// Availability of introspection in a [`RootNode`].
+pub(crate) enum Introspection<CtxT> {
+    /// Introspection is allowed for every request.
+    Enabled,
+
+    /// Introspection is forbidden for every request.
+    Disabled,
+
+    /// Introspection is allowed only for the requests, which context satisfies
+    /// the predicate.
+    Restricted(Box<dyn Fn(&CtxT) -> bool + Send + Sync>),
+}
+
+impl<CtxT> fmt::Debug for Introspection<CtxT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Enabled => f.write_str("Enabled"),
+            Self::Disabled => f.write_str("Disabled"),
+            Self::Restricted(_) => f.write_str("Restricted"),
+        }
+    }
 }
 
 /// Metadata for a schema
@@ -54,7 +90,8 @@ pub struct SchemaType<'a, S> {
     pub(crate) query_type_name: String,
     pub(crate) mutation_type_name: Option<String>,
     pub(crate) subscription_type_name: Option<String>,
-    directives: FnvHashMap<String, DirectiveType<'a, S>>,
+    pub(crate) directives: FnvHashMap<String, DirectiveType<'a, S>>,
+    pub(crate) registration_errors: Vec<SchemaError>,
 }
 
 impl<'a, S> Context for SchemaType<'a, S> {}
@@ -95,6 +132,17 @@ pub enum DirectiveLocation {
     InlineFragment,
     #[graphql(name = "ENUM_VALUE")]
     EnumValue,
+    Schema,
+    Object,
+    #[graphql(name = "ARGUMENT_DEFINITION")]
+    ArgumentDefinition,
+    Interface,
+    Union,
+    Enum,
+    #[graphql(name = "INPUT_OBJECT")]
+    InputObject,
+    #[graphql(name = "INPUT_FIELD_DEFINITION")]
+    InputFieldDefinition,
 }
 
 impl<'a, QueryT, MutationT, SubscriptionT>
@@ -109,6 +157,22 @@ where
     pub fn new(query: QueryT, mutation: MutationT, subscription: SubscriptionT) -> Self {
         Self::new_with_info(query, mutation, subscription, (), (), ())
     }
+
+    /// Constructs a new [`RootNode`] from `query`, `mutation` and `subscription` nodes,
+    /// parametrizing it with a [`DefaultScalarValue`], and [validates] it.
+    ///
+    /// # Errors
+    ///
+    /// If the constructed schema violates the type system rules of the GraphQL spec.
+    ///
+    /// [validates]: RootNode::validate
+    pub fn try_new(
+        query: QueryT,
+        mutation: MutationT,
+        subscription: SubscriptionT,
+    ) -> Result<Self, Vec<SchemaError>> {
+        Self::try_new_with_info(query, mutation, subscription, (), (), ())
+    }
 }
 
 impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
@@ -159,7 +223,247 @@ where
             query_info,
             mutation_info,
             subscription_info,
+            limits: DocumentLimits::default(),
+            validation_rules: Vec::new(),
+            introspection: Introspection::Enabled,
+            usage_collectors: Vec::new(),
+            deprecation_warnings: false,
+        }
+    }
+
+    /// Constructs a new [`RootNode`] from query, mutation and subscription nodes
+    /// along with their type info objects, and [validates] it.
+    ///
+    /// # Errors
+    ///
+    /// If the constructed schema violates the type system rules of the GraphQL spec.
+    ///
+    /// [validates]: RootNode::validate
+    pub fn try_new_with_info(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+        query_info: QueryT::TypeInfo,
+        mutation_info: MutationT::TypeInfo,
+        subscription_info: SubscriptionT::TypeInfo,
+    ) -> Result<Self, Vec<SchemaError>> {
+        let root = Self::new_with_info(
+            query_obj,
+            mutation_obj,
+            subscription_obj,
+            query_info,
+            mutation_info,
+            subscription_info,
+        );
+        root.validate()?;
+        Ok(root)
+    }
+
+    /// Validates this [`RootNode`] against the [type system rules][0] of the
+    /// GraphQL spec, returning all the violations found.
+    ///
+    /// See [`SchemaType::validate()`] for the details.
+    ///
+    /// [0]: https://spec.graphql.org/October2021#sec-Type-System
+    pub fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        self.schema.validate()
+    }
+
+    /// Enforces the provided [`DocumentLimits`] on every document executed
+    /// against this [`RootNode`].
+    #[must_use]
+    pub fn with_limits(mut self, limits: DocumentLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Registers the provided user-defined [`ValidationRule`] to be run along
+    /// with the built-in ones on every document executed against this
+    /// [`RootNode`].
+    ///
+    /// ```rust
+    /// # use juniper::{
+    /// #     validation::{ValidatorContext, Visitor},
+    /// #     EmptyMutation, EmptySubscription, Operation, RootNode, ScalarValue, Spanning,
+    /// # };
+    /// # struct Query;
+    /// # #[juniper::graphql_object]
+    /// # impl Query {
+    /// #     fn hero(id: i32) -> i32 { id }
+    /// # }
+    /// struct NoAnonymousOperations;
+    ///
+    /// impl<'a, S: ScalarValue> Visitor<'a, S> for NoAnonymousOperations {
+    ///     fn enter_operation_definition(
+    ///         &mut self,
+    ///         ctx: &mut ValidatorContext<'a, S>,
+    ///         op: &'a Spanning<Operation<S>>,
+    ///     ) {
+    ///         if op.item.name.is_none() {
+    ///             ctx.report_error("Anonymous operations are not allowed", &[op.start]);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new())
+    ///     .with_validation_rule(|| NoAnonymousOperations);
+    /// ```
+    #[must_use]
+    pub fn with_validation_rule(mut self, rule: impl ValidationRule<S> + 'static) -> Self {
+        self.validation_rules.push(Box::new(rule));
+        self
+    }
+
+    /// Forbids introspection (querying `__schema` and `__type` fields) of this
+    /// [`RootNode`], so any document doing it fails validation.
+    ///
+    /// `__typename` field is still allowed, as it's required by clients for
+    /// resolving abstract types.
+    #[must_use]
+    pub fn disable_introspection(mut self) -> Self {
+        self.introspection = Introspection::Disabled;
+        self
+    }
+
+    /// Allows introspection (querying `__schema` and `__type` fields) of this
+    /// [`RootNode`] only for the requests, which context satisfies the provided
+    /// `predicate`, so any other document doing it fails validation.
+    ///
+    /// ```rust
+    /// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+    /// struct Context {
+    ///     is_staff: bool,
+    /// }
+    ///
+    /// impl juniper::Context for Context {}
+    ///
+    /// # struct Query;
+    /// # #[juniper::graphql_object(context = Context)]
+    /// # impl Query {
+    /// #     fn hero(id: i32) -> i32 { id }
+    /// # }
+    /// let schema = RootNode::new(
+    ///     Query,
+    ///     EmptyMutation::<Context>::new(),
+    ///     EmptySubscription::<Context>::new(),
+    /// )
+    /// .restrict_introspection(|ctx: &Context| ctx.is_staff);
+    /// ```
+    #[must_use]
+    pub fn restrict_introspection(
+        mut self,
+        predicate: impl Fn(&QueryT::Context) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.introspection = Introspection::Restricted(Box::new(predicate));
+        self
+    }
+
+    /// Allows introspection of this [`RootNode`] for every request (default).
+    #[must_use]
+    pub fn enable_introspection(mut self) -> Self {
+        self.introspection = Introspection::Enabled;
+        self
+    }
+
+    /// Indicates whether introspection of this [`RootNode`] is allowed for the
+    /// request with the provided `context`.
+    pub fn is_introspection_allowed(&self, context: &QueryT::Context) -> bool {
+        match &self.introspection {
+            Introspection::Enabled => true,
+            Introspection::Disabled => false,
+            Introspection::Restricted(predicate) => predicate(context),
+        }
+    }
+
+    /// Registers the provided [`UsageCollector`] receiving the
+    /// [`OperationUsage`] of every operation executed against this
+    /// [`RootNode`].
+    ///
+    /// See [`UsageAggregator`] for an in-memory implementation.
+    ///
+    /// [`UsageAggregator`]: crate::usage::UsageAggregator
+    #[must_use]
+    pub fn with_usage_collector(
+        mut self,
+        collector: impl UsageCollector<QueryT::Context> + 'static,
+    ) -> Self {
+        self.usage_collectors.push(Box::new(collector));
+        self
+    }
+
+    /// Reports the deprecated schema members used by an executed operation in
+    /// the `deprecations` field of [`GraphQLResponse`] extensions.
+    ///
+    /// [`GraphQLResponse`]: crate::http::GraphQLResponse
+    #[must_use]
+    pub fn with_deprecation_warnings(mut self) -> Self {
+        self.deprecation_warnings = true;
+        self
+    }
+
+    /// Enables live queries: query operations marked with `@live` directive,
+    /// which are re-executed whenever any of the invalidation keys touched
+    /// during their previous execution (via
+    /// [`Executor::track_invalidation_key()`]) is invalidated.
+    ///
+    /// Only registers the `@live` directive in this [`RootNode`], while the
+    /// re-execution is up to the transport (see `juniper_subscriptions` crate).
+    /// Executed in a regular way, live queries are executed only once.
+    ///
+    /// [`Executor::track_invalidation_key()`]: crate::Executor::track_invalidation_key
+    #[must_use]
+    pub fn enable_live_queries(mut self) -> Self {
+        self.schema.add_directive(DirectiveType::new_live());
+        self
+    }
+
+    /// Indicates whether the operation with the provided `operation_name` in
+    /// the given `document_source` is a live query.
+    ///
+    /// Always returns `false` unless [live queries are enabled][0], or if the
+    /// `document_source` cannot be parsed.
+    ///
+    /// [0]: RootNode::enable_live_queries
+    pub fn is_live_query(&self, document_source: &str, operation_name: Option<&str>) -> bool {
+        if self.schema.directive_by_name(LIVE_DIRECTIVE).is_none() {
+            return false;
+        }
+        let document =
+            match parse_document_source_with_limits(document_source, &self.schema, self.limits) {
+                Ok(document) => document,
+                Err(_) => return false,
+            };
+        get_operation(&document, operation_name).map_or(false, |op| {
+            op.item.operation_type == OperationType::Query
+                && op
+                    .item
+                    .directives
+                    .iter()
+                    .flatten()
+                    .any(|d| d.item.name.item == LIVE_DIRECTIVE)
+        })
+    }
+
+    /// Collects the [`OperationUsage`] of the provided validated `operation`,
+    /// passing it to the registered [`UsageCollector`]s.
+    ///
+    /// Returns [`None`] if deprecation warnings are not enabled.
+    pub(crate) fn collect_usage<'d>(
+        &'d self,
+        document: &'d ast::Document<'d, S>,
+        operation: &'d Spanning<Operation<'d, S>>,
+        variables: &Variables<S>,
+        context: &QueryT::Context,
+    ) -> Option<OperationUsage> {
+        if self.usage_collectors.is_empty() && !self.deprecation_warnings {
+            return None;
+        }
+
+        let usage = usage::collect_usage(&self.schema, document, operation, variables);
+        for collector in &self.usage_collectors {
+            collector.collect(&usage, context);
         }
+        self.deprecation_warnings.then_some(usage)
     }
 
     #[cfg(feature = "schema-language")]
@@ -257,6 +561,7 @@ impl<'a, S> SchemaType<'a, S> {
                 None
             },
             directives,
+            registration_errors: registry.errors,
         }
     }
 
@@ -465,7 +770,7 @@ impl<'a, S> SchemaType<'a, S> {
 
 impl<'a, S> TypeType<'a, S> {
     #[inline]
-    pub fn to_concrete(&self) -> Option<&'a MetaType<S>> {
+    pub fn to_concrete(&self) -> Option<&'a MetaType<'a, S>> {
         match *self {
             TypeType::Concrete(t) => Some(t),
             _ => None,
@@ -473,7 +778,7 @@ impl<'a, S> TypeType<'a, S> {
     }
 
     #[inline]
-    pub fn innermost_concrete(&self) -> &'a MetaType<S> {
+    pub fn innermost_concrete(&self) -> &'a MetaType<'a, S> {
         match *self {
             TypeType::Concrete(t) => t,
             TypeType::NonNull(ref n) | TypeType::List(ref n, _) => n.innermost_concrete(),
@@ -514,7 +819,7 @@ where
         }
     }
 
-    fn new_skip(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_skip(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -530,7 +835,7 @@ where
         )
     }
 
-    fn new_include(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_include(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -546,7 +851,7 @@ where
         )
     }
 
-    fn new_deprecated(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_deprecated(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -554,6 +859,8 @@ where
             "deprecated",
             &[
                 DirectiveLocation::FieldDefinition,
+                DirectiveLocation::ArgumentDefinition,
+                DirectiveLocation::InputFieldDefinition,
                 DirectiveLocation::EnumValue,
             ],
             &[registry.arg::<String>("reason", &())],
@@ -561,7 +868,7 @@ where
         )
     }
 
-    fn new_specified_by(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    pub(crate) fn new_specified_by(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
     where
         S: ScalarValue,
     {
@@ -573,6 +880,16 @@ where
         )
     }
 
+    pub(crate) fn new_live() -> DirectiveType<'a, S>
+    where
+        S: ScalarValue,
+    {
+        Self::new(LIVE_DIRECTIVE, &[DirectiveLocation::Query], &[], false).description(
+            "Marks a query as live, so its result is updated whenever the data it depends on \
+             changes.",
+        )
+    }
+
     pub fn description(mut self, description: &str) -> DirectiveType<'a, S> {
         self.description = Some(description.into());
         self
@@ -593,6 +910,14 @@ impl fmt::Display for DirectiveLocation {
             Self::VariableDefinition => "variable definition",
             Self::Scalar => "scalar",
             Self::EnumValue => "enum value",
+            Self::Schema => "schema",
+            Self::Object => "object",
+            Self::ArgumentDefinition => "argument definition",
+            Self::Interface => "interface",
+            Self::Union => "union",
+            Self::Enum => "enum",
+            Self::InputObject => "input object",
+            Self::InputFieldDefinition => "input field definition",
         })
     }
 }
@@ -749,5 +1074,49 @@ mod test {
             .unwrap();
             assert_eq!(ast.to_string(), schema.as_schema_language());
         }
+
+        #[test]
+        fn deprecated_arguments_and_input_fields() {
+            #[derive(GraphQLInputObject)]
+            struct Point {
+                x: f64,
+                #[graphql(deprecated = "Use `x` instead.", default = 0.0)]
+                y: f64,
+            }
+            struct Query;
+            #[graphql_object]
+            impl Query {
+                fn distance(
+                    point: Point,
+                    #[graphql(deprecated = "Always metric now.")] metric: Option<bool>,
+                ) -> f64 {
+                    _ = metric;
+                    point.x
+                }
+            }
+
+            let schema = RootNode::new(
+                Query,
+                EmptyMutation::<()>::new(),
+                EmptySubscription::<()>::new(),
+            );
+            let ast = graphql_parser::parse_schema::<&str>(
+                r#"
+                input Point {
+                    x: Float!
+                    y: Float! = 0 @deprecated(reason: "Use `x` instead.")
+                }
+                type Query {
+                  distance(point: Point!, metric: Boolean @deprecated(reason: "Always metric now.")): Float!
+                }
+                schema {
+                  query: Query
+                }
+            "#,
+            )
+            .unwrap();
+            assert_eq!(ast.to_string(), schema.as_schema_language());
+            assert_eq!(ast.to_string(), schema.as_parser_document().to_string());
+        }
     }
 }
@@ -238,12 +238,23 @@ impl<'a, S: ScalarValue + 'a> TypeType<'a, S> {
         }
     }
 
-    fn input_fields(&self) -> Option<&[Argument<S>]> {
+    fn input_fields(
+        &self,
+        #[graphql(default = false)] include_deprecated: Option<bool>,
+    ) -> Option<Vec<&Argument<S>>> {
         match self {
             TypeType::Concrete(&MetaType::InputObject(InputObjectMeta {
                 ref input_fields,
                 ..
-            })) => Some(input_fields.as_slice()),
+            })) => Some(
+                input_fields
+                    .iter()
+                    .filter(|f| {
+                        include_deprecated.unwrap_or_default()
+                            || !f.deprecation_status.is_deprecated()
+                    })
+                    .collect(),
+            ),
             _ => None,
         }
     }
@@ -343,10 +354,17 @@ impl<'a, S: ScalarValue + 'a> Field<'a, S> {
         self.description.as_deref()
     }
 
-    fn args(&self) -> Vec<&Argument<S>> {
-        self.arguments
-            .as_ref()
-            .map_or_else(Vec::new, |v| v.iter().collect())
+    fn args(
+        &self,
+        #[graphql(default = false)] include_deprecated: Option<bool>,
+    ) -> Vec<&Argument<S>> {
+        self.arguments.as_ref().map_or_else(Vec::new, |v| {
+            v.iter()
+                .filter(|a| {
+                    include_deprecated.unwrap_or_default() || !a.deprecation_status.is_deprecated()
+                })
+                .collect()
+        })
     }
 
     #[graphql(name = "type")]
@@ -388,6 +406,14 @@ impl<'a, S: ScalarValue + 'a> Argument<'a, S> {
     fn default_value_(&self) -> Option<String> {
         self.default_value.as_ref().map(ToString::to_string)
     }
+
+    fn is_deprecated(&self) -> bool {
+        self.deprecation_status.is_deprecated()
+    }
+
+    fn deprecation_reason(&self) -> Option<&str> {
+        self.deprecation_status.reason()
+    }
 }
 
 #[graphql_object(name = "__EnumValue", internal)]
@@ -434,8 +460,16 @@ impl<'a, S: ScalarValue + 'a> DirectiveType<'a, S> {
         self.is_repeatable
     }
 
-    fn args(&self) -> &[Argument<S>] {
-        &self.arguments
+    fn args(
+        &self,
+        #[graphql(default = false)] include_deprecated: Option<bool>,
+    ) -> Vec<&Argument<S>> {
+        self.arguments
+            .iter()
+            .filter(|a| {
+                include_deprecated.unwrap_or_default() || !a.deprecation_status.is_deprecated()
+            })
+            .collect()
     }
 
     // Included for compatibility with the introspection query in GraphQL.js
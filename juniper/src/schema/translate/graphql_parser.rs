@@ -90,7 +90,7 @@ impl GraphQLParserTranslator {
                 .default_value
                 .as_ref()
                 .map(|x| GraphQLParserTranslator::translate_value(x)),
-            directives: vec![],
+            directives: generate_directives(&input.deprecation_status),
         }
     }
 
@@ -0,0 +1,722 @@
+//! Validation of a [`SchemaType`] against the [type system rules][0] of the
+//! GraphQL spec.
+//!
+//! [0]: https://spec.graphql.org/October2021#sec-Type-System
+
+use std::{borrow::Borrow, fmt};
+
+use crate::{
+    ast::Type,
+    schema::{
+        meta::{
+            Argument, EnumMeta, Field, InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta,
+            UnionMeta,
+        },
+        model::SchemaType,
+    },
+};
+
+/// Types used by GraphQL introspection, allowed to have names starting with
+/// `__`.
+const INTROSPECTION_TYPES: &[&str] = &[
+    "__Schema",
+    "__Type",
+    "__TypeKind",
+    "__Field",
+    "__InputValue",
+    "__EnumValue",
+    "__Directive",
+    "__DirectiveLocation",
+];
+
+/// Error of a schema violating the type system rules of the GraphQL spec.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SchemaError {
+    message: String,
+}
+
+impl SchemaError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Returns the message describing this [`SchemaError`].
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl<'a, S> SchemaType<'a, S> {
+    /// Validates this [`SchemaType`] against the [type system rules][0] of the
+    /// GraphQL spec, returning all the violations found.
+    ///
+    /// Detects:
+    /// - the same type name being registered multiple times with different
+    ///   definitions;
+    /// - references to types missing in this [`SchemaType`];
+    /// - input types used as output ones and vice versa;
+    /// - empty objects, interfaces, unions, enums and input objects;
+    /// - types implementing interfaces incorrectly;
+    /// - names reserved by GraphQL introspection.
+    ///
+    /// [0]: https://spec.graphql.org/October2021#sec-Type-System
+    pub fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        let mut errors = self.registration_errors.clone();
+
+        for (op, name) in [
+            ("mutation", &self.mutation_type_name),
+            ("subscription", &self.subscription_type_name),
+        ] {
+            if let Some(name) = name {
+                if !matches!(self.concrete_type_by_name(name), Some(MetaType::Object(_))) {
+                    errors.push(SchemaError::new(format!(
+                        "Root {op} type \"{name}\" must be an object type",
+                    )));
+                }
+            }
+        }
+
+        let mut types = self.types.iter().collect::<Vec<_>>();
+        types.sort_unstable_by_key(|(a, _)| *a);
+        for (name, meta) in types {
+            let name: &str = name.borrow();
+            if name.starts_with("__") {
+                if !INTROSPECTION_TYPES.contains(&name) {
+                    errors.push(reserved_name_error(&format!("type \"{name}\"")));
+                }
+                continue;
+            }
+            // Empty mutation and subscription markers are never exposed.
+            if meta.is_builtin() {
+                continue;
+            }
+
+            match meta {
+                MetaType::Object(ObjectMeta {
+                    fields,
+                    interface_names,
+                    ..
+                }) => {
+                    self.validate_fields(name, "Object", fields, &mut errors);
+                    self.validate_interfaces(name, fields, interface_names, &mut errors);
+                }
+                MetaType::Interface(InterfaceMeta {
+                    fields,
+                    interface_names,
+                    ..
+                }) => {
+                    self.validate_fields(name, "Interface", fields, &mut errors);
+                    self.validate_interfaces(name, fields, interface_names, &mut errors);
+                }
+                MetaType::Union(UnionMeta { of_type_names, .. }) => {
+                    if of_type_names.is_empty() {
+                        errors.push(SchemaError::new(format!(
+                            "Union type \"{name}\" must define one or more member types",
+                        )));
+                    }
+                    for member in of_type_names {
+                        match self.concrete_type_by_name(member) {
+                            Some(MetaType::Object(_)) => {}
+                            Some(_) => errors.push(SchemaError::new(format!(
+                                "Union type \"{name}\" can only include object types, \
+                                 but \"{member}\" is not an object type",
+                            ))),
+                            None => errors.push(unknown_type_error(
+                                &format!("Union type \"{name}\""),
+                                member,
+                            )),
+                        }
+                    }
+                }
+                MetaType::Enum(EnumMeta { values, .. }) => {
+                    if values.is_empty() {
+                        errors.push(SchemaError::new(format!(
+                            "Enum type \"{name}\" must define one or more values",
+                        )));
+                    }
+                    for v in values {
+                        if matches!(v.name.as_str(), "true" | "false" | "null") {
+                            errors.push(SchemaError::new(format!(
+                                "Enum type \"{name}\" cannot include value \"{}\"",
+                                v.name,
+                            )));
+                        }
+                    }
+                }
+                MetaType::InputObject(InputObjectMeta { input_fields, .. }) => {
+                    if input_fields.is_empty() {
+                        errors.push(SchemaError::new(format!(
+                            "Input Object type \"{name}\" must define one or more fields",
+                        )));
+                    }
+                    for f in input_fields {
+                        self.validate_input_value(&format!("{name}.{}", f.name), f, &mut errors);
+                    }
+                }
+                MetaType::Scalar(_)
+                | MetaType::List(_)
+                | MetaType::Nullable(_)
+                | MetaType::Placeholder(_) => {}
+            }
+        }
+
+        let mut directives = self.directive_list();
+        directives.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        for d in directives {
+            for arg in &d.arguments {
+                self.validate_input_value(&format!("@{}({}:)", d.name, arg.name), arg, &mut errors);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validates the `fields` of the object or interface type with the
+    /// provided `type_name`.
+    fn validate_fields(
+        &self,
+        type_name: &str,
+        kind: &str,
+        fields: &[Field<S>],
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if fields.iter().all(|f| f.is_builtin()) {
+            errors.push(SchemaError::new(format!(
+                "{kind} type \"{type_name}\" must define one or more fields",
+            )));
+        }
+
+        for f in fields {
+            let coordinate = format!("{type_name}.{}", f.name);
+            // Introspection fields are added by `juniper` itself.
+            let is_introspection = f.name == "__typename"
+                || (type_name == self.query_type_name
+                    && matches!(f.name.as_str(), "__schema" | "__type"));
+            if f.is_builtin() && !is_introspection {
+                errors.push(reserved_name_error(&format!("field \"{coordinate}\"")));
+            }
+
+            match self.concrete_type_by_name(f.field_type.innermost_name()) {
+                Some(MetaType::InputObject(_)) => errors.push(SchemaError::new(format!(
+                    "The type of \"{coordinate}\" must be an output type, but got \"{}\"",
+                    f.field_type,
+                ))),
+                Some(_) => {}
+                None => errors.push(unknown_type_error(
+                    &format!("Field \"{coordinate}\""),
+                    f.field_type.innermost_name(),
+                )),
+            }
+
+            if is_introspection {
+                continue;
+            }
+            for arg in f.arguments.iter().flatten() {
+                self.validate_input_value(&format!("{coordinate}({}:)", arg.name), arg, errors);
+            }
+        }
+    }
+
+    /// Validates the input value (argument or input field) with the provided
+    /// `coordinate`.
+    fn validate_input_value(
+        &self,
+        coordinate: &str,
+        arg: &Argument<S>,
+        errors: &mut Vec<SchemaError>,
+    ) {
+        if arg.name.starts_with("__") {
+            errors.push(reserved_name_error(&format!("\"{coordinate}\"")));
+        }
+
+        match self.concrete_type_by_name(arg.arg_type.innermost_name()) {
+            Some(t) if !t.is_input() => errors.push(SchemaError::new(format!(
+                "The type of \"{coordinate}\" must be an input type, but got \"{}\"",
+                arg.arg_type,
+            ))),
+            Some(_) => {}
+            None => errors.push(unknown_type_error(
+                &format!("\"{coordinate}\""),
+                arg.arg_type.innermost_name(),
+            )),
+        }
+
+        if arg.deprecation_status.is_deprecated()
+            && arg.arg_type.is_non_null()
+            && arg.default_value.is_none()
+        {
+            errors.push(SchemaError::new(format!(
+                "Required \"{coordinate}\" cannot be deprecated",
+            )));
+        }
+    }
+
+    /// Validates the object or interface type with the provided `type_name`
+    /// implementing the interfaces with the provided `interface_names`
+    /// correctly.
+    fn validate_interfaces(
+        &self,
+        type_name: &str,
+        fields: &[Field<S>],
+        interface_names: &[String],
+        errors: &mut Vec<SchemaError>,
+    ) {
+        for iface_name in interface_names {
+            if iface_name == type_name {
+                errors.push(SchemaError::new(format!(
+                    "Type \"{type_name}\" cannot implement itself",
+                )));
+                continue;
+            }
+            let iface = match self.concrete_type_by_name(iface_name) {
+                Some(MetaType::Interface(i)) => i,
+                Some(_) => {
+                    errors.push(SchemaError::new(format!(
+                        "Type \"{type_name}\" must only implement interface types, \
+                         but \"{iface_name}\" is not an interface type",
+                    )));
+                    continue;
+                }
+                None => {
+                    errors.push(unknown_type_error(
+                        &format!("Type \"{type_name}\""),
+                        iface_name,
+                    ));
+                    continue;
+                }
+            };
+
+            for transitive in &iface.interface_names {
+                if transitive != type_name && !interface_names.contains(transitive) {
+                    errors.push(SchemaError::new(format!(
+                        "Type \"{type_name}\" must implement \"{transitive}\", \
+                         because it is implemented by \"{iface_name}\"",
+                    )));
+                }
+            }
+
+            for iface_field in iface.fields.iter().filter(|f| !f.is_builtin()) {
+                let field = match fields.iter().find(|f| f.name == iface_field.name) {
+                    Some(f) => f,
+                    None => {
+                        errors.push(SchemaError::new(format!(
+                            "Interface field \"{iface_name}.{}\" expected, \
+                             but \"{type_name}\" does not provide it",
+                            iface_field.name,
+                        )));
+                        continue;
+                    }
+                };
+
+                if !self.is_implementation_subtype(&field.field_type, &iface_field.field_type) {
+                    errors.push(SchemaError::new(format!(
+                        "Interface field \"{iface_name}.{}\" expects type \"{}\", \
+                         but \"{type_name}.{}\" is of type \"{}\"",
+                        iface_field.name, iface_field.field_type, field.name, field.field_type,
+                    )));
+                }
+
+                let args = field.arguments.as_deref().unwrap_or_default();
+                let iface_args = iface_field.arguments.as_deref().unwrap_or_default();
+                for iface_arg in iface_args {
+                    match args.iter().find(|a| a.name == iface_arg.name) {
+                        Some(arg) if arg.arg_type.to_string() != iface_arg.arg_type.to_string() => {
+                            errors.push(SchemaError::new(format!(
+                                "Interface field argument \"{iface_name}.{}({}:)\" expects \
+                                 type \"{}\", but \"{type_name}.{}({}:)\" is of type \"{}\"",
+                                iface_field.name,
+                                iface_arg.name,
+                                iface_arg.arg_type,
+                                field.name,
+                                arg.name,
+                                arg.arg_type,
+                            )))
+                        }
+                        Some(_) => {}
+                        None => errors.push(SchemaError::new(format!(
+                            "Interface field argument \"{iface_name}.{}({}:)\" expected, \
+                             but \"{type_name}.{}\" does not provide it",
+                            iface_field.name, iface_arg.name, field.name,
+                        ))),
+                    }
+                }
+                for arg in args {
+                    let is_required = arg.arg_type.is_non_null() && arg.default_value.is_none();
+                    if is_required && iface_args.iter().all(|a| a.name != arg.name) {
+                        errors.push(SchemaError::new(format!(
+                            "Field \"{type_name}.{}\" includes required argument \"{}\", \
+                             which is missing from the interface field \"{iface_name}.{}\"",
+                            field.name, arg.name, iface_field.name,
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks whether the `sub` type may be used for implementing an interface
+    /// field of the `sup` type.
+    fn is_implementation_subtype(&self, sub: &Type, sup: &Type) -> bool {
+        match (sub, sup) {
+            (Type::NonNullNamed(sub), Type::NonNullNamed(sup) | Type::Named(sup))
+            | (Type::Named(sub), Type::Named(sup)) => self.is_named_implementation(sub, sup),
+            (Type::NonNullList(sub, _), Type::NonNullList(sup, _) | Type::List(sup, _))
+            | (Type::List(sub, _), Type::List(sup, _)) => self.is_implementation_subtype(sub, sup),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the `sub` named type is the same as the `sup` one, or is
+    /// its member or implementer.
+    fn is_named_implementation(&self, sub: &str, sup: &str) -> bool {
+        if sub == sup {
+            return true;
+        }
+        match (
+            self.concrete_type_by_name(sub),
+            self.concrete_type_by_name(sup),
+        ) {
+            (Some(_), Some(MetaType::Union(u))) => u.of_type_names.iter().any(|n| n == sub),
+            (
+                Some(
+                    MetaType::Object(ObjectMeta {
+                        interface_names, ..
+                    })
+                    | MetaType::Interface(InterfaceMeta {
+                        interface_names, ..
+                    }),
+                ),
+                Some(MetaType::Interface(_)),
+            ) => interface_names.iter().any(|n| n == sup),
+            _ => false,
+        }
+    }
+}
+
+/// Returns a shape of the provided [`MetaType`], describing its definition
+/// without descriptions.
+///
+/// Used for detecting the same type name being registered by different Rust
+/// types.
+pub(crate) fn type_shape<S>(meta: &MetaType<S>) -> String {
+    fn fields<S>(fields: &[Field<S>]) -> String {
+        fields
+            .iter()
+            .filter(|f| !f.is_builtin())
+            .map(|f| {
+                let args = f.arguments.as_deref().unwrap_or_default();
+                format!("{}{}: {}", f.name, input_values(args), f.field_type)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+    fn interfaces(names: &[String]) -> String {
+        if names.is_empty() {
+            String::new()
+        } else {
+            format!(" implements {}", names.join(" & "))
+        }
+    }
+    fn input_values<S>(args: &[Argument<S>]) -> String {
+        if args.is_empty() {
+            return String::new();
+        }
+        let args = args
+            .iter()
+            .map(|a| format!("{}: {}", a.name, a.arg_type))
+            .collect::<Vec<_>>();
+        format!("({})", args.join(", "))
+    }
+
+    match meta {
+        MetaType::Scalar(s) => format!("scalar {}", s.name),
+        MetaType::Object(o) => format!(
+            "type {}{} {{ {} }}",
+            o.name,
+            interfaces(&o.interface_names),
+            fields(&o.fields),
+        ),
+        MetaType::Interface(i) => format!(
+            "interface {}{} {{ {} }}",
+            i.name,
+            interfaces(&i.interface_names),
+            fields(&i.fields),
+        ),
+        MetaType::Union(u) => format!("union {} = {}", u.name, u.of_type_names.join(" | ")),
+        MetaType::Enum(e) => {
+            let values = e.values.iter().map(|v| v.name.as_str());
+            format!(
+                "enum {} {{ {} }}",
+                e.name,
+                values.collect::<Vec<_>>().join(", ")
+            )
+        }
+        MetaType::InputObject(i) => {
+            format!("input {} {}", i.name, input_values(&i.input_fields))
+        }
+        MetaType::List(l) => format!("[{}]", l.of_type),
+        MetaType::Nullable(n) => n.of_type.to_string(),
+        MetaType::Placeholder(p) => p.of_type.to_string(),
+    }
+}
+
+fn unknown_type_error(subject: &str, type_name: &str) -> SchemaError {
+    SchemaError::new(format!(
+        "{subject} references type \"{type_name}\" missing in the schema",
+    ))
+}
+
+fn reserved_name_error(subject: &str) -> SchemaError {
+    SchemaError::new(format!(
+        "Name of {subject} must not begin with \"__\", which is reserved by GraphQL introspection",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fnv::FnvHashMap;
+
+    use crate::{
+        ast::Type,
+        executor::Registry,
+        graphql_object,
+        schema::{
+            meta::{Argument, MetaType, ObjectMeta, UnionMeta},
+            model::{RootNode, SchemaType},
+        },
+        tests::fixtures::starwars::schema::{Database, Human, Query},
+        types::scalars::{EmptyMutation, EmptySubscription},
+        DefaultScalarValue, GraphQLObject,
+    };
+
+    use super::SchemaError;
+
+    #[test]
+    fn valid_schema() {
+        let schema = RootNode::try_new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        assert!(schema.is_ok(), "{:?}", schema.err());
+    }
+
+    #[test]
+    fn duplicate_type_names() {
+        mod first {
+            #[derive(crate::GraphQLObject)]
+            #[graphql(name = "Dup")]
+            pub struct Dup {
+                pub a: i32,
+            }
+        }
+
+        mod second {
+            #[derive(crate::GraphQLObject)]
+            #[graphql(name = "Dup")]
+            pub struct Dup {
+                pub b: String,
+            }
+        }
+
+        mod nested_first {
+            #[derive(crate::GraphQLObject)]
+            pub struct Outer {
+                pub inner: Inner,
+            }
+
+            #[derive(crate::GraphQLObject)]
+            pub struct Inner {
+                pub a: i32,
+            }
+        }
+
+        mod nested_second {
+            #[derive(crate::GraphQLObject)]
+            pub struct Outer {
+                pub inner: Inner,
+            }
+
+            #[derive(crate::GraphQLObject)]
+            pub struct Inner {
+                pub b: String,
+            }
+        }
+
+        #[derive(GraphQLObject)]
+        struct Same {
+            a: i32,
+        }
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn first() -> first::Dup {
+                first::Dup { a: 1 }
+            }
+
+            fn second() -> second::Dup {
+                second::Dup { b: "b".into() }
+            }
+
+            fn same() -> Same {
+                Same { a: 1 }
+            }
+
+            fn same_again() -> Same {
+                Same { a: 2 }
+            }
+
+            fn same_boxed() -> Box<Same> {
+                Box::new(Same { a: 3 })
+            }
+
+            // Definitions of nested types are checked too, even if the outer
+            // ones match.
+            fn nested_first() -> nested_first::Outer {
+                nested_first::Outer {
+                    inner: nested_first::Inner { a: 1 },
+                }
+            }
+
+            fn nested_second() -> nested_second::Outer {
+                nested_second::Outer {
+                    inner: nested_second::Inner { b: "b".into() },
+                }
+            }
+        }
+
+        let errors = RootNode::try_new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema should be invalid");
+
+        assert_eq!(
+            errors,
+            [
+                SchemaError::new(
+                    "Type \"Dup\" is registered multiple times with different definitions: \
+                     `type Dup { a: Int! }` and `type Dup { b: String! }`",
+                ),
+                SchemaError::new(
+                    "Type \"Inner\" is registered multiple times with different definitions: \
+                     `type Inner { a: Int! }` and `type Inner { b: String! }`",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn invalid_definitions() {
+        let mut schema = SchemaType::<DefaultScalarValue>::new::<
+            Query,
+            EmptyMutation<Database>,
+            EmptySubscription<Database>,
+        >(&(), &(), &());
+
+        let mut registry = Registry::new(FnvHashMap::default());
+        let fields = [
+            registry
+                .field::<Option<String>>("search", &())
+                .argument(Argument::new("by", Type::Named("Human".into())))
+                .argument(
+                    Argument::new("limit", Type::NonNullNamed("String".into())).deprecated(None),
+                )
+                .argument(Argument::new("after", Type::Named("String".into())).deprecated(None)),
+            registry.field::<String>("__secret", &()),
+        ];
+        let types = [
+            ObjectMeta::new("Broken".into(), &fields)
+                .interfaces(&[
+                    Type::NonNullNamed("Human".into()),
+                    Type::NonNullNamed("Missing".into()),
+                ])
+                .into_meta(),
+            MetaType::Union(UnionMeta::new("Empty".into(), &[])),
+            MetaType::Union(UnionMeta::new(
+                "Bad".into(),
+                &[Type::NonNullNamed("Episode".into())],
+            )),
+        ];
+        for meta in types {
+            let name = meta.name().unwrap().parse().unwrap();
+            schema.types.insert(name, meta);
+        }
+
+        assert_eq!(
+            schema.validate().err().expect("schema should be invalid"),
+            [
+                "Union type \"Bad\" can only include object types, \
+                 but \"Episode\" is not an object type",
+                "The type of \"Broken.search(by:)\" must be an input type, but got \"Human\"",
+                "Required \"Broken.search(limit:)\" cannot be deprecated",
+                "Name of field \"Broken.__secret\" must not begin with \"__\", \
+                 which is reserved by GraphQL introspection",
+                "Type \"Broken\" must only implement interface types, \
+                 but \"Human\" is not an interface type",
+                "Type \"Broken\" references type \"Missing\" missing in the schema",
+                "Union type \"Empty\" must define one or more member types",
+            ]
+            .into_iter()
+            .map(SchemaError::new)
+            .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn invalid_interface_implementation() {
+        let mut schema = SchemaType::<DefaultScalarValue>::new::<
+            Query,
+            EmptyMutation<Database>,
+            EmptySubscription<Database>,
+        >(&(), &(), &());
+
+        let mut registry = Registry::new(FnvHashMap::default());
+        let fields = [
+            registry.field::<Option<String>>("id", &()),
+            registry
+                .field::<Option<String>>("name", &())
+                .argument(registry.arg::<String>("extra", &())),
+            // Narrowing the interface field type is fine.
+            registry.field::<Vec<Human>>("friends", &()),
+        ];
+        let meta = ObjectMeta::new("Broken".into(), &fields)
+            .interfaces(&[Type::NonNullNamed("Character".into())])
+            .into_meta();
+        schema.types.insert("Broken".parse().unwrap(), meta);
+
+        assert_eq!(
+            schema.validate().err().expect("schema should be invalid"),
+            [
+                "Interface field \"Character.id\" expects type \"String!\", \
+                 but \"Broken.id\" is of type \"String\"",
+                "Field \"Broken.name\" includes required argument \"extra\", \
+                 which is missing from the interface field \"Character.name\"",
+                "Interface field \"Character.appearsIn\" expected, \
+                 but \"Broken\" does not provide it",
+            ]
+            .into_iter()
+            .map(SchemaError::new)
+            .collect::<Vec<_>>(),
+        );
+    }
+}
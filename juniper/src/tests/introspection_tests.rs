@@ -1,11 +1,12 @@
 use std::collections::HashSet;
 
 use crate::{
-    graphql_vars,
+    graphql_object, graphql_vars,
     introspection::IntrospectionFormat,
     schema::model::RootNode,
     tests::fixtures::starwars::schema::{Database, Query},
     types::scalars::{EmptyMutation, EmptySubscription},
+    GraphQLInputObject, Value,
 };
 
 use super::schema_introspection::*;
@@ -212,6 +213,8 @@ async fn test_introspection_directives() {
                     "name": "deprecated",
                     "locations": [
                         "FIELD_DEFINITION",
+                        "ARGUMENT_DEFINITION",
+                        "INPUT_FIELD_DEFINITION",
                         "ENUM_VALUE",
                     ],
                 },
@@ -308,3 +311,77 @@ async fn test_builtin_introspection_query_without_descriptions() {
 
     assert_eq!(result, (expected, vec![]));
 }
+
+#[tokio::test]
+async fn test_builtin_introspection_query_includes_deprecated_inputs() {
+    #[derive(GraphQLInputObject)]
+    struct Point {
+        x: i32,
+        #[graphql(deprecated = "Use `x`.")]
+        y: Option<i32>,
+    }
+
+    struct DeprecatedQuery;
+
+    #[graphql_object]
+    impl DeprecatedQuery {
+        fn sum(point: Point, #[graphql(deprecated = "Use `point`.")] z: Option<i32>) -> i32 {
+            point.x + point.y.unwrap_or_default() + z.unwrap_or_default()
+        }
+    }
+
+    fn find<'v>(list: &'v Value, name: &str) -> &'v Value {
+        list.as_list_value()
+            .expect("not a list")
+            .iter()
+            .find(|v| {
+                v.as_object_value()
+                    .and_then(|o| o.get_field_value("name"))
+                    .and_then(|n| n.as_scalar_value::<String>())
+                    .map_or(false, |n| n == name)
+            })
+            .unwrap_or_else(|| panic!("`{name}` not found"))
+    }
+
+    fn field<'v>(value: &'v Value, name: &str) -> &'v Value {
+        value
+            .as_object_value()
+            .expect("not an object")
+            .get_field_value(name)
+            .unwrap_or_else(|| panic!("`{name}` not present"))
+    }
+
+    let schema = RootNode::new(
+        DeprecatedQuery,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    for format in [
+        IntrospectionFormat::All,
+        IntrospectionFormat::WithoutDescriptions,
+    ] {
+        let (result, errs) = crate::introspect(&schema, &(), format).unwrap();
+        assert_eq!(errs, []);
+
+        let types = field(field(&result, "__schema"), "types");
+
+        let query = find(types, "DeprecatedQuery");
+        let arg = find(field(find(field(query, "fields"), "sum"), "args"), "z");
+        assert_eq!(field(arg, "isDeprecated"), &graphql_value!(true));
+        assert_eq!(
+            field(arg, "deprecationReason"),
+            &graphql_value!("Use `point`.")
+        );
+
+        let point = find(types, "Point");
+        let input_field = find(field(point, "inputFields"), "y");
+        assert_eq!(field(input_field, "isDeprecated"), &graphql_value!(true));
+        assert_eq!(
+            field(input_field, "deprecationReason"),
+            &graphql_value!("Use `x`.")
+        );
+        let input_field = find(field(point, "inputFields"), "x");
+        assert_eq!(field(input_field, "isDeprecated"), &graphql_value!(false));
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{
     graphql_value, graphql_vars,
+    parser::{DocumentLimit, DocumentLimits, ParseError, SourcePosition, Spanning},
     schema::model::RootNode,
     tests::fixtures::starwars::schema::{Database, Query},
     types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
+    GraphQLError,
 };
 
 #[tokio::test]
@@ -424,3 +427,128 @@ async fn interface_inline_fragment_friends() {
         ))
     );
 }
+
+#[tokio::test]
+async fn document_limits() {
+    let database = Database::new();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+    .with_limits(DocumentLimits {
+        max_depth: Some(2),
+        max_root_fields: Some(1),
+        ..DocumentLimits::default()
+    });
+
+    let doc = r#"{ hero { name } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Ok((graphql_value!({"hero": {"name": "R2-D2"}}), vec![])),
+    );
+
+    let doc = r#"{ hero { friends { name } } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Err(GraphQLError::ParseError(Spanning::single_width(
+            &SourcePosition::new(17, 0, 17),
+            ParseError::LimitExceeded(DocumentLimit::Depth, 2),
+        ))),
+    );
+
+    let doc = r#"{ hero { name } ...Luke } fragment Luke on Query { human(id: "1000") { name } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Operation exceeds the limit of 1 root fields",
+            &[SourcePosition::new(0, 0, 0)],
+        )])),
+    );
+}
+
+#[tokio::test]
+async fn custom_validation_rules() {
+    use crate::{
+        ast::{Field, Operation},
+        validation::{ValidatorContext, Visitor},
+        value::ScalarValue,
+    };
+
+    struct NoAnonymousOperations;
+
+    impl<'a, S: ScalarValue> Visitor<'a, S> for NoAnonymousOperations {
+        fn enter_operation_definition(
+            &mut self,
+            ctx: &mut ValidatorContext<'a, S>,
+            op: &'a Spanning<Operation<S>>,
+        ) {
+            if op.item.name.is_none() {
+                ctx.report_error("Anonymous operations are not allowed", &[op.start]);
+            }
+        }
+    }
+
+    struct NoHomePlanets;
+
+    impl<'a, S: ScalarValue> Visitor<'a, S> for NoHomePlanets {
+        fn enter_field(
+            &mut self,
+            ctx: &mut ValidatorContext<'a, S>,
+            field: &'a Spanning<Field<S>>,
+        ) {
+            let parent = ctx.parent_type();
+            let meta = parent.and_then(|t| t.field_by_name(field.item.name.item));
+            if let (Some(parent), Some(meta)) = (parent, meta) {
+                if meta.name == "homePlanet" {
+                    let msg = format!(
+                        "Field \"{}.{}\" is internal",
+                        parent.name().unwrap_or_default(),
+                        meta.name,
+                    );
+                    ctx.report_error(&msg, &[field.start]);
+                }
+            }
+        }
+    }
+
+    let database = Database::new();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+    .with_validation_rule(|| NoAnonymousOperations)
+    .with_validation_rule(|| NoHomePlanets);
+
+    let doc = r#"query Hero { hero { name } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Ok((graphql_value!({"hero": {"name": "R2-D2"}}), vec![])),
+    );
+
+    let doc = r#"{ human(id: "1000") { name homePlanet } }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Err(GraphQLError::ValidationError(vec![
+            RuleError::new(
+                "Anonymous operations are not allowed",
+                &[SourcePosition::new(0, 0, 0)],
+            ),
+            RuleError::new(
+                "Field \"Human.homePlanet\" is internal",
+                &[SourcePosition::new(27, 0, 27)],
+            ),
+        ])),
+    );
+
+    // User-defined rules are not run on the documents failing the built-in ones.
+    let doc = r#"{ unknown }"#;
+    assert_eq!(
+        crate::execute(doc, None, &schema, &graphql_vars! {}, &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Unknown field \"unknown\" on type \"Query\"",
+            &[SourcePosition::new(2, 0, 2)],
+        )])),
+    );
+}
@@ -221,6 +221,34 @@ pub(crate) fn schema_introspection_result() -> Value {
                   },
                   "isDeprecated": false,
                   "deprecationReason": null
+                },
+                {
+                  "name": "isDeprecated",
+                  "description": null,
+                  "args": [],
+                  "type": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "deprecationReason",
+                  "description": null,
+                  "args": [],
+                  "type": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ],
               "inputFields": null,
@@ -276,7 +304,20 @@ pub(crate) fn schema_introspection_result() -> Value {
                 {
                   "name": "args",
                   "description": null,
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "description": null,
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "NON_NULL",
                     "name": null,
@@ -465,7 +506,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                         "name": "Boolean",
                         "ofType": null
                       },
-                      "defaultValue": "false"
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -499,7 +542,20 @@ pub(crate) fn schema_introspection_result() -> Value {
                 {
                   "name": "inputFields",
                   "description": null,
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "description": null,
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "LIST",
                     "name": null,
@@ -580,7 +636,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                         "name": "Boolean",
                         "ofType": null
                       },
-                      "defaultValue": "false"
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -845,7 +903,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                           "ofType": null
                         }
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -873,7 +933,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                           "ofType": null
                         }
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -896,7 +958,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                         "name": "Episode",
                         "ofType": null
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -1085,6 +1149,54 @@ pub(crate) fn schema_introspection_result() -> Value {
                   "description": null,
                   "isDeprecated": false,
                   "deprecationReason": null
+                },
+                {
+                  "name": "SCHEMA",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "OBJECT",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "ARGUMENT_DEFINITION",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INTERFACE",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "UNION",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "ENUM",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INPUT_OBJECT",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INPUT_FIELD_DEFINITION",
+                  "description": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ],
               "possibleTypes": null
@@ -1265,7 +1377,20 @@ pub(crate) fn schema_introspection_result() -> Value {
                 {
                   "name": "args",
                   "description": null,
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "description": null,
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "NON_NULL",
                     "name": null,
@@ -1364,7 +1489,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -1390,7 +1517,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -1400,6 +1529,8 @@ pub(crate) fn schema_introspection_result() -> Value {
               "isRepeatable": false,
               "locations": [
                 "FIELD_DEFINITION",
+                "ARGUMENT_DEFINITION",
+                "INPUT_FIELD_DEFINITION",
                 "ENUM_VALUE"
               ],
               "args": [
@@ -1415,7 +1546,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -1439,7 +1572,9 @@ pub(crate) fn schema_introspection_result() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             }
@@ -1625,6 +1760,32 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                   },
                   "isDeprecated": false,
                   "deprecationReason": null
+                },
+                {
+                  "name": "isDeprecated",
+                  "args": [],
+                  "type": {
+                    "kind": "NON_NULL",
+                    "name": null,
+                    "ofType": {
+                      "kind": "SCALAR",
+                      "name": "Boolean",
+                      "ofType": null
+                    }
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "deprecationReason",
+                  "args": [],
+                  "type": {
+                    "kind": "SCALAR",
+                    "name": "String",
+                    "ofType": null
+                  },
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ],
               "inputFields": null,
@@ -1675,7 +1836,19 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                 },
                 {
                   "name": "args",
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "NON_NULL",
                     "name": null,
@@ -1846,7 +2019,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                         "name": "Boolean",
                         "ofType": null
                       },
-                      "defaultValue": "false"
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -1878,7 +2053,19 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                 },
                 {
                   "name": "inputFields",
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "LIST",
                     "name": null,
@@ -1954,7 +2141,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                         "name": "Boolean",
                         "ofType": null
                       },
-                      "defaultValue": "false"
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -2203,7 +2392,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                           "ofType": null
                         }
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -2228,7 +2419,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                           "ofType": null
                         }
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -2249,7 +2442,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                         "name": "Episode",
                         "ofType": null
                       },
-                      "defaultValue": null
+                      "defaultValue": null,
+                      "isDeprecated": false,
+                      "deprecationReason": null
                     }
                   ],
                   "type": {
@@ -2417,6 +2612,46 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                   "name": "ENUM_VALUE",
                   "isDeprecated": false,
                   "deprecationReason": null
+                },
+                {
+                  "name": "SCHEMA",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "OBJECT",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "ARGUMENT_DEFINITION",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INTERFACE",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "UNION",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "ENUM",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INPUT_OBJECT",
+                  "isDeprecated": false,
+                  "deprecationReason": null
+                },
+                {
+                  "name": "INPUT_FIELD_DEFINITION",
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ],
               "possibleTypes": null
@@ -2586,7 +2821,19 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                 },
                 {
                   "name": "args",
-                  "args": [],
+                  "args": [
+                    {
+                      "name": "includeDeprecated",
+                      "type": {
+                        "kind": "SCALAR",
+                        "name": "Boolean",
+                        "ofType": null
+                      },
+                      "defaultValue": "false",
+                      "isDeprecated": false,
+                      "deprecationReason": null
+                    }
+                  ],
                   "type": {
                     "kind": "NON_NULL",
                     "name": null,
@@ -2680,7 +2927,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -2704,7 +2953,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -2713,6 +2964,8 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
               "isRepeatable": false,
               "locations": [
                 "FIELD_DEFINITION",
+                "ARGUMENT_DEFINITION",
+                "INPUT_FIELD_DEFINITION",
                 "ENUM_VALUE"
               ],
               "args": [
@@ -2727,7 +2980,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             },
@@ -2749,7 +3004,9 @@ pub(crate) fn schema_introspection_result_without_descriptions() -> Value {
                       "ofType": null
                     }
                   },
-                  "defaultValue": null
+                  "defaultValue": null,
+                  "isDeprecated": false,
+                  "deprecationReason": null
                 }
               ]
             }
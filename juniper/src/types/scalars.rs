@@ -1,6 +1,4 @@
-use std::{
-    char, convert::From, fmt, marker::PhantomData, ops::Deref, rc::Rc, thread::JoinHandle, u32,
-};
+use std::{convert::From, fmt, marker::PhantomData, ops::Deref, rc::Rc, thread::JoinHandle};
 
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +7,7 @@ use crate::{
     executor::{ExecutionResult, Executor, Registry},
     graphql_scalar,
     macros::reflect,
-    parser::{LexerError, ParseError, ScalarToken, Token},
+    parser::{ParseError, ScalarToken, Token},
     schema::meta::MetaType,
     types::{
         async_await::GraphQLValueAsync,
@@ -85,109 +83,14 @@ mod impl_string_scalar {
     }
 
     pub(super) fn parse_token<S: ScalarValue>(value: ScalarToken<'_>) -> ParseScalarResult<S> {
-        if let ScalarToken::String(value) = value {
-            let mut ret = String::with_capacity(value.len());
-            let mut char_iter = value.chars();
-            while let Some(ch) = char_iter.next() {
-                match ch {
-                    '\\' => match char_iter.next() {
-                        Some('"') => {
-                            ret.push('"');
-                        }
-                        Some('/') => {
-                            ret.push('/');
-                        }
-                        Some('n') => {
-                            ret.push('\n');
-                        }
-                        Some('r') => {
-                            ret.push('\r');
-                        }
-                        Some('t') => {
-                            ret.push('\t');
-                        }
-                        Some('\\') => {
-                            ret.push('\\');
-                        }
-                        Some('f') => {
-                            ret.push('\u{000c}');
-                        }
-                        Some('b') => {
-                            ret.push('\u{0008}');
-                        }
-                        Some('u') => {
-                            ret.push(parse_unicode_codepoint(&mut char_iter)?);
-                        }
-                        Some(s) => {
-                            return Err(ParseError::LexerError(LexerError::UnknownEscapeSequence(
-                                format!("\\{s}"),
-                            )))
-                        }
-                        None => return Err(ParseError::LexerError(LexerError::UnterminatedString)),
-                    },
-                    ch => {
-                        ret.push(ch);
-                    }
-                }
-            }
-            Ok(ret.into())
+        if let ScalarToken::String(lit) = value {
+            Ok(lit.parse()?.into_owned().into())
         } else {
             Err(ParseError::unexpected_token(Token::Scalar(value)))
         }
     }
 }
 
-fn parse_unicode_codepoint<I>(char_iter: &mut I) -> Result<char, ParseError>
-where
-    I: Iterator<Item = char>,
-{
-    let escaped_code_point = char_iter
-        .next()
-        .ok_or_else(|| {
-            ParseError::LexerError(LexerError::UnknownEscapeSequence(String::from("\\u")))
-        })
-        .and_then(|c1| {
-            char_iter
-                .next()
-                .map(|c2| format!("{c1}{c2}"))
-                .ok_or_else(|| {
-                    ParseError::LexerError(LexerError::UnknownEscapeSequence(format!("\\u{c1}")))
-                })
-        })
-        .and_then(|mut s| {
-            char_iter
-                .next()
-                .ok_or_else(|| {
-                    ParseError::LexerError(LexerError::UnknownEscapeSequence(format!("\\u{s}")))
-                })
-                .map(|c2| {
-                    s.push(c2);
-                    s
-                })
-        })
-        .and_then(|mut s| {
-            char_iter
-                .next()
-                .ok_or_else(|| {
-                    ParseError::LexerError(LexerError::UnknownEscapeSequence(format!("\\u{s}")))
-                })
-                .map(|c2| {
-                    s.push(c2);
-                    s
-                })
-        })?;
-    let code_point = u32::from_str_radix(&escaped_code_point, 16).map_err(|_| {
-        ParseError::LexerError(LexerError::UnknownEscapeSequence(format!(
-            "\\u{escaped_code_point}",
-        )))
-    })?;
-    char::from_u32(code_point).ok_or_else(|| {
-        ParseError::LexerError(LexerError::UnknownEscapeSequence(format!(
-            "\\u{escaped_code_point}",
-        )))
-    })
-}
-
 impl<S> reflect::WrappedType<S> for str {
     const VALUE: reflect::WrappedValue = 1;
 }
@@ -465,7 +368,7 @@ impl<T> Default for EmptySubscription<T> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        parser::ScalarToken,
+        parser::{ScalarToken, StringLiteral},
         value::{DefaultScalarValue, ParseScalarValue},
     };
 
@@ -500,8 +403,9 @@ mod tests {
     #[test]
     fn parse_strings() {
         fn parse_string(s: &str, expected: &str) {
-            let s =
-                <String as ParseScalarValue<DefaultScalarValue>>::from_str(ScalarToken::String(s));
+            let s = <String as ParseScalarValue<DefaultScalarValue>>::from_str(
+                ScalarToken::String(StringLiteral::Quoted(s)),
+            );
             assert!(s.is_ok(), "A parsing error occurred: {s:?}");
             let s: Option<String> = s.unwrap().into();
             assert!(s.is_some(), "No string returned");
@@ -517,6 +421,34 @@ mod tests {
             r#"unicode \u1234\u5678\u90AB\uCDEF"#,
             "unicode \u{1234}\u{5678}\u{90ab}\u{cdef}",
         );
+        parse_string(r#"variable \u{1F600} \u{41}"#, "variable \u{1F600} A");
+        parse_string(r#"surrogate pair \uD83D\uDE00"#, "surrogate pair \u{1F600}");
+    }
+
+    #[test]
+    fn parse_block_strings() {
+        fn parse_block_string(s: &str, expected: &str) {
+            let s = <String as ParseScalarValue<DefaultScalarValue>>::from_str(
+                ScalarToken::String(StringLiteral::Block(s)),
+            );
+            assert!(s.is_ok(), "A parsing error occurred: {s:?}");
+            let s: Option<String> = s.unwrap().into();
+            assert!(s.is_some(), "No string returned");
+            assert_eq!(s.unwrap(), expected);
+        }
+
+        parse_block_string("simple", "simple");
+        parse_block_string(r#"no \n escapes \u1234"#, r#"no \n escapes \u1234"#);
+        parse_block_string(r#"escaped \""" quotes"#, r#"escaped """ quotes"#);
+        parse_block_string(
+            "\n    Hello,\n      World!\n\n    Yours,\n      GraphQL.\n  ",
+            "Hello,\n  World!\n\nYours,\n  GraphQL.",
+        );
+        parse_block_string(
+            "  first line\r\n    indented\r  second",
+            "  first line\n  indented\nsecond",
+        );
+        parse_block_string("\n  \n\t\n", "");
     }
 
     #[test]
@@ -10,7 +10,7 @@ use crate::{
 
 /// Represents the result of executing a GraphQL operation (after parsing and validating has been
 /// done).
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ExecutionOutput<S> {
     /// The output data.
     pub data: Value<S>,
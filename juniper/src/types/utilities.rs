@@ -57,6 +57,13 @@ where
             match *arg_value {
                 InputValue::Null | InputValue::Variable(_) => true,
                 ref v @ InputValue::Scalar(_) | ref v @ InputValue::Enum(_) => {
+                    if let (InputValue::Enum(name), MetaType::Enum(EnumMeta { values, .. })) =
+                        (v, t)
+                    {
+                        if !values.iter().any(|ev| &ev.name == name) {
+                            return false;
+                        }
+                    }
                     if let Some(parse_fn) = t.input_value_parse_fn() {
                         parse_fn(v).is_ok()
                     } else {
@@ -0,0 +1,782 @@
+//! Collecting statistics of the schema members used by the executed operations.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    ast::{
+        Directive, Document, Field, Fragment, FragmentSpread, InlineFragment, InputValue,
+        Operation, OperationType, Type, VariableDefinition,
+    },
+    executor::Variables,
+    parser::Spanning,
+    schema::{
+        meta::{self, DeprecationStatus, MetaType},
+        model::SchemaType,
+    },
+    validation::{visit, MultiVisitorNil, ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+/// [Schema coordinate][0] of a schema member used by an operation.
+///
+/// [0]: https://github.com/graphql/graphql-wg/blob/main/rfcs/SchemaCoordinates.md
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SchemaCoordinate {
+    /// Named type, e.g. `Human`.
+    Type {
+        /// Name of the type.
+        name: String,
+    },
+
+    /// Field of an object or interface type, e.g. `Human.name`.
+    Field {
+        /// Name of the object or interface type.
+        type_name: String,
+
+        /// Name of the field.
+        field_name: String,
+    },
+
+    /// Argument of a field, e.g. `Query.human(id:)`.
+    Argument {
+        /// Name of the object or interface type.
+        type_name: String,
+
+        /// Name of the field.
+        field_name: String,
+
+        /// Name of the argument.
+        argument_name: String,
+    },
+
+    /// Field of an input object type, e.g. `Point.x`.
+    InputField {
+        /// Name of the input object type.
+        type_name: String,
+
+        /// Name of the input field.
+        field_name: String,
+    },
+
+    /// Value of an enum type, e.g. `Episode.JEDI`.
+    EnumValue {
+        /// Name of the enum type.
+        type_name: String,
+
+        /// Name of the enum value.
+        value: String,
+    },
+}
+
+impl fmt::Display for SchemaCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type { name } => write!(f, "{name}"),
+            Self::Field {
+                type_name,
+                field_name,
+            }
+            | Self::InputField {
+                type_name,
+                field_name,
+            } => write!(f, "{type_name}.{field_name}"),
+            Self::Argument {
+                type_name,
+                field_name,
+                argument_name,
+            } => write!(f, "{type_name}.{field_name}({argument_name}:)"),
+            Self::EnumValue { type_name, value } => write!(f, "{type_name}.{value}"),
+        }
+    }
+}
+
+/// Schema members used by a single executed operation.
+///
+/// Only the members referenced by the operation itself (directly or via
+/// fragments) are considered, including the enum values and input object
+/// fields provided via variables. Introspection members are omitted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperationUsage {
+    operation_name: Option<String>,
+    operation_type: OperationType,
+    members: BTreeMap<SchemaCoordinate, DeprecationStatus>,
+}
+
+impl OperationUsage {
+    /// Returns name of the executed operation, if any.
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
+    }
+
+    /// Returns type of the executed operation.
+    pub fn operation_type(&self) -> OperationType {
+        self.operation_type
+    }
+
+    /// Iterates over all the used schema members along with their
+    /// [`DeprecationStatus`], ordered by their [`SchemaCoordinate`]s.
+    pub fn members(&self) -> impl Iterator<Item = (&SchemaCoordinate, &DeprecationStatus)> {
+        self.members.iter()
+    }
+
+    /// Iterates over the used deprecated schema members along with their
+    /// deprecation reasons, if any.
+    pub fn deprecated(&self) -> impl Iterator<Item = (&SchemaCoordinate, Option<&str>)> {
+        self.members
+            .iter()
+            .filter(|(_, s)| s.is_deprecated())
+            .map(|(c, s)| (c, s.reason()))
+    }
+
+    /// Indicates whether the schema member with the provided
+    /// [`SchemaCoordinate`] is used by the operation.
+    pub fn contains(&self, coordinate: &SchemaCoordinate) -> bool {
+        self.members.contains_key(coordinate)
+    }
+}
+
+/// Hook receiving an [`OperationUsage`] of every operation executed against a
+/// [`RootNode`].
+///
+/// Called once the operation passes validation, right before its execution.
+///
+/// [`RootNode`]: crate::RootNode
+pub trait UsageCollector<CtxT: ?Sized>: Send + Sync {
+    /// Records the provided [`OperationUsage`] of the operation executed with
+    /// the provided `context`.
+    fn collect(&self, usage: &OperationUsage, context: &CtxT);
+}
+
+impl<CtxT: ?Sized> fmt::Debug for dyn UsageCollector<CtxT> + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageCollector").finish_non_exhaustive()
+    }
+}
+
+impl<CtxT, F> UsageCollector<CtxT> for F
+where
+    CtxT: ?Sized,
+    F: Fn(&OperationUsage, &CtxT) + Send + Sync,
+{
+    fn collect(&self, usage: &OperationUsage, context: &CtxT) {
+        self(usage, context)
+    }
+}
+
+/// Aggregated usage statistics of a single schema member.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UsageStats {
+    /// Number of the executed operations using the schema member.
+    pub count: u64,
+
+    /// Names of the executed operations using the schema member.
+    ///
+    /// Anonymous operations are not listed here, but still are counted.
+    pub operation_names: BTreeSet<String>,
+
+    /// [`DeprecationStatus`] of the schema member.
+    pub deprecation_status: DeprecationStatus,
+}
+
+/// In-memory [`UsageCollector`] aggregating [`UsageStats`] of all the
+/// collected [`OperationUsage`]s.
+///
+/// It's cheap to [`Clone`] and all the clones share the same statistics, so a
+/// clone may be registered in a [`RootNode`], while the other one is used to
+/// dump the statistics periodically.
+///
+/// ```rust
+/// # use juniper::{usage::UsageAggregator, EmptyMutation, EmptySubscription, RootNode};
+/// # struct Query;
+/// # #[juniper::graphql_object]
+/// # impl Query {
+/// #     fn hero(id: i32) -> i32 { id }
+/// # }
+/// let usage = UsageAggregator::new();
+/// let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new())
+///     .with_usage_collector(usage.clone());
+///
+/// // ...execute some operations...
+///
+/// for (coordinate, stats) in usage.take() {
+///     println!("{coordinate}: {}", stats.count);
+/// }
+/// ```
+///
+/// [`RootNode`]: crate::RootNode
+#[derive(Clone, Debug, Default)]
+pub struct UsageAggregator {
+    stats: Arc<Mutex<BTreeMap<SchemaCoordinate, UsageStats>>>,
+}
+
+impl UsageAggregator {
+    /// Creates a new empty [`UsageAggregator`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [`UsageStats`] aggregated so far.
+    pub fn snapshot(&self) -> BTreeMap<SchemaCoordinate, UsageStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Returns the [`UsageStats`] aggregated so far, and resets them.
+    pub fn take(&self) -> BTreeMap<SchemaCoordinate, UsageStats> {
+        std::mem::take(&mut *self.stats.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl<CtxT: ?Sized> UsageCollector<CtxT> for UsageAggregator {
+    fn collect(&self, usage: &OperationUsage, _: &CtxT) {
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        for (coordinate, status) in usage.members() {
+            let entry = stats
+                .entry(coordinate.clone())
+                .or_insert_with(|| UsageStats {
+                    count: 0,
+                    operation_names: BTreeSet::new(),
+                    deprecation_status: status.clone(),
+                });
+            entry.count += 1;
+            if let Some(name) = usage.operation_name() {
+                entry.operation_names.insert(name.into());
+            }
+        }
+    }
+}
+
+/// Collects the [`OperationUsage`] of the provided (already validated)
+/// `operation` of the `document`.
+pub(crate) fn collect_usage<'a, S>(
+    schema: &'a SchemaType<'a, S>,
+    document: &'a Document<'a, S>,
+    operation: &'a Spanning<Operation<'a, S>>,
+    variables: &Variables<S>,
+) -> OperationUsage
+where
+    S: ScalarValue,
+{
+    let scopes = RefCell::new(Scopes::default());
+    {
+        let mut ctx = ValidatorContext::new(schema, document);
+        let mut visitor = MultiVisitorNil.with(UsageVisitor {
+            variables,
+            scopes: &scopes,
+            scope: None,
+            field: None,
+            in_directive: false,
+        });
+        visit(&mut visitor, &mut ctx, document);
+    }
+
+    let mut scopes = scopes.borrow_mut();
+    let op_name = operation.item.name.as_ref().map(|n| n.item);
+    let mut members = BTreeMap::new();
+    let mut visited = Vec::new();
+    let mut queue = vec![Scope::Operation(op_name)];
+    while let Some(scope) = queue.pop() {
+        if visited.contains(&scope) {
+            continue;
+        }
+        visited.push(scope);
+        if let Some(used) = scopes.members.remove(&scope) {
+            members.extend(used);
+        }
+        if let Some(spreads) = scopes.spreads.remove(&scope) {
+            queue.extend(spreads.into_iter().map(Scope::Fragment));
+        }
+    }
+
+    OperationUsage {
+        operation_name: op_name.map(Into::into),
+        operation_type: operation.item.operation_type,
+        members,
+    }
+}
+
+/// Operation or fragment definition the visited nodes belong to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Scope<'a> {
+    Operation(Option<&'a str>),
+    Fragment(&'a str),
+}
+
+/// Schema members and fragment spreads used by every [`Scope`] of a
+/// [`Document`].
+#[derive(Default)]
+struct Scopes<'a> {
+    members: HashMap<Scope<'a>, BTreeMap<SchemaCoordinate, DeprecationStatus>>,
+    spreads: HashMap<Scope<'a>, Vec<&'a str>>,
+}
+
+struct UsageVisitor<'a, 'v, S> {
+    variables: &'v Variables<S>,
+    scopes: &'v RefCell<Scopes<'a>>,
+    scope: Option<Scope<'a>>,
+    /// Name of the parent type and meta of the currently visited field.
+    field: Option<(&'a str, &'a meta::Field<'a, S>)>,
+    in_directive: bool,
+}
+
+impl<'a, 'v, S> UsageVisitor<'a, 'v, S>
+where
+    S: ScalarValue,
+{
+    fn record(&mut self, coordinate: SchemaCoordinate, status: &DeprecationStatus) {
+        if let Some(scope) = self.scope {
+            self.scopes
+                .borrow_mut()
+                .members
+                .entry(scope)
+                .or_default()
+                .insert(coordinate, status.clone());
+        }
+    }
+
+    fn record_type(&mut self, name: &str) {
+        // Introspection types are added by `juniper` itself.
+        if !name.starts_with("__") {
+            let coordinate = SchemaCoordinate::Type { name: name.into() };
+            self.record(coordinate, &DeprecationStatus::Current);
+        }
+    }
+
+    /// Records the enum values and input object fields used by the provided
+    /// input `value` of the provided `ty`pe.
+    fn record_input_value(
+        &mut self,
+        schema: &'a SchemaType<'a, S>,
+        ty: &Type,
+        value: &InputValue<S>,
+    ) {
+        match (ty, value) {
+            (_, InputValue::Null) => {}
+            (_, InputValue::Variable(name)) => {
+                let variables = self.variables;
+                if let Some(v) = variables.get(name) {
+                    self.record_input_value(schema, ty, v);
+                }
+            }
+            (Type::List(inner, _) | Type::NonNullList(inner, _), InputValue::List(items)) => {
+                for item in items {
+                    self.record_input_value(schema, inner, &item.item);
+                }
+            }
+            // Input coercion of a single item into a list.
+            (Type::List(inner, _) | Type::NonNullList(inner, _), _) => {
+                self.record_input_value(schema, inner, value);
+            }
+            (Type::Named(name) | Type::NonNullNamed(name), _) => {
+                match (schema.concrete_type_by_name(name), value) {
+                    // Enum values provided via variables are represented as strings.
+                    (Some(MetaType::Enum(meta)), InputValue::Enum(_) | InputValue::Scalar(_)) => {
+                        let v = match value {
+                            InputValue::Enum(v) => Some(v.as_str()),
+                            InputValue::Scalar(s) => s.as_str(),
+                            _ => None,
+                        };
+                        let enum_value = meta.values.iter().find(|ev| Some(ev.name.as_str()) == v);
+                        if let Some(enum_value) = enum_value {
+                            let coordinate = SchemaCoordinate::EnumValue {
+                                type_name: meta.name.to_string(),
+                                value: enum_value.name.clone(),
+                            };
+                            self.record(coordinate, &enum_value.deprecation_status);
+                        }
+                    }
+                    (Some(MetaType::InputObject(meta)), InputValue::Object(fields)) => {
+                        for (field_name, field_value) in fields {
+                            let field =
+                                meta.input_fields.iter().find(|f| f.name == field_name.item);
+                            if let Some(field) = field {
+                                let coordinate = SchemaCoordinate::InputField {
+                                    type_name: meta.name.to_string(),
+                                    field_name: field.name.clone(),
+                                };
+                                self.record(coordinate, &field.deprecation_status);
+                                self.record_type(field.arg_type.innermost_name());
+                                self.record_input_value(schema, &field.arg_type, &field_value.item);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'v, S> Visitor<'a, S> for UsageVisitor<'a, 'v, S>
+where
+    S: ScalarValue,
+{
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        self.scope = Some(Scope::Operation(op.item.name.as_ref().map(|n| n.item)));
+        if let Some(name) = ctx.current_type().and_then(MetaType::name) {
+            self.record_type(name);
+        }
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        self.scope = Some(Scope::Fragment(f.item.name.item));
+        self.record_type(f.item.type_condition.item);
+    }
+
+    fn enter_variable_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        (_, var_def): &'a (Spanning<&'a str>, VariableDefinition<S>),
+    ) {
+        self.record_type(var_def.var_type.item.innermost_name());
+    }
+
+    fn enter_directive(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Directive<S>>) {
+        self.in_directive = true;
+    }
+
+    fn exit_directive(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Directive<S>>) {
+        self.in_directive = false;
+    }
+
+    fn enter_argument(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        (name, value): &'a (Spanning<&'a str>, Spanning<InputValue<S>>),
+    ) {
+        if self.in_directive {
+            return;
+        }
+        let (type_name, field) = match self.field {
+            Some(f) => f,
+            None => return,
+        };
+        let arg = field
+            .arguments
+            .iter()
+            .flatten()
+            .find(|a| a.name == name.item);
+        if let Some(arg) = arg {
+            let coordinate = SchemaCoordinate::Argument {
+                type_name: type_name.into(),
+                field_name: field.name.to_string(),
+                argument_name: arg.name.clone(),
+            };
+            self.record(coordinate, &arg.deprecation_status);
+            self.record_type(arg.arg_type.innermost_name());
+            self.record_input_value(ctx.schema, &arg.arg_type, &value.item);
+        }
+    }
+
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        self.field = None;
+
+        let parent = match ctx.parent_type() {
+            Some(t) => t,
+            None => return,
+        };
+        let type_name = match parent.name() {
+            Some(n) => n,
+            None => return,
+        };
+        let name = field.item.name.item;
+        if name.starts_with("__") || type_name.starts_with("__") {
+            return;
+        }
+
+        if let Some(meta) = parent.field_by_name(name) {
+            let coordinate = SchemaCoordinate::Field {
+                type_name: type_name.into(),
+                field_name: name.into(),
+            };
+            self.record(coordinate, &meta.deprecation_status);
+            self.record_type(meta.field_type.innermost_name());
+            self.field = Some((type_name, meta));
+        }
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        spread: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        if let Some(scope) = self.scope {
+            self.scopes
+                .borrow_mut()
+                .spreads
+                .entry(scope)
+                .or_default()
+                .push(spread.item.name.item);
+        }
+    }
+
+    fn enter_inline_fragment(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<InlineFragment<S>>,
+    ) {
+        if let Some(cond) = &f.item.type_condition {
+            self.record_type(cond.item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeSet,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::{
+        execute_sync_with_usage, graphql_object, graphql_value, graphql_vars, http::GraphQLRequest,
+        DefaultScalarValue, EmptyMutation, EmptySubscription, Extensions, GraphQLEnum,
+        GraphQLInputObject, RootNode, Variables,
+    };
+
+    use super::{OperationUsage, SchemaCoordinate, UsageAggregator};
+
+    #[derive(GraphQLEnum)]
+    enum Color {
+        Red,
+        #[graphql(deprecated = "Use `RED`.")]
+        Crimson,
+    }
+
+    #[derive(GraphQLInputObject)]
+    struct Filter {
+        color: Option<Color>,
+        #[graphql(deprecated)]
+        legacy: Option<bool>,
+    }
+
+    struct Item;
+
+    #[graphql_object]
+    impl Item {
+        fn id() -> i32 {
+            1
+        }
+
+        fn name() -> &'static str {
+            "item"
+        }
+    }
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn search(
+            _filter: Option<Filter>,
+            #[graphql(deprecated = "Use `filter`.")] _color: Option<Color>,
+        ) -> Vec<Item> {
+            vec![Item]
+        }
+
+        #[graphql(deprecated = "Use `search`.")]
+        fn items() -> Vec<Item> {
+            vec![Item]
+        }
+    }
+
+    type Schema = RootNode<'static, Query, EmptyMutation, EmptySubscription>;
+
+    fn schema() -> Schema {
+        Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+    }
+
+    fn usage_of(
+        schema: &Schema,
+        doc: &str,
+        operation_name: Option<&str>,
+        vars: &Variables<DefaultScalarValue>,
+    ) -> OperationUsage {
+        let ((_, errs), usage) =
+            execute_sync_with_usage(doc, operation_name, schema, vars, &(), &Extensions::new())
+                .expect("execution failed");
+        assert_eq!(errs, []);
+        usage.expect("no usage collected")
+    }
+
+    fn coordinates(usage: &OperationUsage) -> BTreeSet<String> {
+        usage.members().map(|(c, _)| c.to_string()).collect()
+    }
+
+    #[test]
+    fn collects_members_of_executed_operation_only() {
+        let schema = schema().with_deprecation_warnings();
+        let doc = r#"
+            query Search($color: Color) {
+                search(filter: {color: $color}) {
+                    ...ItemFields
+                }
+            }
+
+            query Other {
+                items { name }
+            }
+
+            fragment ItemFields on Item {
+                ... on Item { id }
+            }
+        "#;
+
+        let usage = usage_of(
+            &schema,
+            doc,
+            Some("Search"),
+            &graphql_vars! {"color": "CRIMSON"},
+        );
+
+        assert_eq!(usage.operation_name(), Some("Search"));
+        assert_eq!(
+            coordinates(&usage),
+            [
+                "Color",
+                "Color.CRIMSON",
+                "Filter",
+                "Filter.color",
+                "Int",
+                "Item",
+                "Item.id",
+                "Query",
+                "Query.search",
+                "Query.search(filter:)",
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect::<BTreeSet<String>>(),
+        );
+        assert_eq!(
+            usage.deprecated().collect::<Vec<_>>(),
+            [(
+                &SchemaCoordinate::EnumValue {
+                    type_name: "Color".into(),
+                    value: "CRIMSON".into(),
+                },
+                Some("Use `RED`."),
+            )],
+        );
+    }
+
+    #[test]
+    fn reports_deprecated_arguments_and_input_fields() {
+        let schema = schema().with_deprecation_warnings();
+        let doc = r#"{
+            search(color: RED, filter: {legacy: true}) { id }
+            __typename
+        }"#;
+
+        let usage = usage_of(&schema, doc, None, &graphql_vars! {});
+
+        assert_eq!(
+            usage
+                .deprecated()
+                .map(|(c, r)| (c.to_string(), r))
+                .collect::<Vec<_>>(),
+            [
+                ("Query.search(color:)".into(), Some("Use `filter`.")),
+                ("Filter.legacy".into(), None),
+            ],
+        );
+        assert!(usage.contains(&SchemaCoordinate::EnumValue {
+            type_name: "Color".into(),
+            value: "RED".into(),
+        }));
+        assert!(!usage.contains(&SchemaCoordinate::Field {
+            type_name: "Query".into(),
+            field_name: "__typename".into(),
+        }));
+    }
+
+    #[test]
+    fn aggregates_usage_stats() {
+        let usage = UsageAggregator::new();
+        let calls = AtomicUsize::new(0);
+        let schema = schema()
+            .with_usage_collector(usage.clone())
+            .with_usage_collector(move |_: &OperationUsage, _: &()| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            });
+
+        for (doc, op) in [
+            ("query One { items { id } }", Some("One")),
+            ("query Two { items { id name } }", Some("Two")),
+            ("{ items { name } }", None),
+        ] {
+            let (_, errs) = crate::execute_sync(doc, op, &schema, &graphql_vars! {}, &())
+                .expect("execution failed");
+            assert_eq!(errs, []);
+        }
+
+        let stats = usage.snapshot();
+        let items = &stats[&SchemaCoordinate::Field {
+            type_name: "Query".into(),
+            field_name: "items".into(),
+        }];
+        assert_eq!(items.count, 3);
+        assert_eq!(
+            items.operation_names,
+            ["One".to_owned(), "Two".to_owned()].into_iter().collect(),
+        );
+        assert!(items.deprecation_status.is_deprecated());
+
+        let id = &stats[&SchemaCoordinate::Field {
+            type_name: "Item".into(),
+            field_name: "id".into(),
+        }];
+        assert_eq!(id.count, 2);
+
+        assert_eq!(usage.take(), stats);
+        assert!(usage.snapshot().is_empty());
+    }
+
+    #[test]
+    fn reports_deprecations_in_response_extensions() {
+        let req = GraphQLRequest::<DefaultScalarValue>::new(
+            "{ items { id } search { id } }".into(),
+            None,
+            None,
+        );
+
+        let resp = req.execute_sync(&schema(), &());
+        assert_eq!(
+            serde_json::to_value(&resp).unwrap(),
+            serde_json::json!({"data": {"items": [{"id": 1}], "search": [{"id": 1}]}}),
+        );
+
+        let resp = req.execute_sync(&schema().with_deprecation_warnings(), &());
+        assert_eq!(
+            serde_json::to_value(&resp).unwrap(),
+            serde_json::json!({
+                "data": {"items": [{"id": 1}], "search": [{"id": 1}]},
+                "extensions": {
+                    "deprecations": [{
+                        "coordinate": "Query.items",
+                        "reason": "Use `search`.",
+                    }],
+                },
+            }),
+        );
+        assert_eq!(
+            resp.extensions().get_field_value("deprecations"),
+            Some(&graphql_value!([{
+                "coordinate": "Query.items",
+                "reason": "Use `search`.",
+            }])),
+        );
+    }
+}
@@ -16,8 +16,14 @@ pub struct RuleError {
     message: String,
 }
 
-#[doc(hidden)]
+/// Context of a [`Document`] validation, passed to every [`Visitor`] method.
+///
+/// Tracks the types of the currently visited nodes and collects the reported
+/// [`RuleError`]s.
+///
+/// [`Visitor`]: super::Visitor
 pub struct ValidatorContext<'a, S: Debug + 'a> {
+    /// Schema the [`Document`] is validated against.
     pub schema: &'a SchemaType<'a, S>,
     errors: Vec<RuleError>,
     type_stack: Vec<Option<&'a MetaType<'a, S>>>,
@@ -29,7 +35,8 @@ pub struct ValidatorContext<'a, S: Debug + 'a> {
 }
 
 impl RuleError {
-    #[doc(hidden)]
+    /// Creates a new [`RuleError`] with the provided `message` pointing to
+    /// the provided `locations` in the validated document.
     pub fn new(message: &str, locations: &[SourcePosition]) -> Self {
         Self {
             message: message.into(),
@@ -93,7 +100,8 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         self.errors.append(&mut errors);
     }
 
-    #[doc(hidden)]
+    /// Reports a new [`RuleError`] with the provided `message` pointing to the
+    /// provided `locations` in the validated [`Document`].
     pub fn report_error(&mut self, message: &str, locations: &[SourcePosition]) {
         self.errors.push(RuleError::new(message, locations))
     }
@@ -165,12 +173,14 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         res
     }
 
-    #[doc(hidden)]
+    /// Returns the type of the currently visited field, fragment or operation,
+    /// if it's known to the schema.
     pub fn current_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// Returns the type literal of the currently visited field, fragment or
+    /// operation, as declared in the schema.
     pub fn current_type_literal(&self) -> Option<&Type<'a>> {
         match self.type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -178,12 +188,16 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// Returns the type declaring the currently visited field, if it's known to
+    /// the schema.
+    ///
+    /// Use [`MetaType::field_by_name()`] on it to obtain the metadata of the
+    /// visited field.
     pub fn parent_type(&self) -> Option<&'a MetaType<'a, S>> {
         *self.parent_type_stack.last().unwrap_or(&None)
     }
 
-    #[doc(hidden)]
+    /// Returns the expected type literal of the currently visited input value.
     pub fn current_input_type_literal(&self) -> Option<&Type<'a>> {
         match self.input_type_literal_stack.last() {
             Some(&Some(ref t)) => Some(t),
@@ -191,7 +205,8 @@ impl<'a, S: Debug> ValidatorContext<'a, S> {
         }
     }
 
-    #[doc(hidden)]
+    /// Indicates whether the validated [`Document`] defines a fragment with the
+    /// provided `name`.
     pub fn is_known_fragment(&self, name: &str) -> bool {
         self.fragment_names.contains(name)
     }
@@ -0,0 +1,382 @@
+//! Offline validation (linting) of GraphQL operations against a schema.
+
+use std::{fmt, fs, io, path::Path};
+
+use crate::{
+    parser::{parse_document_source, parse_document_source_recovering, SourcePosition},
+    schema::model::SchemaType,
+    validation::{visit_all_rules, ValidatorContext},
+    value::ScalarValue,
+};
+
+/// Validator of GraphQL executable documents (operations and fragments)
+/// against a [`SchemaType`], without executing them.
+///
+/// Meant to check the operations used by clients (e.g. stored in `.graphql`
+/// files) in CI, having the schema [built out of its SDL][0] or
+/// [introspection][1].
+///
+/// ```rust
+/// # use juniper::{validation::Linter, DefaultScalarValue, SchemaType};
+/// let schema = SchemaType::<DefaultScalarValue>::from_sdl(
+///     "type Query { hero: Hero } type Hero { name: String! }",
+/// )
+/// .unwrap();
+///
+/// let errors = Linter::new(&schema).lint([
+///     ("hero.graphql", "query Hero { hero { ...HeroFields } }"),
+///     ("fields.graphql", "fragment HeroFields on Hero {\n  age\n}"),
+/// ]);
+///
+/// assert_eq!(errors.len(), 3);
+/// assert_eq!(
+///     errors[2].to_string(),
+///     r#"fields.graphql:2:3: Unknown field "age" on type "Hero""#,
+/// );
+///
+/// let errors = Linter::new(&schema).cross_file_fragments(true).lint([
+///     ("hero.graphql", "query Hero { hero { ...HeroFields } }"),
+///     ("fields.graphql", "fragment HeroFields on Hero {\n  name\n}"),
+/// ]);
+///
+/// assert!(errors.is_empty());
+/// ```
+///
+/// [0]: SchemaType::from_sdl
+/// [1]: SchemaType::from_introspection
+#[derive(Debug)]
+pub struct Linter<'a, S> {
+    schema: &'a SchemaType<'a, S>,
+    cross_file_fragments: bool,
+}
+
+impl<'a, S> Linter<'a, S>
+where
+    S: ScalarValue,
+{
+    /// Creates a new [`Linter`] validating documents against the provided
+    /// `schema`.
+    pub fn new(schema: &'a SchemaType<'a, S>) -> Self {
+        Self {
+            schema,
+            cross_file_fragments: false,
+        }
+    }
+
+    /// Makes the fragments defined in any of the linted documents available to
+    /// all of them.
+    ///
+    /// By default, every document is validated separately, so must define all
+    /// the fragments it uses. Once enabled, all the documents are validated as
+    /// a single one, so operation names must be unique across them.
+    #[must_use]
+    pub fn cross_file_fragments(mut self, enabled: bool) -> Self {
+        self.cross_file_fragments = enabled;
+        self
+    }
+
+    /// Validates the provided documents, given as `(file name, source)` pairs,
+    /// returning all the found errors.
+    ///
+    /// Documents having syntax errors are not validated any further.
+    pub fn lint<'s>(
+        &self,
+        documents: impl IntoIterator<Item = (&'s str, &'s str)>,
+    ) -> Vec<LintError> {
+        let mut errors = Vec::new();
+        let mut parsed = Vec::new();
+
+        for (file, source) in documents {
+            let (_, parse_errors) = parse_document_source_recovering(source, self.schema);
+            if !parse_errors.is_empty() {
+                errors.extend(parse_errors.into_iter().map(|e| LintError {
+                    message: e.item.to_string(),
+                    locations: vec![LintLocation {
+                        file: file.into(),
+                        position: e.start,
+                    }],
+                }));
+            } else if self.cross_file_fragments {
+                parsed.push((file, source));
+            } else {
+                self.validate(&[(file, source)], &mut errors);
+            }
+        }
+
+        if !parsed.is_empty() {
+            self.validate(&parsed, &mut errors);
+        }
+
+        errors
+    }
+
+    /// Reads and validates the documents at the provided `paths`, returning
+    /// all the found errors.
+    ///
+    /// See [`Linter::lint()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If any of the files cannot be read.
+    pub fn lint_files<P: AsRef<Path>>(
+        &self,
+        paths: impl IntoIterator<Item = P>,
+    ) -> io::Result<Vec<LintError>> {
+        let documents = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.as_ref();
+                fs::read_to_string(path).map(|source| (path.display().to_string(), source))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(self.lint(documents.iter().map(|(f, s)| (f.as_str(), s.as_str()))))
+    }
+
+    /// Validates the provided syntactically valid documents as a single one.
+    fn validate(&self, documents: &[(&str, &str)], errors: &mut Vec<LintError>) {
+        let mut source = String::new();
+        let mut offsets = Vec::with_capacity(documents.len());
+        let (mut index, mut line) = (0, 0);
+        for (file, src) in documents {
+            offsets.push((*file, index, line));
+            let start = source.len();
+            source.push_str(src);
+            source.push('\n');
+            // Positions are counted in chars, with the line terminators being
+            // the same as the lexer's ones (so a trailing `\r` of a document
+            // and the appended `\n` make a single one).
+            let appended = &source[start..];
+            index += appended.chars().count();
+            line += appended
+                .split("\r\n")
+                .flat_map(|l| l.split(['\n', '\r']))
+                .count()
+                - 1;
+        }
+
+        let document = match parse_document_source(&source, self.schema) {
+            Ok(doc) => doc,
+            // Unreachable, as all the documents have been parsed successfully.
+            Err(_) => return,
+        };
+
+        let mut ctx = ValidatorContext::new(self.schema, &document);
+        visit_all_rules(&mut ctx, &document);
+
+        errors.extend(ctx.into_errors().into_iter().map(|e| {
+            LintError {
+                message: e.message().into(),
+                locations: e
+                    .locations()
+                    .iter()
+                    .map(|pos| {
+                        let (file, index, line) = offsets
+                            .iter()
+                            .rev()
+                            .find(|(_, index, _)| *index <= pos.index())
+                            .unwrap_or(&offsets[0]);
+                        LintLocation {
+                            file: (*file).into(),
+                            position: SourcePosition::new(
+                                pos.index() - index,
+                                pos.line() - line,
+                                pos.column(),
+                            ),
+                        }
+                    })
+                    .collect(),
+            }
+        }));
+    }
+}
+
+/// Error found by a [`Linter`] in a linted document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintError {
+    message: String,
+    locations: Vec<LintLocation>,
+}
+
+impl LintError {
+    /// Returns the message describing this [`LintError`].
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the locations of this [`LintError`] in the linted documents.
+    ///
+    /// Usually there is a single one, but some errors (like conflicting
+    /// fields) may involve several places, possibly in different documents.
+    pub fn locations(&self) -> &[LintLocation] {
+        &self.locations
+    }
+}
+
+/// Formats as `file:line:column: message` of the first location.
+impl fmt::Display for LintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(loc) = self.locations.first() {
+            write!(f, "{loc}: ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LintError {}
+
+/// Location of a [`LintError`] in a linted document.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LintLocation {
+    file: String,
+    position: SourcePosition,
+}
+
+impl LintLocation {
+    /// Returns the name of the document file.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Returns the position in the document file.
+    pub fn position(&self) -> &SourcePosition {
+        &self.position
+    }
+}
+
+/// Formats as `file:line:column` with one-based line and column, as
+/// conventional for editors and CI tools.
+impl fmt::Display for LintLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.file,
+            self.position.line() + 1,
+            self.position.column() + 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{schema::model::SchemaType, DefaultScalarValue};
+
+    use super::Linter;
+
+    fn schema() -> SchemaType<'static, DefaultScalarValue> {
+        SchemaType::from_sdl(
+            r#"
+            type Query { hero(episode: Episode): Hero }
+            type Hero { name: String! friends: [Hero!]! }
+            enum Episode { NEW_HOPE EMPIRE JEDI }
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn lint(linter: &Linter<'_, DefaultScalarValue>, docs: &[(&str, &str)]) -> Vec<String> {
+        linter
+            .lint(docs.iter().copied())
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn passes_valid_documents() {
+        let schema = schema();
+        let linter = Linter::new(&schema);
+
+        assert_eq!(
+            lint(
+                &linter,
+                &[
+                    ("a.graphql", "query A { hero(episode: JEDI) { name } }"),
+                    (
+                        "b.graphql",
+                        "query B { hero { ...F } }\nfragment F on Hero { friends { name } }",
+                    ),
+                ],
+            ),
+            Vec::<String>::new(),
+        );
+    }
+
+    #[test]
+    fn reports_file_positions() {
+        let schema = schema();
+        let linter = Linter::new(&schema);
+
+        assert_eq!(
+            lint(
+                &linter,
+                &[
+                    ("ok.graphql", "query Ok { hero { name } }"),
+                    (
+                        "bad.graphql",
+                        "query Bad {\n  hero(episode: PHANTOM) {\n    age\n  }\n}",
+                    ),
+                    ("broken.graphql", "query Broken {\n  hero {\n}"),
+                ],
+            ),
+            [
+                "bad.graphql:2:17: Invalid value for argument \"episode\", expected type \"Episode\"",
+                "bad.graphql:3:5: Unknown field \"age\" on type \"Hero\"",
+                "broken.graphql:3:1: Unexpected \"}\"",
+                "broken.graphql:3:2: Unexpected end of input",
+            ],
+        );
+    }
+
+    #[test]
+    fn checks_fragments_across_files() {
+        let schema = schema();
+        let docs = [
+            ("query.graphql", "query Q {\n  hero { ...HeroFields }\n}"),
+            (
+                "fragment.graphql",
+                "\n\nfragment HeroFields on Hero {\n  name\n  age\n}",
+            ),
+        ];
+
+        assert_eq!(
+            lint(&Linter::new(&schema), &docs),
+            [
+                "query.graphql:2:13: Unknown fragment: \"HeroFields\"",
+                "fragment.graphql:3:1: Fragment \"HeroFields\" is never used",
+                "fragment.graphql:5:3: Unknown field \"age\" on type \"Hero\"",
+            ],
+        );
+        assert_eq!(
+            lint(&Linter::new(&schema).cross_file_fragments(true), &docs),
+            ["fragment.graphql:5:3: Unknown field \"age\" on type \"Hero\""],
+        );
+    }
+
+    #[test]
+    fn counts_all_line_terminators() {
+        let schema = schema();
+        let docs = [
+            (
+                "crlf.graphql",
+                "query A {\r\n  hero {\r\n    age\r\n  }\r\n}\r\n",
+            ),
+            ("cr.graphql", "query B {\r  hero {\r    age\r  }\r}\r"),
+            ("lf.graphql", "query C {\n  hero {\n    age\n  }\n}"),
+        ];
+
+        assert_eq!(
+            lint(&Linter::new(&schema), &docs),
+            [
+                "crlf.graphql:3:5: Unknown field \"age\" on type \"Hero\"",
+                "cr.graphql:3:5: Unknown field \"age\" on type \"Hero\"",
+                "lf.graphql:3:5: Unknown field \"age\" on type \"Hero\"",
+            ],
+        );
+        assert_eq!(
+            lint(&Linter::new(&schema).cross_file_fragments(true), &docs),
+            lint(&Linter::new(&schema), &docs),
+        );
+    }
+}
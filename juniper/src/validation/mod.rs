@@ -2,6 +2,7 @@
 
 mod context;
 mod input_value;
+mod lint;
 mod multi_visitor;
 mod rules;
 mod traits;
@@ -13,9 +14,10 @@ pub(crate) mod test_harness;
 pub use self::{
     context::{RuleError, ValidatorContext},
     input_value::validate_input_values,
+    lint::{LintError, LintLocation, Linter},
     multi_visitor::MultiVisitorNil,
-    rules::visit_all_rules,
-    traits::Visitor,
+    rules::{visit_all_rules, visit_all_rules_with, visit_introspection_rules, visit_limit_rules},
+    traits::{ValidationRule, Visitor},
     visitor::visit,
 };
 
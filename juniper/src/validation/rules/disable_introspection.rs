@@ -0,0 +1,83 @@
+use crate::{
+    ast::Field,
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct DisableIntrospection;
+
+pub fn factory() -> DisableIntrospection {
+    DisableIntrospection
+}
+
+impl<'a, S> Visitor<'a, S> for DisableIntrospection
+where
+    S: ScalarValue,
+{
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        let name = field.item.name.item;
+        // `__typename` is not considered as introspection, since it's widely
+        // used by clients for resolving abstract types.
+        if matches!(name, "__schema" | "__type") {
+            ctx.report_error(&error_message(name), &[field.start]);
+        }
+    }
+}
+
+fn error_message(field_name: &str) -> String {
+    format!("GraphQL introspection is not allowed, but the query contained \"{field_name}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn allows_typename() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          {
+            __typename
+            dog {
+              __typename
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn forbids_schema_and_type() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          {
+            __schema {
+              queryType { name }
+            }
+            ...TypeFragment
+          }
+
+          fragment TypeFragment on QueryRoot {
+            __type(name: "Dog") { name }
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("__schema"),
+                    &[SourcePosition::new(25, 2, 12)],
+                ),
+                RuleError::new(&error_message("__type"), &[SourcePosition::new(183, 9, 12)]),
+            ],
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    ast::{Definition, Document},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct ExecutableDefinitions;
+
+pub fn factory() -> ExecutableDefinitions {
+    ExecutableDefinitions
+}
+
+impl<'a, S> Visitor<'a, S> for ExecutableDefinitions
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        for def in doc {
+            let (def_name, pos) = match def {
+                Definition::Operation(_) | Definition::Fragment(_) => continue,
+                Definition::Schema(d) | Definition::SchemaExtension(d) => {
+                    ("schema".into(), d.start)
+                }
+                Definition::Type(d) | Definition::TypeExtension(d) => {
+                    (format!("\"{}\"", d.item.name()), d.start)
+                }
+                Definition::Directive(d) => (format!("\"@{}\"", d.item.name.item), d.start),
+            };
+            ctx.report_error(&error_message(&def_name), &[pos]);
+        }
+    }
+}
+
+fn error_message(def_name: &str) -> String {
+    format!("The {def_name} definition is not executable")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn with_only_operation() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn with_operation_and_fragment() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+              ...Frag
+            }
+          }
+
+          fragment Frag on Dog {
+            name
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn with_type_definition() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Foo {
+            dog {
+              name
+            }
+          }
+
+          type Cow {
+            name: String
+          }
+
+          extend type Dog {
+            color: String
+          }
+        "#,
+            &[
+                RuleError::new(&error_message("\"Cow\""), &[SourcePosition::new(97, 7, 10)]),
+                RuleError::new(
+                    &error_message("\"Dog\""),
+                    &[SourcePosition::new(156, 11, 10)],
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn with_schema_and_directive_definitions() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          schema {
+            query: Query
+          }
+
+          "Some directive."
+          directive @some on FIELD
+
+          extend schema @directive
+        "#,
+            &[
+                RuleError::new(&error_message("schema"), &[SourcePosition::new(11, 1, 10)]),
+                RuleError::new(
+                    &error_message("\"@some\""),
+                    &[SourcePosition::new(68, 5, 10)],
+                ),
+                RuleError::new(&error_message("schema"), &[SourcePosition::new(132, 8, 10)]),
+            ],
+        );
+    }
+}
@@ -22,10 +22,7 @@ where
     fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
         self.operation_count = Some(
             doc.iter()
-                .filter(|d| match **d {
-                    Definition::Operation(_) => true,
-                    Definition::Fragment(_) => false,
-                })
+                .filter(|d| matches!(**d, Definition::Operation(_)))
                 .count(),
         );
     }
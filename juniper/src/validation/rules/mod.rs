@@ -1,5 +1,7 @@
 mod arguments_of_correct_type;
 mod default_values_of_correct_type;
+mod disable_introspection;
+mod executable_definitions;
 mod fields_on_correct_type;
 mod fragments_on_composite_types;
 mod known_argument_names;
@@ -14,8 +16,11 @@ mod no_unused_variables;
 mod overlapping_fields_can_be_merged;
 mod possible_fragment_spreads;
 mod provided_non_null_arguments;
+mod root_fields_limit;
 mod scalar_leafs;
+mod single_field_subscriptions;
 mod unique_argument_names;
+mod unique_directives_per_location;
 mod unique_fragment_names;
 mod unique_input_field_names;
 mod unique_operation_names;
@@ -25,7 +30,8 @@ mod variables_in_allowed_position;
 
 use crate::{
     ast::Document,
-    validation::{visit, MultiVisitorNil, ValidatorContext},
+    parser::DocumentLimits,
+    validation::{visit, MultiVisitorNil, ValidationRule, ValidatorContext},
     value::ScalarValue,
 };
 use std::fmt::Debug;
@@ -34,6 +40,23 @@ use std::fmt::Debug;
 pub fn visit_all_rules<'a, S: Debug>(ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>)
 where
     S: ScalarValue,
+{
+    visit_all_rules_with(ctx, doc, &[])
+}
+
+/// Validates the provided [`Document`] with all the built-in rules along with
+/// the provided user-defined [`ValidationRule`]s.
+///
+/// User-defined rules run only once the [`Document`] passes the basic
+/// built-in rules, so may rely on it referencing only the known types, fields
+/// and fragments, and having no fragment cycles.
+#[doc(hidden)]
+pub fn visit_all_rules_with<'a, S>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+    rules: &[Box<dyn ValidationRule<S>>],
+) where
+    S: ScalarValue,
 {
     // Some validators are depending on the results of other ones.
     // For example, validators checking fragments usually rely on the fact that
@@ -45,6 +68,7 @@ where
     let mut stage1 = MultiVisitorNil
         .with(self::arguments_of_correct_type::factory())
         .with(self::default_values_of_correct_type::factory())
+        .with(self::executable_definitions::factory())
         .with(self::fields_on_correct_type::factory())
         .with(self::fragments_on_composite_types::factory())
         .with(self::known_argument_names::factory())
@@ -59,7 +83,9 @@ where
         .with(self::possible_fragment_spreads::factory())
         .with(self::provided_non_null_arguments::factory())
         .with(self::scalar_leafs::factory())
+        .with(self::single_field_subscriptions::factory())
         .with(self::unique_argument_names::factory())
+        .with(self::unique_directives_per_location::factory())
         .with(self::unique_fragment_names::factory())
         .with(self::unique_input_field_names::factory())
         .with(self::unique_operation_names::factory())
@@ -71,10 +97,43 @@ where
         return;
     }
 
-    let mut stage2 = MultiVisitorNil.with(self::overlapping_fields_can_be_merged::factory());
+    let mut stage2 = MultiVisitorNil
+        .with(self::overlapping_fields_can_be_merged::factory())
+        .with(rules.iter().map(|r| r.visitor()).collect::<Vec<_>>());
     visit(&mut stage2, ctx, doc);
 }
 
+/// Validates the provided [`DocumentLimits`] not being checked while parsing.
+///
+/// Should be run before [`visit_all_rules()`], so an abusive document is
+/// rejected before being validated any further.
+#[doc(hidden)]
+pub fn visit_limit_rules<'a, S>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+    limits: DocumentLimits,
+) where
+    S: ScalarValue,
+{
+    if let Some(max) = limits.max_root_fields {
+        let mut limits = MultiVisitorNil.with(self::root_fields_limit::factory(max));
+        visit(&mut limits, ctx, doc);
+    }
+}
+
+/// Forbids querying the introspection `__schema` and `__type` fields.
+///
+/// Should be run along with [`visit_all_rules()`] when introspection is
+/// disabled for the executed document.
+#[doc(hidden)]
+pub fn visit_introspection_rules<'a, S>(ctx: &mut ValidatorContext<'a, S>, doc: &'a Document<S>)
+where
+    S: ScalarValue,
+{
+    let mut introspection = MultiVisitorNil.with(self::disable_introspection::factory());
+    visit(&mut introspection, ctx, doc);
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{parser::SourcePosition, DefaultScalarValue};
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Definition, Document, Operation, Selection},
+    parser::{DocumentLimit, Spanning},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct RootFieldsLimit<'a, S: 'a> {
+    max: usize,
+    fragments: HashMap<&'a str, &'a [Selection<'a, S>]>,
+}
+
+pub fn factory<'a, S>(max: usize) -> RootFieldsLimit<'a, S> {
+    RootFieldsLimit {
+        max,
+        fragments: HashMap::new(),
+    }
+}
+
+impl<'a, S> RootFieldsLimit<'a, S> {
+    fn count_fields(
+        &self,
+        selections: &'a [Selection<S>],
+        visited: &mut HashSet<&'a str>,
+    ) -> usize {
+        selections
+            .iter()
+            .map(|sel| match sel {
+                Selection::Field(_) => 1,
+                Selection::InlineFragment(f) => self.count_fields(&f.item.selection_set, visited),
+                Selection::FragmentSpread(s) => {
+                    let name = s.item.name.item;
+                    // Fragment cycles are reported by another rule, so just make
+                    // sure not to stall on them.
+                    if visited.insert(name) {
+                        self.fragments
+                            .get(name)
+                            .map_or(0, |sels| self.count_fields(sels, visited))
+                    } else {
+                        0
+                    }
+                }
+            })
+            .sum()
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for RootFieldsLimit<'a, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        self.fragments = doc
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Fragment(f) => Some((f.item.name.item, &f.item.selection_set[..])),
+                _ => None,
+            })
+            .collect();
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        if self.count_fields(&op.item.selection_set, &mut HashSet::new()) > self.max {
+            ctx.report_error(&error_message(self.max), &[op.start]);
+        }
+    }
+}
+
+fn error_message(max: usize) -> String {
+    format!(
+        "Operation exceeds the limit of {max} {}",
+        DocumentLimit::RootFields,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn root_fields_within_limit() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(3),
+            r#"
+          {
+            dog { name }
+            ... on QueryRoot {
+              human { name }
+            }
+            ...Cat
+          }
+
+          fragment Cat on QueryRoot {
+            cat { name }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn nested_fields_are_not_counted() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(1),
+            r#"
+          {
+            dog {
+              name
+              nickname
+              barkVolume
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn root_fields_exceed_limit() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(2),
+            r#"
+          query Ok {
+            dog { name }
+          }
+
+          query TooMany {
+            dog { name }
+            ... on QueryRoot {
+              human { name }
+            }
+            ...Cat
+          }
+
+          fragment Cat on QueryRoot {
+            cat { name }
+            ...Cat
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(2),
+                &[SourcePosition::new(70, 5, 10)],
+            )],
+        );
+    }
+}
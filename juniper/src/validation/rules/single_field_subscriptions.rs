@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Definition, Document, Operation, OperationType, Selection},
+    parser::{SourcePosition, Spanning},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct SingleFieldSubscriptions<'a, S: 'a> {
+    fragments: HashMap<&'a str, &'a [Selection<'a, S>]>,
+}
+
+pub fn factory<'a, S>() -> SingleFieldSubscriptions<'a, S> {
+    SingleFieldSubscriptions {
+        fragments: HashMap::new(),
+    }
+}
+
+impl<'a, S> SingleFieldSubscriptions<'a, S> {
+    /// Collects response keys of the root fields along with their first
+    /// positions, resolving fragments.
+    fn collect_fields(
+        &self,
+        selections: &'a [Selection<S>],
+        fields: &mut Vec<(&'a str, SourcePosition)>,
+        visited: &mut HashSet<&'a str>,
+    ) {
+        for sel in selections {
+            match sel {
+                Selection::Field(f) => {
+                    let key = f.item.alias.as_ref().unwrap_or(&f.item.name).item;
+                    if fields.iter().all(|(k, _)| *k != key) {
+                        fields.push((key, f.start));
+                    }
+                }
+                Selection::InlineFragment(f) => {
+                    self.collect_fields(&f.item.selection_set, fields, visited);
+                }
+                Selection::FragmentSpread(s) => {
+                    let name = s.item.name.item;
+                    // Fragment cycles are reported by another rule, so just make
+                    // sure not to stall on them.
+                    if visited.insert(name) {
+                        if let Some(sels) = self.fragments.get(name) {
+                            self.collect_fields(sels, fields, visited);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for SingleFieldSubscriptions<'a, S>
+where
+    S: ScalarValue,
+{
+    fn enter_document(&mut self, _: &mut ValidatorContext<'a, S>, doc: &'a Document<S>) {
+        self.fragments = doc
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Fragment(f) => Some((f.item.name.item, &f.item.selection_set[..])),
+                _ => None,
+            })
+            .collect();
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        if op.item.operation_type != OperationType::Subscription {
+            return;
+        }
+
+        let mut fields = Vec::new();
+        self.collect_fields(&op.item.selection_set, &mut fields, &mut HashSet::new());
+        if fields.len() > 1 {
+            let positions = fields
+                .iter()
+                .skip(1)
+                .map(|(_, pos)| *pos)
+                .collect::<Vec<_>>();
+            ctx.report_error(
+                &error_message(op.item.name.as_ref().map(|n| n.item)),
+                &positions,
+            );
+        }
+    }
+}
+
+fn error_message(op_name: Option<&str>) -> String {
+    if let Some(name) = op_name {
+        format!("Subscription \"{name}\" must select only one top level field")
+    } else {
+        "Anonymous Subscription must select only one top level field".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn valid_subscription() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            importantEmails
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn valid_subscription_with_fragments() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          subscription sub {
+            ...newMessageFields
+            ... on SubscriptionRoot {
+              newMessage {
+                body
+              }
+            }
+          }
+
+          fragment newMessageFields on SubscriptionRoot {
+            newMessage {
+              body
+              sender
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn queries_with_many_fields_are_not_checked() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Q {
+            dog { name }
+            cat { name }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          subscription ImportantEmails {
+            importantEmails
+            notImportantEmails
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(Some("ImportantEmails")),
+                &[SourcePosition::new(82, 3, 12)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fails_with_more_than_one_root_field_in_fragments() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          subscription {
+            ...fields
+            ... on SubscriptionRoot {
+              moreEmails
+            }
+          }
+
+          fragment fields on SubscriptionRoot {
+            importantEmails
+            notImportantEmails
+            ...fields
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(None),
+                &[
+                    SourcePosition::new(226, 10, 12),
+                    SourcePosition::new(100, 4, 14),
+                ],
+            )],
+        );
+    }
+}
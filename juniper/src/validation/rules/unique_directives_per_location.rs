@@ -0,0 +1,237 @@
+use std::collections::hash_map::{Entry, HashMap};
+
+use crate::{
+    ast::{
+        Directive, Field, Fragment, FragmentSpread, InlineFragment, Operation, VariableDefinition,
+    },
+    parser::{SourcePosition, Spanning},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct UniqueDirectivesPerLocation;
+
+pub fn factory() -> UniqueDirectivesPerLocation {
+    UniqueDirectivesPerLocation
+}
+
+impl UniqueDirectivesPerLocation {
+    fn check_directives<'a, S>(
+        &self,
+        ctx: &mut ValidatorContext<'a, S>,
+        directives: Option<&'a [Spanning<Directive<S>>]>,
+    ) where
+        S: ScalarValue,
+    {
+        let mut seen = HashMap::<&str, SourcePosition>::new();
+        for d in directives.unwrap_or_default() {
+            let name = d.item.name.item;
+            // Unknown directives are reported by `KnownDirectives` rule.
+            let is_repeatable = ctx
+                .schema
+                .directive_by_name(name)
+                .map_or(true, |d| d.is_repeatable);
+            if is_repeatable {
+                continue;
+            }
+
+            match seen.entry(name) {
+                Entry::Occupied(e) => ctx.report_error(&error_message(name), &[*e.get(), d.start]),
+                Entry::Vacant(e) => {
+                    e.insert(d.start);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for UniqueDirectivesPerLocation
+where
+    S: ScalarValue,
+{
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        self.check_directives(ctx, op.item.directives.as_deref());
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        self.check_directives(ctx, f.item.directives.as_deref());
+    }
+
+    fn enter_variable_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        (_, var_def): &'a (Spanning<&'a str>, VariableDefinition<S>),
+    ) {
+        self.check_directives(ctx, var_def.directives.as_deref());
+    }
+
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        self.check_directives(ctx, field.item.directives.as_deref());
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        spread: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        self.check_directives(ctx, spread.item.directives.as_deref());
+    }
+
+    fn enter_inline_fragment(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<InlineFragment<S>>,
+    ) {
+        self.check_directives(ctx, f.item.directives.as_deref());
+    }
+}
+
+fn error_message(directive_name: &str) -> String {
+    format!("The directive \"@{directive_name}\" can only be used once at this location")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn no_directives() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type {
+            field
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unique_directives_in_different_locations() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type @onFragmentDefinition {
+            field @skip(if: true) @include(if: true)
+            ...Frag @skip(if: true)
+            ... @skip(if: false) {
+              field @skip(if: false)
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn repeatable_directives_in_same_location() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type {
+            field @onFieldRepeatable @onFieldRepeatable
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unknown_directives_in_same_location() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type {
+            field @unknown @unknown
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_in_one_location() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type {
+            field @skip(if: true) @skip(if: false)
+          }
+        "#,
+            &[RuleError::new(
+                &error_message("skip"),
+                &[
+                    SourcePosition::new(53, 2, 18),
+                    SourcePosition::new(69, 2, 34),
+                ],
+            )],
+        );
+    }
+
+    #[test]
+    fn many_duplicate_directives_in_one_location() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          fragment Test on Type {
+            field @onField @onField @onField
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("onField"),
+                    &[
+                        SourcePosition::new(53, 2, 18),
+                        SourcePosition::new(62, 2, 27),
+                    ],
+                ),
+                RuleError::new(
+                    &error_message("onField"),
+                    &[
+                        SourcePosition::new(53, 2, 18),
+                        SourcePosition::new(71, 2, 36),
+                    ],
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn duplicate_directives_in_many_locations() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Q @onQuery @onQuery {
+            ...Frag @skip(if: true) @skip(if: true)
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("onQuery"),
+                    &[
+                        SourcePosition::new(19, 1, 18),
+                        SourcePosition::new(28, 1, 27),
+                    ],
+                ),
+                RuleError::new(
+                    &error_message("skip"),
+                    &[
+                        SourcePosition::new(59, 2, 20),
+                        SourcePosition::new(75, 2, 36),
+                    ],
+                ),
+            ],
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::{
     ast::{
         Directive, Document, Field, Fragment, FragmentSpread, InlineFragment, InputValue,
@@ -8,7 +10,14 @@ use crate::{
     value::ScalarValue,
 };
 
-#[doc(hidden)]
+/// Visitor of an executable GraphQL [`Document`] being validated.
+///
+/// Every method is called while walking the [`Document`] in order, with the
+/// [`ValidatorContext`] tracking the types of the currently visited nodes.
+/// Errors are reported via [`ValidatorContext::report_error()`].
+///
+/// All the methods do nothing by default, so only the required ones should be
+/// implemented.
 pub trait Visitor<'a, S>
 where
     S: ScalarValue,
@@ -145,4 +154,45 @@ where
     }
 }
 
+/// User-defined validation rule, registered on a [`RootNode`] via
+/// [`RootNode::with_validation_rule()`].
+///
+/// Creates a fresh [`Visitor`] for every validated [`Document`], so the
+/// [`Visitor`] may hold any per-document state.
+///
+/// Any `Fn() -> V` closure, where `V` is a [`Visitor`] for any [`Document`],
+/// implements this trait.
+///
+/// [`RootNode`]: crate::RootNode
+/// [`RootNode::with_validation_rule()`]: crate::RootNode::with_validation_rule
+pub trait ValidationRule<S>: Send + Sync
+where
+    S: ScalarValue,
+{
+    /// Creates a new [`Visitor`] validating a single [`Document`].
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a;
+}
+
+impl<S: ScalarValue> fmt::Debug for dyn ValidationRule<S> + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationRule").finish_non_exhaustive()
+    }
+}
+
+impl<F, V, S> ValidationRule<S> for F
+where
+    F: Fn() -> V + Send + Sync,
+    V: for<'a> Visitor<'a, S> + 'static,
+    S: ScalarValue,
+{
+    fn visitor<'a>(&self) -> Box<dyn Visitor<'a, S> + 'a>
+    where
+        S: 'a,
+    {
+        Box::new(self())
+    }
+}
+
 type SpannedObject<'a, S> = Spanning<&'a Vec<(Spanning<String>, Spanning<InputValue<S>>)>>;
@@ -72,6 +72,11 @@ where
                 .schema
                 .concrete_subscription_type()
                 .map(|t| Type::NonNullNamed(Cow::Borrowed(t.name().unwrap()))),
+            Definition::Schema(_)
+            | Definition::Type(_)
+            | Definition::Directive(_)
+            | Definition::SchemaExtension(_)
+            | Definition::TypeExtension(_) => None,
         };
 
         ctx.with_pushed_type(def_type.as_ref(), |ctx| {
@@ -90,6 +95,11 @@ where
     match *def {
         Definition::Operation(ref op) => v.enter_operation_definition(ctx, op),
         Definition::Fragment(ref f) => v.enter_fragment_definition(ctx, f),
+        Definition::Schema(_)
+        | Definition::Type(_)
+        | Definition::Directive(_)
+        | Definition::SchemaExtension(_)
+        | Definition::TypeExtension(_) => {}
     }
 }
 
@@ -101,6 +111,11 @@ where
     match *def {
         Definition::Operation(ref op) => v.exit_operation_definition(ctx, op),
         Definition::Fragment(ref f) => v.exit_fragment_definition(ctx, f),
+        Definition::Schema(_)
+        | Definition::Type(_)
+        | Definition::Directive(_)
+        | Definition::SchemaExtension(_)
+        | Definition::TypeExtension(_) => {}
     }
 }
 
@@ -119,6 +134,11 @@ where
             visit_directives(v, ctx, &f.item.directives);
             visit_selection_set(v, ctx, &f.item.selection_set);
         }
+        Definition::Schema(_)
+        | Definition::Type(_)
+        | Definition::Directive(_)
+        | Definition::SchemaExtension(_)
+        | Definition::TypeExtension(_) => {}
     }
 }
 
@@ -4,17 +4,20 @@
 #![deny(warnings)]
 
 use actix_web::{
-    error::JsonPayloadError, http::Method, web, Error, FromRequest, HttpMessage, HttpRequest,
-    HttpResponse,
+    error::JsonPayloadError,
+    http::{header, Method, StatusCode},
+    web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse,
 };
 use juniper::{
     http::{
-        graphiql::graphiql_source, playground::playground_source, GraphQLBatchRequest,
-        GraphQLRequest,
+        graphiql::graphiql_source,
+        playground::playground_source,
+        policy::{self, MediaType, PolicyError},
+        GraphQLBatchRequest, GraphQLRequest,
     },
     ScalarValue,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Clone, PartialEq, Debug)]
 #[serde(deny_unknown_fields)]
@@ -79,17 +82,17 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return Ok(render_policy_error(e)),
+    };
     let get_req = web::Query::<GetGraphQLRequest>::from_query(req.query_string())?;
-    let req = GraphQLRequest::from(get_req.into_inner());
-    let gql_response = req.execute(schema, context).await;
-    let body_response = serde_json::to_string(&gql_response)?;
-    let mut response = match gql_response.is_ok() {
-        true => HttpResponse::Ok(),
-        false => HttpResponse::BadRequest(),
+    let req = GraphQLBatchRequest::Single(GraphQLRequest::from(get_req.into_inner()));
+    let gql_response = match policy::check_get_request(&req, schema) {
+        Ok(req) => req.execute(schema, context).await,
+        Err(e) => return Ok(render_policy_error(e)),
     };
-    Ok(response
-        .content_type("application/json")
-        .body(body_response))
+    render_response(&gql_response, gql_response.status_code(), media_type)
 }
 
 /// Actix GraphQL Handler for POST requests
@@ -109,6 +112,10 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return Ok(render_policy_error(e)),
+    };
     let req = match req.content_type() {
         "application/json" => {
             let body = String::from_request(&req, &mut payload.into_inner()).await?;
@@ -124,12 +131,113 @@ where
         _ => Err(JsonPayloadError::ContentType),
     }?;
     let gql_batch_response = req.execute(schema, context).await;
-    let gql_response = serde_json::to_string(&gql_batch_response)?;
-    let mut response = match gql_batch_response.is_ok() {
-        true => HttpResponse::Ok(),
-        false => HttpResponse::BadRequest(),
+    render_response(
+        &gql_batch_response,
+        gql_batch_response.status_code(),
+        media_type,
+    )
+}
+
+/// Negotiates the [`MediaType`] of the response to the provided [`HttpRequest`] out of its
+/// `Accept` header.
+fn negotiate_media_type(req: &HttpRequest) -> Result<MediaType, PolicyError> {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok());
+    MediaType::negotiate(accept).ok_or(PolicyError::NotAcceptable)
+}
+
+fn render_policy_error(err: PolicyError) -> HttpResponse {
+    let mut resp =
+        HttpResponse::build(StatusCode::from_u16(err.status_code()).expect("valid status code"));
+    if err == PolicyError::NonQueryOverGet {
+        resp.insert_header((header::ALLOW, "POST"));
+    }
+    resp.body(err.to_string())
+}
+
+fn render_response<T: Serialize>(
+    response: &T,
+    status_code: u16,
+    media_type: MediaType,
+) -> Result<HttpResponse, Error> {
+    let body = serde_json::to_string(response)?;
+    Ok(
+        HttpResponse::build(StatusCode::from_u16(status_code).expect("valid status code"))
+            .content_type(media_type.as_str())
+            .body(body),
+    )
+}
+
+/// Actix Web GraphQL Handler for GET and POST requests, additionally accepting
+/// [GraphQL multipart requests][1] with file uploads.
+///
+/// The uploaded files are stored according to the provided [`MultipartOptions`]. Responds with
+/// `413 Payload Too Large` if a request exceeds its limits.
+///
+/// [`MultipartOptions`]: juniper::http::multipart::MultipartOptions
+/// [1]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[cfg(feature = "multipart")]
+pub async fn multipart_graphql_handler<Query, Mutation, Subscription, CtxT, S>(
+    schema: &juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context: &CtxT,
+    req: HttpRequest,
+    payload: actix_web::web::Payload,
+    options: &juniper::http::multipart::MultipartOptions,
+) -> Result<HttpResponse, Error>
+where
+    Query: juniper::GraphQLTypeAsync<S, Context = CtxT>,
+    Query::TypeInfo: Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = CtxT>,
+    Mutation::TypeInfo: Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = CtxT>,
+    Subscription::TypeInfo: Sync,
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    use actix_web::error::PayloadError;
+    use futures::{channel::mpsc, future, SinkExt as _, StreamExt as _};
+    use juniper::http::multipart;
+
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|ct| multipart::is_multipart(ct));
+    let content_type = match (req.method(), content_type) {
+        (&Method::POST, Some(ct)) => ct,
+        _ => return graphql_handler(schema, context, req, payload).await,
+    };
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return Ok(render_policy_error(e)),
+    };
+
+    // `web::Payload` is not `Send`, so it's forwarded to the parser through a channel, being
+    // polled concurrently on the same task.
+    let (mut tx, rx) = mpsc::channel::<Result<web::Bytes, PayloadError>>(1);
+    let forward = async move {
+        let mut payload = payload;
+        while let Some(chunk) = payload.next().await {
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    };
+    let parse = multipart::parse_multipart_request::<S, _, _, _>(content_type, rx, options);
+    let gql_req = match future::join(forward, parse).await.1 {
+        Ok(gql_req) => gql_req,
+        Err(e) if e.is_too_large() => return Err(actix_web::error::ErrorPayloadTooLarge(e)),
+        Err(e) => return Err(actix_web::error::ErrorBadRequest(e)),
     };
-    Ok(response.content_type("application/json").body(gql_response))
+
+    let gql_batch_response = gql_req.execute(schema, context).await;
+    render_response(
+        &gql_batch_response,
+        gql_batch_response.status_code(),
+        media_type,
+    )
 }
 
 /// Create a handler that replies with an HTML page containing GraphiQL. This does not handle routing, so you can mount it on any endpoint
@@ -174,25 +282,89 @@ pub async fn playground_handler(
 /// [1]: https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md
 #[cfg(feature = "subscriptions")]
 pub mod subscriptions {
-    use std::{fmt, sync::Arc};
+    use std::{convert::Infallible, fmt, pin::Pin, sync::Arc};
 
     use actix::{prelude::*, Actor, StreamHandler};
     use actix_web::{
-        http::header::{HeaderName, HeaderValue},
-        web, HttpRequest, HttpResponse,
+        error::JsonPayloadError,
+        http::{
+            header::{HeaderName, HeaderValue},
+            Method,
+        },
+        web, FromRequest, HttpRequest, HttpResponse,
     };
     use actix_web_actors::ws;
     use juniper::{
         futures::{
-            stream::{SplitSink, SplitStream, StreamExt},
-            SinkExt,
+            stream::{LocalBoxStream, StreamExt},
+            Sink, SinkExt,
         },
-        GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+        http::GraphQLRequest,
+        GraphQLError, GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+        SubscriptionCoordinator,
+    };
+    use juniper_graphql_ws::{
+        graphql_transport_ws, ArcSchema, ClientMessage, Connection, IncomingMessage, Init, Protocol,
     };
-    use juniper_graphql_ws::{ArcSchema, ClientMessage, Connection, Init, ServerMessage};
+    use juniper_subscriptions::sse;
+    use serde::Serialize;
     use tokio::sync::Mutex;
 
-    /// Serves the graphql-ws protocol over a WebSocket connection.
+    use super::GetGraphQLRequest;
+
+    /// Serves subscriptions over [Server-Sent Events][1], for environments where WebSockets are
+    /// not available.
+    ///
+    /// Accepts a GET request with the operation in query parameters, or a POST request with the
+    /// operation in a JSON body, and replies with a `text/event-stream` response, consisting of a
+    /// `next` event per result of the operation and a `complete` event at the end.
+    ///
+    /// Any [`SubscriptionCoordinator`] may serve the subscriptions, like a
+    /// [`juniper_subscriptions::Coordinator`] or a [`juniper_subscriptions::SharedCoordinator`].
+    ///
+    /// [1]: https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md
+    pub async fn sse_handler<Coord, CtxT, S>(
+        req: HttpRequest,
+        payload: web::Payload,
+        coordinator: Arc<Coord>,
+        context: CtxT,
+    ) -> Result<HttpResponse, actix_web::Error>
+    where
+        Coord: for<'a> SubscriptionCoordinator<'a, CtxT, S, Error = GraphQLError>
+            + Send
+            + Sync
+            + 'static,
+        for<'a> <Coord as SubscriptionCoordinator<'a, CtxT, S>>::Connection: Send,
+        CtxT: Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        let gql_req = match *req.method() {
+            Method::POST => {
+                let body = String::from_request(&req, &mut payload.into_inner()).await?;
+                serde_json::from_str::<GraphQLRequest<S>>(&body)
+                    .map_err(JsonPayloadError::Deserialize)?
+            }
+            Method::GET => {
+                let get_req = web::Query::<GetGraphQLRequest>::from_query(req.query_string())?;
+                GraphQLRequest::from(get_req.into_inner())
+            }
+            _ => return Err(actix_web::error::UrlGenerationError::ResourceNotFound.into()),
+        };
+
+        let events = sse::subscribe(coordinator, gql_req, context)
+            .map(|event| Ok::<_, Infallible>(web::Bytes::from(event.to_string())));
+
+        Ok(HttpResponse::Ok()
+            .content_type(sse::CONTENT_TYPE)
+            .insert_header(("cache-control", "no-cache"))
+            .streaming(events))
+    }
+
+    /// Serves GraphQL over a WebSocket connection.
+    ///
+    /// The protocol is negotiated from the `Sec-WebSocket-Protocol` request header: the
+    /// graphql-transport-ws protocol is served if the client asks for it, and the legacy graphql-ws
+    /// protocol otherwise. The chosen protocol is returned in the same response header.
     ///
     /// The `init` argument is used to provide the context and additional configuration for
     /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
@@ -216,12 +388,48 @@ pub mod subscriptions {
         S: ScalarValue + Send + Sync + 'static,
         I: Init<S, CtxT> + Send,
     {
-        let (s_tx, s_rx) = Connection::new(ArcSchema(root_node), init).split::<Message>();
+        let protocol = Protocol::negotiate(
+            req.headers()
+                .get("sec-websocket-protocol")
+                .and_then(|h| h.to_str().ok()),
+        );
+
+        let (graphql_tx, graphql_rx): (ClientMessageSink, ServerMessageStream) = match protocol {
+            Protocol::GraphQLWs => {
+                let (s_tx, s_rx) = Connection::new(ArcSchema(root_node), init).split::<Message>();
+                (
+                    Box::pin(s_tx),
+                    s_rx.map(|msg| ServerMessageWrapper::text(&msg))
+                        .boxed_local(),
+                )
+            }
+            Protocol::GraphQLTransportWs => {
+                let (s_tx, s_rx) =
+                    graphql_transport_ws::Connection::new(ArcSchema(root_node), init)
+                        .split::<Message>();
+                (
+                    Box::pin(s_tx),
+                    s_rx.map(|output| match output {
+                        graphql_transport_ws::Output::Message(msg) => {
+                            ServerMessageWrapper::text(&msg)
+                        }
+                        graphql_transport_ws::Output::Close { code, message } => {
+                            ServerMessageWrapper::Close(ws::CloseReason {
+                                code: ws::CloseCode::Other(code),
+                                description: Some(message),
+                            })
+                        }
+                    })
+                    .boxed_local(),
+                )
+            }
+        };
 
         let mut resp = ws::start(
             SubscriptionActor {
-                graphql_tx: Arc::new(Mutex::new(s_tx)),
-                graphql_rx: Arc::new(Mutex::new(s_rx)),
+                protocol,
+                graphql_tx: Arc::new(Mutex::new(graphql_tx)),
+                graphql_rx: Arc::new(Mutex::new(graphql_rx)),
             },
             &req,
             stream,
@@ -229,66 +437,43 @@ pub mod subscriptions {
 
         resp.headers_mut().insert(
             HeaderName::from_static("sec-websocket-protocol"),
-            HeaderValue::from_static("graphql-ws"),
+            HeaderValue::from_static(protocol.as_str()),
         );
 
         Ok(resp)
     }
 
-    type ConnectionSplitSink<Query, Mutation, Subscription, CtxT, S, I> = Arc<
-        Mutex<SplitSink<Connection<ArcSchema<Query, Mutation, Subscription, CtxT, S>, I>, Message>>,
-    >;
+    type ClientMessageSink = Pin<Box<dyn Sink<Message, Error = Infallible>>>;
 
-    type ConnectionSplitStream<Query, Mutation, Subscription, CtxT, S, I> =
-        Arc<Mutex<SplitStream<Connection<ArcSchema<Query, Mutation, Subscription, CtxT, S>, I>>>>;
+    type ServerMessageStream = LocalBoxStream<'static, ServerMessageWrapper>;
 
     /// Subscription Actor
     /// coordinates messages between actix_web and juniper_graphql_ws
     /// ws message -> actor -> juniper
     /// juniper -> actor -> ws response
-    struct SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
-    where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
-    {
-        graphql_tx: ConnectionSplitSink<Query, Mutation, Subscription, CtxT, S, I>,
-        graphql_rx: ConnectionSplitStream<Query, Mutation, Subscription, CtxT, S, I>,
+    struct SubscriptionActor {
+        protocol: Protocol,
+        graphql_tx: Arc<Mutex<ClientMessageSink>>,
+        graphql_rx: Arc<Mutex<ServerMessageStream>>,
     }
 
     /// ws message -> actor -> juniper
-    impl<Query, Mutation, Subscription, CtxT, S, I>
-        StreamHandler<Result<ws::Message, ws::ProtocolError>>
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
-    where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
-    {
+    impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for SubscriptionActor {
         fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
-            let msg = msg.map(Message);
-
-            #[allow(clippy::single_match)]
             match msg {
+                Ok(ws::Message::Close(reason)) if self.protocol == Protocol::GraphQLTransportWs => {
+                    // graphql-transport-ws has no termination message, so the client just closes
+                    // the socket.
+                    ctx.close(reason);
+                    ctx.stop();
+                }
                 Ok(msg) => {
                     let tx = self.graphql_tx.clone();
 
                     async move {
                         tx.lock()
                             .await
-                            .send(msg)
+                            .send(Message(msg))
                             .await
                             .expect("Infallible: this should not happen");
                     }
@@ -304,19 +489,7 @@ pub mod subscriptions {
     }
 
     /// juniper -> actor
-    impl<Query, Mutation, Subscription, CtxT, S, I> Actor
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
-    where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
-    {
+    impl Actor for SubscriptionActor {
         type Context = ws::WebsocketContext<Self>;
 
         fn started(&mut self, ctx: &mut Self::Context) {
@@ -327,7 +500,7 @@ pub mod subscriptions {
                 let mut stream = stream.lock().await;
                 while let Some(message) = stream.next().await {
                     // sending the message to self so that it can be forwarded back to the client
-                    addr.do_send(ServerMessageWrapper { message });
+                    addr.do_send(message);
                 }
             }
             .into_actor(self);
@@ -342,53 +515,57 @@ pub mod subscriptions {
     }
 
     /// actor -> websocket response
-    impl<Query, Mutation, Subscription, CtxT, S, I> actix::prelude::Handler<ServerMessageWrapper<S>>
-        for SubscriptionActor<Query, Mutation, Subscription, CtxT, S, I>
-    where
-        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Query::TypeInfo: Send + Sync,
-        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
-        Mutation::TypeInfo: Send + Sync,
-        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
-        Subscription::TypeInfo: Send + Sync,
-        CtxT: Unpin + Send + Sync + 'static,
-        S: ScalarValue + Send + Sync + 'static,
-        I: Init<S, CtxT> + Send,
-    {
+    impl actix::prelude::Handler<ServerMessageWrapper> for SubscriptionActor {
         type Result = ();
 
-        fn handle(
-            &mut self,
-            msg: ServerMessageWrapper<S>,
-            ctx: &mut Self::Context,
-        ) -> Self::Result {
-            let msg = serde_json::to_string(&msg.message);
+        fn handle(&mut self, msg: ServerMessageWrapper, ctx: &mut Self::Context) -> Self::Result {
             match msg {
-                Ok(msg) => ctx.text(msg),
-                Err(e) => {
-                    let reason = ws::CloseReason {
-                        code: ws::CloseCode::Error,
-                        description: Some(format!("error serializing response: {e}")),
-                    };
-
+                ServerMessageWrapper::Text(msg) => ctx.text(msg),
+                ServerMessageWrapper::Close(reason) => {
                     // TODO: trace
-                    ctx.close(Some(reason))
+                    ctx.close(Some(reason));
+                    ctx.stop();
                 }
             };
         }
     }
+
+    /// Server message, prepared to be sent over the WebSocket.
     #[derive(Message)]
     #[rtype(result = "()")]
-    struct ServerMessageWrapper<S>
-    where
-        S: ScalarValue + Send + Sync + 'static,
-    {
-        message: ServerMessage<S>,
+    enum ServerMessageWrapper {
+        /// Serialized protocol message.
+        Text(String),
+
+        /// Closing of the connection.
+        Close(ws::CloseReason),
+    }
+
+    impl ServerMessageWrapper {
+        fn text<T: Serialize>(msg: &T) -> Self {
+            match serde_json::to_string(msg) {
+                Ok(msg) => Self::Text(msg),
+                Err(e) => Self::Close(ws::CloseReason {
+                    code: ws::CloseCode::Error,
+                    description: Some(format!("error serializing response: {e}")),
+                }),
+            }
+        }
     }
 
     #[derive(Debug)]
     struct Message(ws::Message);
 
+    impl IncomingMessage for Message {
+        fn size(&self) -> Option<usize> {
+            match &self.0 {
+                ws::Message::Text(text) => Some(text.len()),
+                ws::Message::Binary(bytes) => Some(bytes.len()),
+                _ => None,
+            }
+        }
+    }
+
     impl<S: ScalarValue> TryFrom<Message> for ClientMessage<S> {
         type Error = Error;
 
@@ -403,6 +580,19 @@ pub mod subscriptions {
         }
     }
 
+    impl<S: ScalarValue> TryFrom<Message> for graphql_transport_ws::ClientMessage<S> {
+        type Error = Error;
+
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            match msg.0 {
+                ws::Message::Text(text) => {
+                    serde_json::from_slice(text.as_bytes()).map_err(Error::Serde)
+                }
+                _ => Err(Error::UnexpectedClientMessage),
+            }
+        }
+    }
+
     /// Errors that can happen while handling client messages
     #[derive(Debug)]
     enum Error {
@@ -594,6 +784,95 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "multipart")]
+    #[actix_web::rt::test]
+    async fn multipart_graphql_handler_works() {
+        use juniper::{
+            graphql_object,
+            http::multipart::{MultipartOptions, Upload},
+            Executor,
+        };
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        struct Mutation;
+
+        #[graphql_object]
+        impl Mutation {
+            fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> Option<i32> {
+                file.file(executor.extensions()).map(|f| f.size() as i32)
+            }
+        }
+
+        type UploadSchema = RootNode<'static, Query, Mutation, EmptySubscription>;
+
+        async fn upload_index(
+            req: HttpRequest,
+            payload: actix_web::web::Payload,
+            schema: web::Data<UploadSchema>,
+            options: web::Data<MultipartOptions>,
+        ) -> Result<HttpResponse, Error> {
+            multipart_graphql_handler(&schema, &(), req, payload, &options).await
+        }
+
+        let body = "--boundary\r\n\
+                    Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+                    {\"query\": \"mutation($file: Upload!) { upload(file: $file) }\", \"variables\": {\"file\": null}}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+                    {\"0\": [\"variables.file\"]}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n\
+                    Hello, world!\r\n\
+                    --boundary--\r\n";
+        let request = || {
+            TestRequest::post()
+                .append_header(("content-type", "multipart/form-data; boundary=boundary"))
+                .set_payload(body)
+                .uri("/")
+                .to_request()
+        };
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(UploadSchema::new(
+                    Query,
+                    Mutation,
+                    EmptySubscription::new(),
+                )))
+                .app_data(Data::new(MultipartOptions::new()))
+                .route("/", web::post().to(upload_index)),
+        )
+        .await;
+        let resp = test::call_service(&mut app, request()).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            take_response_body_string(resp).await,
+            r#"{"data":{"upload":13}}"#,
+        );
+
+        let mut app = test::init_service(
+            App::new()
+                .app_data(Data::new(UploadSchema::new(
+                    Query,
+                    Mutation,
+                    EmptySubscription::new(),
+                )))
+                .app_data(Data::new(MultipartOptions::new().with_max_file_size(5)))
+                .route("/", web::post().to(upload_index)),
+        )
+        .await;
+        let resp = test::call_service(&mut app, request()).await;
+        assert_eq!(resp.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
     #[actix_web::rt::test]
     async fn graphql_get_works() {
         let schema: Schema = RootNode::new(
@@ -725,6 +1004,24 @@ mod tests {
                     .uri(url),
             )
         }
+
+        fn get_with_accept(&self, url: &str, accept: &str) -> TestResponse {
+            self.make_request(
+                TestRequest::get()
+                    .append_header(("accept", accept.to_owned()))
+                    .uri(url),
+            )
+        }
+
+        fn post_json_with_accept(&self, url: &str, body: &str, accept: &str) -> TestResponse {
+            self.make_request(
+                TestRequest::post()
+                    .append_header(("content-type", "application/json"))
+                    .append_header(("accept", accept.to_owned()))
+                    .set_payload(body.to_owned())
+                    .uri(url),
+            )
+        }
     }
 
     async fn make_test_response(resp: ServiceResponse) -> TestResponse {
@@ -732,10 +1029,8 @@ mod tests {
         let content_type = resp
             .headers()
             .get(CONTENT_TYPE)
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .into();
+            .map(|ct| ct.to_str().unwrap().into())
+            .unwrap_or_default();
         let body = take_response_body_string(resp).await;
         TestResponse {
             status_code: status_code as i32,
@@ -750,6 +1045,171 @@ mod tests {
     }
 }
 
+#[cfg(feature = "subscriptions")]
+#[cfg(test)]
+mod sse_tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use actix_web::{
+        http::{self, header::CONTENT_TYPE},
+        test::{self, TestRequest},
+        web, App, Error, HttpRequest, HttpResponse,
+    };
+    use juniper::{
+        futures::{stream, Stream},
+        graphql_object, graphql_subscription, DefaultScalarValue, EmptyMutation, FieldError,
+        RootNode,
+    };
+    use juniper_subscriptions::{sse, Coordinator, SharedCoordinator};
+
+    use super::subscriptions::sse_handler;
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    type Counter = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+    #[graphql_subscription]
+    impl Subscription {
+        async fn counter(to: i32) -> Counter {
+            Box::pin(stream::iter((1..=to).map(Ok)))
+        }
+    }
+
+    fn schema() -> RootNode<'static, Query, EmptyMutation, Subscription> {
+        RootNode::new(Query, EmptyMutation::new(), Subscription)
+    }
+
+    const EVENTS: &str = "event: next\ndata: {\"data\":{\"counter\":1}}\n\n\
+                          event: next\ndata: {\"data\":{\"counter\":2}}\n\n\
+                          event: complete\ndata:\n\n";
+
+    async fn sse(
+        req: HttpRequest,
+        payload: web::Payload,
+        coordinator: web::Data<
+            Coordinator<'static, Query, EmptyMutation, Subscription, (), DefaultScalarValue>,
+        >,
+    ) -> Result<HttpResponse, Error> {
+        sse_handler(req, payload, coordinator.into_inner(), ()).await
+    }
+
+    async fn call(req: TestRequest) -> (http::StatusCode, Option<String>, String) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Coordinator::new(schema())))
+                .route("/", web::to(sse)),
+        )
+        .await;
+
+        let resp = test::call_service(&app, req.to_request()).await;
+        let status = resp.status();
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|v| v.to_str().unwrap().to_owned());
+        let body = test::read_body(resp).await;
+        (
+            status,
+            content_type,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    #[actix_web::rt::test]
+    async fn streams_get_request() {
+        let req = TestRequest::get().uri("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D");
+
+        assert_eq!(
+            call(req).await,
+            (
+                http::StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                EVENTS.into(),
+            ),
+        );
+    }
+
+    #[actix_web::rt::test]
+    async fn streams_post_request() {
+        let req = TestRequest::post()
+            .insert_header((CONTENT_TYPE, "application/json"))
+            .set_payload(
+                r#"{
+                    "query": "subscription($to: Int!) { counter(to: $to) }",
+                    "variables": {"to": 2}
+                }"#,
+            );
+
+        assert_eq!(
+            call(req).await,
+            (
+                http::StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                EVENTS.into(),
+            ),
+        );
+    }
+
+    #[actix_web::rt::test]
+    async fn streams_error_before_start() {
+        let req = TestRequest::post()
+            .insert_header((CONTENT_TYPE, "application/json"))
+            .set_payload(r#"{"query": "subscription { unknown }"}"#);
+
+        assert_eq!(
+            call(req).await,
+            (
+                http::StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                "event: next\ndata: {\"errors\":[{\"message\":\
+                 \"Unknown field \\\"unknown\\\" on type \\\"Subscription\\\"\",\
+                 \"locations\":[{\"line\":1,\"column\":16}]}]}\n\n\
+                 event: complete\ndata:\n\n"
+                    .into(),
+            ),
+        );
+    }
+
+    #[actix_web::rt::test]
+    async fn rejects_get_request_without_query() {
+        let (status, ..) = call(TestRequest::get().uri("/")).await;
+
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::rt::test]
+    async fn streams_via_shared_coordinator() {
+        let coordinator = Arc::new(SharedCoordinator::new(schema(), |_: &()| String::new()));
+        let app = test::init_service(App::new().route(
+            "/",
+            web::to(move |req: HttpRequest, payload: web::Payload| {
+                sse_handler(req, payload, coordinator.clone(), ())
+            }),
+        ))
+        .await;
+
+        let req = TestRequest::get()
+            .uri("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(test::read_body(resp).await.to_vec()).unwrap(),
+            EVENTS,
+        );
+    }
+}
+
 #[cfg(feature = "subscriptions")]
 #[cfg(test)]
 mod subscription_tests {
@@ -763,24 +1223,26 @@ mod subscription_tests {
     use actix_web_actors::ws;
     use juniper::{
         futures::{SinkExt, StreamExt},
-        http::tests::{run_ws_test_suite, WsIntegration, WsIntegrationMessage},
+        http::tests::{
+            run_graphql_transport_ws_test_suite, run_ws_test_suite, WsIntegration,
+            WsIntegrationMessage,
+        },
         tests::fixtures::starwars::schema::{Database, Query, Subscription},
         EmptyMutation, LocalBoxFuture,
     };
-    use juniper_graphql_ws::ConnectionConfig;
+    use juniper_graphql_ws::{ConnectionConfig, Protocol};
     use tokio::time::timeout;
 
     use super::subscriptions::subscriptions_handler;
 
-    #[derive(Default)]
-    struct TestActixWsIntegration;
+    struct TestActixWsIntegration(Protocol);
 
     impl TestActixWsIntegration {
         async fn run_async(
             &self,
             messages: Vec<WsIntegrationMessage>,
         ) -> Result<(), anyhow::Error> {
-            let mut server = start(|| {
+            let server = start(|| {
                 App::new()
                     .app_data(Data::new(Schema::new(
                         Query,
@@ -789,7 +1251,12 @@ mod subscription_tests {
                     )))
                     .service(web::resource("/subscriptions").to(subscriptions))
             });
-            let mut framed = server.ws_at("/subscriptions").await.unwrap();
+            let (_, mut framed) = awc::Client::new()
+                .ws(server.url("/subscriptions"))
+                .protocols([self.0.as_str()])
+                .connect()
+                .await
+                .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
 
             for message in &messages {
                 match message {
@@ -857,6 +1324,12 @@ mod subscription_tests {
 
     #[actix_web::rt::test]
     async fn test_actix_ws_integration() {
-        run_ws_test_suite(&mut TestActixWsIntegration::default()).await;
+        run_ws_test_suite(&TestActixWsIntegration(Protocol::GraphQLWs)).await;
+    }
+
+    #[actix_web::rt::test]
+    async fn test_actix_graphql_transport_ws_integration() {
+        run_graphql_transport_ws_test_suite(&TestActixWsIntegration(Protocol::GraphQLTransportWs))
+            .await;
     }
 }
@@ -15,7 +15,7 @@ use syn::{
 };
 
 use crate::common::{
-    default, diagnostic, filter_attrs,
+    default, deprecation, diagnostic, filter_attrs,
     parse::{
         attr::{err, OptionExt as _},
         ParseBufferExt as _, TypeExt as _,
@@ -52,6 +52,12 @@ pub(crate) struct Attr {
     /// [2]: https://spec.graphql.org/October2021#sec-Required-Arguments
     pub(crate) default: Option<SpanContainer<default::Value>>,
 
+    /// Explicitly specified [deprecation][2] of this [GraphQL argument][1].
+    ///
+    /// [1]: https://spec.graphql.org/October2021#sec-Language.Arguments
+    /// [2]: https://spec.graphql.org/October2021#sec-Deprecation
+    pub(crate) deprecated: Option<SpanContainer<deprecation::Directive>>,
+
     /// Explicitly specified marker indicating that this method argument doesn't
     /// represent a [GraphQL argument][1], but is a [`Context`] being injected
     /// into a [GraphQL field][2] resolving function.
@@ -103,6 +109,16 @@ impl Parse for Attr {
                         .replace(SpanContainer::new(ident.span(), Some(val.span()), val))
                         .none_or_else(|_| err::dup_arg(&ident))?
                 }
+                "deprecated" => {
+                    let directive = input.parse::<deprecation::Directive>()?;
+                    out.deprecated
+                        .replace(SpanContainer::new(
+                            ident.span(),
+                            directive.reason.as_ref().map(|r| r.span()),
+                            directive,
+                        ))
+                        .none_or_else(|_| err::dup_arg(&ident))?
+                }
                 "ctx" | "context" | "Context" => {
                     let span = ident.span();
                     out.context
@@ -133,6 +149,7 @@ impl Attr {
             name: try_merge_opt!(name: self, another),
             description: try_merge_opt!(description: self, another),
             default: try_merge_opt!(default: self, another),
+            deprecated: try_merge_opt!(deprecated: self, another),
             context: try_merge_opt!(context: self, another),
             executor: try_merge_opt!(executor: self, another),
         })
@@ -149,6 +166,7 @@ impl Attr {
             if attr.name.is_some()
                 || attr.description.is_some()
                 || attr.default.is_some()
+                || attr.deprecated.is_some()
                 || attr.executor.is_some()
             {
                 return Err(syn::Error::new(
@@ -162,6 +180,7 @@ impl Attr {
             if attr.name.is_some()
                 || attr.description.is_some()
                 || attr.default.is_some()
+                || attr.deprecated.is_some()
                 || attr.context.is_some()
             {
                 return Err(syn::Error::new(
@@ -186,6 +205,9 @@ impl Attr {
         if let Some(span) = &self.default {
             return Err(Self::err_disallowed(&span, "default"));
         }
+        if let Some(span) = &self.deprecated {
+            return Err(Self::err_disallowed(&span, "deprecated"));
+        }
         Ok(())
     }
 
@@ -229,6 +251,13 @@ pub(crate) struct OnField {
     /// [1]: https://spec.graphql.org/October2021#sec-Language.Arguments
     /// [2]: https://spec.graphql.org/October2021#sec-Required-Arguments
     pub(crate) default: Option<default::Value>,
+
+    /// [Deprecation][2] of this [GraphQL field argument][1] to put into GraphQL
+    /// schema.
+    ///
+    /// [1]: https://spec.graphql.org/October2021#sec-Language.Arguments
+    /// [2]: https://spec.graphql.org/October2021#sec-Deprecation
+    pub(crate) deprecated: Option<deprecation::Directive>,
 }
 
 /// Possible kinds of Rust method arguments for code generation.
@@ -300,6 +329,7 @@ impl OnMethod {
         let (name, ty) = (&arg.name, &arg.ty);
 
         let description = &arg.description;
+        let deprecated = &arg.deprecated;
 
         let method = if let Some(val) = &arg.default {
             quote_spanned! { val.span() =>
@@ -309,7 +339,7 @@ impl OnMethod {
             quote! { .arg::<#ty>(#name, info) }
         };
 
-        Some(quote! { .argument(registry #method #description) })
+        Some(quote! { .argument(registry #method #description #deprecated) })
     }
 
     /// Returns generated code for the [`GraphQLValue::resolve_field`] method,
@@ -433,6 +463,7 @@ impl OnMethod {
             ty: argument.ty.as_ref().clone(),
             description: attr.description.map(SpanContainer::into_inner),
             default: attr.default.map(SpanContainer::into_inner),
+            deprecated: attr.deprecated.map(SpanContainer::into_inner),
         })))
     }
 }
@@ -15,7 +15,7 @@ use syn::{
 };
 
 use crate::common::{
-    default, filter_attrs,
+    default, deprecation, filter_attrs,
     parse::{
         attr::{err, OptionExt as _},
         ParseBufferExt as _,
@@ -205,6 +205,16 @@ struct FieldAttr {
     /// [2]: https://spec.graphql.org/October2021#sec-Descriptions
     description: Option<SpanContainer<Description>>,
 
+    /// Explicitly specified [deprecation][2] of this
+    /// [GraphQL input object field][1].
+    ///
+    /// If [`None`], then Rust `#[deprecated]` attribute will be used as the
+    /// [deprecation][2], if any.
+    ///
+    /// [1]: https://spec.graphql.org/October2021#InputValueDefinition
+    /// [2]: https://spec.graphql.org/October2021#sec-Deprecation
+    deprecated: Option<SpanContainer<deprecation::Directive>>,
+
     /// Explicitly specified marker for the Rust struct field to be ignored and
     /// not included into the code generated for a [GraphQL input object][0]
     /// implementation.
@@ -247,6 +257,16 @@ impl Parse for FieldAttr {
                         .replace(SpanContainer::new(ident.span(), Some(desc.span()), desc))
                         .none_or_else(|_| err::dup_arg(&ident))?
                 }
+                "deprecated" => {
+                    let directive = input.parse::<deprecation::Directive>()?;
+                    out.deprecated
+                        .replace(SpanContainer::new(
+                            ident.span(),
+                            directive.reason.as_ref().map(|r| r.span()),
+                            directive,
+                        ))
+                        .none_or_else(|_| err::dup_arg(&ident))?
+                }
                 "ignore" | "skip" => out
                     .ignore
                     .replace(SpanContainer::new(ident.span(), None, ident.clone()))
@@ -269,6 +289,7 @@ impl FieldAttr {
             name: try_merge_opt!(name: self, another),
             default: try_merge_opt!(default: self, another),
             description: try_merge_opt!(description: self, another),
+            deprecated: try_merge_opt!(deprecated: self, another),
             ignore: try_merge_opt!(ignore: self, another),
         })
     }
@@ -284,6 +305,10 @@ impl FieldAttr {
             attr.description = Description::parse_from_doc_attrs(attrs)?;
         }
 
+        if attr.deprecated.is_none() {
+            attr.deprecated = deprecation::Directive::parse_from_deprecated_attr(attrs)?;
+        }
+
         Ok(attr)
     }
 }
@@ -326,6 +351,13 @@ struct FieldDefinition {
     /// [2]: https://spec.graphql.org/October2021#sec-Descriptions
     description: Option<Description>,
 
+    /// [Deprecation][2] of this [GraphQL input object field][1] to put into
+    /// GraphQL schema.
+    ///
+    /// [1]: https://spec.graphql.org/October2021#InputValueDefinition
+    /// [2]: https://spec.graphql.org/October2021#sec-Deprecation
+    deprecated: Option<deprecation::Directive>,
+
     /// Indicator whether the Rust struct field behinds this
     /// [GraphQL input object field][1] is being ignored and should not be
     /// included into the generated code.
@@ -468,8 +500,9 @@ impl Definition {
                     quote! { .arg::<#ty>(#name, info) }
                 };
                 let description = &f.description;
+                let deprecated = &f.deprecated;
 
-                quote! { registry #arg #description }
+                quote! { registry #arg #description #deprecated }
             })
         });
 
@@ -614,6 +647,7 @@ impl Definition {
             {
                 type Error = ::juniper::FieldError<#scalar>;
 
+                #[allow(deprecated)]
                 fn from_input_value(
                     value: &::juniper::InputValue<#scalar>,
                 ) -> Result<Self, Self::Error> {
@@ -129,7 +129,7 @@ use proc_macro_error::{proc_macro_error, ResultExt as _};
 /// }
 /// ```
 ///
-/// # Custom name and description
+/// # Custom name, description and deprecation
 ///
 /// The name of a [GraphQL input object][0] or its [fields][1] may be overridden
 /// with the `name` attribute's argument. By default, a type name or a struct
@@ -139,7 +139,13 @@ use proc_macro_error::{proc_macro_error, ResultExt as _};
 /// specified either with the `description`/`desc` attribute's argument, or with
 /// a regular Rust doc comment.
 ///
+/// A [field][1] of [GraphQL input object][0] may be deprecated by specifying
+/// the `deprecated` attribute's argument, or with a regular Rust `#[deprecated]`
+/// attribute. Only a nullable or defaulted [field][1] may be deprecated.
+///
 /// ```rust
+/// # #![allow(deprecated)]
+/// #
 /// # use juniper::GraphQLInputObject;
 /// #
 /// #[derive(GraphQLInputObject)]
@@ -156,6 +162,13 @@ use proc_macro_error::{proc_macro_error, ResultExt as _};
 ///
 ///     #[graphql(name = "y", desc = "Ordinate value")]
 ///     y_coord: f64,
+///
+///     #[graphql(deprecated = "Use `y` instead")]
+///     ordinate: Option<f64>,
+///
+///     #[deprecated]
+///     #[graphql(default = 0.0)]
+///     z: f64,
 /// }
 /// ```
 ///
@@ -939,7 +952,8 @@ pub fn derive_scalar_value(input: TokenStream) -> TokenStream {
 /// either with a `description`/`desc` attribute's argument, or with a regular Rust doc comment.
 ///
 /// A field of [GraphQL interface][1] may be deprecated by specifying a `deprecated` attribute's
-/// argument, or with regular Rust `#[deprecated]` attribute.
+/// argument, or with regular Rust `#[deprecated]` attribute. A nullable or defaulted field argument
+/// may be deprecated with a `deprecated` attribute's argument only.
 ///
 /// The default value of a field argument may be specified with a `default` attribute argument (if
 /// no exact value is specified then [`Default::default`] is used).
@@ -1565,7 +1579,8 @@ pub fn derive_object(body: TokenStream) -> TokenStream {
 ///
 /// A field of [GraphQL object][1] may be deprecated by specifying a
 /// `deprecated` attribute's argument, or with regular Rust `#[deprecated]`
-/// attribute.
+/// attribute. A nullable or defaulted field argument may be deprecated with a
+/// `deprecated` attribute's argument only.
 ///
 /// The default value of a field argument may be specified with a `default`
 /// attribute argument (if no exact value is specified then [`Default::default`]
@@ -1593,6 +1608,7 @@ pub fn derive_object(body: TokenStream) -> TokenStream {
 ///         // A default can be any valid expression that yields the right type.
 ///         #[graphql(default = 5)]
 ///         num: i32,
+///         #[graphql(deprecated = "Use `number` instead")] old_num: Option<i32>,
 ///     ) -> &str {
 ///         "Don't use me!"
 ///     }
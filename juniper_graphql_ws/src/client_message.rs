@@ -1,4 +1,4 @@
-use juniper::Variables;
+use juniper::{http::GraphQLRequest, InputValue, ScalarValue, Spanning, Variables};
 use serde::Deserialize;
 
 use crate::utils::default_for_null;
@@ -20,6 +20,28 @@ pub struct StartPayload<S> {
     pub operation_name: Option<String>,
 }
 
+impl<S: ScalarValue> StartPayload<S> {
+    /// Converts this payload into a [`GraphQLRequest`].
+    pub(crate) fn to_request(&self) -> GraphQLRequest<S> {
+        let variables = InputValue::Object(
+            self.variables
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        Spanning::unlocated(k.clone()),
+                        Spanning::unlocated(v.clone()),
+                    )
+                })
+                .collect(),
+        );
+        GraphQLRequest::new(
+            self.query.clone(),
+            self.operation_name.clone(),
+            Some(variables),
+        )
+    }
+}
+
 /// ClientMessage defines the message types that clients can send.
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(bound(deserialize = "S: Deserialize<'de>"))]
@@ -51,6 +73,24 @@ pub enum ClientMessage<S> {
     ConnectionTerminate,
 }
 
+impl<S> IncomingMessage for ClientMessage<S> {}
+
+/// IncomingMessage defines the requirements for types that a connection accepts as messages from
+/// clients.
+///
+/// Integrations should report the size of raw messages, so the
+/// [`ConnectionConfig::with_max_incoming_message_size()`] limit can be enforced before parsing
+/// them.
+///
+/// [`ConnectionConfig::with_max_incoming_message_size()`]: crate::ConnectionConfig::with_max_incoming_message_size
+pub trait IncomingMessage {
+    /// Returns the size of this message in bytes, if it's known. By default, it's unknown, so the
+    /// message is never rejected for its size.
+    fn size(&self) -> Option<usize> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use juniper::{graphql_vars, DefaultScalarValue};
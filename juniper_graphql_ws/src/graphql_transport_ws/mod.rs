@@ -0,0 +1,1738 @@
+//! Implementation of the [`graphql-transport-ws` protocol][1], used by the
+//! [`graphql-ws`][2] library and modern GraphQL clients.
+//!
+//! [1]: https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md
+//! [2]: https://github.com/enisdenjo/graphql-ws
+
+mod client_message;
+pub use client_message::*;
+
+mod server_message;
+pub use server_message::*;
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    error::Error,
+    marker::PhantomPinned,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use juniper::{
+    futures::{
+        channel::oneshot,
+        future::{self, BoxFuture, Either, Future, FutureExt, TryFutureExt},
+        stream::{self, BoxStream, SelectAll, StreamExt},
+        task::{Context, Poll, Waker},
+        Sink, Stream,
+    },
+    GraphQLError, RuleError,
+};
+
+use crate::{
+    outgoing::{OutgoingQueue, POLL_BUDGET},
+    BufferLimits, ConnectionConfig, DataPayload, DisconnectReason, ErrorPayload, IncomingMessage,
+    Init, Schema, StartPayload,
+};
+
+/// Close code sent when the client sends an invalid message.
+pub const CLOSE_CODE_BAD_REQUEST: u16 = 4400;
+
+/// Close code sent when the client subscribes before its connection has been acknowledged.
+pub const CLOSE_CODE_UNAUTHORIZED: u16 = 4401;
+
+/// Close code sent when the client's initialization parameters are rejected.
+pub const CLOSE_CODE_FORBIDDEN: u16 = 4403;
+
+/// Close code sent when the client doesn't send a ConnectionInit message in time.
+pub const CLOSE_CODE_INIT_TIMEOUT: u16 = 4408;
+
+/// Close code sent when the client subscribes with an id of an in-flight operation.
+pub const CLOSE_CODE_SUBSCRIBER_ALREADY_EXISTS: u16 = 4409;
+
+/// Close code sent when the client sends more than one ConnectionInit message.
+pub const CLOSE_CODE_TOO_MANY_INIT_REQUESTS: u16 = 4429;
+
+/// Close code sent when the client doesn't read messages fast enough, and the
+/// [`OverflowPolicy::Close`] is used.
+///
+/// [`OverflowPolicy::Close`]: crate::OverflowPolicy::Close
+pub const CLOSE_CODE_SLOW_CONSUMER: u16 = 1008;
+
+/// Close code sent when the client sends a message exceeding the
+/// [`ConnectionConfig::with_max_incoming_message_size()`] limit.
+pub const CLOSE_CODE_MESSAGE_TOO_BIG: u16 = 1009;
+
+struct ExecutionParams<S: Schema> {
+    subscribe_payload: StartPayload<S::ScalarValue>,
+    config: Arc<ConnectionConfig<S::Context, S::ScalarValue>>,
+    schema: S,
+}
+
+enum Reaction<S: Schema> {
+    ServerMessage(ServerMessage<S::ScalarValue>),
+    Close { code: u16, message: String },
+}
+
+impl<S: Schema> Reaction<S> {
+    /// Converts the reaction into a one-item stream.
+    fn into_stream(self) -> BoxStream<'static, Self> {
+        stream::once(future::ready(self)).boxed()
+    }
+
+    fn close(code: u16, message: impl Into<String>) -> Self {
+        Self::Close {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+enum ConnectionState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    /// PreInit is the state before a ConnectionInit message has been accepted.
+    PreInit { init: I, schema: S },
+    /// Active is the state after a ConnectionInit message has been accepted.
+    Active {
+        config: Arc<ConnectionConfig<S::Context, S::ScalarValue>>,
+        stoppers: HashMap<String, oneshot::Sender<()>>,
+        schema: S,
+    },
+    /// Terminated is the state after the connection has been closed.
+    Terminated,
+}
+
+impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
+    /// Returns the configuration of the connection, once it has been accepted.
+    fn config(&self) -> Option<&Arc<ConnectionConfig<S::Context, S::ScalarValue>>> {
+        match self {
+            Self::Active { config, .. } => Some(config),
+            Self::PreInit { .. } | Self::Terminated => None,
+        }
+    }
+
+    // Each message we receive results in a stream of zero or more reactions. For example, a
+    // Ping message results in a one-item stream with the Pong message.
+    async fn handle_message(
+        self,
+        msg: ClientMessage<S::ScalarValue>,
+    ) -> (Self, BoxStream<'static, Reaction<S>>) {
+        match msg {
+            ClientMessage::Ping { payload } => {
+                let pong = Reaction::ServerMessage(ServerMessage::Pong).into_stream();
+                return match self {
+                    // The client sends new connection parameters mid-session.
+                    Self::Active {
+                        config,
+                        mut stoppers,
+                        schema,
+                    } if !payload.is_empty() => {
+                        let refreshed = config.refresh_context(payload, &mut stoppers).await;
+                        match refreshed {
+                            Ok(config) => (
+                                Self::Active {
+                                    config,
+                                    stoppers,
+                                    schema,
+                                },
+                                pong,
+                            ),
+                            Err(e) => (
+                                Self::Terminated,
+                                Reaction::close(CLOSE_CODE_FORBIDDEN, e.to_string()).into_stream(),
+                            ),
+                        }
+                    }
+                    state => (state, pong),
+                };
+            }
+            ClientMessage::Pong { .. } => return (self, stream::empty().boxed()),
+            _ => {}
+        }
+
+        match self {
+            Self::PreInit { init, schema } => match msg {
+                ClientMessage::ConnectionInit { payload } => match init.init(payload).await {
+                    Ok(config) => {
+                        let keep_alive_interval = config.keep_alive_interval;
+
+                        let mut s =
+                            Reaction::ServerMessage(ServerMessage::ConnectionAck).into_stream();
+
+                        if keep_alive_interval > Duration::from_secs(0) {
+                            s = s
+                                .chain(stream::unfold((), move |_| async move {
+                                    tokio::time::sleep(keep_alive_interval).await;
+                                    Some((Reaction::ServerMessage(ServerMessage::Pong), ()))
+                                }))
+                                .boxed();
+                        }
+
+                        (
+                            Self::Active {
+                                config: Arc::new(config),
+                                stoppers: HashMap::new(),
+                                schema,
+                            },
+                            s,
+                        )
+                    }
+                    Err(e) => (
+                        Self::Terminated,
+                        Reaction::close(CLOSE_CODE_FORBIDDEN, e.to_string()).into_stream(),
+                    ),
+                },
+                ClientMessage::Subscribe { .. } => (
+                    Self::Terminated,
+                    Reaction::close(CLOSE_CODE_UNAUTHORIZED, "Unauthorized").into_stream(),
+                ),
+                _ => (Self::PreInit { init, schema }, stream::empty().boxed()),
+            },
+            Self::Active {
+                config,
+                mut stoppers,
+                schema,
+            } => {
+                let reactions = match msg {
+                    ClientMessage::ConnectionInit { .. } => {
+                        return (
+                            Self::Terminated,
+                            Reaction::close(
+                                CLOSE_CODE_TOO_MANY_INIT_REQUESTS,
+                                "Too many initialisation requests",
+                            )
+                            .into_stream(),
+                        );
+                    }
+                    ClientMessage::Subscribe { id, payload } => {
+                        // Go ahead and prune finished or canceled stoppers before checking the id.
+                        stoppers.retain(|_, tx| !tx.is_canceled());
+
+                        if stoppers.contains_key(&id) {
+                            // We already have an operation with this id, which is a protocol
+                            // violation.
+                            return (
+                                Self::Terminated,
+                                Reaction::close(
+                                    CLOSE_CODE_SUBSCRIBER_ALREADY_EXISTS,
+                                    format!("Subscriber for {id} already exists"),
+                                )
+                                .into_stream(),
+                            );
+                        }
+
+                        if config.max_in_flight_operations > 0
+                            && stoppers.len() >= config.max_in_flight_operations
+                        {
+                            // Too many in-flight operations. Just send back a validation error.
+                            Reaction::ServerMessage(ServerMessage::Error {
+                                id,
+                                payload: GraphQLError::ValidationError(vec![RuleError::new(
+                                    "Too many in-flight operations.",
+                                    &[],
+                                )])
+                                .into(),
+                            })
+                            .into_stream()
+                        } else {
+                            let started = config.start_operation(&id, &payload).await;
+                            match started {
+                                Err(e) => {
+                                    // The operation is rejected, so send back the error.
+                                    Reaction::ServerMessage(ServerMessage::Error {
+                                        id,
+                                        payload: GraphQLError::ValidationError(vec![
+                                            RuleError::new(&e.to_string(), &[]),
+                                        ])
+                                        .into(),
+                                    })
+                                    .into_stream()
+                                }
+                                Ok(guard) => {
+                                    // Create a channel that we can use to cancel the operation.
+                                    let (tx, rx) = oneshot::channel::<()>();
+                                    stoppers.insert(id.clone(), tx);
+
+                                    // Create the operation stream. This stream will emit Next and
+                                    // Error messages, and Complete once the operation is finished.
+                                    let s = Self::start(
+                                        id.clone(),
+                                        ExecutionParams {
+                                            subscribe_payload: payload,
+                                            config: config.clone(),
+                                            schema: schema.clone(),
+                                        },
+                                    )
+                                    .into_stream()
+                                    .flatten();
+
+                                    // Combine this with our oneshot channel so that the stream ends
+                                    // if the oneshot is ever fired. The client doesn't expect a
+                                    // Complete message if it has stopped the operation itself
+                                    // (dropping the sender), but does if the server has (sending
+                                    // through it). The guard is dropped along with the stream,
+                                    // notifying the lifecycle hooks.
+                                    stream::unfold(
+                                        Some((rx, s.boxed(), guard, id)),
+                                        |state| async move {
+                                            let (rx, mut s, guard, id) = state?;
+                                            let (r, rx) = match future::select(rx, s.next()).await {
+                                                Either::Left((Ok(()), _)) => {
+                                                    let complete = ServerMessage::Complete { id };
+                                                    return Some((
+                                                        Reaction::ServerMessage(complete),
+                                                        None,
+                                                    ));
+                                                }
+                                                Either::Left((Err(_), _)) => return None,
+                                                Either::Right((r, rx)) => (r?, rx),
+                                            };
+                                            // Release the stopper as soon as the operation is finished,
+                                            // so its id may be reused right after the client receives
+                                            // the final message.
+                                            let is_finished = matches!(
+                                                r,
+                                                Reaction::ServerMessage(
+                                                    ServerMessage::Complete { .. }
+                                                        | ServerMessage::Error { .. }
+                                                ),
+                                            );
+                                            Some((r, (!is_finished).then(|| (rx, s, guard, id))))
+                                        },
+                                    )
+                                    .boxed()
+                                }
+                            }
+                        }
+                    }
+                    ClientMessage::Complete { id } => {
+                        stoppers.remove(&id);
+                        stream::empty().boxed()
+                    }
+                    ClientMessage::Ping { .. } | ClientMessage::Pong { .. } => {
+                        stream::empty().boxed()
+                    }
+                };
+                (
+                    Self::Active {
+                        config,
+                        stoppers,
+                        schema,
+                    },
+                    reactions,
+                )
+            }
+            Self::Terminated => (self, stream::empty().boxed()),
+        }
+    }
+
+    async fn start(id: String, params: ExecutionParams<S>) -> BoxStream<'static, Reaction<S>> {
+        let params = Arc::new(params);
+
+        // Live queries last until stopped, so are served the same way as subscriptions.
+        let is_live =
+            crate::is_live_query(&params.subscribe_payload, &params.schema, &params.config);
+        if is_live {
+            return SubscriptionStart::new(id, params, true).boxed();
+        }
+
+        // Try to execute this as a query or mutation.
+        match juniper::execute(
+            &params.subscribe_payload.query,
+            params.subscribe_payload.operation_name.as_deref(),
+            params.schema.root_node(),
+            &params.subscribe_payload.variables,
+            &params.config.context,
+        )
+        .await
+        {
+            Ok((data, errors)) => {
+                return stream::iter([
+                    Reaction::ServerMessage(ServerMessage::Next {
+                        id: id.clone(),
+                        payload: DataPayload { data, errors },
+                    }),
+                    Reaction::ServerMessage(ServerMessage::Complete { id }),
+                ])
+                .boxed();
+            }
+            Err(GraphQLError::IsSubscription) => {}
+            Err(e) => {
+                return Reaction::ServerMessage(ServerMessage::Error {
+                    id,
+                    payload: ErrorPayload::new(Box::new(params.clone()), e),
+                })
+                .into_stream();
+            }
+        }
+
+        // Try to execute as a subscription.
+        SubscriptionStart::new(id, params.clone(), false).boxed()
+    }
+}
+
+/// SubscriptionStartState is the state for a subscription operation.
+enum SubscriptionStartState<S: Schema> {
+    /// Init is the start before being polled for the first time.
+    Init { id: String, is_live: bool },
+    /// ResolvingIntoStream is the state after being polled for the first time. In this state,
+    /// we're parsing, validating, and getting the actual event stream.
+    ResolvingIntoStream {
+        id: String,
+        future: BoxFuture<
+            'static,
+            Result<juniper_subscriptions::Connection<'static, S::ScalarValue>, GraphQLError>,
+        >,
+    },
+    /// Streaming is the state after we've successfully obtained the event stream for the
+    /// subscription. In this state, we're just forwarding events back to the client.
+    Streaming {
+        id: String,
+        stream: juniper_subscriptions::Connection<'static, S::ScalarValue>,
+    },
+    /// Terminated is the state once we're all done.
+    Terminated,
+}
+
+/// SubscriptionStart is the stream for a subscription operation.
+struct SubscriptionStart<S: Schema> {
+    params: Arc<ExecutionParams<S>>,
+    state: SubscriptionStartState<S>,
+    _marker: PhantomPinned,
+}
+
+impl<S: Schema> SubscriptionStart<S> {
+    fn new(id: String, params: Arc<ExecutionParams<S>>, is_live: bool) -> Pin<Box<Self>> {
+        Box::pin(Self {
+            params,
+            state: SubscriptionStartState::Init { id, is_live },
+            _marker: PhantomPinned,
+        })
+    }
+}
+
+impl<S: Schema> Stream for SubscriptionStart<S> {
+    type Item = Reaction<S>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let (params, state) = unsafe {
+            // XXX: The execution parameters are referenced by state and must not be modified.
+            // Modifying state is fine though.
+            let inner = self.get_unchecked_mut();
+            (&inner.params, &mut inner.state)
+        };
+
+        loop {
+            match state {
+                SubscriptionStartState::Init { id, is_live } => {
+                    // XXX: resolve_into_stream returns a Future that references the execution
+                    // parameters, and the returned stream also references them. We can guarantee
+                    // that everything has the same lifetime in this self-referential struct.
+                    let params = Arc::as_ptr(params);
+                    let future = if *is_live {
+                        unsafe {
+                            crate::execute_live_query(
+                                &(*params).subscribe_payload,
+                                &(*params).schema,
+                                &(*params).config,
+                            )
+                        }
+                    } else {
+                        unsafe {
+                            juniper::resolve_into_stream(
+                                &(*params).subscribe_payload.query,
+                                (*params).subscribe_payload.operation_name.as_deref(),
+                                (*params).schema.root_node(),
+                                &(*params).subscribe_payload.variables,
+                                &(*params).config.context,
+                            )
+                        }
+                        .map_ok(|(stream, errors)| {
+                            juniper_subscriptions::Connection::from_stream(stream, errors)
+                        })
+                        .boxed()
+                    };
+                    *state = SubscriptionStartState::ResolvingIntoStream {
+                        id: id.clone(),
+                        future,
+                    };
+                }
+                SubscriptionStartState::ResolvingIntoStream {
+                    ref id,
+                    ref mut future,
+                } => match future.as_mut().poll(cx) {
+                    Poll::Ready(r) => match r {
+                        Ok(stream) => {
+                            *state = SubscriptionStartState::Streaming {
+                                id: id.clone(),
+                                stream,
+                            }
+                        }
+                        Err(e) => {
+                            let id = id.clone();
+                            *state = SubscriptionStartState::Terminated;
+                            return Poll::Ready(Some(Reaction::ServerMessage(
+                                ServerMessage::Error {
+                                    id,
+                                    payload: ErrorPayload::new(Box::new(params.clone()), e),
+                                },
+                            )));
+                        }
+                    },
+                    Poll::Pending => return Poll::Pending,
+                },
+                SubscriptionStartState::Streaming {
+                    ref id,
+                    ref mut stream,
+                } => match Pin::new(stream).poll_next(cx) {
+                    Poll::Ready(Some(output)) => {
+                        return Poll::Ready(Some(Reaction::ServerMessage(ServerMessage::Next {
+                            id: id.clone(),
+                            payload: DataPayload {
+                                data: output.data,
+                                errors: output.errors,
+                            },
+                        })));
+                    }
+                    Poll::Ready(None) => {
+                        let id = id.clone();
+                        *state = SubscriptionStartState::Terminated;
+                        return Poll::Ready(Some(Reaction::ServerMessage(
+                            ServerMessage::Complete { id },
+                        )));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                SubscriptionStartState::Terminated => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+enum ConnectionSinkState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    Ready {
+        state: ConnectionState<S, I>,
+    },
+    HandlingMessage {
+        #[allow(clippy::type_complexity)]
+        result: BoxFuture<'static, (ConnectionState<S, I>, BoxStream<'static, Reaction<S>>)>,
+    },
+    Closed,
+}
+
+/// Implements the graphql-transport-ws protocol. This is a sink for `TryInto<ClientMessage>` and a
+/// stream of `Output`.
+pub struct Connection<S: Schema, I: Init<S::ScalarValue, S::Context>> {
+    reactions: SelectAll<BoxStream<'static, Reaction<S>>>,
+    outgoing: OutgoingQueue<Reaction<S>>,
+    stream_waker: Option<Waker>,
+    sink_state: ConnectionSinkState<S, I>,
+    init_timeout: Option<Duration>,
+    is_initialized: Arc<AtomicBool>,
+    is_closed: bool,
+    buffer_limits: BufferLimits,
+    max_incoming_message_size: usize,
+    config: Option<Arc<ConnectionConfig<S::Context, S::ScalarValue>>>,
+    disconnect_reason: Option<DisconnectReason>,
+}
+
+impl<S, I> Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    /// Creates a new connection, which is a sink for `TryInto<ClientMessage>` and a stream of
+    /// `Output`.
+    ///
+    /// The `schema` argument should typically be an `Arc<RootNode<...>>`.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections. This can be a `ConnectionConfig` if the context and configuration are already
+    /// known, or it can be a closure that gets executed asynchronously when the client sends the
+    /// ConnectionInit message. Using a closure allows you to perform authentication based on the
+    /// parameters provided by the client.
+    pub fn new(schema: S, init: I) -> Self {
+        Self {
+            reactions: SelectAll::new(),
+            outgoing: OutgoingQueue::default(),
+            stream_waker: None,
+            sink_state: ConnectionSinkState::Ready {
+                state: ConnectionState::PreInit { init, schema },
+            },
+            init_timeout: Some(Duration::from_secs(15)),
+            is_initialized: Arc::new(AtomicBool::new(false)),
+            is_closed: false,
+            buffer_limits: BufferLimits::default(),
+            max_incoming_message_size: 0,
+            config: None,
+            disconnect_reason: None,
+        }
+    }
+
+    /// Specifies the time the client has to send a ConnectionInit message after the connection has
+    /// been established, before it's closed. Specifying a zero duration will disable the timeout.
+    /// By default, the client has 15 seconds.
+    #[must_use]
+    pub fn with_init_timeout(mut self, timeout: Duration) -> Self {
+        self.init_timeout = Some(timeout);
+        self
+    }
+
+    fn push_reactions(&mut self, reactions: BoxStream<'static, Reaction<S>>) {
+        self.reactions.push(reactions);
+        if let Some(waker) = self.stream_waker.take() {
+            // Wake up the stream so it can emit new reactions.
+            waker.wake();
+        }
+    }
+
+    /// Moves ready reactions into the outgoing queue. Unless any buffer limits are configured, only
+    /// a single reaction is ever queued, so operations are polled only as fast as the client reads.
+    fn buffer_reactions(&mut self, cx: &mut Context) {
+        for _ in 0..POLL_BUDGET {
+            if self.reactions.is_empty()
+                || !(self.buffer_limits.is_bounded() || self.outgoing.is_empty())
+            {
+                return;
+            }
+            match Pin::new(&mut self.reactions).poll_next(cx) {
+                Poll::Ready(Some(reaction)) => {
+                    let operation = match &reaction {
+                        Reaction::ServerMessage(ServerMessage::Next { id, .. }) => Some(id.clone()),
+                        _ => None,
+                    };
+                    if self
+                        .outgoing
+                        .push(operation, reaction, &self.buffer_limits)
+                        .is_err()
+                    {
+                        // The client is too slow, so drop everything and close the connection.
+                        self.outgoing.clear();
+                        self.reactions = SelectAll::new();
+                        self.reactions.push(
+                            Reaction::close(CLOSE_CODE_SLOW_CONSUMER, "Too many buffered messages")
+                                .into_stream(),
+                        );
+                    }
+                }
+                Poll::Ready(None) => {
+                    // In rare cases, the reaction stream may terminate. For example, this will
+                    // happen if the first message we receive does not require any reaction. Just
+                    // recreate it in that case.
+                    self.reactions = SelectAll::new();
+                    return;
+                }
+                Poll::Pending => return,
+            }
+        }
+        // The budget is exhausted, so continue on the next poll.
+        cx.waker().wake_by_ref();
+    }
+}
+
+impl<S, I, T> Sink<T> for Connection<S, I>
+where
+    T: TryInto<ClientMessage<S::ScalarValue>> + IncomingMessage,
+    T::Error: Error,
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context> + Send,
+{
+    type Error = Infallible;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        match &mut self.sink_state {
+            ConnectionSinkState::Ready { .. } => Poll::Ready(Ok(())),
+            ConnectionSinkState::HandlingMessage { ref mut result } => {
+                match Pin::new(result).poll(cx) {
+                    Poll::Ready((state, reactions)) => {
+                        if let Some(config) = state.config() {
+                            self.buffer_limits = config.buffer_limits;
+                            self.max_incoming_message_size = config.max_incoming_message_size;
+                            self.config = Some(config.clone());
+                        }
+                        self.push_reactions(reactions);
+                        self.sink_state = ConnectionSinkState::Ready { state };
+                        Poll::Ready(Ok(()))
+                    }
+                    Poll::Pending => Poll::Pending,
+                }
+            }
+            ConnectionSinkState::Closed => panic!("poll_ready called after close"),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        let s = self.get_mut();
+        let max_size = s.max_incoming_message_size;
+        s.sink_state = match std::mem::replace(&mut s.sink_state, ConnectionSinkState::Closed) {
+            ConnectionSinkState::Ready { .. }
+                if max_size > 0 && item.size().map_or(false, |size| size > max_size) =>
+            {
+                // Don't even try to parse the message, just close the connection.
+                s.push_reactions(
+                    Reaction::close(
+                        CLOSE_CODE_MESSAGE_TOO_BIG,
+                        format!("Message exceeds {max_size} bytes"),
+                    )
+                    .into_stream(),
+                );
+                ConnectionSinkState::Ready {
+                    state: ConnectionState::Terminated,
+                }
+            }
+            ConnectionSinkState::Ready { state } => {
+                match item.try_into() {
+                    Ok(msg) => {
+                        if let ClientMessage::ConnectionInit { .. } = msg {
+                            s.is_initialized.store(true, Ordering::Relaxed);
+                        }
+                        ConnectionSinkState::HandlingMessage {
+                            result: state.handle_message(msg).boxed(),
+                        }
+                    }
+                    Err(e) => {
+                        // If we weren't able to parse the message, close the connection.
+                        s.push_reactions(
+                            Reaction::close(CLOSE_CODE_BAD_REQUEST, e.to_string()).into_stream(),
+                        );
+                        ConnectionSinkState::Ready {
+                            state: ConnectionState::Terminated,
+                        }
+                    }
+                }
+            }
+            _ => panic!("start_send called when not ready"),
+        };
+        Ok(())
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let res = <Self as Sink<T>>::poll_ready(self.as_mut(), cx);
+
+        // Keep buffering results of operations even while the client doesn't read them, so the
+        // overflow policy is applied here rather than by the operations' sources.
+        if self.buffer_limits.is_bounded() && !self.is_closed {
+            self.buffer_reactions(cx);
+            if !self.outgoing.is_empty() {
+                if let Some(waker) = self.stream_waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+
+        res
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.sink_state = ConnectionSinkState::Closed;
+        self.disconnect_reason
+            .get_or_insert(DisconnectReason::Client);
+        if let Some(waker) = self.stream_waker.take() {
+            // Wake up the stream so it can close too.
+            waker.wake();
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S, I> Drop for Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    fn drop(&mut self) {
+        // Finish all the operations first, so their hooks run before the disconnection one.
+        self.reactions = SelectAll::new();
+        self.outgoing.clear();
+
+        if let Some(config) = &self.config {
+            let reason = self
+                .disconnect_reason
+                .take()
+                .unwrap_or(DisconnectReason::Dropped);
+            config.disconnect(&reason);
+        }
+    }
+}
+
+impl<S, I> Stream for Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    type Item = Output<S::ScalarValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.stream_waker = Some(cx.waker().clone());
+
+        if self.is_closed {
+            return Poll::Ready(None);
+        }
+        if let ConnectionSinkState::Closed = self.sink_state {
+            return Poll::Ready(None);
+        }
+
+        // Start the init timeout once the connection is polled for the first time.
+        if let Some(timeout) = self.init_timeout.take() {
+            if timeout > Duration::from_secs(0) {
+                let is_initialized = self.is_initialized.clone();
+                self.reactions.push(
+                    stream::once(tokio::time::sleep(timeout))
+                        .filter_map(move |_| {
+                            future::ready((!is_initialized.load(Ordering::Relaxed)).then(|| {
+                                Reaction::close(
+                                    CLOSE_CODE_INIT_TIMEOUT,
+                                    "Connection initialisation timeout",
+                                )
+                            }))
+                        })
+                        .boxed(),
+                );
+            }
+        }
+
+        // Poll the reactions for new outgoing messages.
+        self.buffer_reactions(cx);
+        match self.outgoing.pop() {
+            Some(Reaction::ServerMessage(msg)) => Poll::Ready(Some(Output::Message(msg))),
+            Some(Reaction::Close { code, message }) => {
+                self.is_closed = true;
+                self.disconnect_reason
+                    .get_or_insert_with(|| DisconnectReason::Server {
+                        code: Some(code),
+                        message: message.clone(),
+                    });
+                Poll::Ready(Some(Output::Close { code, message }))
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{convert::Infallible, io, sync::atomic::AtomicI32};
+
+    use juniper::{
+        futures::sink::SinkExt, graphql_input_value, graphql_object, graphql_subscription,
+        graphql_value, graphql_vars, DefaultScalarValue, EmptyMutation, Executor, FieldError,
+        FieldResult, RootNode, ScalarValue, Variables,
+    };
+    use juniper_subscriptions::Invalidator;
+
+    use crate::{ContextRefresh, Lifecycle, LifecycleError, OverflowPolicy};
+
+    use super::*;
+
+    struct Context(i32);
+
+    impl juniper::Context for Context {}
+
+    /// COUNTER is the value resolved by `Query.counter`.
+    static COUNTER: AtomicI32 = AtomicI32::new(0);
+
+    struct Query;
+
+    #[graphql_object(context = Context)]
+    impl Query {
+        /// context just resolves to the current context.
+        async fn context(context: &Context) -> i32 {
+            context.0
+        }
+
+        /// counter resolves to the current `COUNTER`, tracking the `counter` invalidation key.
+        fn counter<S: ScalarValue>(executor: &Executor<Context, S>) -> i32 {
+            executor.track_invalidation_key("counter");
+            COUNTER.load(Ordering::SeqCst)
+        }
+    }
+
+    struct Subscription;
+
+    #[graphql_subscription(context = Context)]
+    impl Subscription {
+        /// never never emits anything.
+        async fn never(_context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            tokio::time::sleep(Duration::from_secs(10000))
+                .map(|_| unreachable!())
+                .into_stream()
+                .boxed()
+        }
+
+        /// context emits the current context once, then never emits anything else.
+        async fn context(context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            stream::once(future::ready(Ok(context.0)))
+                .chain(
+                    tokio::time::sleep(Duration::from_secs(10000))
+                        .map(|_| unreachable!())
+                        .into_stream(),
+                )
+                .boxed()
+        }
+
+        /// once emits the current context once, then completes.
+        async fn once(context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            stream::once(future::ready(Ok(context.0))).boxed()
+        }
+
+        /// count emits the numbers from 1 up to the provided one, then completes.
+        async fn count(to: i32) -> BoxStream<'static, FieldResult<i32>> {
+            stream::iter((1..=to).map(Ok)).boxed()
+        }
+
+        /// error emits an error once, then never emits anything else.
+        async fn error(_context: &Context) -> BoxStream<'static, FieldResult<i32>> {
+            stream::once(future::ready(Err(FieldError::new(
+                "field error",
+                graphql_value!(null),
+            ))))
+            .chain(
+                tokio::time::sleep(Duration::from_secs(10000))
+                    .map(|_| unreachable!())
+                    .into_stream(),
+            )
+            .boxed()
+        }
+    }
+
+    type ClientMessage = super::ClientMessage<DefaultScalarValue>;
+    type ServerMessage = super::ServerMessage<DefaultScalarValue>;
+    type Output = super::Output<DefaultScalarValue>;
+
+    fn new_test_schema() -> Arc<RootNode<'static, Query, EmptyMutation<Context>, Subscription>> {
+        Arc::new(RootNode::new(Query, EmptyMutation::new(), Subscription))
+    }
+
+    fn subscribe(id: &str, query: &str) -> ClientMessage {
+        ClientMessage::Subscribe {
+            id: id.into(),
+            payload: StartPayload {
+                query: query.into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        }
+    }
+
+    fn next(id: &str, data: juniper::Value) -> Output {
+        Output::Message(ServerMessage::Next {
+            id: id.into(),
+            payload: DataPayload {
+                data,
+                errors: vec![],
+            },
+        })
+    }
+
+    fn complete(id: &str) -> Output {
+        Output::Message(ServerMessage::Complete { id: id.into() })
+    }
+
+    fn close(code: u16, message: &str) -> Output {
+        Output::Close {
+            code,
+            message: message.into(),
+        }
+    }
+
+    const ACK: Output = Output::Message(ServerMessage::ConnectionAck);
+
+    async fn initialized_connection() -> Connection<
+        Arc<RootNode<'static, Query, EmptyMutation<Context>, Subscription>>,
+        ConnectionConfig<Context>,
+    > {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_secs(0)),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        conn
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(subscribe("foo", "{context}")).await.unwrap();
+
+        assert_eq!(
+            next("foo", graphql_value!({"context": 1})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("foo"), conn.next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(subscribe("foo", "subscription Foo {context}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next("foo", graphql_value!({"context": 1})),
+            conn.next().await.unwrap(),
+        );
+
+        conn.send(subscribe("bar", "subscription Bar {once}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next("bar", graphql_value!({"once": 1})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("bar"), conn.next().await.unwrap());
+
+        // Once completed, the id may be reused.
+        conn.send(subscribe("bar", "{context}")).await.unwrap();
+
+        assert_eq!(
+            next("bar", graphql_value!({"context": 1})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("bar"), conn.next().await.unwrap());
+
+        // Completing by the client stops the operation silently.
+        conn.send(ClientMessage::Complete { id: "foo".into() })
+            .await
+            .unwrap();
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ping_before_init() {
+        let mut conn = Connection::new(new_test_schema(), ConnectionConfig::new(Context(1)));
+
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_init_params_ok() {
+        let mut conn = Connection::new(new_test_schema(), |params: Variables| async move {
+            assert_eq!(params.get("foo"), Some(&graphql_input_value!("bar")));
+            Ok(ConnectionConfig::new(Context(1))) as Result<_, Infallible>
+        });
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {"foo": "bar"},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_init_params_error() {
+        let mut conn = Connection::new(new_test_schema(), |params: Variables| async move {
+            assert_eq!(params.get("foo"), Some(&graphql_input_value!("bar")));
+            Err(io::Error::new(io::ErrorKind::Other, "init error"))
+        });
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {"foo": "bar"},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            close(CLOSE_CODE_FORBIDDEN, "init error"),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(None, conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_subscribe() {
+        let mut conn = Connection::new(new_test_schema(), ConnectionConfig::new(Context(1)));
+
+        conn.send(subscribe("foo", "{context}")).await.unwrap();
+
+        assert_eq!(
+            close(CLOSE_CODE_UNAUTHORIZED, "Unauthorized"),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_too_many_init_requests() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            close(
+                CLOSE_CODE_TOO_MANY_INIT_REQUESTS,
+                "Too many initialisation requests",
+            ),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_already_exists() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            close(
+                CLOSE_CODE_SUBSCRIBER_ALREADY_EXISTS,
+                "Subscriber for foo already exists",
+            ),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(None, conn.next().await);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_message() {
+        let mut conn = Connection::new(new_test_schema(), ConnectionConfig::new(Context(1)));
+
+        struct Invalid;
+
+        impl IncomingMessage for Invalid {}
+
+        impl TryFrom<Invalid> for ClientMessage {
+            type Error = io::Error;
+
+            fn try_from(_: Invalid) -> Result<Self, Self::Error> {
+                Err(io::Error::new(io::ErrorKind::Other, "invalid message"))
+            }
+        }
+
+        conn.send(Invalid).await.unwrap();
+
+        assert_eq!(
+            close(CLOSE_CODE_BAD_REQUEST, "invalid message"),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_init_timeout() {
+        let mut conn = Connection::new(new_test_schema(), ConnectionConfig::new(Context(1)))
+            .with_init_timeout(Duration::from_millis(20));
+
+        assert_eq!(
+            close(CLOSE_CODE_INIT_TIMEOUT, "Connection initialisation timeout"),
+            conn.next().await.unwrap(),
+        );
+
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_millis(50)),
+        )
+        .with_init_timeout(Duration::from_millis(20));
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_operations() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_max_in_flight_operations(1),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        conn.send(subscribe("bar", "subscription Bar {never}"))
+            .await
+            .unwrap();
+
+        match conn.next().await.unwrap() {
+            Output::Message(ServerMessage::Error { id, .. }) => {
+                assert_eq!(id, "bar");
+            }
+            msg @ _ => panic!("expected error, got: {msg:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_error() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(subscribe("foo", "asd")).await.unwrap();
+        conn.send(subscribe("bar", "subscription Bar {unknown}"))
+            .await
+            .unwrap();
+
+        for id in ["foo", "bar"] {
+            match conn.next().await.unwrap() {
+                Output::Message(ServerMessage::Error { id: actual, .. }) => {
+                    assert_eq!(actual, id);
+                }
+                msg @ _ => panic!("expected error, got: {msg:?}"),
+            }
+        }
+
+        // No Complete messages are sent after errors.
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_introspection_restricted() {
+        let schema = RootNode::new(Query, EmptyMutation::new(), Subscription)
+            .restrict_introspection(|ctx: &Context| ctx.0 > 1);
+        let mut conn = Connection::new(
+            Arc::new(schema),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_secs(0)),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        conn.send(subscribe("foo", "{__schema {queryType {name}}}"))
+            .await
+            .unwrap();
+        conn.send(subscribe("bar", r#"{__type(name: "Query") {name}}"#))
+            .await
+            .unwrap();
+
+        for (id, field) in [("foo", "__schema"), ("bar", "__type")] {
+            match conn.next().await.unwrap() {
+                Output::Message(ServerMessage::Error {
+                    id: actual,
+                    payload,
+                }) => {
+                    assert_eq!(actual, id);
+                    match payload.graphql_error() {
+                        GraphQLError::ValidationError(errors) => {
+                            assert_eq!(errors.len(), 1, "{errors:?}");
+                            assert_eq!(
+                                errors[0].message(),
+                                format!(
+                                    "GraphQL introspection is not allowed, \
+                                     but the query contained \"{field}\"",
+                                ),
+                            );
+                        }
+                        e => panic!("expected validation error, got: {e:?}"),
+                    }
+                }
+                msg @ _ => panic!("expected error, got: {msg:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keep_alives() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_millis(20)),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        for _ in 0..10 {
+            assert_eq!(
+                Output::Message(ServerMessage::Pong),
+                conn.next().await.unwrap(),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_field_error() {
+        let mut conn = initialized_connection().await;
+
+        conn.send(subscribe("foo", "subscription Foo {error}"))
+            .await
+            .unwrap();
+
+        match conn.next().await.unwrap() {
+            Output::Message(ServerMessage::Next {
+                id,
+                payload: DataPayload { data, errors },
+            }) => {
+                assert_eq!(id, "foo");
+                assert_eq!(data, graphql_value!({ "error": null }));
+                assert_eq!(errors.len(), 1);
+            }
+            msg @ _ => panic!("expected next, got: {msg:?}"),
+        }
+    }
+
+    async fn buffered_connection(
+        config: ConnectionConfig<Context>,
+    ) -> Connection<
+        Arc<RootNode<'static, Query, EmptyMutation<Context>, Subscription>>,
+        ConnectionConfig<Context>,
+    > {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            config.with_keep_alive_interval(Duration::from_secs(0)),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        // Flushing without reading lets the connection buffer all the results at once, as if the
+        // client was too slow to read them.
+        conn.send(subscribe("foo", "subscription Foo {count(to: 5)}"))
+            .await
+            .unwrap();
+        SinkExt::<ClientMessage>::flush(&mut conn).await.unwrap();
+
+        conn
+    }
+
+    #[tokio::test]
+    async fn test_overflow_drop_oldest() {
+        let mut conn = buffered_connection(
+            ConnectionConfig::new(Context(1)).with_max_buffered_messages_per_operation(2),
+        )
+        .await;
+
+        assert_eq!(
+            next("foo", graphql_value!({"count": 4})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(
+            next("foo", graphql_value!({"count": 5})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("foo"), conn.next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_overflow_coalesce_to_latest() {
+        let mut conn = buffered_connection(
+            ConnectionConfig::new(Context(1))
+                .with_max_buffered_messages(3)
+                .with_overflow_policy(OverflowPolicy::CoalesceToLatest),
+        )
+        .await;
+
+        assert_eq!(
+            next("foo", graphql_value!({"count": 4})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(
+            next("foo", graphql_value!({"count": 5})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("foo"), conn.next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_overflow_close() {
+        let mut conn = buffered_connection(
+            ConnectionConfig::new(Context(1))
+                .with_max_buffered_messages(3)
+                .with_overflow_policy(OverflowPolicy::Close),
+        )
+        .await;
+
+        assert_eq!(
+            close(CLOSE_CODE_SLOW_CONSUMER, "Too many buffered messages"),
+            conn.next().await.unwrap(),
+        );
+        assert!(conn.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_incoming_message_size() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_max_incoming_message_size(32),
+        );
+
+        struct Raw(&'static str);
+
+        impl IncomingMessage for Raw {
+            fn size(&self) -> Option<usize> {
+                Some(self.0.len())
+            }
+        }
+
+        impl TryFrom<Raw> for ClientMessage {
+            type Error = serde_json::Error;
+
+            fn try_from(raw: Raw) -> Result<Self, Self::Error> {
+                serde_json::from_str(raw.0)
+            }
+        }
+
+        // The limit is enforced only once the connection is initialized.
+        conn.send(Raw(
+            r#"{"type": "connection_init", "payload": {"padding": "padding"}}"#,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        conn.send(Raw(r#"{"type": "ping"}"#)).await.unwrap();
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+
+        conn.send(Raw(
+            r#"{"type": "ping", "payload": {"padding": "padding"}}"#,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            close(CLOSE_CODE_MESSAGE_TOO_BIG, "Message exceeds 32 bytes"),
+            conn.next().await.unwrap(),
+        );
+    }
+
+    /// Lifecycle recording all the events, rejecting operations with the `denied` id, and
+    /// refreshing the context with the `ctx` parameter (stopping all the running operations if
+    /// the `stop` one is present).
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Recorder {
+        fn events(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn record(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    impl Lifecycle<Context> for Recorder {
+        fn on_operation_start<'a>(
+            &'a self,
+            context: &'a Context,
+            id: &'a str,
+            _: &'a StartPayload<DefaultScalarValue>,
+        ) -> BoxFuture<'a, Result<(), LifecycleError>> {
+            self.record(format!("start {id} {}", context.0));
+            let res = if id == "denied" {
+                Err("Access denied".into())
+            } else {
+                Ok(())
+            };
+            future::ready(res).boxed()
+        }
+
+        fn on_operation_complete(&self, context: &Context, id: &str) {
+            self.record(format!("complete {id} {}", context.0));
+        }
+
+        fn on_disconnect(&self, context: &Context, reason: &DisconnectReason) {
+            self.record(format!("disconnect {reason:?} {}", context.0));
+        }
+
+        fn refresh_context<'a>(
+            &'a self,
+            _: &'a Context,
+            params: Variables,
+            operations: &'a [String],
+        ) -> BoxFuture<'a, Result<ContextRefresh<Context>, LifecycleError>> {
+            let res = match params.get("ctx").and_then(|v| v.as_int_value()) {
+                Some(ctx) if params.contains_key("stop") => {
+                    Ok(ContextRefresh::replace(Context(ctx)).stop_operations(operations))
+                }
+                Some(ctx) => Ok(ContextRefresh::replace(Context(ctx))),
+                None => Err("Session revoked".into()),
+            };
+            future::ready(res).boxed()
+        }
+    }
+
+    async fn connection_with_lifecycle(
+        recorder: &Recorder,
+    ) -> Connection<
+        Arc<RootNode<'static, Query, EmptyMutation<Context>, Subscription>>,
+        ConnectionConfig<Context>,
+    > {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_lifecycle(recorder.clone()),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        conn
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        let recorder = Recorder::default();
+        let mut conn = connection_with_lifecycle(&recorder).await;
+
+        conn.send(subscribe("denied", "{context}")).await.unwrap();
+
+        assert_eq!(
+            Output::Message(ServerMessage::Error {
+                id: "denied".into(),
+                payload: GraphQLError::ValidationError(vec![RuleError::new("Access denied", &[])])
+                    .into(),
+            }),
+            conn.next().await.unwrap(),
+        );
+
+        conn.send(subscribe("foo", "subscription Foo {context}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next("foo", graphql_value!({"context": 1})),
+            conn.next().await.unwrap(),
+        );
+
+        // A Ping message with a payload refreshes the context of the operations started
+        // afterwards.
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {"ctx": 2},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            Output::Message(ServerMessage::Pong),
+            conn.next().await.unwrap(),
+        );
+
+        conn.send(subscribe("bar", "{context}")).await.unwrap();
+
+        assert_eq!(
+            next("bar", graphql_value!({"context": 2})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("bar"), conn.next().await.unwrap());
+
+        // Running operations keep their context, unless stopped on refresh.
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {"ctx": 3, "stop": true},
+        })
+        .await
+        .unwrap();
+
+        let outputs = vec![conn.next().await.unwrap(), conn.next().await.unwrap()];
+        assert!(
+            outputs.contains(&Output::Message(ServerMessage::Pong)),
+            "{outputs:?}",
+        );
+        assert!(outputs.contains(&complete("foo")), "{outputs:?}");
+
+        SinkExt::<ClientMessage>::close(&mut conn).await.unwrap();
+        drop(conn);
+
+        assert_eq!(
+            recorder.events(),
+            [
+                "start denied 1",
+                "start foo 1",
+                "start bar 2",
+                "complete bar 2",
+                "complete foo 1",
+                "disconnect Client 3",
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_refresh_error() {
+        let recorder = Recorder::default();
+        let mut conn = connection_with_lifecycle(&recorder).await;
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        conn.send(ClientMessage::Ping {
+            payload: graphql_vars! {"revoke": true},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            close(CLOSE_CODE_FORBIDDEN, "Session revoked"),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(None, conn.next().await);
+
+        drop(conn);
+
+        assert_eq!(
+            recorder.events(),
+            [
+                "start foo 1",
+                "complete foo 1",
+                "disconnect Server { code: Some(4403), message: \"Session revoked\" } 1",
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_dropped() {
+        let recorder = Recorder::default();
+        let mut conn = connection_with_lifecycle(&recorder).await;
+
+        conn.send(subscribe("foo", "subscription Foo {never}"))
+            .await
+            .unwrap();
+        SinkExt::<ClientMessage>::flush(&mut conn).await.unwrap();
+        drop(conn);
+
+        assert_eq!(
+            recorder.events(),
+            ["start foo 1", "complete foo 1", "disconnect Dropped 1"],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_queries() {
+        let invalidator = Invalidator::new();
+        let mut conn = Connection::new(
+            Arc::new(
+                RootNode::new(Query, EmptyMutation::new(), Subscription).enable_live_queries(),
+            ),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_live_queries(invalidator.clone()),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ACK, conn.next().await.unwrap());
+
+        COUNTER.store(1, Ordering::SeqCst);
+        conn.send(subscribe("foo", "query @live {counter}"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            next("foo", graphql_value!({"counter": 1})),
+            conn.next().await.unwrap(),
+        );
+
+        COUNTER.store(2, Ordering::SeqCst);
+        assert_eq!(invalidator.invalidate("counter"), 1);
+
+        assert_eq!(
+            next("foo", graphql_value!({"counter": 2})),
+            conn.next().await.unwrap(),
+        );
+
+        // Queries without `@live` are executed once.
+        conn.send(subscribe("bar", "{counter}")).await.unwrap();
+
+        assert_eq!(
+            next("bar", graphql_value!({"counter": 2})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("bar"), conn.next().await.unwrap());
+
+        // Completing by the client stops watching the live query.
+        conn.send(ClientMessage::Complete { id: "foo".into() })
+            .await
+            .unwrap();
+        conn.send(subscribe("bar", "{context}")).await.unwrap();
+
+        assert_eq!(
+            next("bar", graphql_value!({"context": 1})),
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(complete("bar"), conn.next().await.unwrap());
+        assert_eq!(invalidator.watchers_count(), 0);
+    }
+}
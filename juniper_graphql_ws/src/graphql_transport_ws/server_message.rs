@@ -0,0 +1,114 @@
+use serde::Serialize;
+
+use crate::{DataPayload, ErrorPayload};
+
+/// ServerMessage defines the message types that servers can send.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum ServerMessage<S> {
+    /// ConnectionAck is sent in response to a client's ConnectionInit message if the server accepted a
+    /// connection.
+    ConnectionAck,
+    /// Ping is used for detecting failed connections, displaying latency metrics or other types of
+    /// network probing.
+    Ping,
+    /// Pong is sent in response to a client's Ping message, and periodically as a keep-alive after
+    /// accepting a connection.
+    Pong,
+    /// Next contains the result of a query, mutation, or subscription event.
+    Next {
+        /// The id of the operation that the data is for.
+        id: String,
+
+        /// The data and errors that occurred during execution.
+        payload: DataPayload<S>,
+    },
+    /// Error contains an error that occurs before execution, such as validation errors. No more
+    /// data will be sent for the given operation after it.
+    Error {
+        /// The id of the operation that triggered this error.
+        id: String,
+
+        /// The error(s).
+        payload: ErrorPayload,
+    },
+    /// Complete indicates that no more data will be sent for the given operation.
+    Complete {
+        /// The id of the operation that has completed.
+        id: String,
+    },
+}
+
+/// Output of a [`Connection`], being either a [`ServerMessage`] to send, or a request to close the
+/// WebSocket connection.
+///
+/// [`Connection`]: super::Connection
+#[derive(Debug, PartialEq)]
+pub enum Output<S> {
+    /// Message to be sent to the client.
+    Message(ServerMessage<S>),
+
+    /// Request to close the WebSocket connection with the provided close `code` and `message`. No
+    /// more outputs will be produced after it.
+    Close {
+        /// WebSocket close code defined by the protocol (4400, 4401, etc.).
+        code: u16,
+
+        /// Reason of closing the connection.
+        message: String,
+    },
+}
+
+#[cfg(test)]
+mod test {
+    use juniper::{graphql_value, DefaultScalarValue, GraphQLError};
+
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        type ServerMessage = super::ServerMessage<DefaultScalarValue>;
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::ConnectionAck).unwrap(),
+            r##"{"type":"connection_ack"}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Ping).unwrap(),
+            r##"{"type":"ping"}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Pong).unwrap(),
+            r##"{"type":"pong"}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Next {
+                id: "foo".into(),
+                payload: DataPayload {
+                    data: graphql_value!(null),
+                    errors: vec![],
+                },
+            })
+            .unwrap(),
+            r##"{"type":"next","id":"foo","payload":{"data":null}}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Error {
+                id: "foo".into(),
+                payload: GraphQLError::UnknownOperationName.into(),
+            })
+            .unwrap(),
+            r##"{"type":"error","id":"foo","payload":[{"message":"Unknown operation"}]}"##,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&ServerMessage::Complete { id: "foo".into() }).unwrap(),
+            r##"{"type":"complete","id":"foo"}"##,
+        );
+    }
+}
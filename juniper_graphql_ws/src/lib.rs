@@ -10,6 +10,15 @@ pub use server_message::*;
 mod schema;
 pub use schema::*;
 
+mod protocol;
+pub use protocol::Protocol;
+
+mod lifecycle;
+pub use lifecycle::{ContextRefresh, DisconnectReason, Lifecycle, LifecycleError};
+
+pub mod graphql_transport_ws;
+
+mod outgoing;
 mod utils;
 
 use std::{
@@ -25,30 +34,41 @@ use juniper::{
         task::{Context, Poll, Waker},
         Sink, Stream,
     },
-    GraphQLError, RuleError, ScalarValue, Variables,
+    DefaultScalarValue, GraphQLError, RuleError, ScalarValue, Variables,
 };
+use juniper_subscriptions::Invalidator;
+
+use self::outgoing::{OutgoingQueue, POLL_BUDGET};
 
 struct ExecutionParams<S: Schema> {
     start_payload: StartPayload<S::ScalarValue>,
-    config: Arc<ConnectionConfig<S::Context>>,
+    config: Arc<ConnectionConfig<S::Context, S::ScalarValue>>,
     schema: S,
 }
 
 /// ConnectionConfig is used to configure the connection once the client sends the ConnectionInit
 /// message.
-pub struct ConnectionConfig<CtxT> {
+pub struct ConnectionConfig<CtxT, S = DefaultScalarValue> {
     context: CtxT,
     max_in_flight_operations: usize,
     keep_alive_interval: Duration,
+    max_incoming_message_size: usize,
+    buffer_limits: BufferLimits,
+    lifecycle: Option<Arc<dyn Lifecycle<CtxT, S>>>,
+    invalidator: Option<Invalidator>,
 }
 
-impl<CtxT> ConnectionConfig<CtxT> {
+impl<CtxT, S> ConnectionConfig<CtxT, S> {
     /// Constructs the configuration required for a connection to be accepted.
     pub fn new(context: CtxT) -> Self {
         Self {
             context,
             max_in_flight_operations: 0,
             keep_alive_interval: Duration::from_secs(15),
+            max_incoming_message_size: 0,
+            buffer_limits: BufferLimits::default(),
+            lifecycle: None,
+            invalidator: None,
         }
     }
 
@@ -68,9 +88,108 @@ impl<CtxT> ConnectionConfig<CtxT> {
         self.keep_alive_interval = interval;
         self
     }
+
+    /// Specifies the maximum size (in bytes) of an incoming message, as reported by its
+    /// [`IncomingMessage::size()`]. Larger messages are rejected without being parsed. By default,
+    /// there is no limit to the size of incoming messages.
+    #[must_use]
+    pub fn with_max_incoming_message_size(mut self, bytes: usize) -> Self {
+        self.max_incoming_message_size = bytes;
+        self
+    }
+
+    /// Specifies the maximum number of results of a single operation that may be buffered while the
+    /// client doesn't read them. Exceeding it triggers the [`OverflowPolicy`]. By default, there is
+    /// no limit to buffered results of an operation.
+    #[must_use]
+    pub fn with_max_buffered_messages_per_operation(mut self, max: usize) -> Self {
+        self.buffer_limits.per_operation = max;
+        self
+    }
+
+    /// Specifies the maximum number of results of all operations that may be buffered while the
+    /// client doesn't read them. Exceeding it triggers the [`OverflowPolicy`]. By default, there is
+    /// no limit to buffered results of a connection.
+    #[must_use]
+    pub fn with_max_buffered_messages(mut self, max: usize) -> Self {
+        self.buffer_limits.total = max;
+        self
+    }
+
+    /// Specifies what to do once the client falls behind and the buffered results exceed the
+    /// limits. By default, the oldest results are dropped.
+    #[must_use]
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.buffer_limits.policy = policy;
+        self
+    }
+
+    /// Specifies the [`Lifecycle`] hooks to run on the connection's operations and disconnection.
+    /// By default, there are no hooks.
+    #[must_use]
+    pub fn with_lifecycle(mut self, lifecycle: impl Lifecycle<CtxT, S>) -> Self {
+        self.lifecycle = Some(Arc::new(lifecycle));
+        self
+    }
+
+    /// Serves [live queries][0] as long-running operations, re-executing them and sending the new
+    /// results once their keys are invalidated via the provided [`Invalidator`]. By default, live
+    /// queries are executed once, as regular queries.
+    ///
+    /// Live queries should be [enabled][1] in the schema too.
+    ///
+    /// [0]: juniper_subscriptions::live
+    /// [1]: juniper::RootNode::enable_live_queries
+    #[must_use]
+    pub fn with_live_queries(mut self, invalidator: Invalidator) -> Self {
+        self.invalidator = Some(invalidator);
+        self
+    }
+}
+
+/// OverflowPolicy defines how a connection handles results of operations exceeding its buffer
+/// limits, which happens once the client doesn't read them fast enough.
+///
+/// Only results of operations (`data` and `next` messages) are ever dropped, while other
+/// messages are always delivered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// Drops the oldest buffered result of the overflowed operation (or of the whole connection,
+    /// if its limit is exceeded).
+    #[default]
+    DropOldest,
+
+    /// Drops all the buffered results of the overflowed operation, so only the latest one is
+    /// delivered.
+    CoalesceToLatest,
+
+    /// Closes the connection with an error.
+    Close,
+}
+
+/// Limits of the outgoing messages buffered by a connection.
+#[derive(Clone, Copy, Debug, Default)]
+struct BufferLimits {
+    /// Maximum number of buffered results of a single operation, or zero if unlimited.
+    per_operation: usize,
+
+    /// Maximum number of buffered results of all operations, or zero if unlimited.
+    total: usize,
+
+    /// What to do once any of the limits is exceeded.
+    policy: OverflowPolicy,
+}
+
+impl BufferLimits {
+    /// Indicates whether any of these limits is set, so the outgoing messages should be buffered.
+    fn is_bounded(&self) -> bool {
+        self.per_operation > 0 || self.total > 0
+    }
 }
 
-impl<S: ScalarValue, CtxT: Unpin + Send + 'static> Init<S, CtxT> for ConnectionConfig<CtxT> {
+impl<S: ScalarValue + 'static, CtxT: Unpin + Send + 'static> Init<S, CtxT>
+    for ConnectionConfig<CtxT, S>
+{
     type Error = Infallible;
     type Future = future::Ready<Result<Self, Self::Error>>;
 
@@ -81,7 +200,7 @@ impl<S: ScalarValue, CtxT: Unpin + Send + 'static> Init<S, CtxT> for ConnectionC
 
 enum Reaction<S: Schema> {
     ServerMessage(ServerMessage<S::ScalarValue>),
-    EndStream,
+    EndStream(DisconnectReason),
 }
 
 impl<S: Schema> Reaction<S> {
@@ -89,6 +208,23 @@ impl<S: Schema> Reaction<S> {
     fn into_stream(self) -> BoxStream<'static, Self> {
         stream::once(future::ready(self)).boxed()
     }
+
+    /// Returns a stream sending the provided error `message` to the client, and closing the
+    /// connection afterwards.
+    fn close(message: String) -> BoxStream<'static, Self> {
+        stream::iter([
+            Self::ServerMessage(ServerMessage::ConnectionError {
+                payload: ConnectionErrorPayload {
+                    message: message.clone(),
+                },
+            }),
+            Self::EndStream(DisconnectReason::Server {
+                code: None,
+                message,
+            }),
+        ])
+        .boxed()
+    }
 }
 
 /// Init defines the requirements for types that can provide connection configurations when
@@ -100,7 +236,7 @@ pub trait Init<S: ScalarValue, CtxT>: Unpin + 'static {
     type Error: Error;
 
     /// The future configuration type.
-    type Future: Future<Output = Result<ConnectionConfig<CtxT>, Self::Error>> + Send + 'static;
+    type Future: Future<Output = Result<ConnectionConfig<CtxT, S>, Self::Error>> + Send + 'static;
 
     /// Returns a future for the configuration to use.
     fn init(self, params: Variables<S>) -> Self::Future;
@@ -110,7 +246,7 @@ impl<F, S, CtxT, Fut, E> Init<S, CtxT> for F
 where
     S: ScalarValue,
     F: FnOnce(Variables<S>) -> Fut + Unpin + 'static,
-    Fut: Future<Output = Result<ConnectionConfig<CtxT>, E>> + Send + 'static,
+    Fut: Future<Output = Result<ConnectionConfig<CtxT, S>, E>> + Send + 'static,
     E: Error,
 {
     type Error = E;
@@ -126,7 +262,7 @@ enum ConnectionState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
     PreInit { init: I, schema: S },
     /// Active is the state after a ConnectionInit message has been accepted.
     Active {
-        config: Arc<ConnectionConfig<S::Context>>,
+        config: Arc<ConnectionConfig<S::Context, S::ScalarValue>>,
         stoppers: HashMap<String, oneshot::Sender<()>>,
         schema: S,
     },
@@ -135,6 +271,14 @@ enum ConnectionState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
 }
 
 impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
+    /// Returns the configuration of the connection, once it has been accepted.
+    fn config(&self) -> Option<&Arc<ConnectionConfig<S::Context, S::ScalarValue>>> {
+        match self {
+            Self::Active { config, .. } => Some(config),
+            Self::PreInit { .. } | Self::Terminated => None,
+        }
+    }
+
     // Each message we receive results in a stream of zero or more reactions. For example, a
     // ConnectionTerminate message results in a one-item stream with the EndStream reaction.
     async fn handle_message(
@@ -142,7 +286,10 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
         msg: ClientMessage<S::ScalarValue>,
     ) -> (Self, BoxStream<'static, Reaction<S>>) {
         if let ClientMessage::ConnectionTerminate = msg {
-            return (self, Reaction::EndStream.into_stream());
+            return (
+                self,
+                Reaction::EndStream(DisconnectReason::Client).into_stream(),
+            );
         }
 
         match self {
@@ -183,27 +330,24 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
                             s,
                         )
                     }
-                    Err(e) => (
-                        Self::Terminated,
-                        stream::iter(vec![
-                            Reaction::ServerMessage(ServerMessage::ConnectionError {
-                                payload: ConnectionErrorPayload {
-                                    message: e.to_string(),
-                                },
-                            }),
-                            Reaction::EndStream,
-                        ])
-                        .boxed(),
-                    ),
+                    Err(e) => (Self::Terminated, Reaction::close(e.to_string())),
                 },
                 _ => (Self::PreInit { init, schema }, stream::empty().boxed()),
             },
             Self::Active {
-                config,
+                mut config,
                 mut stoppers,
                 schema,
             } => {
                 let reactions = match msg {
+                    ClientMessage::ConnectionInit { payload } => {
+                        // The client sends new connection parameters mid-session.
+                        match config.refresh_context(payload, &mut stoppers).await {
+                            Ok(refreshed) => config = refreshed,
+                            Err(e) => return (Self::Terminated, Reaction::close(e.to_string())),
+                        }
+                        stream::empty().boxed()
+                    }
                     ClientMessage::Start { id, payload } => {
                         if stoppers.contains_key(&id) {
                             // We already have an operation with this id, so we can't start a new
@@ -229,40 +373,65 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
                                 ])
                                 .boxed()
                             } else {
-                                // Create a channel that we can use to cancel the operation.
-                                let (tx, rx) = oneshot::channel::<()>();
-                                stoppers.insert(id.clone(), tx);
-
-                                // Create the operation stream. This stream will emit Data and Error
-                                // messages, but will not emit Complete – that part is up to us.
-                                let s = Self::start(
-                                    id.clone(),
-                                    ExecutionParams {
-                                        start_payload: payload,
-                                        config: config.clone(),
-                                        schema: schema.clone(),
-                                    },
-                                )
-                                .into_stream()
-                                .flatten();
-
-                                // Combine this with our oneshot channel so that the stream ends if the
-                                // oneshot is ever fired.
-                                let s = stream::unfold((rx, s.boxed()), |(rx, mut s)| async move {
-                                    let next = match future::select(rx, s.next()).await {
-                                        Either::Left(_) => None,
-                                        Either::Right((r, rx)) => r.map(|r| (r, rx)),
-                                    };
-                                    next.map(|(r, rx)| (r, (rx, s)))
-                                });
-
-                                // Once the stream ends, send the Complete message.
-                                let s = s.chain(
-                                    Reaction::ServerMessage(ServerMessage::Complete { id })
-                                        .into_stream(),
-                                );
-
-                                s.boxed()
+                                let started = config.start_operation(&id, &payload).await;
+                                match started {
+                                    Err(e) => {
+                                        // The operation is rejected, so send back the error.
+                                        stream::iter(vec![
+                                            Reaction::ServerMessage(ServerMessage::Error {
+                                                id: id.clone(),
+                                                payload: GraphQLError::ValidationError(vec![
+                                                    RuleError::new(&e.to_string(), &[]),
+                                                ])
+                                                .into(),
+                                            }),
+                                            Reaction::ServerMessage(ServerMessage::Complete { id }),
+                                        ])
+                                        .boxed()
+                                    }
+                                    Ok(guard) => {
+                                        // Create a channel that we can use to cancel the operation.
+                                        let (tx, rx) = oneshot::channel::<()>();
+                                        stoppers.insert(id.clone(), tx);
+
+                                        // Create the operation stream. This stream will emit Data
+                                        // and Error messages, but will not emit Complete – that
+                                        // part is up to us.
+                                        let s = Self::start(
+                                            id.clone(),
+                                            ExecutionParams {
+                                                start_payload: payload,
+                                                config: config.clone(),
+                                                schema: schema.clone(),
+                                            },
+                                        )
+                                        .into_stream()
+                                        .flatten();
+
+                                        // Combine this with our oneshot channel so that the stream
+                                        // ends if the oneshot is ever fired. The guard is dropped
+                                        // along with the stream, notifying the lifecycle hooks.
+                                        let s = stream::unfold(
+                                            (rx, s.boxed(), guard),
+                                            |(rx, mut s, guard)| async move {
+                                                let next = match future::select(rx, s.next()).await
+                                                {
+                                                    Either::Left(_) => None,
+                                                    Either::Right((r, rx)) => r.map(|r| (r, rx)),
+                                                };
+                                                next.map(|(r, rx)| (r, (rx, s, guard)))
+                                            },
+                                        );
+
+                                        // Once the stream ends, send the Complete message.
+                                        let s = s.chain(
+                                            Reaction::ServerMessage(ServerMessage::Complete { id })
+                                                .into_stream(),
+                                        );
+
+                                        s.boxed()
+                                    }
+                                }
                             }
                         }
                     }
@@ -293,6 +462,12 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
 
         let params = Arc::new(params);
 
+        // Live queries last until stopped, so are served the same way as subscriptions.
+        let is_live = crate::is_live_query(&params.start_payload, &params.schema, &params.config);
+        if is_live {
+            return SubscriptionStart::new(id, params, true).boxed();
+        }
+
         // Try to execute this as a query or mutation.
         match juniper::execute(
             &params.start_payload.query,
@@ -321,7 +496,7 @@ impl<S: Schema, I: Init<S::ScalarValue, S::Context>> ConnectionState<S, I> {
         }
 
         // Try to execute as a subscription.
-        SubscriptionStart::new(id, params.clone()).boxed()
+        SubscriptionStart::new(id, params.clone(), false).boxed()
     }
 }
 
@@ -342,10 +517,44 @@ impl<S: Stream + Unpin> Stream for InterruptableStream<S> {
     }
 }
 
+/// Indicates whether the provided `payload` is a live query to be served as a long-running
+/// operation with the given `config`.
+pub(crate) fn is_live_query<S: Schema>(
+    payload: &StartPayload<S::ScalarValue>,
+    schema: &S,
+    config: &ConnectionConfig<S::Context, S::ScalarValue>,
+) -> bool {
+    config.invalidator.is_some()
+        && schema
+            .root_node()
+            .is_live_query(&payload.query, payload.operation_name.as_deref())
+}
+
+/// Executes the provided live query `payload`, re-executing it on invalidation via the
+/// [`Invalidator`] of the given `config`.
+pub(crate) fn execute_live_query<'a, S: Schema>(
+    payload: &StartPayload<S::ScalarValue>,
+    schema: &'a S,
+    config: &'a ConnectionConfig<S::Context, S::ScalarValue>,
+) -> BoxFuture<'a, Result<juniper_subscriptions::Connection<'a, S::ScalarValue>, GraphQLError>> {
+    let invalidator = config
+        .invalidator
+        .as_ref()
+        .expect("live queries are served only with `Invalidator`");
+    juniper_subscriptions::live::execute_live(
+        payload.to_request(),
+        schema.root_node(),
+        &config.context,
+        invalidator,
+    )
+    .map_ok(Into::into)
+    .boxed()
+}
+
 /// SubscriptionStartState is the state for a subscription operation.
 enum SubscriptionStartState<S: Schema> {
     /// Init is the start before being polled for the first time.
-    Init { id: String },
+    Init { id: String, is_live: bool },
     /// ResolvingIntoStream is the state after being polled for the first time. In this state,
     /// we're parsing, validating, and getting the actual event stream.
     ResolvingIntoStream {
@@ -373,10 +582,10 @@ struct SubscriptionStart<S: Schema> {
 }
 
 impl<S: Schema> SubscriptionStart<S> {
-    fn new(id: String, params: Arc<ExecutionParams<S>>) -> Pin<Box<Self>> {
+    fn new(id: String, params: Arc<ExecutionParams<S>>, is_live: bool) -> Pin<Box<Self>> {
         Box::pin(Self {
             params,
-            state: SubscriptionStartState::Init { id },
+            state: SubscriptionStartState::Init { id, is_live },
             _marker: PhantomPinned,
         })
     }
@@ -395,14 +604,21 @@ impl<S: Schema> Stream for SubscriptionStart<S> {
 
         loop {
             match state {
-                SubscriptionStartState::Init { id } => {
+                SubscriptionStartState::Init { id, is_live } => {
                     // XXX: resolve_into_stream returns a Future that references the execution
                     // parameters, and the returned stream also references them. We can guarantee
                     // that everything has the same lifetime in this self-referential struct.
                     let params = Arc::as_ptr(params);
-                    *state = SubscriptionStartState::ResolvingIntoStream {
-                        id: id.clone(),
-                        future: unsafe {
+                    let future = if *is_live {
+                        unsafe {
+                            crate::execute_live_query(
+                                &(*params).start_payload,
+                                &(*params).schema,
+                                &(*params).config,
+                            )
+                        }
+                    } else {
+                        unsafe {
                             juniper::resolve_into_stream(
                                 &(*params).start_payload.query,
                                 (*params).start_payload.operation_name.as_deref(),
@@ -414,7 +630,11 @@ impl<S: Schema> Stream for SubscriptionStart<S> {
                         .map_ok(|(stream, errors)| {
                             juniper_subscriptions::Connection::from_stream(stream, errors)
                         })
-                        .boxed(),
+                        .boxed()
+                    };
+                    *state = SubscriptionStartState::ResolvingIntoStream {
+                        id: id.clone(),
+                        future,
                     };
                 }
                 SubscriptionStartState::ResolvingIntoStream {
@@ -475,12 +695,19 @@ enum ConnectionSinkState<S: Schema, I: Init<S::ScalarValue, S::Context>> {
     Closed,
 }
 
-/// Implements the graphql-ws protocol. This is a sink for `TryInto<ClientMessage>` and a stream of
-/// `ServerMessage`.
+/// Implements the legacy graphql-ws protocol. This is a sink for `TryInto<ClientMessage>` and a
+/// stream of `ServerMessage`.
+///
+/// See [`graphql_transport_ws::Connection`] for the graphql-transport-ws protocol implementation.
 pub struct Connection<S: Schema, I: Init<S::ScalarValue, S::Context>> {
     reactions: SelectAll<BoxStream<'static, Reaction<S>>>,
+    outgoing: OutgoingQueue<Reaction<S>>,
     stream_waker: Option<Waker>,
     sink_state: ConnectionSinkState<S, I>,
+    buffer_limits: BufferLimits,
+    max_incoming_message_size: usize,
+    config: Option<Arc<ConnectionConfig<S::Context, S::ScalarValue>>>,
+    disconnect_reason: Option<DisconnectReason>,
 }
 
 impl<S, I> Connection<S, I>
@@ -500,17 +727,63 @@ where
     pub fn new(schema: S, init: I) -> Self {
         Self {
             reactions: SelectAll::new(),
+            outgoing: OutgoingQueue::default(),
             stream_waker: None,
             sink_state: ConnectionSinkState::Ready {
                 state: ConnectionState::PreInit { init, schema },
             },
+            buffer_limits: BufferLimits::default(),
+            max_incoming_message_size: 0,
+            config: None,
+            disconnect_reason: None,
         }
     }
+
+    /// Moves ready reactions into the outgoing queue. Unless any buffer limits are configured, only
+    /// a single reaction is ever queued, so operations are polled only as fast as the client reads.
+    fn buffer_reactions(&mut self, cx: &mut Context) {
+        for _ in 0..POLL_BUDGET {
+            if self.reactions.is_empty()
+                || !(self.buffer_limits.is_bounded() || self.outgoing.is_empty())
+            {
+                return;
+            }
+            match Pin::new(&mut self.reactions).poll_next(cx) {
+                Poll::Ready(Some(reaction)) => {
+                    let operation = match &reaction {
+                        Reaction::ServerMessage(ServerMessage::Data { id, .. }) => Some(id.clone()),
+                        _ => None,
+                    };
+                    if self
+                        .outgoing
+                        .push(operation, reaction, &self.buffer_limits)
+                        .is_err()
+                    {
+                        // The client is too slow, so drop everything and close the connection.
+                        self.outgoing.clear();
+                        self.reactions = SelectAll::new();
+                        self.reactions
+                            .push(Reaction::close("Too many buffered messages".into()));
+                    }
+                }
+                Poll::Ready(None) => {
+                    // In rare cases, the reaction stream may terminate. For example, this will
+                    // happen if the first message we receive does not require any reaction. Just
+                    // recreate it in that case.
+                    self.reactions = SelectAll::new();
+                    return;
+                }
+                Poll::Pending => return,
+            }
+        }
+        // The budget is exhausted, so continue on the next poll.
+        cx.waker().wake_by_ref();
+    }
 }
 
 impl<S, I, T> Sink<T> for Connection<S, I>
 where
-    T: TryInto<ClientMessage<S::ScalarValue>>,
+    T: TryInto<ClientMessage<S::ScalarValue>> + IncomingMessage,
     T::Error: Error,
     S: Schema,
     I: Init<S::ScalarValue, S::Context> + Send,
@@ -523,6 +796,11 @@ where
             ConnectionSinkState::HandlingMessage { ref mut result } => {
                 match Pin::new(result).poll(cx) {
                     Poll::Ready((state, reactions)) => {
+                        if let Some(config) = state.config() {
+                            self.buffer_limits = config.buffer_limits;
+                            self.max_incoming_message_size = config.max_incoming_message_size;
+                            self.config = Some(config.clone());
+                        }
                         self.reactions.push(reactions);
                         self.sink_state = ConnectionSinkState::Ready { state };
                         Poll::Ready(Ok(()))
@@ -536,8 +814,23 @@ where
 
     fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
         let s = self.get_mut();
+        let max_size = s.max_incoming_message_size;
         let state = &mut s.sink_state;
         *state = match std::mem::replace(state, ConnectionSinkState::Closed) {
+            ConnectionSinkState::Ready { state }
+                if max_size > 0 && item.size().map_or(false, |size| size > max_size) =>
+            {
+                // Don't even try to parse the message, just send back an error.
+                s.reactions.push(
+                    Reaction::ServerMessage(ServerMessage::ConnectionError {
+                        payload: ConnectionErrorPayload {
+                            message: format!("Message exceeds {max_size} bytes"),
+                        },
+                    })
+                    .into_stream(),
+                );
+                ConnectionSinkState::Ready { state }
+            }
             ConnectionSinkState::Ready { state } => {
                 match item.try_into() {
                     Ok(msg) => ConnectionSinkState::HandlingMessage {
@@ -562,12 +855,27 @@ where
         Ok(())
     }
 
-    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        <Self as Sink<T>>::poll_ready(self, cx)
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let res = <Self as Sink<T>>::poll_ready(self.as_mut(), cx);
+
+        // Keep buffering results of operations even while the client doesn't read them, so the
+        // overflow policy is applied here rather than by the operations' sources.
+        if self.buffer_limits.is_bounded() {
+            self.buffer_reactions(cx);
+            if !self.outgoing.is_empty() {
+                if let Some(waker) = self.stream_waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+
+        res
     }
 
     fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
         self.sink_state = ConnectionSinkState::Closed;
+        self.disconnect_reason
+            .get_or_insert(DisconnectReason::Client);
         if let Some(waker) = self.stream_waker.take() {
             // Wake up the stream so it can close too.
             waker.wake();
@@ -576,6 +884,26 @@ where
     }
 }
 
+impl<S, I> Drop for Connection<S, I>
+where
+    S: Schema,
+    I: Init<S::ScalarValue, S::Context>,
+{
+    fn drop(&mut self) {
+        // Finish all the operations first, so their hooks run before the disconnection one.
+        self.reactions = SelectAll::new();
+        self.outgoing.clear();
+
+        if let Some(config) = &self.config {
+            let reason = self
+                .disconnect_reason
+                .take()
+                .unwrap_or(DisconnectReason::Dropped);
+            config.disconnect(&reason);
+        }
+    }
+}
+
 impl<S, I> Stream for Connection<S, I>
 where
     S: Schema,
@@ -591,34 +919,31 @@ where
         }
 
         // Poll the reactions for new outgoing messages.
-        if !self.reactions.is_empty() {
-            match Pin::new(&mut self.reactions).poll_next(cx) {
-                Poll::Ready(Some(reaction)) => match reaction {
-                    Reaction::ServerMessage(msg) => return Poll::Ready(Some(msg)),
-                    Reaction::EndStream => return Poll::Ready(None),
-                },
-                Poll::Ready(None) => {
-                    // In rare cases, the reaction stream may terminate. For example, this will
-                    // happen if the first message we receive does not require any reaction. Just
-                    // recreate it in that case.
-                    self.reactions = SelectAll::new();
-                }
-                _ => (),
+        self.buffer_reactions(cx);
+        match self.outgoing.pop() {
+            Some(Reaction::ServerMessage(msg)) => Poll::Ready(Some(msg)),
+            Some(Reaction::EndStream(reason)) => {
+                self.disconnect_reason.get_or_insert(reason);
+                Poll::Ready(None)
             }
+            None => Poll::Pending,
         }
-        Poll::Pending
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{convert::Infallible, io};
+    use std::{
+        convert::Infallible,
+        io,
+        sync::atomic::{AtomicI32, Ordering},
+    };
 
     use juniper::{
         futures::sink::SinkExt,
         graphql_input_value, graphql_object, graphql_subscription, graphql_value, graphql_vars,
         parser::{ParseError, Spanning},
-        DefaultScalarValue, EmptyMutation, FieldError, FieldResult, RootNode,
+        DefaultScalarValue, EmptyMutation, Executor, FieldError, FieldResult, RootNode,
     };
 
     use super::*;
@@ -627,6 +952,9 @@ mod test {
 
     impl juniper::Context for Context {}
 
+    /// COUNTER is the value resolved by `Query.counter`.
+    static COUNTER: AtomicI32 = AtomicI32::new(0);
+
     struct Query;
 
     #[graphql_object(context = Context)]
@@ -635,6 +963,12 @@ mod test {
         async fn context(context: &Context) -> i32 {
             context.0
         }
+
+        /// counter resolves to the current `COUNTER`, tracking the `counter` invalidation key.
+        fn counter<S: ScalarValue>(executor: &Executor<Context, S>) -> i32 {
+            executor.track_invalidation_key("counter");
+            COUNTER.load(Ordering::SeqCst)
+        }
     }
 
     struct Subscription;
@@ -660,6 +994,11 @@ mod test {
                 .boxed()
         }
 
+        /// count emits the numbers from 1 up to the provided one, then completes.
+        async fn count(to: i32) -> BoxStream<'static, FieldResult<i32>> {
+            stream::iter((1..=to).map(Ok)).boxed()
+        }
+
         /// error emits an error once, then never emits anything else.
         async fn error(_context: &Context) -> BoxStream<'static, FieldResult<i32>> {
             stream::once(future::ready(Err(FieldError::new(
@@ -921,6 +1260,53 @@ mod test {
         }
     }
 
+    #[tokio::test]
+    async fn test_introspection_disabled() {
+        let schema =
+            RootNode::new(Query, EmptyMutation::new(), Subscription).disable_introspection();
+        let mut conn = Connection::new(
+            Arc::new(schema),
+            ConnectionConfig::new(Context(1)).with_keep_alive_interval(Duration::from_secs(0)),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        conn.send(ClientMessage::Start {
+            id: "foo".into(),
+            payload: StartPayload {
+                query: "{__schema {queryType {name}}}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        match conn.next().await.unwrap() {
+            ServerMessage::Error { id, payload } => {
+                assert_eq!(id, "foo");
+                match payload.graphql_error() {
+                    GraphQLError::ValidationError(errors) => {
+                        assert_eq!(errors.len(), 1, "{errors:?}");
+                        assert_eq!(
+                            errors[0].message(),
+                            "GraphQL introspection is not allowed, \
+                             but the query contained \"__schema\"",
+                        );
+                    }
+                    e => panic!("expected validation error, got: {e:?}"),
+                }
+            }
+            msg @ _ => panic!("expected error, got: {msg:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn test_keep_alives() {
         let mut conn = Connection::new(
@@ -1021,4 +1407,424 @@ mod test {
             msg @ _ => panic!("expected data, got: {msg:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn test_overflow_close() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_max_buffered_messages_per_operation(2)
+                .with_overflow_policy(OverflowPolicy::Close),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        conn.send(ClientMessage::Start {
+            id: "foo".into(),
+            payload: StartPayload {
+                query: "subscription Foo {count(to: 5)}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+        // Flushing without reading lets the connection buffer all the results at once, as if the
+        // client was too slow to read them.
+        SinkExt::<ClientMessage>::flush(&mut conn).await.unwrap();
+
+        assert_eq!(
+            ServerMessage::ConnectionError {
+                payload: ConnectionErrorPayload {
+                    message: "Too many buffered messages".into(),
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+        assert!(conn.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_incoming_message_size() {
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_max_incoming_message_size(72),
+        );
+
+        struct Raw(&'static str);
+
+        impl IncomingMessage for Raw {
+            fn size(&self) -> Option<usize> {
+                Some(self.0.len())
+            }
+        }
+
+        impl TryFrom<Raw> for ClientMessage {
+            type Error = serde_json::Error;
+
+            fn try_from(raw: Raw) -> Result<Self, Self::Error> {
+                serde_json::from_str(raw.0)
+            }
+        }
+
+        conn.send(Raw(r#"{"type": "connection_init"}"#))
+            .await
+            .unwrap();
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        conn.send(Raw(
+            r#"{"type": "start", "id": "foo", "payload": {"query": "{context}", "variables": {}}}"#,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            ServerMessage::ConnectionError {
+                payload: ConnectionErrorPayload {
+                    message: "Message exceeds 72 bytes".into(),
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+
+        // The connection is still usable for smaller messages.
+        conn.send(Raw(
+            r#"{"type": "start", "id": "foo", "payload": {"query": "{context}"}}"#,
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            ServerMessage::Data {
+                id: "foo".into(),
+                payload: DataPayload {
+                    data: graphql_value!({"context": 1}),
+                    errors: vec![],
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+    }
+
+    /// Lifecycle recording all the events, rejecting operations with the `denied` id, and
+    /// refreshing the context with the `ctx` parameter (stopping all the running operations if
+    /// the `stop` one is present).
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Recorder {
+        fn events(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn record(&self, event: String) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    impl Lifecycle<Context> for Recorder {
+        fn on_operation_start<'a>(
+            &'a self,
+            context: &'a Context,
+            id: &'a str,
+            _: &'a StartPayload<DefaultScalarValue>,
+        ) -> BoxFuture<'a, Result<(), LifecycleError>> {
+            self.record(format!("start {id} {}", context.0));
+            let res = if id == "denied" {
+                Err("Access denied".into())
+            } else {
+                Ok(())
+            };
+            future::ready(res).boxed()
+        }
+
+        fn on_operation_complete(&self, context: &Context, id: &str) {
+            self.record(format!("complete {id} {}", context.0));
+        }
+
+        fn on_disconnect(&self, context: &Context, reason: &DisconnectReason) {
+            self.record(format!("disconnect {reason:?} {}", context.0));
+        }
+
+        fn refresh_context<'a>(
+            &'a self,
+            _: &'a Context,
+            params: Variables,
+            operations: &'a [String],
+        ) -> BoxFuture<'a, Result<ContextRefresh<Context>, LifecycleError>> {
+            let res = match params.get("ctx").and_then(|v| v.as_int_value()) {
+                Some(ctx) if params.contains_key("stop") => {
+                    Ok(ContextRefresh::replace(Context(ctx)).stop_operations(operations))
+                }
+                Some(ctx) => Ok(ContextRefresh::replace(Context(ctx))),
+                None => Err("Session revoked".into()),
+            };
+            future::ready(res).boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle() {
+        let recorder = Recorder::default();
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_lifecycle(recorder.clone()),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        conn.send(ClientMessage::Start {
+            id: "denied".into(),
+            payload: StartPayload {
+                query: "{context}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ServerMessage::Error {
+                id: "denied".into(),
+                payload: GraphQLError::ValidationError(vec![RuleError::new("Access denied", &[])])
+                    .into(),
+            },
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(
+            ServerMessage::Complete {
+                id: "denied".into()
+            },
+            conn.next().await.unwrap(),
+        );
+
+        conn.send(ClientMessage::Start {
+            id: "foo".into(),
+            payload: StartPayload {
+                query: "subscription Foo {context}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ServerMessage::Data {
+                id: "foo".into(),
+                payload: DataPayload {
+                    data: graphql_value!({"context": 1}),
+                    errors: vec![],
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+
+        // A repeated ConnectionInit message refreshes the context of the operations started
+        // afterwards.
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {"ctx": 2},
+        })
+        .await
+        .unwrap();
+
+        conn.send(ClientMessage::Start {
+            id: "bar".into(),
+            payload: StartPayload {
+                query: "{context}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ServerMessage::Data {
+                id: "bar".into(),
+                payload: DataPayload {
+                    data: graphql_value!({"context": 2}),
+                    errors: vec![],
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(
+            ServerMessage::Complete { id: "bar".into() },
+            conn.next().await.unwrap(),
+        );
+
+        // Running operations keep their context, unless stopped on refresh.
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {"ctx": 3, "stop": true},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ServerMessage::Complete { id: "foo".into() },
+            conn.next().await.unwrap(),
+        );
+
+        SinkExt::<ClientMessage>::close(&mut conn).await.unwrap();
+        drop(conn);
+
+        assert_eq!(
+            recorder.events(),
+            [
+                "start denied 1",
+                "start foo 1",
+                "start bar 2",
+                "complete bar 2",
+                "complete foo 1",
+                "disconnect Client 3",
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_refresh_error() {
+        let recorder = Recorder::default();
+        let mut conn = Connection::new(
+            new_test_schema(),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_lifecycle(recorder.clone()),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        conn.send(ClientMessage::Start {
+            id: "foo".into(),
+            payload: StartPayload {
+                query: "subscription Foo {never}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        // Revoking the session stops all the operations.
+        assert_eq!(
+            ServerMessage::Complete { id: "foo".into() },
+            conn.next().await.unwrap(),
+        );
+        assert_eq!(
+            ServerMessage::ConnectionError {
+                payload: ConnectionErrorPayload {
+                    message: "Session revoked".into(),
+                },
+            },
+            conn.next().await.unwrap(),
+        );
+        assert!(conn.next().await.is_none());
+
+        drop(conn);
+
+        assert_eq!(
+            recorder.events(),
+            [
+                "start foo 1",
+                "complete foo 1",
+                "disconnect Server { code: None, message: \"Session revoked\" } 1",
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_queries() {
+        let invalidator = Invalidator::new();
+        let mut conn = Connection::new(
+            Arc::new(
+                RootNode::new(Query, EmptyMutation::new(), Subscription).enable_live_queries(),
+            ),
+            ConnectionConfig::new(Context(1))
+                .with_keep_alive_interval(Duration::from_secs(0))
+                .with_live_queries(invalidator.clone()),
+        );
+
+        conn.send(ClientMessage::ConnectionInit {
+            payload: graphql_vars! {},
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(ServerMessage::ConnectionAck, conn.next().await.unwrap());
+
+        COUNTER.store(1, Ordering::SeqCst);
+        conn.send(ClientMessage::Start {
+            id: "foo".into(),
+            payload: StartPayload {
+                query: "query @live {counter}".into(),
+                variables: graphql_vars! {},
+                operation_name: None,
+            },
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            ServerMessage::Data {
+                id: "foo".into(),
+                payload: DataPayload {
+                    data: graphql_value!({"counter": 1}),
+                    errors: vec![],
+                },
+            },
+            conn.next().await.unwrap()
+        );
+
+        COUNTER.store(2, Ordering::SeqCst);
+        assert_eq!(invalidator.invalidate("counter"), 1);
+
+        assert_eq!(
+            ServerMessage::Data {
+                id: "foo".into(),
+                payload: DataPayload {
+                    data: graphql_value!({"counter": 2}),
+                    errors: vec![],
+                },
+            },
+            conn.next().await.unwrap()
+        );
+
+        conn.send(ClientMessage::Stop { id: "foo".into() })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ServerMessage::Complete { id: "foo".into() },
+            conn.next().await.unwrap()
+        );
+        assert_eq!(invalidator.watchers_count(), 0);
+    }
 }
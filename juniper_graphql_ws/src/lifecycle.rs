@@ -0,0 +1,408 @@
+use std::{collections::HashMap, error::Error, sync::Arc};
+
+use juniper::{
+    futures::{
+        channel::oneshot,
+        future::{self, BoxFuture},
+    },
+    DefaultScalarValue, Variables,
+};
+
+use crate::{ConnectionConfig, StartPayload};
+
+/// Error returned by [`Lifecycle`] hooks to reject an operation or a context refresh. Its
+/// formatted message is sent back to the client.
+pub type LifecycleError = Box<dyn Error + Send + Sync>;
+
+/// Lifecycle defines hooks into the lifecycle of an accepted connection, which may be used for
+/// audit logs, metrics, or revoking access mid-session. It's set via
+/// [`ConnectionConfig::with_lifecycle()`].
+///
+/// All the hooks do nothing by default.
+pub trait Lifecycle<CtxT, S = DefaultScalarValue>: Send + Sync + 'static {
+    /// Called before an operation with the provided `id` is started. Returning an error rejects
+    /// the operation, and the error is sent back to the client as the operation's error.
+    fn on_operation_start<'a>(
+        &'a self,
+        context: &'a CtxT,
+        id: &'a str,
+        payload: &'a StartPayload<S>,
+    ) -> BoxFuture<'a, Result<(), LifecycleError>> {
+        let _ = (context, id, payload);
+        Box::pin(future::ready(Ok(())))
+    }
+
+    /// Called once a started operation with the provided `id` is finished, either because it's
+    /// completed, stopped by the client, or its connection has ended.
+    fn on_operation_complete(&self, context: &CtxT, id: &str) {
+        let _ = (context, id);
+    }
+
+    /// Called once the connection has ended for the provided `reason`.
+    fn on_disconnect(&self, context: &CtxT, reason: &DisconnectReason) {
+        let _ = (context, reason);
+    }
+
+    /// Called when the client sends new connection parameters mid-session (a repeated
+    /// ConnectionInit message in the legacy graphql-ws protocol, or a Ping message with a
+    /// non-empty payload in the graphql-transport-ws protocol), along with the ids of the
+    /// currently running `operations`.
+    ///
+    /// A new context of the returned [`ContextRefresh`] is used for all the operations started
+    /// afterwards. The running operations keep using the context they've been started with, so
+    /// the ones which shouldn't (for example, because of revoked access) should be stopped via
+    /// [`ContextRefresh::stop_operations()`]. Returning an error closes the connection (and so
+    /// stops all its operations). By default, the current context is kept.
+    fn refresh_context<'a>(
+        &'a self,
+        context: &'a CtxT,
+        params: Variables<S>,
+        operations: &'a [String],
+    ) -> BoxFuture<'a, Result<ContextRefresh<CtxT>, LifecycleError>>
+    where
+        CtxT: Send,
+    {
+        let _ = (context, params, operations);
+        Box::pin(future::ready(Ok(ContextRefresh::keep())))
+    }
+}
+
+/// Outcome of the [`Lifecycle::refresh_context()`] hook.
+#[derive(Debug)]
+pub struct ContextRefresh<CtxT> {
+    context: Option<CtxT>,
+    stopped_operations: Vec<String>,
+}
+
+impl<CtxT> ContextRefresh<CtxT> {
+    /// Keeps the current context.
+    pub fn keep() -> Self {
+        Self {
+            context: None,
+            stopped_operations: Vec::new(),
+        }
+    }
+
+    /// Replaces the current context with the provided one for the operations started afterwards.
+    pub fn replace(context: CtxT) -> Self {
+        Self {
+            context: Some(context),
+            stopped_operations: Vec::new(),
+        }
+    }
+
+    /// Stops the running operations with the provided `ids`, completing them for the client (which
+    /// may start them again with the refreshed context).
+    ///
+    /// Unknown ids are ignored.
+    #[must_use]
+    pub fn stop_operations<I>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.stopped_operations
+            .extend(ids.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl<CtxT> Default for ContextRefresh<CtxT> {
+    fn default() -> Self {
+        Self::keep()
+    }
+}
+
+/// DisconnectReason describes why a connection has ended.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisconnectReason {
+    /// The client has closed the connection, or requested its termination.
+    Client,
+
+    /// The server has closed the connection because of an error.
+    Server {
+        /// The close code sent to the client, if the protocol supports them.
+        code: Option<u16>,
+
+        /// The error message sent to the client.
+        message: String,
+    },
+
+    /// The connection has been dropped without being closed, for example, because of a transport
+    /// error.
+    Dropped,
+}
+
+impl<CtxT: Send + 'static, S: 'static> ConnectionConfig<CtxT, S> {
+    /// Runs the [`Lifecycle::on_operation_start()`] hook, if any, returning a guard running the
+    /// [`Lifecycle::on_operation_complete()`] hook once dropped.
+    pub(crate) async fn start_operation(
+        self: &Arc<Self>,
+        id: &str,
+        payload: &StartPayload<S>,
+    ) -> Result<Option<OperationGuard<CtxT, S>>, LifecycleError> {
+        match &self.lifecycle {
+            Some(lifecycle) => {
+                lifecycle
+                    .on_operation_start(&self.context, id, payload)
+                    .await?;
+                Ok(Some(OperationGuard {
+                    config: self.clone(),
+                    id: id.into(),
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Runs the [`Lifecycle::refresh_context()`] hook, if any, with the operations of the
+    /// provided `stoppers`, returning the configuration with the refreshed context.
+    ///
+    /// Stops the operations requested by the hook, by firing their `stoppers`.
+    pub(crate) async fn refresh_context(
+        self: Arc<Self>,
+        params: Variables<S>,
+        stoppers: &mut HashMap<String, oneshot::Sender<()>>,
+    ) -> Result<Arc<Self>, LifecycleError> {
+        let lifecycle = match &self.lifecycle {
+            Some(lifecycle) => lifecycle,
+            None => return Ok(self),
+        };
+
+        // Prune finished or canceled operations, so only the running ones are reported.
+        stoppers.retain(|_, tx| !tx.is_canceled());
+        let mut operations = stoppers.keys().cloned().collect::<Vec<_>>();
+        operations.sort();
+
+        let refresh = lifecycle
+            .refresh_context(&self.context, params, &operations)
+            .await?;
+        for id in &refresh.stopped_operations {
+            if let Some(tx) = stoppers.remove(id) {
+                // The operation is stopped by the server rather than the client, so is completed
+                // for the client.
+                let _ = tx.send(());
+            }
+        }
+        Ok(match refresh.context {
+            Some(context) => Arc::new(Self {
+                context,
+                max_in_flight_operations: self.max_in_flight_operations,
+                keep_alive_interval: self.keep_alive_interval,
+                max_incoming_message_size: self.max_incoming_message_size,
+                buffer_limits: self.buffer_limits,
+                lifecycle: Some(lifecycle.clone()),
+                invalidator: self.invalidator.clone(),
+            }),
+            None => self,
+        })
+    }
+
+    /// Runs the [`Lifecycle::on_disconnect()`] hook, if any.
+    pub(crate) fn disconnect(&self, reason: &DisconnectReason) {
+        if let Some(lifecycle) = &self.lifecycle {
+            lifecycle.on_disconnect(&self.context, reason);
+        }
+    }
+}
+
+/// Guard of a started operation, running the [`Lifecycle::on_operation_complete()`] hook once
+/// dropped.
+pub(crate) struct OperationGuard<CtxT: 'static, S: 'static> {
+    config: Arc<ConnectionConfig<CtxT, S>>,
+    id: String,
+}
+
+impl<CtxT: 'static, S: 'static> Drop for OperationGuard<CtxT, S> {
+    fn drop(&mut self) {
+        if let Some(lifecycle) = &self.config.lifecycle {
+            lifecycle.on_operation_complete(&self.config.context, &self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use juniper::{
+        futures::{
+            channel::oneshot,
+            future::{self, BoxFuture, FutureExt as _},
+        },
+        graphql_vars, Variables,
+    };
+
+    use crate::{ConnectionConfig, StartPayload};
+
+    use super::{ContextRefresh, Lifecycle, LifecycleError};
+
+    /// Lifecycle recording the operations it's called with, and refreshing the context with the
+    /// `ctx` parameter, stopping the operations listed in the `stop` one.
+    #[derive(Clone, Default)]
+    struct Refresher(Arc<Mutex<Vec<String>>>);
+
+    impl Lifecycle<i32> for Refresher {
+        fn on_operation_complete(&self, context: &i32, id: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("complete {id} {context}"));
+        }
+
+        fn refresh_context<'a>(
+            &'a self,
+            context: &'a i32,
+            params: Variables,
+            operations: &'a [String],
+        ) -> BoxFuture<'a, Result<ContextRefresh<i32>, LifecycleError>> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("refresh {context} {operations:?}"));
+
+            let res = match params.get("ctx").map(|v| v.as_int_value()) {
+                None => Ok(ContextRefresh::keep()),
+                Some(Some(ctx)) => Ok(ContextRefresh::replace(ctx)),
+                Some(None) => Err("Session revoked".into()),
+            };
+            let stop = params
+                .get("stop")
+                .and_then(|v| v.to_list_value())
+                .map(|ids| {
+                    ids.iter()
+                        .filter_map(|id| id.as_string_value().map(str::to_owned))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            future::ready(res.map(|r| r.stop_operations(stop))).boxed()
+        }
+    }
+
+    fn stoppers(
+        ids: &[&str],
+    ) -> (
+        HashMap<String, oneshot::Sender<()>>,
+        HashMap<String, oneshot::Receiver<()>>,
+    ) {
+        ids.iter()
+            .map(|id| {
+                let (tx, rx) = oneshot::channel();
+                (((*id).to_owned(), tx), ((*id).to_owned(), rx))
+            })
+            .unzip()
+    }
+
+    fn config(refresher: &Refresher) -> Arc<ConnectionConfig<i32>> {
+        Arc::new(ConnectionConfig::new(1).with_lifecycle(refresher.clone()))
+    }
+
+    #[tokio::test]
+    async fn keeps_context_and_operations_without_lifecycle() {
+        let config = Arc::new(ConnectionConfig::<i32>::new(1));
+        let (mut stoppers, _rxs) = stoppers(&["a"]);
+
+        let refreshed = config
+            .clone()
+            .refresh_context(graphql_vars! {"ctx": 2}, &mut stoppers)
+            .await
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&config, &refreshed));
+        assert_eq!(stoppers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn keeps_context() {
+        let refresher = Refresher::default();
+        let config = config(&refresher);
+        let (mut stoppers, _rxs) = stoppers(&["a"]);
+
+        let refreshed = config
+            .clone()
+            .refresh_context(graphql_vars! {}, &mut stoppers)
+            .await
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&config, &refreshed));
+        assert_eq!(stoppers.len(), 1);
+        assert_eq!(*refresher.0.lock().unwrap(), [r#"refresh 1 ["a"]"#]);
+    }
+
+    #[tokio::test]
+    async fn replaces_context() {
+        let refresher = Refresher::default();
+        let (mut stoppers, _rxs) = stoppers(&["a"]);
+
+        let refreshed = config(&refresher)
+            .refresh_context(graphql_vars! {"ctx": 2}, &mut stoppers)
+            .await
+            .unwrap();
+
+        assert_eq!(refreshed.context, 2);
+        assert!(refreshed.lifecycle.is_some());
+        assert_eq!(stoppers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn stops_requested_running_operations() {
+        let refresher = Refresher::default();
+        let (mut stoppers, mut rxs) = stoppers(&["b", "a", "c", "done"]);
+        drop(rxs.remove("done"));
+
+        let refreshed = config(&refresher)
+            .refresh_context(
+                graphql_vars! {"ctx": 2, "stop": ["a", "c", "unknown"]},
+                &mut stoppers,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(refreshed.context, 2);
+        // Finished operations are not reported to the hook.
+        assert_eq!(
+            *refresher.0.lock().unwrap(),
+            [r#"refresh 1 ["a", "b", "c"]"#],
+        );
+        assert_eq!(stoppers.keys().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(rxs.remove("a").unwrap().await, Ok(()));
+        assert_eq!(rxs.remove("c").unwrap().await, Ok(()));
+        assert_eq!(rxs.get_mut("b").unwrap().try_recv(), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn propagates_error() {
+        let refresher = Refresher::default();
+        let (mut stoppers, _rxs) = stoppers(&["a"]);
+
+        let err = config(&refresher)
+            .refresh_context(graphql_vars! {"ctx": "invalid"}, &mut stoppers)
+            .await
+            .err()
+            .unwrap();
+
+        assert_eq!(err.to_string(), "Session revoked");
+        assert_eq!(stoppers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn completes_operation_once_guard_dropped() {
+        let refresher = Refresher::default();
+        let config = config(&refresher);
+
+        let payload = StartPayload {
+            query: "{a}".into(),
+            variables: graphql_vars! {},
+            operation_name: None,
+        };
+
+        let guard = config.start_operation("a", &payload).await.unwrap();
+        assert!(refresher.0.lock().unwrap().is_empty());
+
+        drop(guard);
+        assert_eq!(*refresher.0.lock().unwrap(), ["complete a 1"]);
+    }
+}
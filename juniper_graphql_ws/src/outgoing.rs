@@ -0,0 +1,220 @@
+//! Buffer of outgoing messages of a connection, enforcing its [`BufferLimits`].
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{BufferLimits, OverflowPolicy};
+
+/// Maximum number of reactions buffered during a single poll of a connection, so an always ready
+/// operation doesn't starve other tasks.
+pub(crate) const POLL_BUDGET: usize = 128;
+
+/// Outgoing message of a connection, along with the id of the operation it's a result of.
+struct Outgoing<R> {
+    /// ID of the operation, if this message is its result (and so may be dropped).
+    operation: Option<String>,
+    reaction: R,
+}
+
+/// Error of pushing a message to a full [`OutgoingQueue`] with [`OverflowPolicy::Close`].
+#[derive(Debug)]
+pub(crate) struct Overflow;
+
+/// Queue of outgoing messages of a connection, waiting for the client to read them.
+pub(crate) struct OutgoingQueue<R> {
+    messages: VecDeque<Outgoing<R>>,
+
+    /// Number of buffered results of each operation.
+    per_operation: HashMap<String, usize>,
+
+    /// Number of buffered results of all operations.
+    total: usize,
+}
+
+impl<R> Default for OutgoingQueue<R> {
+    fn default() -> Self {
+        Self {
+            messages: VecDeque::new(),
+            per_operation: HashMap::new(),
+            total: 0,
+        }
+    }
+}
+
+// Buffered messages are never pinned, so moving them around is fine.
+impl<R> Unpin for OutgoingQueue<R> {}
+
+impl<R> OutgoingQueue<R> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Pushes the provided `reaction` to the back of this queue.
+    ///
+    /// If the `reaction` is a result of the `operation` and the `limits` are exceeded, the
+    /// [`OverflowPolicy`] is applied to the buffered results.
+    pub(crate) fn push(
+        &mut self,
+        operation: Option<String>,
+        reaction: R,
+        limits: &BufferLimits,
+    ) -> Result<(), Overflow> {
+        if let Some(id) = &operation {
+            let buffered = self.per_operation.get(id).copied().unwrap_or_default();
+            let is_operation_full = limits.per_operation > 0 && buffered >= limits.per_operation;
+            let is_total_full = limits.total > 0 && self.total >= limits.total;
+
+            if is_operation_full || is_total_full {
+                match limits.policy {
+                    OverflowPolicy::Close => return Err(Overflow),
+                    OverflowPolicy::DropOldest => {
+                        if is_operation_full {
+                            self.remove_oldest(Some(id));
+                        } else {
+                            self.remove_oldest(None);
+                        }
+                    }
+                    OverflowPolicy::CoalesceToLatest => {
+                        self.remove_all(id);
+                        if limits.total > 0 && self.total >= limits.total {
+                            self.remove_oldest(None);
+                        }
+                    }
+                }
+            }
+
+            *self.per_operation.entry(id.clone()).or_default() += 1;
+            self.total += 1;
+        }
+
+        self.messages.push_back(Outgoing {
+            operation,
+            reaction,
+        });
+        Ok(())
+    }
+
+    /// Pops the next message to be sent to the client.
+    pub(crate) fn pop(&mut self) -> Option<R> {
+        let msg = self.messages.pop_front()?;
+        if let Some(id) = &msg.operation {
+            self.forget(id);
+        }
+        Some(msg.reaction)
+    }
+
+    /// Removes all the buffered messages.
+    pub(crate) fn clear(&mut self) {
+        self.messages.clear();
+        self.per_operation.clear();
+        self.total = 0;
+    }
+
+    /// Removes the oldest buffered result of the operation with the provided `id`, or of any
+    /// operation if `None`.
+    fn remove_oldest(&mut self, id: Option<&str>) {
+        let pos = self
+            .messages
+            .iter()
+            .position(|msg| match (&msg.operation, id) {
+                (Some(op), Some(id)) => op == id,
+                (Some(_), None) => true,
+                (None, _) => false,
+            });
+        if let Some(msg) = pos.and_then(|pos| self.messages.remove(pos)) {
+            if let Some(op) = &msg.operation {
+                self.forget(op);
+            }
+        }
+    }
+
+    /// Removes all the buffered results of the operation with the provided `id`.
+    fn remove_all(&mut self, id: &str) {
+        self.messages
+            .retain(|msg| msg.operation.as_deref() != Some(id));
+        if let Some(count) = self.per_operation.remove(id) {
+            self.total -= count;
+        }
+    }
+
+    /// Decrements the counters of buffered results of the operation with the provided `id`.
+    fn forget(&mut self, id: &str) {
+        if let Some(count) = self.per_operation.get_mut(id) {
+            *count -= 1;
+            if *count == 0 {
+                self.per_operation.remove(id);
+            }
+        }
+        self.total -= 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limits(per_operation: usize, total: usize, policy: OverflowPolicy) -> BufferLimits {
+        BufferLimits {
+            per_operation,
+            total,
+            policy,
+        }
+    }
+
+    fn drain(queue: &mut OutgoingQueue<&'static str>) -> Vec<&'static str> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn drops_oldest() {
+        let limits = limits(2, 3, OverflowPolicy::DropOldest);
+        let mut queue = OutgoingQueue::default();
+
+        for (op, r) in [
+            (Some("a"), "a1"),
+            (None, "ack"),
+            (Some("a"), "a2"),
+            (Some("a"), "a3"),
+            (Some("b"), "b1"),
+            (Some("b"), "b2"),
+        ] {
+            queue.push(op.map(Into::into), r, &limits).unwrap();
+        }
+
+        assert_eq!(drain(&mut queue), ["ack", "a3", "b1", "b2"]);
+        assert_eq!(queue.total, 0);
+        assert!(queue.per_operation.is_empty());
+    }
+
+    #[test]
+    fn coalesces_to_latest() {
+        let limits = limits(2, 0, OverflowPolicy::CoalesceToLatest);
+        let mut queue = OutgoingQueue::default();
+
+        for (op, r) in [
+            (Some("a"), "a1"),
+            (Some("b"), "b1"),
+            (Some("a"), "a2"),
+            (Some("a"), "a3"),
+            (None, "complete"),
+        ] {
+            queue.push(op.map(Into::into), r, &limits).unwrap();
+        }
+
+        assert_eq!(drain(&mut queue), ["b1", "a3", "complete"]);
+        assert_eq!(queue.total, 0);
+    }
+
+    #[test]
+    fn overflows() {
+        let limits = limits(0, 1, OverflowPolicy::Close);
+        let mut queue = OutgoingQueue::default();
+
+        queue.push(Some("a".into()), "a1", &limits).unwrap();
+        queue.push(None, "ka", &limits).unwrap();
+        assert!(queue.push(Some("b".into()), "b1", &limits).is_err());
+
+        queue.clear();
+        assert!(queue.is_empty());
+        queue.push(Some("b".into()), "b1", &limits).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+/// GraphQL over WebSocket protocol, negotiated via `Sec-WebSocket-Protocol` header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Protocol {
+    /// Legacy [`graphql-ws` protocol][1] of Apollo's `subscriptions-transport-ws` library,
+    /// implemented by [`Connection`].
+    ///
+    /// [`Connection`]: crate::Connection
+    /// [1]: https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md
+    GraphQLWs,
+
+    /// [`graphql-transport-ws` protocol][1] of the `graphql-ws` library, implemented by
+    /// [`graphql_transport_ws::Connection`].
+    ///
+    /// [`graphql_transport_ws::Connection`]: crate::graphql_transport_ws::Connection
+    /// [1]: https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md
+    GraphQLTransportWs,
+}
+
+impl Protocol {
+    /// Returns the name of this [`Protocol`], as used in `Sec-WebSocket-Protocol` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GraphQLWs => "graphql-ws",
+            Self::GraphQLTransportWs => "graphql-transport-ws",
+        }
+    }
+
+    /// Negotiates a [`Protocol`] out of the provided `Sec-WebSocket-Protocol` header value,
+    /// picking the first supported one in the client's order of preference.
+    ///
+    /// Falls back to [`Protocol::GraphQLWs`] if the header is absent or doesn't list any supported
+    /// protocol, for compatibility with clients not specifying it.
+    pub fn negotiate(header: Option<&str>) -> Self {
+        header
+            .into_iter()
+            .flat_map(|h| h.split(','))
+            .find_map(|p| Self::from_name(p.trim()))
+            .unwrap_or(Self::GraphQLWs)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "graphql-ws" => Some(Self::GraphQLWs),
+            "graphql-transport-ws" => Some(Self::GraphQLTransportWs),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Protocol;
+
+    #[test]
+    fn test_negotiation() {
+        assert_eq!(Protocol::negotiate(None), Protocol::GraphQLWs);
+        assert_eq!(Protocol::negotiate(Some("graphql-ws")), Protocol::GraphQLWs);
+        assert_eq!(
+            Protocol::negotiate(Some("graphql-transport-ws")),
+            Protocol::GraphQLTransportWs,
+        );
+        assert_eq!(
+            Protocol::negotiate(Some("foo, graphql-transport-ws, graphql-ws")),
+            Protocol::GraphQLTransportWs,
+        );
+        assert_eq!(Protocol::negotiate(Some("foo")), Protocol::GraphQLWs);
+    }
+}
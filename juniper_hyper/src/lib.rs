@@ -1,15 +1,28 @@
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "subscriptions")]
+use std::convert::Infallible;
 use std::{error::Error, fmt, string::FromUtf8Error, sync::Arc};
 
+#[cfg(feature = "subscriptions")]
+use futures::StreamExt as _;
 use hyper::{
     header::{self, HeaderValue},
     Body, Method, Request, Response, StatusCode,
 };
+#[cfg(feature = "multipart")]
+use juniper::http::multipart::{self, MultipartError, MultipartOptions};
 use juniper::{
-    http::{GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest, GraphQLRequest},
+    http::{
+        policy::{self, MediaType, PolicyError},
+        GraphQLBatchRequest, GraphQLRequest as JuniperGraphQLRequest, GraphQLRequest,
+    },
     GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, InputValue, RootNode, ScalarValue,
 };
+#[cfg(feature = "subscriptions")]
+use juniper::{GraphQLError, SubscriptionCoordinator};
+#[cfg(feature = "subscriptions")]
+use juniper_subscriptions::sse;
 use serde_json::error::Error as SerdeError;
 use url::form_urlencoded;
 
@@ -28,8 +41,13 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return render_policy_error(e),
+    };
+    let is_get = req.method() == Method::GET;
     match parse_req(req).await {
-        Ok(req) => execute_request_sync(root_node, context, req).await,
+        Ok(req) => execute_request_sync(root_node, context, req, is_get, media_type).await,
         Err(resp) => resp,
     }
 }
@@ -49,12 +67,106 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return render_policy_error(e),
+    };
+    let is_get = req.method() == Method::GET;
     match parse_req(req).await {
-        Ok(req) => execute_request(root_node, context, req).await,
+        Ok(req) => execute_request(root_node, context, req, is_get, media_type).await,
         Err(resp) => resp,
     }
 }
 
+/// Same as [`graphql()`], but additionally accepts [GraphQL multipart requests][1] with file
+/// uploads, storing the uploaded files according to the provided [`MultipartOptions`].
+///
+/// Responds with `413 Payload Too Large` if the request exceeds the limits of the
+/// [`MultipartOptions`].
+///
+/// [1]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[cfg(feature = "multipart")]
+pub async fn graphql_multipart<CtxT, QueryT, MutationT, SubscriptionT, S>(
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+    options: &MultipartOptions,
+) -> Response<String>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT>,
+    QueryT::TypeInfo: Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT>,
+    MutationT::TypeInfo: Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT>,
+    SubscriptionT::TypeInfo: Sync,
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync,
+{
+    let media_type = match negotiate_media_type(&req) {
+        Ok(media_type) => media_type,
+        Err(e) => return render_policy_error(e),
+    };
+    let is_get = req.method() == Method::GET;
+    match parse_multipart_req(req, options).await {
+        Ok(req) => execute_request(root_node, context, req, is_get, media_type).await,
+        Err(resp) => resp,
+    }
+}
+
+/// Serves a subscription over [Server-Sent Events][1], replying with a `text/event-stream` response
+/// consisting of a `next` event per result of the operation and a `complete` event at the end.
+///
+/// Any [`SubscriptionCoordinator`] may serve the subscriptions, like a
+/// [`juniper_subscriptions::Coordinator`] or a [`juniper_subscriptions::SharedCoordinator`].
+///
+/// [1]: https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md
+#[cfg(feature = "subscriptions")]
+pub async fn graphql_sse<Coord, CtxT, S>(
+    coordinator: Arc<Coord>,
+    context: Arc<CtxT>,
+    req: Request<Body>,
+) -> Response<Body>
+where
+    Coord:
+        for<'a> SubscriptionCoordinator<'a, CtxT, S, Error = GraphQLError> + Send + Sync + 'static,
+    for<'a> <Coord as SubscriptionCoordinator<'a, CtxT, S>>::Connection: Send,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let req = match parse_req(req).await {
+        Ok(GraphQLBatchRequest::Single(req)) => req,
+        Ok(GraphQLBatchRequest::Batch(_)) => {
+            return render_error(GraphQLRequestError::Invalid(
+                "batch requests are not supported over Server-Sent Events".into(),
+            ))
+            .map(Body::from)
+        }
+        Err(resp) => return resp.map(Body::from),
+    };
+
+    let events = sse::subscribe(coordinator, req, context)
+        .map(|event| Ok::<_, Infallible>(event.to_string()));
+
+    let mut resp = Response::new(Body::wrap_stream(events));
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(sse::CONTENT_TYPE),
+    );
+    resp.headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    resp
+}
+
+/// Negotiates the [`MediaType`] of the response to the provided [`Request`] out of its `Accept`
+/// header.
+fn negotiate_media_type(req: &Request<Body>) -> Result<MediaType, PolicyError> {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok());
+    MediaType::negotiate(accept).ok_or(PolicyError::NotAcceptable)
+}
+
 async fn parse_req<S: ScalarValue>(
     req: Request<Body>,
 ) -> Result<GraphQLBatchRequest<S>, Response<String>> {
@@ -76,6 +188,25 @@ async fn parse_req<S: ScalarValue>(
     .map_err(render_error)
 }
 
+#[cfg(feature = "multipart")]
+async fn parse_multipart_req<S: ScalarValue>(
+    req: Request<Body>,
+    options: &MultipartOptions,
+) -> Result<GraphQLBatchRequest<S>, Response<String>> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|ct| *req.method() == Method::POST && multipart::is_multipart(ct))
+        .map(str::to_owned);
+    match content_type {
+        Some(ct) => multipart::parse_multipart_request(&ct, req.into_body(), options)
+            .await
+            .map_err(|e| render_error(GraphQLRequestError::Multipart(e))),
+        None => parse_req(req).await,
+    }
+}
+
 fn parse_get_req<S: ScalarValue>(
     req: Request<Body>,
 ) -> Result<GraphQLBatchRequest<S>, GraphQLRequestError> {
@@ -139,8 +270,24 @@ pub async fn playground(
     resp
 }
 
+fn render_policy_error(err: PolicyError) -> Response<String> {
+    let code = StatusCode::from_u16(err.status_code()).expect("valid status code");
+    let mut resp = new_response(code);
+    if err == PolicyError::NonQueryOverGet {
+        resp.headers_mut()
+            .insert(header::ALLOW, HeaderValue::from_static("POST"));
+    }
+    *resp.body_mut() = err.to_string();
+    resp
+}
+
 fn render_error(err: GraphQLRequestError) -> Response<String> {
-    let mut resp = new_response(StatusCode::BAD_REQUEST);
+    let code = match &err {
+        #[cfg(feature = "multipart")]
+        GraphQLRequestError::Multipart(e) if e.is_too_large() => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    let mut resp = new_response(code);
     *resp.body_mut() = err.to_string();
     resp
 }
@@ -149,6 +296,8 @@ async fn execute_request_sync<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
     request: GraphQLBatchRequest<S>,
+    is_get: bool,
+    media_type: MediaType,
 ) -> Response<String>
 where
     QueryT: GraphQLType<S, Context = CtxT>,
@@ -160,17 +309,21 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let res = request.execute_sync(&*root_node, &context);
-    let body = serde_json::to_string_pretty(&res).unwrap();
-    let code = if res.is_ok() {
-        StatusCode::OK
+    // Mutations received via GET requests are rejected.
+    let res = if is_get {
+        match policy::check_get_request(&request, &root_node) {
+            Ok(request) => request.execute_sync(&*root_node, &context),
+            Err(e) => return render_policy_error(e),
+        }
     } else {
-        StatusCode::BAD_REQUEST
+        request.execute_sync(&*root_node, &context)
     };
+    let body = serde_json::to_string_pretty(&res).unwrap();
+    let code = StatusCode::from_u16(res.status_code()).expect("valid status code");
     let mut resp = new_response(code);
     resp.headers_mut().insert(
         header::CONTENT_TYPE,
-        HeaderValue::from_static("application/json"),
+        HeaderValue::from_static(media_type.as_str()),
     );
     *resp.body_mut() = body;
     resp
@@ -180,6 +333,8 @@ async fn execute_request<CtxT, QueryT, MutationT, SubscriptionT, S>(
     root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
     context: Arc<CtxT>,
     request: GraphQLBatchRequest<S>,
+    is_get: bool,
+    media_type: MediaType,
 ) -> Response<String>
 where
     QueryT: GraphQLTypeAsync<S, Context = CtxT>,
@@ -191,17 +346,21 @@ where
     CtxT: Sync,
     S: ScalarValue + Send + Sync,
 {
-    let res = request.execute(&*root_node, &context).await;
-    let body = serde_json::to_string_pretty(&res).unwrap();
-    let code = if res.is_ok() {
-        StatusCode::OK
+    // Mutations received via GET requests are rejected.
+    let res = if is_get {
+        match policy::check_get_request(&request, &root_node) {
+            Ok(request) => request.execute(&*root_node, &context).await,
+            Err(e) => return render_policy_error(e),
+        }
     } else {
-        StatusCode::BAD_REQUEST
+        request.execute(&*root_node, &context).await
     };
+    let body = serde_json::to_string_pretty(&res).unwrap();
+    let code = StatusCode::from_u16(res.status_code()).expect("valid status code");
     let mut resp = new_response(code);
     resp.headers_mut().insert(
         header::CONTENT_TYPE,
-        HeaderValue::from_static("application/json"),
+        HeaderValue::from_static(media_type.as_str()),
     );
     *resp.body_mut() = body;
     resp
@@ -277,6 +436,8 @@ enum GraphQLRequestError {
     BodyJSONError(SerdeError),
     Variables(SerdeError),
     Invalid(String),
+    #[cfg(feature = "multipart")]
+    Multipart(MultipartError),
 }
 
 impl fmt::Display for GraphQLRequestError {
@@ -287,6 +448,8 @@ impl fmt::Display for GraphQLRequestError {
             GraphQLRequestError::BodyJSONError(err) => fmt::Display::fmt(err, f),
             GraphQLRequestError::Variables(err) => fmt::Display::fmt(err, f),
             GraphQLRequestError::Invalid(err) => fmt::Display::fmt(err, f),
+            #[cfg(feature = "multipart")]
+            GraphQLRequestError::Multipart(err) => fmt::Display::fmt(err, f),
         }
     }
 }
@@ -299,6 +462,316 @@ impl Error for GraphQLRequestError {
             GraphQLRequestError::BodyJSONError(err) => Some(err),
             GraphQLRequestError::Variables(err) => Some(err),
             GraphQLRequestError::Invalid(_) => None,
+            #[cfg(feature = "multipart")]
+            GraphQLRequestError::Multipart(err) => Some(err),
+        }
+    }
+}
+
+/// `juniper_hyper` subscriptions handler implementation, serving GraphQL over WebSocket.
+///
+/// *Note: this implementation is in an alpha state.*
+#[cfg(feature = "subscriptions")]
+pub mod subscriptions {
+    use std::{convert::Infallible, fmt, sync::Arc};
+
+    use futures::{
+        future::{self, Either},
+        SinkExt as _, StreamExt as _, TryStreamExt as _,
+    };
+    use hyper::{header, upgrade::Upgraded, Body, Request, Response, StatusCode};
+    use juniper::{GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue};
+    use juniper_graphql_ws::{
+        graphql_transport_ws, ArcSchema, ClientMessage, Connection, IncomingMessage, Init, Protocol,
+    };
+    use tokio_tungstenite::{
+        tungstenite::{
+            self,
+            handshake::derive_accept_key,
+            protocol::{frame::coding::CloseCode, CloseFrame, Role},
+        },
+        WebSocketStream,
+    };
+
+    struct Message(tungstenite::Message);
+
+    impl IncomingMessage for Message {
+        fn size(&self) -> Option<usize> {
+            Some(self.0.len())
+        }
+    }
+
+    impl<S: ScalarValue> TryFrom<Message> for ClientMessage<S> {
+        type Error = Error;
+
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            match msg.0 {
+                tungstenite::Message::Close(_) => Ok(Self::ConnectionTerminate),
+                msg => serde_json::from_slice(&msg.into_data()).map_err(Error::Serde),
+            }
+        }
+    }
+
+    impl<S: ScalarValue> TryFrom<Message> for graphql_transport_ws::ClientMessage<S> {
+        type Error = Error;
+
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            serde_json::from_slice(&msg.0.into_data()).map_err(Error::Serde)
+        }
+    }
+
+    /// Errors that can happen while serving a connection.
+    #[derive(Debug)]
+    pub enum Error {
+        /// Errors that can happen in the WebSocket transport while serving a connection.
+        WebSocket(tungstenite::Error),
+
+        /// Errors that can happen while serializing outgoing messages or deserializing incoming
+        /// ones. Note that the latter are not returned, but reported to the client by the
+        /// protocol.
+        Serde(serde_json::Error),
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::WebSocket(e) => write!(f, "websocket error: {e}"),
+                Self::Serde(e) => write!(f, "serde error: {e}"),
+            }
+        }
+    }
+
+    impl std::error::Error for Error {}
+
+    impl From<tungstenite::Error> for Error {
+        fn from(err: tungstenite::Error) -> Self {
+            Self::WebSocket(err)
+        }
+    }
+
+    impl From<Infallible> for Error {
+        fn from(_err: Infallible) -> Self {
+            unreachable!()
+        }
+    }
+
+    /// Performs the WebSocket handshake on the provided `req`, and serves GraphQL over the
+    /// upgraded connection in a spawned task.
+    ///
+    /// The protocol is negotiated from the `Sec-WebSocket-Protocol` request header: the
+    /// graphql-transport-ws protocol is served if the client asks for it, and the legacy graphql-ws
+    /// protocol otherwise.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
+    /// configuration are already known, or it can be a closure that gets executed asynchronously
+    /// when the client sends the ConnectionInit message. Using a closure allows you to perform
+    /// authentication based on the parameters provided by the client.
+    ///
+    /// Replies with `400 Bad Request` if `req` is not a WebSocket upgrade request. Errors happening
+    /// once the connection is upgraded are ignored, so use [`serve_ws()`] directly with your own
+    /// upgrade logic to handle them.
+    pub async fn graphql_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        mut req: Request<Body>,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Response<Body>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        let accept_key = match websocket_key(&req) {
+            Some(key) => derive_accept_key(key.as_bytes()),
+            None => {
+                let mut resp = Response::new(Body::from("expected WebSocket upgrade request"));
+                *resp.status_mut() = StatusCode::BAD_REQUEST;
+                return resp;
+            }
+        };
+        let protocol = Protocol::negotiate(
+            req.headers()
+                .get(header::SEC_WEBSOCKET_PROTOCOL)
+                .and_then(|h| h.to_str().ok()),
+        );
+
+        let upgrade = hyper::upgrade::on(&mut req);
+        tokio::spawn(async move {
+            if let Ok(upgraded) = upgrade.await {
+                let websocket =
+                    WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+                let _ = serve_ws(websocket, protocol, root_node, init).await;
+            }
+        });
+
+        Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::CONNECTION, "upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept_key)
+            .header(header::SEC_WEBSOCKET_PROTOCOL, protocol.as_str())
+            .body(Body::empty())
+            .expect("response is valid")
+    }
+
+    /// Returns the `Sec-WebSocket-Key` of the provided `req`, if it's a valid WebSocket upgrade
+    /// request.
+    fn websocket_key(req: &Request<Body>) -> Option<&str> {
+        let headers = req.headers();
+        let has_token = |name: header::HeaderName, token: &str| {
+            headers
+                .get_all(name)
+                .iter()
+                .filter_map(|h| h.to_str().ok())
+                .flat_map(|h| h.split(','))
+                .any(|t| t.trim().eq_ignore_ascii_case(token))
+        };
+
+        if !has_token(header::CONNECTION, "upgrade")
+            || !has_token(header::UPGRADE, "websocket")
+            || !has_token(header::SEC_WEBSOCKET_VERSION, "13")
+        {
+            return None;
+        }
+        headers
+            .get(header::SEC_WEBSOCKET_KEY)
+            .and_then(|h| h.to_str().ok())
+    }
+
+    /// Serves the provided GraphQL over WebSocket [`Protocol`] over an upgraded WebSocket
+    /// connection.
+    ///
+    /// See [`serve_graphql_ws()`] and [`serve_graphql_transport_ws()`] for details.
+    pub async fn serve_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: WebSocketStream<Upgraded>,
+        protocol: Protocol,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        match protocol {
+            Protocol::GraphQLWs => serve_graphql_ws(websocket, root_node, init).await,
+            Protocol::GraphQLTransportWs => {
+                serve_graphql_transport_ws(websocket, root_node, init).await
+            }
+        }
+    }
+
+    /// Serves the legacy graphql-ws protocol over an upgraded WebSocket connection.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
+    /// configuration are already known, or it can be a closure that gets executed asynchronously
+    /// when the client sends the ConnectionInit message. Using a closure allows you to perform
+    /// authentication based on the parameters provided by the client.
+    pub async fn serve_graphql_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: WebSocketStream<Upgraded>,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        let (ws_tx, ws_rx) = websocket.split();
+        let (s_tx, s_rx) = Connection::new(ArcSchema(root_node), init).split();
+
+        let ws_rx = ws_rx
+            .try_filter(|msg| future::ready(!msg.is_ping() && !msg.is_pong()))
+            .map(|r| r.map(Message).map_err(Error::from));
+        let s_rx = s_rx.map(|msg| {
+            serde_json::to_string(&msg)
+                .map(tungstenite::Message::Text)
+                .map_err(Error::Serde)
+        });
+
+        match future::select(
+            ws_rx.forward(s_tx.sink_err_into()),
+            s_rx.forward(ws_tx.sink_err_into()),
+        )
+        .await
+        {
+            Either::Left((r, _)) => r,
+            Either::Right((r, _)) => r,
+        }
+    }
+
+    /// Serves the graphql-transport-ws protocol over an upgraded WebSocket connection.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
+    /// configuration are already known, or it can be a closure that gets executed asynchronously
+    /// when the client sends the ConnectionInit message. Using a closure allows you to perform
+    /// authentication based on the parameters provided by the client.
+    ///
+    /// Protocol violations close the WebSocket connection with the corresponding close code.
+    pub async fn serve_graphql_transport_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: WebSocketStream<Upgraded>,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        let (ws_tx, ws_rx) = websocket.split();
+        let (s_tx, s_rx) =
+            graphql_transport_ws::Connection::new(ArcSchema(root_node), init).split();
+
+        // graphql-transport-ws has no termination message, so the client just closes the socket.
+        let ws_rx = ws_rx
+            .try_filter(|msg| future::ready(msg.is_text() || msg.is_binary()))
+            .map(|r| r.map(Message).map_err(Error::from));
+        let s_rx = s_rx.map(|output| match output {
+            graphql_transport_ws::Output::Message(msg) => serde_json::to_string(&msg)
+                .map(tungstenite::Message::Text)
+                .map_err(Error::Serde),
+            graphql_transport_ws::Output::Close { code, message } => {
+                Ok(tungstenite::Message::Close(Some(CloseFrame {
+                    code: CloseCode::from(code),
+                    reason: message.into(),
+                })))
+            }
+        });
+
+        match future::select(
+            ws_rx.forward(s_tx.sink_err_into()),
+            s_rx.forward(ws_tx.sink_err_into()),
+        )
+        .await
+        {
+            Either::Left((r, _)) => r,
+            Either::Right((r, _)) => r,
         }
     }
 }
@@ -351,6 +824,35 @@ mod tests {
                 .expect(&format!("failed POST {url}"));
             make_test_response(res)
         }
+
+        fn get_with_accept(&self, url: &str, accept: &str) -> http_tests::TestResponse {
+            let url = format!("http://127.0.0.1:{}/graphql{url}", self.port);
+            let client = reqwest::blocking::Client::new();
+            let res = client
+                .get(&url)
+                .header(reqwest::header::ACCEPT, accept)
+                .send()
+                .expect(&format!("failed GET {url}"));
+            make_test_response(res)
+        }
+
+        fn post_json_with_accept(
+            &self,
+            url: &str,
+            body: &str,
+            accept: &str,
+        ) -> http_tests::TestResponse {
+            let url = format!("http://127.0.0.1:{}/graphql{url}", self.port);
+            let client = reqwest::blocking::Client::new();
+            let res = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(reqwest::header::ACCEPT, accept)
+                .body(body.to_owned())
+                .send()
+                .expect(&format!("failed POST {url}"));
+            make_test_response(res)
+        }
     }
 
     fn make_test_response(response: ReqwestResponse) -> http_tests::TestResponse {
@@ -444,4 +946,358 @@ mod tests {
     async fn test_sync_hyper_integration() {
         run_hyper_integration(true).await
     }
+
+    #[cfg(feature = "multipart")]
+    #[tokio::test]
+    async fn test_hyper_multipart() {
+        use hyper::{Body, Request};
+        use juniper::{
+            graphql_object,
+            http::multipart::{MultipartOptions, Upload},
+            Executor, ScalarValue,
+        };
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        struct Mutation;
+
+        #[graphql_object]
+        impl Mutation {
+            fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> Option<i32> {
+                file.file(executor.extensions()).map(|f| f.size() as i32)
+            }
+        }
+
+        let body = "--boundary\r\n\
+                    Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+                    {\"query\": \"mutation($file: Upload!) { upload(file: $file) }\", \"variables\": {\"file\": null}}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+                    {\"0\": [\"variables.file\"]}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n\
+                    Hello, world!\r\n\
+                    --boundary--\r\n";
+
+        let root_node = Arc::new(RootNode::new(Query, Mutation, EmptySubscription::new()));
+        let request = || {
+            Request::post("/graphql")
+                .header(
+                    hyper::header::CONTENT_TYPE,
+                    "multipart/form-data; boundary=boundary",
+                )
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let resp = super::graphql_multipart(
+            root_node.clone(),
+            Arc::new(()),
+            request(),
+            &MultipartOptions::new(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(resp.body()).unwrap(),
+            serde_json::json!({"data": {"upload": 13}}),
+        );
+
+        let resp = super::graphql_multipart(
+            root_node,
+            Arc::new(()),
+            request(),
+            &MultipartOptions::new().with_max_file_size(5),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+#[cfg(test)]
+mod sse_tests {
+    use std::{pin::Pin, sync::Arc};
+
+    use futures::{stream, Stream};
+    use hyper::{header, Body, Method, Request, StatusCode};
+    use juniper::{graphql_object, graphql_subscription, EmptyMutation, FieldError, RootNode};
+    use juniper_subscriptions::{sse, Coordinator, SharedCoordinator};
+
+    use super::graphql_sse;
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    type Counter = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+    #[graphql_subscription]
+    impl Subscription {
+        async fn counter(to: i32) -> Counter {
+            Box::pin(stream::iter((1..=to).map(Ok)))
+        }
+    }
+
+    fn schema() -> RootNode<'static, Query, EmptyMutation, Subscription> {
+        RootNode::new(Query, EmptyMutation::new(), Subscription)
+    }
+
+    const EVENTS: &str = "event: next\ndata: {\"data\":{\"counter\":1}}\n\n\
+                          event: next\ndata: {\"data\":{\"counter\":2}}\n\n\
+                          event: complete\ndata:\n\n";
+
+    async fn call(req: Request<Body>) -> (StatusCode, Option<String>, String) {
+        let resp = graphql_sse(Arc::new(Coordinator::new(schema())), Arc::new(()), req).await;
+
+        let status = resp.status();
+        let content_type = resp
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|v| v.to_str().unwrap().to_owned());
+        let body = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+        (
+            status,
+            content_type,
+            String::from_utf8(body.to_vec()).unwrap(),
+        )
+    }
+
+    fn post(body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn streams_get_request() {
+        let req = Request::builder()
+            .uri("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(
+            call(req).await,
+            (
+                StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                EVENTS.into()
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_post_request() {
+        let req = post(
+            r#"{
+                "query": "subscription($to: Int!) { counter(to: $to) }",
+                "variables": {"to": 2}
+            }"#,
+        );
+
+        assert_eq!(
+            call(req).await,
+            (
+                StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                EVENTS.into()
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_error_before_start() {
+        let req = post(r#"{"query": "subscription { unknown }"}"#);
+
+        assert_eq!(
+            call(req).await,
+            (
+                StatusCode::OK,
+                Some(sse::CONTENT_TYPE.into()),
+                "event: next\ndata: {\"errors\":[{\"message\":\
+                 \"Unknown field \\\"unknown\\\" on type \\\"Subscription\\\"\",\
+                 \"locations\":[{\"line\":1,\"column\":16}]}]}\n\n\
+                 event: complete\ndata:\n\n"
+                    .into(),
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_get_request_without_query() {
+        let req = Request::builder().uri("/").body(Body::empty()).unwrap();
+
+        let (status, ..) = call(req).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn streams_via_shared_coordinator() {
+        let coordinator = Arc::new(SharedCoordinator::new(schema(), |_: &()| String::new()));
+        let req = Request::builder()
+            .uri("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = graphql_sse(coordinator, Arc::new(()), req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            hyper::body::to_bytes(resp.into_body()).await.unwrap(),
+            EVENTS,
+        );
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+#[cfg(test)]
+mod subscription_tests {
+    use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+
+    use futures::{SinkExt as _, StreamExt as _};
+    use hyper::{
+        server::Server,
+        service::{make_service_fn, service_fn},
+    };
+    use juniper::{
+        http::tests::{
+            run_graphql_transport_ws_test_suite, run_ws_test_suite, WsIntegration,
+            WsIntegrationMessage,
+        },
+        tests::fixtures::starwars::schema::{Database, Query, Subscription},
+        EmptyMutation, LocalBoxFuture, RootNode,
+    };
+    use juniper_graphql_ws::{ConnectionConfig, Protocol};
+    use tokio::time::timeout;
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{client::IntoClientRequest as _, http::HeaderValue, Message},
+    };
+
+    use super::subscriptions::graphql_ws;
+
+    struct TestHyperWsIntegration(Protocol);
+
+    impl TestHyperWsIntegration {
+        async fn run_async(
+            &self,
+            messages: Vec<WsIntegrationMessage>,
+        ) -> Result<(), anyhow::Error> {
+            let root_node = Arc::new(RootNode::new(
+                Query,
+                EmptyMutation::<Database>::new(),
+                Subscription,
+            ));
+
+            let new_service = make_service_fn(move |_| {
+                let root_node = root_node.clone();
+
+                async move {
+                    Ok::<_, hyper::Error>(service_fn(move |req| {
+                        let root_node = root_node.clone();
+                        let config = ConnectionConfig::new(Database::new());
+
+                        async move { Ok::<_, Infallible>(graphql_ws(req, root_node, config).await) }
+                    }))
+                }
+            });
+
+            let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+            let server = Server::bind(&addr).serve(new_service);
+            let addr = server.local_addr();
+            let server = tokio::spawn(server);
+
+            let mut request = format!("ws://{addr}/subscriptions")
+                .into_client_request()
+                .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+            request.headers_mut().insert(
+                "sec-websocket-protocol",
+                HeaderValue::from_static(self.0.as_str()),
+            );
+
+            let (mut websocket, _) = connect_async(request)
+                .await
+                .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+
+            for message in &messages {
+                match message {
+                    WsIntegrationMessage::Send(body) => {
+                        websocket
+                            .send(Message::Text(body.to_owned()))
+                            .await
+                            .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+                    }
+                    WsIntegrationMessage::Expect(body, message_timeout) => {
+                        let message =
+                            timeout(Duration::from_millis(*message_timeout), websocket.next())
+                                .await
+                                .map_err(|_| anyhow::anyhow!("Timed-out waiting for message"))?
+                                .ok_or_else(|| anyhow::anyhow!("Empty message received"))?
+                                .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+
+                        match message {
+                            Message::Text(text) => {
+                                let expected_value =
+                                    serde_json::from_str::<serde_json::Value>(body)
+                                        .map_err(|e| anyhow::anyhow!("Serde error: {e:?}"))?;
+
+                                let value: serde_json::Value = serde_json::from_str(&text)
+                                    .map_err(|e| anyhow::anyhow!("Serde error: {e:?}"))?;
+
+                                if value != expected_value {
+                                    return Err(anyhow::anyhow!(
+                                        "Expected message: {expected_value}. \
+                                         Received message: {value}",
+                                    ));
+                                }
+                            }
+                            _ => return Err(anyhow::anyhow!("Received non-text message")),
+                        }
+                    }
+                }
+            }
+
+            server.abort();
+
+            Ok(())
+        }
+    }
+
+    impl WsIntegration for TestHyperWsIntegration {
+        fn run(
+            &self,
+            messages: Vec<WsIntegrationMessage>,
+        ) -> LocalBoxFuture<Result<(), anyhow::Error>> {
+            Box::pin(self.run_async(messages))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hyper_ws_integration() {
+        run_ws_test_suite(&TestHyperWsIntegration(Protocol::GraphQLWs)).await;
+    }
+
+    #[tokio::test]
+    async fn test_hyper_graphql_transport_ws_integration() {
+        run_graphql_transport_ws_test_suite(&TestHyperWsIntegration(Protocol::GraphQLTransportWs))
+            .await;
+    }
 }
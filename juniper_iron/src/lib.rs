@@ -3,7 +3,7 @@
 use std::{error::Error, fmt, io::Read, ops::Deref as _};
 
 use iron::{
-    headers::ContentType,
+    headers::{Allow, ContentType},
     itry, method,
     middleware::Handler,
     mime::{Mime, TopLevel},
@@ -11,8 +11,12 @@ use iron::{
     status,
 };
 use juniper::{
-    http, http::GraphQLBatchRequest, DefaultScalarValue, GraphQLType, InputValue, RootNode,
-    ScalarValue,
+    http,
+    http::{
+        policy::{self, MediaType, PolicyError},
+        GraphQLBatchRequest,
+    },
+    DefaultScalarValue, GraphQLType, InputValue, RootNode, ScalarValue,
 };
 use serde_json::error::Error as SerdeError;
 use urlencoded::{UrlDecodingError, UrlEncodedQuery};
@@ -155,19 +159,46 @@ where
         &self,
         context: &CtxT,
         request: GraphQLBatchRequest<S>,
+        is_get: bool,
+        media_type: MediaType,
     ) -> IronResult<Response> {
-        let response = request.execute_sync(&self.root_node, context);
-        let content_type = "application/json".parse::<Mime>().unwrap();
-        let json = serde_json::to_string_pretty(&response).unwrap();
-        let status = if response.is_ok() {
-            status::Ok
+        // Mutations received via GET requests are rejected.
+        let response = if is_get {
+            match policy::check_get_request(&request, &self.root_node) {
+                Ok(request) => request.execute_sync(&self.root_node, context),
+                Err(e) => return Ok(render_policy_error(e)),
+            }
         } else {
-            status::BadRequest
+            request.execute_sync(&self.root_node, context)
         };
+        let content_type = media_type.as_str().parse::<Mime>().unwrap();
+        let json = serde_json::to_string_pretty(&response).unwrap();
+        let status = status::Status::from_u16(response.status_code());
         Ok(Response::with((content_type, status, json)))
     }
 }
 
+/// Negotiates the [`MediaType`] of the response to the provided [`Request`] out of its `Accept`
+/// header.
+fn negotiate_media_type(req: &Request) -> Result<MediaType, PolicyError> {
+    let accept = req.headers.get_raw("accept").map(|values| {
+        values
+            .iter()
+            .map(|v| String::from_utf8_lossy(v))
+            .collect::<Vec<_>>()
+            .join(",")
+    });
+    MediaType::negotiate(accept.as_deref()).ok_or(PolicyError::NotAcceptable)
+}
+
+fn render_policy_error(err: PolicyError) -> Response {
+    let mut resp = Response::with((status::Status::from_u16(err.status_code()), err.to_string()));
+    if err == PolicyError::NonQueryOverGet {
+        resp.headers.set(Allow(vec![method::Post]));
+    }
+    resp
+}
+
 impl GraphiQLHandler {
     /// Build a new GraphiQL handler targeting the specified URL.
     ///
@@ -205,8 +236,14 @@ where
     Subscription: GraphQLType<S, Context = CtxT, TypeInfo = ()> + Send + Sync + 'static,
 {
     fn handle(&self, req: &mut Request) -> IronResult<Response> {
+        let media_type = match negotiate_media_type(req) {
+            Ok(media_type) => media_type,
+            Err(e) => return Ok(render_policy_error(e)),
+        };
+
         let context = (self.context_factory)(req)?;
 
+        let is_get = req.method == method::Get;
         let graphql_request = match req.method {
             method::Get => self.handle_get(req)?,
             method::Post => match req.headers.get::<ContentType>().map(ContentType::deref) {
@@ -220,7 +257,7 @@ where
             _ => return Ok(Response::with(status::MethodNotAllowed)),
         };
 
-        self.execute_sync(&context, graphql_request)
+        self.execute_sync(&context, graphql_request, is_get, media_type)
     }
 }
 
@@ -354,6 +391,28 @@ mod tests {
                 .map(make_test_response)
                 .unwrap_or_else(make_test_error_response)
         }
+
+        fn get_with_accept(&self, url: &str, accept: &str) -> http_tests::TestResponse {
+            let mut headers = Headers::new();
+            headers.set_raw("accept", vec![accept.as_bytes().to_vec()]);
+            request::get(&fixup_url(url), headers, &make_handler())
+                .map(make_test_response)
+                .unwrap_or_else(make_test_error_response)
+        }
+
+        fn post_json_with_accept(
+            &self,
+            url: &str,
+            body: &str,
+            accept: &str,
+        ) -> http_tests::TestResponse {
+            let mut headers = Headers::new();
+            headers.set(ContentType::json());
+            headers.set_raw("accept", vec![accept.as_bytes().to_vec()]);
+            request::post(&fixup_url(url), headers, body, &make_handler())
+                .map(make_test_response)
+                .unwrap_or_else(make_test_error_response)
+        }
     }
 
     #[test]
@@ -382,14 +441,11 @@ mod tests {
             .status
             .expect("No status code returned from handler")
             .to_u16() as i32;
-        let content_type = String::from_utf8(
-            response
-                .headers
-                .get_raw("content-type")
-                .expect("No content type header from handler")[0]
-                .clone(),
-        )
-        .expect("Content-type header invalid UTF-8");
+        let content_type = response
+            .headers
+            .get_raw("content-type")
+            .map(|ct| String::from_utf8(ct[0].clone()).expect("Content-type header invalid UTF-8"))
+            .unwrap_or_default();
         let body = response::extract_body_to_string(response);
 
         http_tests::TestResponse {
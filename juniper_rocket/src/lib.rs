@@ -5,14 +5,18 @@ use std::{borrow::Cow, io::Cursor};
 use rocket::{
     data::{self, FromData, ToByteUnit},
     form::{error::ErrorKind, DataField, Error, Errors, FromForm, Options, ValueField},
-    http::{ContentType, Status},
+    http::{ContentType, Header, Status},
     outcome::Outcome::{Failure, Forward, Success},
     response::{self, content, Responder, Response},
     Data, Request,
 };
 
 use juniper::{
-    http::{self, GraphQLBatchRequest},
+    http::{
+        self,
+        policy::{self, MediaType, PolicyError},
+        GraphQLBatchRequest,
+    },
     DefaultScalarValue, FieldError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync,
     InputValue, RootNode, ScalarValue,
 };
@@ -22,8 +26,12 @@ use juniper::{
 /// See the `http` module for more information. This type can be constructed
 /// automatically from both GET and POST routes by implementing the `FromForm`
 /// and `FromData` traits.
+///
+/// Operations other than queries are rejected with `405 Method Not Allowed` if
+/// the request has been constructed via `FromForm` (out of a query string of a
+/// GET request).
 #[derive(Debug, PartialEq)]
-pub struct GraphQLRequest<S = DefaultScalarValue>(GraphQLBatchRequest<S>)
+pub struct GraphQLRequest<S = DefaultScalarValue>(GraphQLBatchRequest<S>, bool)
 where
     S: ScalarValue;
 
@@ -79,12 +87,16 @@ where
         MutationT: GraphQLType<S, Context = CtxT>,
         SubscriptionT: GraphQLType<S, Context = CtxT>,
     {
-        let response = self.0.execute_sync(root_node, context);
-        let status = if response.is_ok() {
-            Status::Ok
+        // Mutations received via GET requests are rejected.
+        let response = if self.1 {
+            match policy::check_get_request(&self.0, root_node) {
+                Ok(req) => req.execute_sync(root_node, context),
+                Err(e) => return GraphQLResponse::policy_error(e),
+            }
         } else {
-            Status::BadRequest
+            self.0.execute_sync(root_node, context)
         };
+        let status = Status::new(response.status_code());
         let json = serde_json::to_string(&response).unwrap();
 
         GraphQLResponse(status, json)
@@ -106,12 +118,16 @@ where
         CtxT: Sync,
         S: Send + Sync,
     {
-        let response = self.0.execute(root_node, context).await;
-        let status = if response.is_ok() {
-            Status::Ok
+        // Mutations received via GET requests are rejected.
+        let response = if self.1 {
+            match policy::check_get_request(&self.0, root_node) {
+                Ok(req) => req.execute(root_node, context).await,
+                Err(e) => return GraphQLResponse::policy_error(e),
+            }
         } else {
-            Status::BadRequest
+            self.0.execute(root_node, context).await
         };
+        let status = Status::new(response.status_code());
         let json = serde_json::to_string(&response).unwrap();
 
         GraphQLResponse(status, json)
@@ -171,6 +187,13 @@ impl GraphQLResponse {
         let json = serde_json::to_string(&response).unwrap();
         GraphQLResponse(status, json)
     }
+
+    fn policy_error(error: PolicyError) -> Self {
+        Self::custom(
+            Status::new(error.status_code()),
+            serde_json::json!({ "errors": [{ "message": error.to_string() }] }),
+        )
+    }
 }
 
 pub struct GraphQLContext<'f, S: ScalarValue> {
@@ -278,9 +301,14 @@ where
         }
 
         match ctx.errors.is_empty() {
-            true => Ok(GraphQLRequest(GraphQLBatchRequest::Single(
-                http::GraphQLRequest::new(ctx.query.unwrap(), ctx.operation_name, ctx.variables),
-            ))),
+            true => Ok(GraphQLRequest(
+                GraphQLBatchRequest::Single(http::GraphQLRequest::new(
+                    ctx.query.unwrap(),
+                    ctx.operation_name,
+                    ctx.variables,
+                )),
+                true,
+            )),
             false => Err(ctx.errors),
         }
     }
@@ -288,10 +316,13 @@ where
 
 const BODY_LIMIT: u64 = 1024 * 100;
 
+#[cfg(feature = "multipart")]
+const MULTIPART_BODY_LIMIT: u64 = 1024 * 1024 * 2;
+
 #[rocket::async_trait]
 impl<'r, S> FromData<'r> for GraphQLRequest<S>
 where
-    S: ScalarValue,
+    S: ScalarValue + Send,
 {
     type Error = String;
 
@@ -299,7 +330,14 @@ where
         req: &'r Request<'_>,
         data: Data<'r>,
     ) -> data::Outcome<'r, Self, Self::Error> {
-        use rocket::tokio::io::AsyncReadExt as _;
+        if let Err(e) = negotiate_media_type(req) {
+            return Failure((Status::new(e.status_code()), e.to_string()));
+        }
+
+        #[cfg(feature = "multipart")]
+        if req.content_type().map_or(false, |ct| ct.is_form_data()) {
+            return Box::pin(from_multipart_data(req, data)).await;
+        }
 
         let content_type = req
             .content_type()
@@ -315,34 +353,139 @@ where
                 .limits()
                 .get("graphql")
                 .unwrap_or_else(|| BODY_LIMIT.bytes());
-            let mut reader = data.open(limit);
-            let mut body = String::new();
-            if let Err(e) = reader.read_to_string(&mut body).await {
-                return Failure((Status::InternalServerError, format!("{e:?}")));
-            }
-
-            Success(GraphQLRequest(if is_json {
-                match serde_json::from_str(&body) {
-                    Ok(req) => req,
-                    Err(e) => return Failure((Status::BadRequest, e.to_string())),
+            let body = match data.open(limit).into_string().await {
+                Ok(body) if body.is_complete() => body.into_inner(),
+                Ok(_) => {
+                    let e = format!("Request body exceeds the limit of {limit}");
+                    return Failure((Status::PayloadTooLarge, e));
                 }
-            } else {
-                GraphQLBatchRequest::Single(http::GraphQLRequest::new(body, None, None))
-            }))
+                Err(e) => return Failure((Status::InternalServerError, format!("{e:?}"))),
+            };
+
+            Success(GraphQLRequest(
+                if is_json {
+                    match serde_json::from_str(&body) {
+                        Ok(req) => req,
+                        Err(e) => return Failure((Status::BadRequest, e.to_string())),
+                    }
+                } else {
+                    GraphQLBatchRequest::Single(http::GraphQLRequest::new(body, None, None))
+                },
+                false,
+            ))
         })
         .await
     }
 }
 
+/// Parses a [GraphQL multipart request][1] with file uploads out of the provided [`Data`].
+///
+/// The uploaded files are stored according to the [`MultipartOptions`] managed by [`rocket`], if
+/// any, while the size of the whole request is bounded by the `data-form` limit (exceeding it is
+/// reported as [`MultipartError::RequestTooLarge`]).
+///
+/// [`MultipartError::RequestTooLarge`]: juniper::http::multipart::MultipartError::RequestTooLarge
+/// [`MultipartOptions`]: juniper::http::multipart::MultipartOptions
+/// [1]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[cfg(feature = "multipart")]
+async fn from_multipart_data<'r, S: ScalarValue + Send>(
+    req: &'r Request<'_>,
+    data: Data<'r>,
+) -> data::Outcome<'r, GraphQLRequest<S>, String> {
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    use juniper::http::multipart::{self, MultipartError, MultipartOptions};
+    use rocket::tokio::io::AsyncReadExt as _;
+
+    let default_options;
+    let options = match req.rocket().state::<MultipartOptions>() {
+        Some(options) => options,
+        None => {
+            default_options = MultipartOptions::default();
+            &default_options
+        }
+    };
+    let content_type = req
+        .content_type()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let limit = req
+        .limits()
+        .get("data-form")
+        .unwrap_or_else(|| MULTIPART_BODY_LIMIT.bytes());
+
+    // `DataStream` silently truncates the body at the limit, so one more byte is read to detect
+    // the overflow.
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let state = (data.open(limit + 1), 0, exceeded.clone());
+    let body = futures::stream::unfold(state, move |(mut reader, read, exceeded)| async move {
+        let mut chunk = vec![0; 8 * 1024];
+        match reader.read(&mut chunk).await {
+            Ok(0) => None,
+            Ok(n) if read + n as u64 > limit.as_u64() => {
+                exceeded.store(true, Ordering::SeqCst);
+                let e = io::Error::new(io::ErrorKind::Other, "request body exceeds the limit");
+                Some((Err(e), (reader, read, exceeded)))
+            }
+            Ok(n) => {
+                chunk.truncate(n);
+                Some((Ok(chunk), (reader, read + n as u64, exceeded)))
+            }
+            Err(e) => Some((Err(e), (reader, read, exceeded))),
+        }
+    });
+
+    match multipart::parse_multipart_request(&content_type, body, options).await {
+        _ if exceeded.load(Ordering::SeqCst) => {
+            let e = MultipartError::RequestTooLarge {
+                limit: limit.as_u64(),
+            };
+            Failure((Status::PayloadTooLarge, e.to_string()))
+        }
+        Ok(req) => Success(GraphQLRequest(req, false)),
+        Err(e) if e.is_too_large() => Failure((Status::PayloadTooLarge, e.to_string())),
+        Err(e) => Failure((Status::BadRequest, e.to_string())),
+    }
+}
+
+/// Negotiates the [`MediaType`] of the response to the provided [`Request`] out of its `Accept`
+/// header.
+fn negotiate_media_type(req: &Request<'_>) -> Result<MediaType, PolicyError> {
+    MediaType::negotiate(req.headers().get_one("Accept")).ok_or(PolicyError::NotAcceptable)
+}
+
 impl<'r, 'o: 'r> Responder<'r, 'o> for GraphQLResponse {
-    fn respond_to(self, _req: &'r Request<'_>) -> response::Result<'o> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
         let GraphQLResponse(status, body) = self;
 
-        Response::build()
-            .header(ContentType::new("application", "json"))
+        let content_type = match negotiate_media_type(req) {
+            Ok(MediaType::GraphQLResponseJson) => {
+                ContentType::new("application", "graphql-response+json")
+            }
+            Ok(MediaType::Json) => ContentType::new("application", "json"),
+            Err(e) => {
+                let body = e.to_string();
+                return Response::build()
+                    .status(Status::new(e.status_code()))
+                    .sized_body(body.len(), Cursor::new(body))
+                    .ok();
+            }
+        };
+
+        let mut resp = Response::build();
+        resp.header(content_type)
             .status(status)
-            .sized_body(body.len(), Cursor::new(body))
-            .ok()
+            .sized_body(body.len(), Cursor::new(body));
+        if status == Status::MethodNotAllowed {
+            resp.header(Header::new("Allow", "POST"));
+        }
+        resp.ok()
     }
 }
 
@@ -450,11 +593,14 @@ mod fromform_tests {
             Form::parse_encoded(RawStr::new(r#"query=test&variables={"foo":"bar"}"#));
         assert!(result.is_ok());
         let variables = ::serde_json::from_str::<InputValue>(r#"{"foo":"bar"}"#).unwrap();
-        let expected = GraphQLRequest(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            "test".into(),
-            None,
-            Some(variables),
-        )));
+        let expected = GraphQLRequest(
+            GraphQLBatchRequest::Single(http::GraphQLRequest::new(
+                "test".into(),
+                None,
+                Some(variables),
+            )),
+            true,
+        );
         assert_eq!(result.unwrap(), expected);
     }
 
@@ -464,11 +610,14 @@ mod fromform_tests {
             r#"query=test&variables={"foo":"x%20y%26%3F+z"}"#,
         ));
         let variables = ::serde_json::from_str::<InputValue>(r#"{"foo":"x y&? z"}"#).unwrap();
-        let expected = GraphQLRequest(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            "test".into(),
-            None,
-            Some(variables),
-        )));
+        let expected = GraphQLRequest(
+            GraphQLBatchRequest::Single(http::GraphQLRequest::new(
+                "test".into(),
+                None,
+                Some(variables),
+            )),
+            true,
+        );
         assert_eq!(result.unwrap(), expected);
     }
 
@@ -478,11 +627,14 @@ mod fromform_tests {
             "query=%25foo%20bar+baz%26%3F&operation_name=test",
         ));
         assert!(result.is_ok());
-        let expected = GraphQLRequest(GraphQLBatchRequest::Single(http::GraphQLRequest::new(
-            "%foo bar baz&?".into(),
-            Some("test".into()),
-            None,
-        )));
+        let expected = GraphQLRequest(
+            GraphQLBatchRequest::Single(http::GraphQLRequest::new(
+                "%foo bar baz&?".into(),
+                Some("test".into()),
+                None,
+            )),
+            true,
+        );
         assert_eq!(result.unwrap(), expected);
     }
 }
@@ -499,7 +651,7 @@ mod tests {
     };
     use rocket::{
         self, get,
-        http::ContentType,
+        http::{ContentType, Header},
         local::asynchronous::{Client, LocalResponse},
         post, routes, Build, Rocket, State,
     };
@@ -550,6 +702,31 @@ mod tests {
             let req = futures::executor::block_on(req.dispatch());
             futures::executor::block_on(make_test_response(req))
         }
+
+        fn get_with_accept(&self, url: &str, accept: &str) -> http_tests::TestResponse {
+            let req = self
+                .client
+                .get(url)
+                .header(Header::new("Accept", accept.to_owned()));
+            let req = futures::executor::block_on(req.dispatch());
+            futures::executor::block_on(make_test_response(req))
+        }
+
+        fn post_json_with_accept(
+            &self,
+            url: &str,
+            body: &str,
+            accept: &str,
+        ) -> http_tests::TestResponse {
+            let req = self
+                .client
+                .post(url)
+                .header(ContentType::JSON)
+                .header(Header::new("Accept", accept.to_owned()))
+                .body(body);
+            let req = futures::executor::block_on(req.dispatch());
+            futures::executor::block_on(make_test_response(req))
+        }
     }
 
     #[rocket::async_test]
@@ -561,6 +738,29 @@ mod tests {
         http_tests::run_http_test_suite(&integration);
     }
 
+    #[rocket::async_test]
+    async fn test_oversized_body() {
+        use rocket::{
+            data::{Limits, ToByteUnit as _},
+            http::Status,
+            Config,
+        };
+
+        let body = r#"{"query": "{hero{name}}"}"#;
+        let limits = Limits::default().limit("graphql", (body.len() - 1).bytes());
+        let rocket = make_rocket().configure(Config::figment().merge(("limits", limits)));
+        let client = Client::untracked(rocket).await.expect("valid rocket");
+
+        let resp = client
+            .post("/")
+            .header(ContentType::JSON)
+            .body(body)
+            .dispatch()
+            .await;
+
+        assert_eq!(resp.status(), Status::PayloadTooLarge);
+    }
+
     #[rocket::async_test]
     async fn test_operation_names() {
         #[post("/", data = "<request>")]
@@ -588,6 +788,107 @@ mod tests {
         assert_eq!(resp.await.status_code, 200);
     }
 
+    #[cfg(feature = "multipart")]
+    #[rocket::async_test]
+    async fn test_multipart_uploads() {
+        use juniper::{
+            graphql_object,
+            http::multipart::{MultipartOptions, Upload},
+            Executor, ScalarValue,
+        };
+        use rocket::{
+            data::{Limits, ToByteUnit as _},
+            http::Status,
+            Config,
+        };
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        struct Mutation;
+
+        #[graphql_object]
+        impl Mutation {
+            fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> Option<i32> {
+                file.file(executor.extensions()).map(|f| f.size() as i32)
+            }
+        }
+
+        type UploadSchema = RootNode<'static, Query, Mutation, EmptySubscription>;
+
+        #[post("/", data = "<request>")]
+        async fn post_upload_handler(
+            request: super::GraphQLRequest,
+            schema: &State<UploadSchema>,
+        ) -> super::GraphQLResponse {
+            request.execute(&*schema, &()).await
+        }
+
+        let body = "--boundary\r\n\
+                    Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+                    {\"query\": \"mutation($file: Upload!) { upload(file: $file) }\", \"variables\": {\"file\": null}}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+                    {\"0\": [\"variables.file\"]}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n\
+                    Hello, world!\r\n\
+                    --boundary--\r\n";
+        let content_type =
+            ContentType::new("multipart", "form-data").with_params(("boundary", "boundary"));
+
+        let make_client = |options, limits: Limits| async move {
+            let rocket = Rocket::custom(Config::figment().merge(("limits", limits)))
+                .manage(UploadSchema::new(Query, Mutation, EmptySubscription::new()))
+                .manage(options)
+                .mount("/", routes![post_upload_handler]);
+            Client::untracked(rocket).await.expect("valid rocket")
+        };
+
+        let client = make_client(MultipartOptions::new(), Limits::default()).await;
+        let resp = client
+            .post("/")
+            .header(content_type.clone())
+            .body(body)
+            .dispatch()
+            .await;
+        let resp = make_test_response(resp).await;
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(resp.body.unwrap(), r#"{"data":{"upload":13}}"#);
+
+        let client = make_client(
+            MultipartOptions::new().with_max_file_size(5),
+            Limits::default(),
+        )
+        .await;
+        let resp = client
+            .post("/")
+            .header(content_type.clone())
+            .body(body)
+            .dispatch()
+            .await;
+        assert_eq!(resp.status(), Status::PayloadTooLarge);
+
+        let client = make_client(
+            MultipartOptions::new(),
+            Limits::default().limit("data-form", (body.len() - 1).bytes()),
+        )
+        .await;
+        let resp = client
+            .post("/")
+            .header(content_type)
+            .body(body)
+            .dispatch()
+            .await;
+        assert_eq!(resp.status(), Status::PayloadTooLarge);
+    }
+
     fn make_rocket() -> Rocket<Build> {
         make_rocket_without_routes().mount("/", routes![post_graphql_handler, get_graphql_handler])
     }
@@ -604,8 +905,8 @@ mod tests {
         let status_code = response.status().code as i32;
         let content_type = response
             .content_type()
-            .expect("No content type header from handler")
-            .to_string();
+            .map(|ct| ct.to_string())
+            .unwrap_or_default();
         let body = response
             .into_string()
             .await
@@ -2,6 +2,11 @@
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+pub mod live;
+pub mod pubsub;
+pub mod shared;
+pub mod sse;
+
 use std::{
     pin::Pin,
     task::{self, Poll},
@@ -14,6 +19,12 @@ use juniper::{
     SubscriptionCoordinator, Value, ValuesStream,
 };
 
+pub use self::{
+    live::Invalidator,
+    pubsub::{Filter, InMemoryPubSub, Lagged, PubSub, PubSubStream, Topic},
+    shared::SharedCoordinator,
+};
+
 /// Simple [`SubscriptionCoordinator`] implementation:
 /// - contains the schema
 /// - handles subscription start
@@ -29,6 +40,7 @@ where
     S: ScalarValue + Send + Sync,
 {
     root_node: juniper::RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    invalidator: Option<Invalidator>,
 }
 
 impl<'a, QueryT, MutationT, SubscriptionT, CtxT, S>
@@ -45,12 +57,28 @@ where
 {
     /// Builds new [`Coordinator`] with specified `root_node`
     pub fn new(root_node: juniper::RootNode<'a, QueryT, MutationT, SubscriptionT, S>) -> Self {
-        Self { root_node }
+        Self {
+            root_node,
+            invalidator: None,
+        }
+    }
+
+    /// Serves [live queries][0] along with subscriptions, re-executing them once their keys are
+    /// invalidated via the provided [`Invalidator`].
+    ///
+    /// Live queries should be [enabled][1] in the schema too.
+    ///
+    /// [0]: live
+    /// [1]: juniper::RootNode::enable_live_queries
+    #[must_use]
+    pub fn with_live_queries(mut self, invalidator: Invalidator) -> Self {
+        self.invalidator = Some(invalidator);
+        self
     }
 }
 
-impl<'a, QueryT, MutationT, SubscriptionT, CtxT, S> SubscriptionCoordinator<'a, CtxT, S>
-    for Coordinator<'a, QueryT, MutationT, SubscriptionT, CtxT, S>
+impl<'a, 'r: 'a, QueryT, MutationT, SubscriptionT, CtxT, S> SubscriptionCoordinator<'a, CtxT, S>
+    for Coordinator<'r, QueryT, MutationT, SubscriptionT, CtxT, S>
 where
     QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send,
     QueryT::TypeInfo: Send + Sync,
@@ -69,6 +97,17 @@ where
         req: &'a GraphQLRequest<S>,
         context: &'a CtxT,
     ) -> BoxFuture<'a, Result<Self::Connection, Self::Error>> {
+        if let Some(invalidator) = &self.invalidator {
+            if self
+                .root_node
+                .is_live_query(&req.query, req.operation_name.as_deref())
+            {
+                return live::execute_live(req.clone(), &self.root_node, context, invalidator)
+                    .map_ok(Connection::from)
+                    .boxed();
+            }
+        }
+
         juniper::http::resolve_into_stream(req, &self.root_node, context)
             .map_ok(|(stream, errors)| Connection::from_stream(stream, errors))
             .boxed()
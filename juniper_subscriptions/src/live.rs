@@ -0,0 +1,784 @@
+//! Live queries: `query` operations marked with `@live` directive, re-executed once any of the
+//! invalidation keys touched during their resolution is invalidated.
+//!
+//! Resolvers register the keys via [`Executor::track_invalidation_key()`], while application
+//! code (mutations, background jobs, etc.) invalidates them via [`Invalidator::invalidate()`].
+//! Live queries should be [enabled][0] in the schema first.
+//!
+//! ```rust
+//! # use futures::StreamExt as _;
+//! # use juniper::{
+//! #     graphql_object, graphql_value, http::GraphQLRequest, EmptyMutation, EmptySubscription,
+//! #     Executor, RootNode, ScalarValue,
+//! # };
+//! # use juniper_subscriptions::live::{execute_live, Invalidator};
+//! #
+//! struct Query;
+//!
+//! #[graphql_object]
+//! impl Query {
+//!     fn counter<S: ScalarValue>(executor: &Executor<(), S>) -> i32 {
+//!         executor.track_invalidation_key("counter");
+//!         42
+//!     }
+//! }
+//!
+//! # futures::executor::block_on(async {
+//! let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+//!     .enable_live_queries();
+//! let invalidator = Invalidator::new();
+//!
+//! let request = GraphQLRequest::new("query @live { counter }".into(), None, None);
+//! let mut stream = execute_live(request, &schema, &(), &invalidator)
+//!     .await
+//!     .expect("execution failed");
+//!
+//! let output = stream.next().await.unwrap();
+//! assert_eq!(output.data, graphql_value!({"counter": 42}));
+//! assert_eq!(invalidator.watchers_count(), 1);
+//! # });
+//! ```
+//!
+//! [`Executor::track_invalidation_key()`]: juniper::Executor::track_invalidation_key
+//! [0]: juniper::RootNode::enable_live_queries
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    task::{self, Poll, Waker},
+};
+
+use futures::{future, stream, Stream, StreamExt as _};
+use juniper::{
+    http::GraphQLRequest, ExecutionError, ExecutionOutput, GraphQLError, GraphQLSubscriptionType,
+    GraphQLTypeAsync, InvalidationKeys, RootNode, ScalarValue, Value,
+};
+use serde::Serialize;
+
+use crate::Connection;
+
+/// Invalidator of the keys live queries depend on.
+///
+/// Cloning is cheap and shares the same watched live queries.
+#[derive(Clone, Default)]
+pub struct Invalidator {
+    watchers: Arc<Watchers>,
+}
+
+impl Invalidator {
+    /// Creates a new [`Invalidator`] without any watched live queries.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Invalidates the provided `key`, re-executing all the live queries depending on it.
+    ///
+    /// Returns the number of the invalidated live queries.
+    pub fn invalidate(&self, key: impl AsRef<str>) -> usize {
+        self.invalidate_all([key])
+    }
+
+    /// Invalidates all the provided `keys` at once, re-executing every live query depending on
+    /// any of them only once.
+    ///
+    /// Returns the number of the invalidated live queries.
+    pub fn invalidate_all<K: AsRef<str>>(&self, keys: impl IntoIterator<Item = K>) -> usize {
+        let mut state = self.watchers.lock();
+        let keys = keys
+            .into_iter()
+            .map(|k| k.as_ref().to_owned())
+            .collect::<HashSet<_>>();
+
+        let mut ids = HashSet::new();
+        for key in &keys {
+            if let Some(watching) = state.by_key.get(key) {
+                ids.extend(watching.iter().copied());
+            }
+        }
+
+        // Live queries being executed right now don't know their keys yet, so they record the
+        // invalidated ones to check them once the execution is finished.
+        for id in &state.executing {
+            if let Some(watch) = state.watches.get(id) {
+                watch.lock().recorded.extend(keys.iter().cloned());
+            }
+        }
+
+        ids.iter()
+            .filter_map(|id| state.watches.get(id))
+            .for_each(|watch| watch.lock().invalidate());
+        let count = ids.len();
+
+        for id in ids {
+            state.unwatch(id);
+        }
+        count
+    }
+
+    /// Returns the number of live queries watched by this [`Invalidator`].
+    pub fn watchers_count(&self) -> usize {
+        self.watchers.lock().watches.len()
+    }
+
+    /// Registers a new [`Watch`] of a live query about to be executed.
+    fn watch(&self) -> Watch {
+        let mut state = self.watchers.lock();
+        state.next_id += 1;
+        let id = state.next_id;
+        let shared = Arc::new(WatchShared::default());
+        state.watches.insert(id, shared.clone());
+        state.executing.insert(id);
+        Watch {
+            watchers: Arc::downgrade(&self.watchers),
+            id,
+            shared,
+        }
+    }
+}
+
+impl fmt::Debug for Invalidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Invalidator")
+            .field("watchers", &self.watchers_count())
+            .finish()
+    }
+}
+
+/// Live queries watched by an [`Invalidator`].
+#[derive(Default)]
+struct Watchers(Mutex<WatchersState>);
+
+impl Watchers {
+    fn lock(&self) -> MutexGuard<'_, WatchersState> {
+        // The state is never left half-modified, so poisoning may be safely ignored.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Default)]
+struct WatchersState {
+    /// Shared states of all the watched live queries.
+    watches: HashMap<u64, Arc<WatchShared>>,
+
+    /// IDs of the live queries depending on a key.
+    by_key: HashMap<String, HashSet<u64>>,
+
+    /// IDs of the live queries being executed at the moment.
+    executing: HashSet<u64>,
+
+    next_id: u64,
+}
+
+impl WatchersState {
+    /// Stops indexing the live query with the provided `id` by its keys.
+    fn unwatch(&mut self, id: u64) {
+        let keys = self
+            .watches
+            .get(&id)
+            .map(|w| std::mem::take(&mut w.lock().keys))
+            .unwrap_or_default();
+        for key in keys {
+            if let Some(ids) = self.by_key.get_mut(&key) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.by_key.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// State of a single live query shared with its [`Invalidator`].
+#[derive(Default)]
+struct WatchShared(Mutex<WatchState>);
+
+impl WatchShared {
+    fn lock(&self) -> MutexGuard<'_, WatchState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    /// Keys the last execution of the live query depends on.
+    keys: BTreeSet<String>,
+
+    /// Keys invalidated during the current execution of the live query.
+    recorded: BTreeSet<String>,
+
+    is_invalidated: bool,
+
+    waker: Option<Waker>,
+}
+
+impl WatchState {
+    fn invalidate(&mut self) {
+        self.is_invalidated = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Registration of a live query in an [`Invalidator`], removed on drop.
+struct Watch {
+    watchers: Weak<Watchers>,
+    id: u64,
+    shared: Arc<WatchShared>,
+}
+
+impl Watch {
+    /// Marks the live query as being executed, so keys invalidated meanwhile are recorded.
+    fn start(&self) {
+        if let Some(watchers) = self.watchers.upgrade() {
+            let mut state = watchers.lock();
+            state.unwatch(self.id);
+            state.executing.insert(self.id);
+        }
+        let mut watch = self.shared.lock();
+        watch.is_invalidated = false;
+        watch.recorded.clear();
+    }
+
+    /// Marks the live query as executed, depending on the provided `keys` from now on.
+    fn finish(&self, keys: BTreeSet<String>) {
+        let watchers = match self.watchers.upgrade() {
+            Some(watchers) => watchers,
+            // Nothing can be invalidated anymore.
+            None => return,
+        };
+        let mut state = watchers.lock();
+        state.executing.remove(&self.id);
+
+        let mut watch = self.shared.lock();
+        if watch.recorded.intersection(&keys).next().is_some() {
+            // Already outdated, so should be re-executed right away.
+            watch.recorded.clear();
+            watch.invalidate();
+            return;
+        }
+        for key in &keys {
+            state.by_key.entry(key.clone()).or_default().insert(self.id);
+        }
+        watch.keys = keys;
+    }
+
+    /// Polls whether the live query has been invalidated since its last execution.
+    fn poll_invalidated(&self, cx: &mut task::Context<'_>) -> Poll<()> {
+        let mut watch = self.shared.lock();
+        if watch.is_invalidated {
+            Poll::Ready(())
+        } else {
+            watch.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        if let Some(watchers) = self.watchers.upgrade() {
+            let mut state = watchers.lock();
+            state.unwatch(self.id);
+            state.executing.remove(&self.id);
+            state.watches.remove(&self.id);
+        }
+    }
+}
+
+/// Executes the provided live query `request`, re-executing it each time the keys it depends on
+/// are invalidated via the given `invalidator`.
+///
+/// Errors with a [`GraphQLError`] if the first execution fails. Re-executions failing this way
+/// end the returned [`LiveStream`], while the ones resulting in the same output as the previous
+/// one are not yielded at all.
+///
+/// Doesn't check whether the `request` is a [live query][0] actually, so may be used for
+/// re-executing any `query` operation.
+///
+/// [0]: RootNode::is_live_query
+pub async fn execute_live<'a, QueryT, MutationT, SubscriptionT, CtxT, S>(
+    mut request: GraphQLRequest<S>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    context: &'a CtxT,
+    invalidator: &Invalidator,
+) -> Result<LiveStream<'a, S>, GraphQLError>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync + 'a,
+{
+    request.extensions.insert(InvalidationKeys::new());
+    let watch = invalidator.watch();
+
+    let first = execute_once(&request, root_node, context, &watch).await?;
+
+    let stream = stream::unfold(
+        (request, watch, first.clone()),
+        move |(request, watch, mut last)| async move {
+            loop {
+                future::poll_fn(|cx| watch.poll_invalidated(cx)).await;
+
+                let output = execute_once(&request, root_node, context, &watch)
+                    .await
+                    .ok()?;
+                if output.data != last.data || output.errors != last.errors {
+                    last = output.clone();
+                    return Some((output, (request, watch, last)));
+                }
+            }
+        },
+    );
+
+    Ok(LiveStream {
+        stream: stream::once(future::ready(first)).chain(stream).boxed(),
+    })
+}
+
+/// Executes the provided live query `request` once, tracking its invalidation keys with the
+/// given [`Watch`].
+async fn execute_once<'a, QueryT, MutationT, SubscriptionT, CtxT, S>(
+    request: &GraphQLRequest<S>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    context: &'a CtxT,
+    watch: &Watch,
+) -> Result<ExecutionOutput<S>, GraphQLError>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Sync,
+    S: ScalarValue + Send + Sync + 'a,
+{
+    watch.start();
+    let result = request.execute(root_node, context).await.into_result();
+    let keys = request
+        .extensions
+        .get::<InvalidationKeys>()
+        .map(InvalidationKeys::take)
+        .unwrap_or_default();
+    watch.finish(keys);
+
+    result.map(|(data, errors)| ExecutionOutput { data, errors })
+}
+
+/// Stream of the whole results of a live query, as returned by [`execute_live()`].
+///
+/// Converts into a [`Connection`], so may be served as a regular subscription.
+pub struct LiveStream<'a, S> {
+    stream: Pin<Box<dyn Stream<Item = ExecutionOutput<S>> + Send + 'a>>,
+}
+
+impl<'a, S> LiveStream<'a, S>
+where
+    S: ScalarValue + Send + Sync + 'a,
+{
+    /// Converts this [`LiveStream`] into a stream of [`LiveResult`]s, representing each result
+    /// after the first one as a [JSON patch][0] to the previous one.
+    ///
+    /// [0]: https://datatracker.ietf.org/doc/html/rfc6902
+    pub fn into_patches(self) -> impl Stream<Item = LiveResult<S>> + Send + 'a {
+        self.stream.scan(
+            (None, 0),
+            |(last, revision): &mut (Option<Value<S>>, u64), output| {
+                *revision += 1;
+                let result = match last.replace(output.data.clone()) {
+                    None => LiveResult::Full {
+                        data: output.data,
+                        errors: output.errors,
+                        revision: *revision,
+                    },
+                    Some(prev) => LiveResult::Patch {
+                        patch: diff(&prev, &output.data),
+                        errors: output.errors,
+                        revision: *revision,
+                    },
+                };
+                future::ready(Some(result))
+            },
+        )
+    }
+}
+
+impl<'a, S> Stream for LiveStream<'a, S> {
+    type Item = ExecutionOutput<S>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl<'a, S> From<LiveStream<'a, S>> for Connection<'a, S> {
+    fn from(live: LiveStream<'a, S>) -> Self {
+        Self {
+            stream: live.stream,
+        }
+    }
+}
+
+/// Result of a live query, as yielded by [`LiveStream::into_patches()`].
+///
+/// Serializes as `{"data", "errors", "revision"}` or `{"patch", "errors", "revision"}` object
+/// (omitting empty `errors`), so the client applies each `patch` to the `data` of the previous
+/// `revision`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum LiveResult<S> {
+    /// Whole result of the first execution.
+    Full {
+        /// Resulting data.
+        data: Value<S>,
+
+        /// Errors occurred during the execution.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<ExecutionError<S>>,
+
+        /// Revision of this result, starting from `1`.
+        revision: u64,
+    },
+
+    /// Changes of the data since the previous revision.
+    Patch {
+        /// [JSON patch][0] to apply to the data of the previous revision.
+        ///
+        /// [0]: https://datatracker.ietf.org/doc/html/rfc6902
+        patch: Vec<PatchOperation<S>>,
+
+        /// Errors occurred during the execution.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<ExecutionError<S>>,
+
+        /// Revision of this result.
+        revision: u64,
+    },
+}
+
+/// Single [JSON patch][0] operation.
+///
+/// [0]: https://datatracker.ietf.org/doc/html/rfc6902
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation<S> {
+    /// Adds the `value` at the `path`.
+    Add {
+        /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the added value.
+        path: String,
+
+        /// Added value.
+        value: Value<S>,
+    },
+
+    /// Removes the value at the `path`.
+    Remove {
+        /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the removed value.
+        path: String,
+    },
+
+    /// Replaces the value at the `path` with the `value`.
+    Replace {
+        /// [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) to the replaced value.
+        path: String,
+
+        /// New value.
+        value: Value<S>,
+    },
+}
+
+/// Computes [`PatchOperation`]s transforming the `old` [`Value`] into the `new` one.
+///
+/// Descends into objects and equally sized lists, replacing anything else as a whole.
+fn diff<S: ScalarValue>(old: &Value<S>, new: &Value<S>) -> Vec<PatchOperation<S>> {
+    fn go<S: ScalarValue>(
+        path: &str,
+        old: &Value<S>,
+        new: &Value<S>,
+        ops: &mut Vec<PatchOperation<S>>,
+    ) {
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                for (key, _) in old.iter().filter(|(k, _)| !new.contains_field(k)) {
+                    ops.push(PatchOperation::Remove {
+                        path: pointer(path, key),
+                    });
+                }
+                for (key, value) in new.iter() {
+                    match old.get_field_value(key) {
+                        Some(prev) => go(&pointer(path, key), prev, value, ops),
+                        None => ops.push(PatchOperation::Add {
+                            path: pointer(path, key),
+                            value: value.clone(),
+                        }),
+                    }
+                }
+            }
+            (Value::List(old), Value::List(new)) if old.len() == new.len() => {
+                for (i, (prev, value)) in old.iter().zip(new).enumerate() {
+                    go(&pointer(path, &i.to_string()), prev, value, ops);
+                }
+            }
+            (old, new) if old != new => ops.push(PatchOperation::Replace {
+                path: path.into(),
+                value: new.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    let mut ops = vec![];
+    go("", old, new, &mut ops);
+    ops
+}
+
+/// Appends the provided `key` to the given JSON pointer `path`, escaping it.
+fn pointer(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::atomic::{AtomicI32, Ordering},
+        task::Poll,
+    };
+
+    use futures::{future, StreamExt as _};
+    use juniper::{
+        graphql_object, graphql_value, http::GraphQLRequest, DefaultScalarValue, EmptyMutation,
+        EmptySubscription, Executor, FieldResult, RootNode, ScalarValue,
+        SubscriptionCoordinator as _,
+    };
+
+    use crate::Coordinator;
+
+    use super::{diff, execute_live, Invalidator, LiveResult, PatchOperation};
+
+    struct Context {
+        counter: AtomicI32,
+    }
+
+    struct Query;
+
+    #[graphql_object(context = Context)]
+    impl Query {
+        fn counter<S: ScalarValue>(executor: &Executor<Context, S>) -> i32 {
+            executor.track_invalidation_key("counter");
+            executor.context().counter.load(Ordering::SeqCst)
+        }
+
+        fn constant<S: ScalarValue>(executor: &Executor<Context, S>) -> i32 {
+            executor.track_invalidation_key("constant");
+            1
+        }
+
+        fn fail<S: ScalarValue>(executor: &Executor<Context, S>) -> FieldResult<i32> {
+            executor.track_invalidation_key("counter");
+            Err("failed".into())
+        }
+    }
+
+    type Schema = RootNode<'static, Query, EmptyMutation<Context>, EmptySubscription<Context>>;
+
+    fn schema() -> Schema {
+        RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new()).enable_live_queries()
+    }
+
+    fn request(query: &str) -> GraphQLRequest {
+        GraphQLRequest::new(query.into(), None, None)
+    }
+
+    #[tokio::test]
+    async fn re_executes_on_invalidation() {
+        let schema = schema();
+        let ctx = Context {
+            counter: AtomicI32::new(0),
+        };
+        let invalidator = Invalidator::new();
+
+        let mut stream = execute_live(
+            request("query @live { counter constant }"),
+            &schema,
+            &ctx,
+            &invalidator,
+        )
+        .await
+        .expect("execution failed");
+
+        let output = stream.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!({"counter": 0, "constant": 1}));
+
+        ctx.counter.store(1, Ordering::SeqCst);
+        assert_eq!(invalidator.invalidate("unknown"), 0);
+        assert_eq!(invalidator.invalidate("counter"), 1);
+
+        let output = stream.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!({"counter": 1, "constant": 1}));
+
+        // Results not changed are not yielded.
+        assert_eq!(invalidator.invalidate("constant"), 1);
+        future::poll_fn(|cx| {
+            assert!(stream.poll_next_unpin(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        ctx.counter.store(2, Ordering::SeqCst);
+        assert_eq!(invalidator.invalidate_all(["counter", "constant"]), 1);
+
+        let output = stream.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!({"counter": 2, "constant": 1}));
+
+        assert_eq!(invalidator.watchers_count(), 1);
+        drop(stream);
+        assert_eq!(invalidator.watchers_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let schema = schema();
+        let ctx = Context {
+            counter: AtomicI32::new(0),
+        };
+        let invalidator = Invalidator::new();
+
+        let err = execute_live(
+            request("query @live { unknown }"),
+            &schema,
+            &ctx,
+            &invalidator,
+        )
+        .await
+        .err()
+        .expect("validation should fail");
+        assert!(err.to_string().contains("Unknown field"), "{err}");
+        assert_eq!(invalidator.watchers_count(), 0);
+
+        let mut stream = execute_live(request("query @live { fail }"), &schema, &ctx, &invalidator)
+            .await
+            .expect("execution failed");
+
+        let output = stream.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!(null));
+        assert_eq!(output.errors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn yields_patches() {
+        let schema = schema();
+        let ctx = Context {
+            counter: AtomicI32::new(0),
+        };
+        let invalidator = Invalidator::new();
+
+        let mut stream = execute_live(
+            request("query @live { counter constant }"),
+            &schema,
+            &ctx,
+            &invalidator,
+        )
+        .await
+        .expect("execution failed")
+        .into_patches()
+        .boxed();
+
+        assert_eq!(
+            stream.next().await.unwrap(),
+            LiveResult::Full {
+                data: graphql_value!({"counter": 0, "constant": 1}),
+                errors: vec![],
+                revision: 1,
+            },
+        );
+
+        ctx.counter.store(5, Ordering::SeqCst);
+        invalidator.invalidate("counter");
+
+        let result = stream.next().await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "patch": [{"op": "replace", "path": "/counter", "value": 5}],
+                "revision": 2,
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn serves_via_coordinator() {
+        let invalidator = Invalidator::new();
+        let coordinator = Coordinator::new(schema()).with_live_queries(invalidator.clone());
+        let ctx = Context {
+            counter: AtomicI32::new(0),
+        };
+
+        let req = request("query @live { counter }");
+        let mut conn = coordinator
+            .subscribe(&req, &ctx)
+            .await
+            .expect("execution failed");
+
+        let output = conn.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!({"counter": 0}));
+
+        ctx.counter.store(1, Ordering::SeqCst);
+        invalidator.invalidate("counter");
+
+        let output = conn.next().await.unwrap();
+        assert_eq!(output.data, graphql_value!({"counter": 1}));
+
+        // Non-live queries are still rejected.
+        let req = request("{ counter }");
+        assert!(coordinator.subscribe(&req, &ctx).await.is_err());
+    }
+
+    #[test]
+    fn diffs_values() {
+        let old = graphql_value!({
+            "same": 1,
+            "removed": true,
+            "a/b~c": "old",
+            "nested": {"list": [1, 2, 3], "other": [1]},
+        });
+        let new = graphql_value!({
+            "same": 1,
+            "a/b~c": "new",
+            "nested": {"list": [1, 4, 3], "other": [1, 2]},
+            "added": null,
+        });
+
+        assert_eq!(
+            diff::<DefaultScalarValue>(&old, &new),
+            [
+                PatchOperation::Remove {
+                    path: "/removed".into(),
+                },
+                PatchOperation::Replace {
+                    path: "/a~1b~0c".into(),
+                    value: graphql_value!("new"),
+                },
+                PatchOperation::Replace {
+                    path: "/nested/list/1".into(),
+                    value: graphql_value!(4),
+                },
+                PatchOperation::Replace {
+                    path: "/nested/other".into(),
+                    value: graphql_value!([1, 2]),
+                },
+                PatchOperation::Add {
+                    path: "/added".into(),
+                    value: graphql_value!(null),
+                },
+            ],
+        );
+        assert_eq!(diff::<DefaultScalarValue>(&old, &old), []);
+    }
+}
@@ -0,0 +1,599 @@
+//! Publish/subscribe broker feeding subscriptions with events published elsewhere (mutations,
+//! background jobs, etc.).
+
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    task::{self, Poll, Waker},
+};
+
+use futures::{future, Stream, StreamExt as _};
+use juniper::BoxFuture;
+
+/// Typed topic of a [`PubSub`], carrying messages of type `T`.
+///
+/// Topics of different message types are distinct, even if they have the same name.
+///
+/// ```rust
+/// # use juniper_subscriptions::Topic;
+/// #
+/// #[derive(Clone)]
+/// struct Message {
+///     text: String,
+/// }
+///
+/// const MESSAGES: Topic<Message> = Topic::new("messages");
+///
+/// fn room_messages(room_id: u32) -> Topic<Message> {
+///     Topic::named(format!("messages:{room_id}"))
+/// }
+/// ```
+pub struct Topic<T> {
+    name: Cow<'static, str>,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> Topic<T> {
+    /// Creates a new [`Topic`] with the provided static `name`.
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            _message: PhantomData,
+        }
+    }
+
+    /// Creates a new [`Topic`] with the provided dynamic `name`.
+    #[must_use]
+    pub fn named(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            _message: PhantomData,
+        }
+    }
+
+    /// Returns the name of this [`Topic`].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> Clone for Topic<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Topic<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Topic").field(&self.name).finish()
+    }
+}
+
+/// Server-side filter of a [`PubSubStream`], deciding whether a published message should be
+/// delivered to it.
+pub type Filter<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Error of a [`PubSubStream`] whose consumer has fallen behind the publishers, so the specified
+/// number of the oldest messages has been skipped.
+///
+/// Converts into a [`juniper::FieldError`], so may be simply propagated to the client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Lagged(pub u64);
+
+impl fmt::Display for Lagged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "subscriber lagged behind, {} messages were skipped",
+            self.0,
+        )
+    }
+}
+
+impl Error for Lagged {}
+
+/// Stream of messages published to a [`Topic`], as returned by [`PubSub::subscribe()`].
+///
+/// Yields [`Lagged`] error if its consumer falls behind, and continues with the oldest message
+/// still retained.
+pub struct PubSubStream<T> {
+    stream: Pin<Box<dyn Stream<Item = Result<T, Lagged>> + Send>>,
+}
+
+impl<T> PubSubStream<T> {
+    /// Wraps the provided `stream` into a [`PubSubStream`].
+    ///
+    /// Intended for [`PubSub`] implementations.
+    pub fn new(stream: impl Stream<Item = Result<T, Lagged>> + Send + 'static) -> Self {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+
+    /// Converts this [`PubSubStream`] into a stream of messages only, silently skipping
+    /// [`Lagged`] errors.
+    pub fn ignore_lagged(self) -> impl Stream<Item = T> + Send
+    where
+        T: Send + 'static,
+    {
+        self.filter_map(|res| future::ready(res.ok()))
+    }
+}
+
+impl<T> Stream for PubSubStream<T> {
+    type Item = Result<T, Lagged>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+/// Transport-agnostic publish/subscribe broker of messages of type `T`.
+///
+/// Usually, is provided to resolvers via the context: mutations [`publish()`] messages, while
+/// subscriptions [`subscribe()`] to them. [`InMemoryPubSub`] is the default implementation for a
+/// single process, while implementations backed by external brokers (Redis, NATS, etc.) are
+/// able to restrict `T` to their encoding needs.
+///
+/// [`publish()`]: PubSub::publish
+/// [`subscribe()`]: PubSub::subscribe
+pub trait PubSub<T>: Send + Sync {
+    /// Error of publishing or subscribing.
+    type Error: Error + Send + Sync + 'static;
+
+    /// Publishes the provided `message` to the given `topic`, returning the number of subscribers
+    /// it has been delivered to (as far as it's known).
+    fn publish<'a>(
+        &'a self,
+        topic: &'a Topic<T>,
+        message: T,
+    ) -> BoxFuture<'a, Result<usize, Self::Error>>;
+
+    /// Subscribes to the messages of the given `topic` passing the provided `filter`.
+    ///
+    /// The `filter` is evaluated for each published message before it's buffered for the
+    /// returned [`PubSubStream`], so filtered out messages never cause it to lag.
+    fn subscribe_filtered<'a>(
+        &'a self,
+        topic: &'a Topic<T>,
+        filter: Filter<T>,
+    ) -> BoxFuture<'a, Result<PubSubStream<T>, Self::Error>>;
+
+    /// Subscribes to all the messages of the given `topic`.
+    fn subscribe<'a>(
+        &'a self,
+        topic: &'a Topic<T>,
+    ) -> BoxFuture<'a, Result<PubSubStream<T>, Self::Error>> {
+        self.subscribe_filtered(topic, Box::new(|_| true))
+    }
+}
+
+/// Default number of messages buffered for each subscriber of an [`InMemoryPubSub`].
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// [`PubSub`] delivering messages in memory of a single process.
+///
+/// Buffers up to [`capacity`] messages for each subscriber, dropping the oldest ones (and
+/// reporting [`Lagged`]) once a subscriber falls behind. Topics are created on the first
+/// subscription, and removed once all their subscribers are dropped.
+///
+/// Cloning is cheap and shares the same topics.
+///
+/// [`capacity`]: InMemoryPubSub::with_capacity
+#[derive(Clone)]
+pub struct InMemoryPubSub {
+    topics: Arc<Topics>,
+    capacity: usize,
+}
+
+impl InMemoryPubSub {
+    /// Creates a new [`InMemoryPubSub`] with the [`DEFAULT_CAPACITY`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            topics: Arc::default(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Specifies the number of messages buffered for each subscriber before it starts lagging.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "`InMemoryPubSub` capacity must be positive");
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the number of topics having subscribers.
+    pub fn topics_count(&self) -> usize {
+        self.topics.lock().len()
+    }
+
+    /// Returns the number of subscribers of the given `topic`.
+    pub fn subscribers_count<T: Send + 'static>(&self, topic: &Topic<T>) -> usize {
+        self.topics
+            .lock()
+            .get_mut(&TopicKey::of(topic))
+            .and_then(|t| t.as_any_mut().downcast_mut::<TopicState<T>>())
+            .map_or(0, |t| t.subscribers.len())
+    }
+}
+
+impl Default for InMemoryPubSub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for InMemoryPubSub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryPubSub")
+            .field("topics", &self.topics_count())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl<T: Clone + Send + 'static> PubSub<T> for InMemoryPubSub {
+    type Error = Infallible;
+
+    fn publish<'a>(
+        &'a self,
+        topic: &'a Topic<T>,
+        message: T,
+    ) -> BoxFuture<'a, Result<usize, Self::Error>> {
+        // Filters are arbitrary user code, so are run outside of the topics lock.
+        let subscribers = self
+            .topics
+            .lock()
+            .get_mut(&TopicKey::of(topic))
+            .and_then(|t| t.as_any_mut().downcast_mut::<TopicState<T>>())
+            .map(|t| t.subscribers.values().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let delivered = subscribers
+            .iter()
+            .filter(|s| s.deliver(&message, self.capacity))
+            .count();
+
+        Box::pin(future::ready(Ok(delivered)))
+    }
+
+    fn subscribe_filtered<'a>(
+        &'a self,
+        topic: &'a Topic<T>,
+        filter: Filter<T>,
+    ) -> BoxFuture<'a, Result<PubSubStream<T>, Self::Error>> {
+        let key = TopicKey::of(topic);
+        let subscriber = Arc::new(Subscriber {
+            filter,
+            queue: Mutex::new(Queue {
+                messages: VecDeque::new(),
+                lagged: 0,
+                waker: None,
+                is_closed: false,
+            }),
+        });
+
+        let id = {
+            let mut topics = self.topics.lock();
+            let state = topics
+                .entry(key.clone())
+                .or_insert_with(|| Box::new(TopicState::<T>::default()))
+                .as_any_mut()
+                .downcast_mut::<TopicState<T>>()
+                .expect("`TopicKey` contains `TypeId` of messages");
+            state.next_id += 1;
+            state.subscribers.insert(state.next_id, subscriber.clone());
+            state.next_id
+        };
+
+        let stream = InMemoryStream {
+            subscriber,
+            _registration: Registration {
+                topics: Arc::downgrade(&self.topics),
+                key,
+                id,
+                _message: PhantomData::<fn() -> T>,
+            },
+        };
+
+        Box::pin(future::ready(Ok(PubSubStream::new(stream))))
+    }
+}
+
+/// Key of a topic in [`Topics`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct TopicKey {
+    name: Cow<'static, str>,
+    message: TypeId,
+}
+
+impl TopicKey {
+    fn of<T: 'static>(topic: &Topic<T>) -> Self {
+        Self {
+            name: topic.name.clone(),
+            message: TypeId::of::<T>(),
+        }
+    }
+}
+
+/// Topics of an [`InMemoryPubSub`] with their subscribers.
+#[derive(Default)]
+struct Topics(Mutex<HashMap<TopicKey, Box<dyn ErasedTopic>>>);
+
+impl Topics {
+    fn lock(&self) -> MutexGuard<'_, HashMap<TopicKey, Box<dyn ErasedTopic>>> {
+        // Poisoning is impossible to be observed inconsistently here, as the map is never left
+        // half-modified.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Topics {
+    fn drop(&mut self) {
+        // No more messages can be published, so end all the streams.
+        for topic in self.lock().values_mut() {
+            topic.close();
+        }
+    }
+}
+
+/// Type-erased [`TopicState`].
+trait ErasedTopic: Send {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Removes the subscriber with the provided `id`, returning whether no subscribers left.
+    fn unsubscribe(&mut self, id: u64) -> bool;
+
+    fn close(&mut self);
+}
+
+/// Subscribers of a single topic.
+struct TopicState<T> {
+    subscribers: HashMap<u64, Arc<Subscriber<T>>>,
+    next_id: u64,
+}
+
+impl<T> Default for TopicState<T> {
+    fn default() -> Self {
+        Self {
+            subscribers: HashMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<T: Send + 'static> ErasedTopic for TopicState<T> {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn unsubscribe(&mut self, id: u64) -> bool {
+        self.subscribers.remove(&id);
+        self.subscribers.is_empty()
+    }
+
+    fn close(&mut self) {
+        for subscriber in self.subscribers.values() {
+            let mut queue = subscriber.lock();
+            queue.is_closed = true;
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// Single subscriber of a topic.
+struct Subscriber<T> {
+    filter: Filter<T>,
+    queue: Mutex<Queue<T>>,
+}
+
+impl<T> Subscriber<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T: Clone> Subscriber<T> {
+    /// Buffers the provided `message` if it passes the filter of this [`Subscriber`], returning
+    /// whether it was buffered.
+    fn deliver(&self, message: &T, capacity: usize) -> bool {
+        if !(self.filter)(message) {
+            return false;
+        }
+
+        let mut queue = self.lock();
+        if queue.messages.len() >= capacity {
+            queue.messages.pop_front();
+            queue.lagged += 1;
+        }
+        queue.messages.push_back(message.clone());
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+        true
+    }
+}
+
+/// Buffered messages of a [`Subscriber`].
+struct Queue<T> {
+    messages: VecDeque<T>,
+    lagged: u64,
+    waker: Option<Waker>,
+    is_closed: bool,
+}
+
+/// Registration of a [`Subscriber`] in [`Topics`], removing it once dropped.
+struct Registration<T> {
+    topics: Weak<Topics>,
+    key: TopicKey,
+    id: u64,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> Drop for Registration<T> {
+    fn drop(&mut self) {
+        let topics = match self.topics.upgrade() {
+            Some(topics) => topics,
+            None => return,
+        };
+        let mut topics = topics.lock();
+        if let Some(topic) = topics.get_mut(&self.key) {
+            if topic.unsubscribe(self.id) {
+                topics.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// [`Stream`] of an [`InMemoryPubSub`] subscriber.
+struct InMemoryStream<T> {
+    subscriber: Arc<Subscriber<T>>,
+
+    /// Unsubscribes once this [`InMemoryStream`] is dropped.
+    _registration: Registration<T>,
+}
+
+impl<T: Clone> Stream for InMemoryStream<T> {
+    type Item = Result<T, Lagged>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut queue = self.subscriber.lock();
+
+        if queue.lagged > 0 {
+            return Poll::Ready(Some(Err(Lagged(std::mem::take(&mut queue.lagged)))));
+        }
+        if let Some(message) = queue.messages.pop_front() {
+            return Poll::Ready(Some(Ok(message)));
+        }
+        if queue.is_closed {
+            return Poll::Ready(None);
+        }
+
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{FutureExt as _, StreamExt as _};
+
+    use super::{InMemoryPubSub, Lagged, PubSub as _, Topic};
+
+    const NUMBERS: Topic<i32> = Topic::new("numbers");
+
+    #[tokio::test]
+    async fn delivers_to_all_subscribers() {
+        let pubsub = InMemoryPubSub::new();
+        let mut first = pubsub.subscribe(&NUMBERS).await.unwrap();
+        let mut second = pubsub.subscribe(&NUMBERS).await.unwrap();
+
+        assert_eq!(pubsub.publish(&NUMBERS, 1).await, Ok(2));
+        assert_eq!(pubsub.publish(&NUMBERS, 2).await, Ok(2));
+
+        assert_eq!(first.next().await, Some(Ok(1)));
+        assert_eq!(first.next().await, Some(Ok(2)));
+        assert_eq!(second.next().await, Some(Ok(1)));
+        assert_eq!(second.next().await, Some(Ok(2)));
+        assert_eq!(first.next().now_or_never(), None);
+    }
+
+    #[tokio::test]
+    async fn distinguishes_topics() {
+        let pubsub = InMemoryPubSub::new();
+        let mut numbers = pubsub.subscribe(&NUMBERS).await.unwrap();
+        let mut names = pubsub
+            .subscribe(&Topic::<String>::new("numbers"))
+            .await
+            .unwrap();
+
+        assert_eq!(pubsub.publish(&Topic::named("other"), 1).await, Ok(0));
+        assert_eq!(pubsub.publish(&NUMBERS, 2).await, Ok(1));
+
+        assert_eq!(numbers.next().await, Some(Ok(2)));
+        assert_eq!(names.next().now_or_never(), None);
+    }
+
+    #[tokio::test]
+    async fn filters_before_buffering() {
+        let pubsub = InMemoryPubSub::new().with_capacity(2);
+        let mut even = pubsub
+            .subscribe_filtered(&NUMBERS, Box::new(|n| n % 2 == 0))
+            .await
+            .unwrap();
+
+        for n in 1..=4 {
+            pubsub.publish(&NUMBERS, n).await.unwrap();
+        }
+
+        assert_eq!(even.next().await, Some(Ok(2)));
+        assert_eq!(even.next().await, Some(Ok(4)));
+        assert_eq!(even.next().now_or_never(), None);
+    }
+
+    #[tokio::test]
+    async fn reports_lag() {
+        let pubsub = InMemoryPubSub::new().with_capacity(2);
+        let mut numbers = pubsub.subscribe(&NUMBERS).await.unwrap();
+
+        for n in 1..=5 {
+            pubsub.publish(&NUMBERS, n).await.unwrap();
+        }
+
+        assert_eq!(numbers.next().await, Some(Err(Lagged(3))));
+        assert_eq!(numbers.next().await, Some(Ok(4)));
+        assert_eq!(numbers.next().await, Some(Ok(5)));
+
+        pubsub.publish(&NUMBERS, 6).await.unwrap();
+        let rest = numbers.ignore_lagged().next().await;
+        assert_eq!(rest, Some(6));
+    }
+
+    #[tokio::test]
+    async fn removes_topics_without_subscribers() {
+        let pubsub = InMemoryPubSub::new();
+        let first = pubsub.subscribe(&NUMBERS).await.unwrap();
+        let second = pubsub.subscribe(&NUMBERS).await.unwrap();
+
+        assert_eq!(pubsub.topics_count(), 1);
+        assert_eq!(pubsub.subscribers_count(&NUMBERS), 2);
+
+        drop(first);
+        assert_eq!(pubsub.subscribers_count(&NUMBERS), 1);
+
+        drop(second);
+        assert_eq!(pubsub.topics_count(), 0);
+        assert_eq!(pubsub.publish(&NUMBERS, 1).await, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn ends_streams_once_dropped() {
+        let pubsub = InMemoryPubSub::new();
+        let mut numbers = pubsub.subscribe(&NUMBERS).await.unwrap();
+
+        pubsub.publish(&NUMBERS, 1).await.unwrap();
+        drop(pubsub);
+
+        assert_eq!(numbers.next().await, Some(Ok(1)));
+        assert_eq!(numbers.next().await, None);
+    }
+}
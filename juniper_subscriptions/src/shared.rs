@@ -0,0 +1,877 @@
+//! Sharing of a single execution between identical subscriptions, so a subscription requested by
+//! many clients at once runs its resolvers once per event, instead of once per client.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use futures::{
+    channel::mpsc,
+    future, stream,
+    stream::BoxStream,
+    task::{waker_ref, ArcWake},
+    FutureExt as _, SinkExt as _, Stream, StreamExt as _,
+};
+use juniper::{
+    http::GraphQLRequest, parser::parse_document_source_with_limits, print_document_minified,
+    BoxFuture, ExecutionOutput, GraphQLError, GraphQLSubscriptionType, GraphQLTypeAsync, RootNode,
+    ScalarValue, SubscriptionCoordinator,
+};
+
+use crate::{whole_responses_stream, Connection};
+
+/// Default number of outputs buffered for each subscription of a [`SharedCoordinator`].
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Function deriving a partition key from a context. Subscriptions are shared only between the
+/// contexts having the same partition key.
+pub type PartitionKey<CtxT> = Box<dyn Fn(&CtxT) -> String + Send + Sync>;
+
+/// [`SubscriptionCoordinator`] sharing a single execution between identical subscriptions.
+///
+/// Subscriptions are identical if they have the same document (ignoring whitespace and comments),
+/// operation name, variables, and the [`PartitionKey`] of their contexts. Documents selecting the
+/// same fields in different order are not identical, as their responses differ in order too. The first of them is resolved with a
+/// clone of its context, while the others receive clones of its [`ExecutionOutput`]s. So, the
+/// [`PartitionKey`] must capture everything in the context affecting the resolution (the user
+/// identity, its permissions, etc.), otherwise one client may receive data meant for another.
+/// [`GraphQLRequest::extensions`] are not compared either.
+///
+/// Subscriptions joining an already running execution receive only the outputs emitted after
+/// they have joined. The execution progresses while any of its subscriptions is polled. Once all the subscriptions of an execution are dropped, it's dropped too.
+///
+/// Up to [`capacity`] outputs are buffered for each subscription. A subscription falling behind
+/// further is ended (so its client may subscribe again), rather than slowing down the others or
+/// buffering without bound.
+///
+/// [`capacity`]: SharedCoordinator::with_capacity
+pub struct SharedCoordinator<QueryT, MutationT, SubscriptionT, CtxT, S>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Clone + Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+{
+    root_node: Arc<RootNode<'static, QueryT, MutationT, SubscriptionT, S>>,
+    partition_key: PartitionKey<CtxT>,
+    upstreams: Arc<Upstreams<S>>,
+    capacity: usize,
+}
+
+impl<QueryT, MutationT, SubscriptionT, CtxT, S>
+    SharedCoordinator<QueryT, MutationT, SubscriptionT, CtxT, S>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Clone + Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+{
+    /// Builds a new [`SharedCoordinator`] with the specified `root_node`, sharing subscriptions
+    /// between the contexts having the same `partition_key`, and buffering up to the
+    /// [`DEFAULT_CAPACITY`] outputs for each of them.
+    pub fn new(
+        root_node: RootNode<'static, QueryT, MutationT, SubscriptionT, S>,
+        partition_key: impl Fn(&CtxT) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            root_node: Arc::new(root_node),
+            partition_key: Box::new(partition_key),
+            upstreams: Arc::default(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    /// Specifies the number of outputs buffered for each subscription before it's ended as
+    /// lagging.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "`SharedCoordinator` capacity must be positive"
+        );
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the number of running shared executions.
+    pub fn upstreams_count(&self) -> usize {
+        self.upstreams.lock().len()
+    }
+
+    /// Computes the [`SharedKey`] of the provided `req` in the given `context`, if the `req` may
+    /// be shared at all.
+    fn key(&self, req: &GraphQLRequest<S>, context: &CtxT) -> Option<SharedKey> {
+        // Documents failing to parse are not executed anyway, so aren't worth sharing.
+        let doc = parse_document_source_with_limits(
+            &req.query,
+            &self.root_node.schema,
+            self.root_node.limits,
+        )
+        .ok()?;
+        let variables = req.variables().into_iter().collect::<BTreeMap<_, _>>();
+        Some(SharedKey {
+            partition: (self.partition_key)(context),
+            document: print_document_minified(&doc),
+            operation_name: req.operation_name.clone(),
+            variables: serde_json::to_string(&variables).ok()?,
+        })
+    }
+
+    /// Starts the execution of the provided `req` in the given `context`, returning the stream of
+    /// its [`Event`]s.
+    ///
+    /// The returned stream owns all its arguments, so may be shared between subscriptions.
+    fn execute(&self, req: GraphQLRequest<S>, context: CtxT) -> BoxStream<'static, Event<S>> {
+        let root_node = self.root_node.clone();
+
+        // The operation's stream borrows the `root_node`, `req` and `context`, so is driven inside
+        // a future owning them, forwarding its events through a channel.
+        let (mut tx, rx) = mpsc::channel(0);
+
+        let driver = async move {
+            let (stream, errors) =
+                match juniper::http::resolve_into_stream(&req, &root_node, &context).await {
+                    Ok(res) => res,
+                    Err(e) => {
+                        let _ = tx.send(Event::Started(Err(e))).await;
+                        return;
+                    }
+                };
+            if tx.send(Event::Started(Ok(()))).await.is_err() {
+                return;
+            }
+
+            let mut outputs =
+                whole_responses_stream(stream, errors).map(|out| Ok(Event::Output(out)));
+            // Failing to send means all the subscriptions have gone away.
+            let _ = tx.send_all(&mut outputs).await;
+        };
+
+        stream::select(rx.map(Some), driver.into_stream().map(|()| None))
+            .filter_map(future::ready)
+            .boxed()
+    }
+}
+
+impl<QueryT, MutationT, SubscriptionT, CtxT, S> fmt::Debug
+    for SharedCoordinator<QueryT, MutationT, SubscriptionT, CtxT, S>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Clone + Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedCoordinator")
+            .field("upstreams", &self.upstreams_count())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, CtxT, S> SubscriptionCoordinator<'a, CtxT, S>
+    for SharedCoordinator<QueryT, MutationT, SubscriptionT, CtxT, S>
+where
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Clone + Send + Sync + 'static,
+    S: ScalarValue + Send + Sync,
+{
+    type Connection = Connection<'a, S>;
+    type Error = GraphQLError;
+
+    fn subscribe(
+        &'a self,
+        req: &'a GraphQLRequest<S>,
+        context: &'a CtxT,
+    ) -> BoxFuture<'a, Result<Self::Connection, Self::Error>> {
+        let key = self.key(req, context);
+        if let Some(stream) = key.as_ref().and_then(|key| self.upstreams.join(key)) {
+            return future::ready(Ok(Connection::from_shared(stream))).boxed();
+        }
+
+        let mut events = self.execute(req.clone(), context.clone());
+        async move {
+            // Operation errors are reported only by the subscription starting the execution, so
+            // it's shared only once started successfully.
+            match events.next().await {
+                Some(Event::Started(Ok(()))) => {}
+                Some(Event::Started(Err(e))) => return Err(e),
+                Some(Event::Output(_)) => unreachable!("`Event::Started` is emitted first"),
+                None => unreachable!("`Event::Started` is always emitted"),
+            }
+            let outputs = events.filter_map(|ev| {
+                future::ready(match ev {
+                    Event::Output(out) => Some(out),
+                    Event::Started(_) => None,
+                })
+            });
+            Ok(Connection::from_shared(self.upstreams.insert(
+                key,
+                outputs.boxed(),
+                self.capacity,
+            )))
+        }
+        .boxed()
+    }
+}
+
+impl<'a, S> Connection<'a, S>
+where
+    S: ScalarValue + Send + Sync + 'a,
+{
+    /// Creates a new [`Connection`] of a shared execution.
+    fn from_shared(stream: SharedStream<S>) -> Self {
+        Self {
+            stream: Box::pin(stream),
+        }
+    }
+}
+
+/// Event of an execution started by [`SharedCoordinator::execute()`].
+enum Event<S> {
+    /// Result of starting the execution, always emitted first.
+    Started(Result<(), GraphQLError>),
+
+    /// Output of the execution.
+    Output(ExecutionOutput<S>),
+}
+
+/// Key identifying identical subscriptions.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct SharedKey {
+    partition: String,
+    document: String,
+    operation_name: Option<String>,
+    variables: String,
+}
+
+/// Running shared executions of a [`SharedCoordinator`].
+struct Upstreams<S>(Mutex<HashMap<SharedKey, Arc<Upstream<S>>>>);
+
+impl<S> Default for Upstreams<S> {
+    fn default() -> Self {
+        Self(Mutex::default())
+    }
+}
+
+impl<S: Clone + Send + 'static> Upstreams<S> {
+    fn lock(&self) -> MutexGuard<'_, HashMap<SharedKey, Arc<Upstream<S>>>> {
+        // Poisoning is impossible to be observed inconsistently here, as the map is never left
+        // half-modified.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Subscribes to the running execution with the provided `key`, if any.
+    fn join(self: &Arc<Self>, key: &SharedKey) -> Option<SharedStream<S>> {
+        let upstreams = self.lock();
+        let upstream = upstreams.get(key)?;
+        let id = upstream.lock().subscribe()?;
+        Some(SharedStream {
+            upstream: upstream.clone(),
+            id,
+            upstreams: Arc::downgrade(self),
+            key: Some(key.clone()),
+        })
+    }
+
+    /// Registers the provided execution `outputs` with the given `key` (if it may be shared at
+    /// all), returning its first subscription.
+    ///
+    /// Each subscription of the execution buffers up to `capacity` outputs.
+    fn insert(
+        self: &Arc<Self>,
+        key: Option<SharedKey>,
+        outputs: BoxStream<'static, ExecutionOutput<S>>,
+        capacity: usize,
+    ) -> SharedStream<S> {
+        let mut state = UpstreamState {
+            stream: Some(outputs),
+            subscribers: HashMap::new(),
+            next_id: 0,
+            capacity,
+        };
+        let id = state
+            .subscribe()
+            .expect("`UpstreamState::stream` is present");
+        let upstream = Arc::new(Upstream {
+            state: Mutex::new(state),
+            wakers: Arc::default(),
+        });
+
+        if let Some(key) = &key {
+            // May replace an identical execution started concurrently, which is fine, as it keeps
+            // serving its subscriptions anyway.
+            self.lock().insert(key.clone(), upstream.clone());
+        }
+
+        SharedStream {
+            upstream,
+            id,
+            upstreams: Arc::downgrade(self),
+            key,
+        }
+    }
+}
+
+/// Shared execution along with its subscriptions.
+struct Upstream<S> {
+    state: Mutex<UpstreamState<S>>,
+
+    /// [`Waker`]s of the subscriptions waiting for the execution, all woken by it.
+    wakers: Arc<Wakers>,
+}
+
+impl<S> Upstream<S> {
+    fn lock(&self) -> MutexGuard<'_, UpstreamState<S>> {
+        // Poisoning is impossible to be observed inconsistently here, as the state is never left
+        // half-modified.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// [`Waker`]s of the subscriptions waiting for a shared execution.
+///
+/// The execution is polled with a [`Waker`] waking all of them (like [`future::Shared`] does), so
+/// it progresses as long as any of its subscriptions is polled, regardless of which one has polled
+/// it last.
+#[derive(Default)]
+struct Wakers(Mutex<HashMap<u64, Waker>>);
+
+impl Wakers {
+    fn lock(&self) -> MutexGuard<'_, HashMap<u64, Waker>> {
+        // Poisoning is impossible to be observed inconsistently here, as the map is never left
+        // half-modified.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers the `waker` of the subscription with the provided `id`.
+    fn register(&self, id: u64, waker: &Waker) {
+        let mut wakers = self.lock();
+        match wakers.get_mut(&id) {
+            Some(w) if w.will_wake(waker) => {}
+            Some(w) => *w = waker.clone(),
+            None => {
+                wakers.insert(id, waker.clone());
+            }
+        }
+    }
+
+    /// Unregisters the [`Waker`] of the subscription with the provided `id`.
+    fn unregister(&self, id: u64) {
+        self.lock().remove(&id);
+    }
+
+    /// Wakes all the registered [`Waker`]s, unregistering them.
+    fn wake_all(&self) {
+        // Woken outside the lock, as waking may re-register synchronously.
+        let wakers = self.lock().drain().map(|(_, w)| w).collect::<Vec<_>>();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl ArcWake for Wakers {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.wake_all();
+    }
+}
+
+struct UpstreamState<S> {
+    /// Outputs of the execution, or [`None`] once it has ended.
+    stream: Option<BoxStream<'static, ExecutionOutput<S>>>,
+
+    /// Subscriptions of the execution.
+    subscribers: HashMap<u64, Subscriber<S>>,
+
+    next_id: u64,
+
+    /// Maximum number of outputs buffered for each subscription.
+    capacity: usize,
+}
+
+impl<S> UpstreamState<S> {
+    /// Adds a new subscriber, returning its ID, unless the execution has ended already.
+    fn subscribe(&mut self) -> Option<u64> {
+        self.stream.as_ref()?;
+        self.next_id += 1;
+        self.subscribers.insert(self.next_id, Subscriber::default());
+        Some(self.next_id)
+    }
+}
+
+/// Subscription of a shared execution, along with the outputs not received by it yet.
+struct Subscriber<S> {
+    queue: VecDeque<ExecutionOutput<S>>,
+
+    /// Whether an output has been missed because of the full `queue`, so the subscription is to
+    /// be ended.
+    is_lagging: bool,
+}
+
+impl<S> Default for Subscriber<S> {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            is_lagging: false,
+        }
+    }
+}
+
+/// Stream of [`ExecutionOutput`]s of a shared execution.
+///
+/// The execution is polled by whichever of its subscriptions is polled, and each its output is
+/// buffered for all the other subscriptions.
+struct SharedStream<S: Clone + Send + 'static> {
+    upstream: Arc<Upstream<S>>,
+    id: u64,
+    upstreams: Weak<Upstreams<S>>,
+
+    /// Key the execution is registered with in the [`Upstreams`], if any.
+    key: Option<SharedKey>,
+}
+
+impl<S: Clone + Send + 'static> Stream for SharedStream<S> {
+    type Item = ExecutionOutput<S>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let id = self.id;
+        let mut state = self.upstream.lock();
+        let state = &mut *state;
+
+        if let Some(sub) = state.subscribers.get_mut(&id) {
+            if let Some(out) = sub.queue.pop_front() {
+                return Poll::Ready(Some(out));
+            }
+            if sub.is_lagging {
+                return Poll::Ready(None);
+            }
+        }
+
+        let stream = match &mut state.stream {
+            Some(stream) => stream,
+            None => return Poll::Ready(None),
+        };
+        // Registered before polling, so no wake-up is missed.
+        let wakers = &self.upstream.wakers;
+        wakers.register(id, cx.waker());
+        let res = stream.poll_next_unpin(&mut Context::from_waker(&waker_ref(wakers)));
+        if res.is_pending() {
+            return Poll::Pending;
+        }
+        wakers.unregister(id);
+
+        if let Poll::Ready(Some(out)) = &res {
+            for (_, sub) in state
+                .subscribers
+                .iter_mut()
+                .filter(|(sub_id, _)| **sub_id != id)
+            {
+                if sub.queue.len() >= state.capacity {
+                    // Ends once the buffered outputs are received.
+                    sub.is_lagging = true;
+                } else if !sub.is_lagging {
+                    sub.queue.push_back(out.clone());
+                }
+            }
+        } else {
+            state.stream = None;
+        }
+        // Other subscriptions have something to receive now.
+        wakers.wake_all();
+        res
+    }
+}
+
+impl<S: Clone + Send + 'static> Drop for SharedStream<S> {
+    fn drop(&mut self) {
+        // Lock the registry first (as `Upstreams::join()` does), so no subscription may join while
+        // the execution is being removed.
+        let upstreams = self.upstreams.upgrade();
+        let mut upstreams = upstreams.as_ref().map(|u| u.lock());
+
+        let mut state = self.upstream.lock();
+        state.subscribers.remove(&self.id);
+        self.upstream.wakers.unregister(self.id);
+
+        if state.subscribers.is_empty() {
+            state.stream = None;
+            if let (Some(upstreams), Some(key)) = (&mut upstreams, &self.key) {
+                if upstreams
+                    .get(key)
+                    .map_or(false, |u| Arc::ptr_eq(u, &self.upstream))
+                {
+                    upstreams.remove(key);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+        task,
+    };
+
+    use futures::{
+        task::{waker, ArcWake},
+        FutureExt as _, Stream, StreamExt as _, TryStreamExt as _,
+    };
+    use juniper::{
+        graphql_input_value, graphql_object, graphql_subscription, graphql_value,
+        http::GraphQLRequest, parser::DocumentLimits, DefaultScalarValue, EmptyMutation,
+        FieldError, GraphQLError, GraphQLObject, RootNode, SubscriptionCoordinator as _,
+    };
+
+    use crate::{InMemoryPubSub, PubSub as _, Topic};
+
+    use super::SharedCoordinator;
+
+    const MESSAGES: Topic<String> = Topic::new("messages");
+
+    #[derive(Clone)]
+    struct Context {
+        user: String,
+        pubsub: InMemoryPubSub,
+        resolved: Arc<AtomicUsize>,
+    }
+
+    impl juniper::Context for Context {}
+
+    struct Query;
+
+    #[graphql_object(context = Context)]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    type Messages = std::pin::Pin<Box<dyn Stream<Item = Result<String, FieldError>> + Send>>;
+
+    #[derive(GraphQLObject)]
+    #[graphql(context = Context)]
+    struct Message {
+        user: String,
+        text: String,
+    }
+
+    type MessageObjects = std::pin::Pin<Box<dyn Stream<Item = Result<Message, FieldError>> + Send>>;
+
+    #[graphql_subscription(context = Context)]
+    impl Subscription {
+        async fn messages(prefix: String, ctx: &Context) -> Messages {
+            ctx.resolved.fetch_add(1, Ordering::SeqCst);
+            let user = ctx.user.clone();
+            let stream = ctx
+                .pubsub
+                .subscribe(&MESSAGES)
+                .await
+                .unwrap_or_else(|e| match e {});
+            Box::pin(
+                stream
+                    .map_ok(move |text| format!("{prefix}{user}: {text}"))
+                    .map_err(FieldError::from),
+            )
+        }
+
+        async fn message_objects(ctx: &Context) -> MessageObjects {
+            ctx.resolved.fetch_add(1, Ordering::SeqCst);
+            let user = ctx.user.clone();
+            let stream = ctx
+                .pubsub
+                .subscribe(&MESSAGES)
+                .await
+                .unwrap_or_else(|e| match e {});
+            Box::pin(
+                stream
+                    .map_ok(move |text| Message {
+                        user: user.clone(),
+                        text,
+                    })
+                    .map_err(FieldError::from),
+            )
+        }
+    }
+
+    type Coordinator =
+        SharedCoordinator<Query, EmptyMutation<Context>, Subscription, Context, DefaultScalarValue>;
+
+    fn coordinator() -> Coordinator {
+        SharedCoordinator::new(
+            RootNode::new(Query, EmptyMutation::new(), Subscription),
+            |ctx: &Context| ctx.user.clone(),
+        )
+    }
+
+    fn context(user: &str, pubsub: &InMemoryPubSub) -> Context {
+        Context {
+            user: user.into(),
+            pubsub: pubsub.clone(),
+            resolved: Arc::default(),
+        }
+    }
+
+    fn request(query: &str) -> GraphQLRequest {
+        GraphQLRequest::new(
+            query.into(),
+            None,
+            Some(graphql_input_value!({"prefix": "> "})),
+        )
+    }
+
+    #[tokio::test]
+    async fn shares_identical_subscriptions() {
+        let coordinator = coordinator();
+        let pubsub = InMemoryPubSub::new();
+        let (ctx1, ctx2) = (context("alice", &pubsub), context("alice", &pubsub));
+        let req1 = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+        let req2 = request(
+            "subscription ($prefix: String!) {\n  messages(prefix: $prefix)\n  # comment\n}",
+        );
+
+        let mut conn1 = coordinator.subscribe(&req1, &ctx1).await.unwrap();
+        let mut conn2 = coordinator.subscribe(&req2, &ctx2).await.unwrap();
+
+        assert_eq!(coordinator.upstreams_count(), 1);
+        assert_eq!(ctx1.resolved.load(Ordering::SeqCst), 1);
+        assert_eq!(ctx2.resolved.load(Ordering::SeqCst), 0);
+        assert_eq!(pubsub.subscribers_count(&MESSAGES), 1);
+
+        pubsub.publish(&MESSAGES, "hi".into()).await.unwrap();
+
+        for conn in [&mut conn1, &mut conn2] {
+            let out = conn.next().await.unwrap();
+            assert_eq!(out.data, graphql_value!({"messages": "> alice: hi"}));
+            assert_eq!(out.errors, []);
+        }
+
+        drop(conn1);
+        pubsub.publish(&MESSAGES, "bye".into()).await.unwrap();
+
+        let out = conn2.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> alice: bye"}));
+
+        drop(conn2);
+        assert_eq!(coordinator.upstreams_count(), 0);
+        assert_eq!(pubsub.subscribers_count(&MESSAGES), 0);
+    }
+
+    #[tokio::test]
+    async fn isolates_partitions() {
+        let coordinator = coordinator();
+        let pubsub = InMemoryPubSub::new();
+        let (alice, bob) = (context("alice", &pubsub), context("bob", &pubsub));
+        let req = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+
+        let mut conn1 = coordinator.subscribe(&req, &alice).await.unwrap();
+        let mut conn2 = coordinator.subscribe(&req, &bob).await.unwrap();
+
+        assert_eq!(coordinator.upstreams_count(), 2);
+        assert_eq!(pubsub.subscribers_count(&MESSAGES), 2);
+
+        pubsub.publish(&MESSAGES, "hi".into()).await.unwrap();
+
+        let out = conn1.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> alice: hi"}));
+        let out = conn2.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> bob: hi"}));
+    }
+
+    #[tokio::test]
+    async fn distinguishes_variables() {
+        let coordinator = coordinator();
+        let pubsub = InMemoryPubSub::new();
+        let ctx = context("alice", &pubsub);
+        let req1 = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+        let mut req2 = req1.clone();
+        req2.variables = Some(graphql_input_value!({"prefix": "# "}));
+
+        let _conn1 = coordinator.subscribe(&req1, &ctx).await.unwrap();
+        let _conn2 = coordinator.subscribe(&req2, &ctx).await.unwrap();
+
+        assert_eq!(coordinator.upstreams_count(), 2);
+        assert_eq!(ctx.resolved.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn ends_lagging_subscriptions() {
+        let coordinator = coordinator().with_capacity(2);
+        let pubsub = InMemoryPubSub::new();
+        let ctx = context("alice", &pubsub);
+        let req = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+
+        let mut conn1 = coordinator.subscribe(&req, &ctx).await.unwrap();
+        let mut conn2 = coordinator.subscribe(&req, &ctx).await.unwrap();
+
+        for text in ["1", "2", "3", "4"] {
+            pubsub.publish(&MESSAGES, text.into()).await.unwrap();
+            let out = conn1.next().await.unwrap();
+            assert_eq!(
+                out.data,
+                graphql_value!({"messages": (format!("> alice: {text}"))})
+            );
+        }
+
+        for text in ["1", "2"] {
+            let out = conn2.next().await.unwrap();
+            assert_eq!(
+                out.data,
+                graphql_value!({"messages": (format!("> alice: {text}"))})
+            );
+        }
+        assert!(conn2.next().await.is_none());
+
+        drop(conn2);
+        pubsub.publish(&MESSAGES, "5".into()).await.unwrap();
+
+        let out = conn1.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> alice: 5"}));
+    }
+
+    /// [`ArcWake`] recording whether it has been woken.
+    #[derive(Default)]
+    struct Woken(AtomicBool);
+
+    impl ArcWake for Woken {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn progresses_without_last_polling_subscription() {
+        let coordinator = coordinator();
+        let pubsub = InMemoryPubSub::new();
+        let ctx = context("alice", &pubsub);
+        let req = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+
+        let mut conn1 = coordinator.subscribe(&req, &ctx).await.unwrap();
+        let mut conn2 = coordinator.subscribe(&req, &ctx).await.unwrap();
+
+        let woken = Arc::new(Woken::default());
+        let waker = waker(woken.clone());
+        assert!(conn2
+            .poll_next_unpin(&mut task::Context::from_waker(&waker))
+            .is_pending());
+        // `conn1` polls the execution after `conn2` has started waiting, and then stops polling
+        // at all.
+        assert!(conn1.next().now_or_never().is_none());
+
+        pubsub.publish(&MESSAGES, "hi".into()).await.unwrap();
+
+        assert!(
+            woken.0.load(Ordering::SeqCst),
+            "`conn2` is stalled by `conn1`"
+        );
+        let out = conn2.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> alice: hi"}));
+
+        let out = conn1.next().await.unwrap();
+        assert_eq!(out.data, graphql_value!({"messages": "> alice: hi"}));
+    }
+
+    #[tokio::test]
+    async fn keeps_fields_order() {
+        let coordinator = coordinator();
+        let pubsub = InMemoryPubSub::new();
+        let ctx = context("alice", &pubsub);
+        let req1 = request("subscription { messageObjects { user text } }");
+        let req2 = request("subscription { messageObjects { text user } }");
+
+        let mut conn1 = coordinator.subscribe(&req1, &ctx).await.unwrap();
+        let mut conn2 = coordinator.subscribe(&req2, &ctx).await.unwrap();
+
+        assert_eq!(coordinator.upstreams_count(), 2);
+
+        pubsub.publish(&MESSAGES, "hi".into()).await.unwrap();
+
+        for (conn, expected) in [
+            (&mut conn1, ["user", "text"]),
+            (&mut conn2, ["text", "user"]),
+        ] {
+            let out = conn.next().await.unwrap();
+            let fields = out
+                .data
+                .as_object_value()
+                .and_then(|o| o.get_field_value("messageObjects"))
+                .and_then(|v| v.as_object_value())
+                .unwrap()
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(fields, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn respects_document_limits() {
+        let coordinator = SharedCoordinator::new(
+            RootNode::new(Query, EmptyMutation::new(), Subscription).with_limits(DocumentLimits {
+                max_tokens: Some(3),
+                ..DocumentLimits::default()
+            }),
+            |ctx: &Context| ctx.user.clone(),
+        );
+        let ctx = context("alice", &InMemoryPubSub::new());
+        let req = request("subscription($prefix: String!) { messages(prefix: $prefix) }");
+
+        let res = coordinator.subscribe(&req, &ctx).await;
+
+        assert!(
+            matches!(res, Err(GraphQLError::ParseError(_))),
+            "{:?}",
+            res.err(),
+        );
+        assert_eq!(coordinator.upstreams_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn reports_errors() {
+        let coordinator = coordinator();
+        let ctx = context("alice", &InMemoryPubSub::new());
+
+        let req = request("subscription { unknown }");
+
+        let res = coordinator.subscribe(&req, &ctx).await;
+
+        assert!(
+            matches!(res, Err(GraphQLError::ValidationError(_))),
+            "{:?}",
+            res.err(),
+        );
+        assert_eq!(coordinator.upstreams_count(), 0);
+    }
+}
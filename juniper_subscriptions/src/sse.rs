@@ -0,0 +1,210 @@
+//! [GraphQL over Server-Sent Events][1] transport of subscriptions, for environments where
+//! WebSockets are not available.
+//!
+//! Implements the "distinct connections" mode: each subscription operation is served by a separate
+//! HTTP response of [`CONTENT_TYPE`], consisting of a `next` event per [`ExecutionOutput`] of the
+//! operation, and a single `complete` event at the end.
+//!
+//! [`ExecutionOutput`]: juniper::ExecutionOutput
+//! [1]: https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md
+
+use std::{borrow::Borrow, fmt, sync::Arc};
+
+use futures::{
+    channel::mpsc, future, stream, FutureExt as _, SinkExt as _, Stream, StreamExt as _,
+};
+use juniper::{
+    http::{GraphQLRequest, GraphQLResponse},
+    DefaultScalarValue, GraphQLError, ScalarValue, SubscriptionCoordinator,
+};
+
+/// `Content-Type` of a Server-Sent Events response.
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// Server-Sent Event of a subscription operation.
+///
+/// Its [`Display`] implementation renders the event in the `text/event-stream` format, ready to be
+/// written into a response body.
+///
+/// [`Display`]: fmt::Display
+#[derive(Debug)]
+pub enum Event<S = DefaultScalarValue> {
+    /// Single result of the operation, or an error preventing it from being started.
+    Next(GraphQLResponse<S>),
+
+    /// End of the operation.
+    Complete,
+}
+
+impl<S> Event<S> {
+    /// Returns the name of this [`Event`], as used in its `event` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Next(_) => "next",
+            Self::Complete => "complete",
+        }
+    }
+}
+
+impl<S: ScalarValue> fmt::Display for Event<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "event: {}\ndata:", self.name())?;
+        if let Self::Next(resp) = self {
+            // Compact JSON never contains newlines, so fits into a single `data` field.
+            let data = serde_json::to_string(resp).map_err(|_| fmt::Error)?;
+            write!(f, " {data}")?;
+        }
+        f.write_str("\n\n")
+    }
+}
+
+/// Subscribes to the operation of the provided `request` via the given `coordinator`, returning
+/// the stream of its [`Event`]s, always ended with an [`Event::Complete`].
+///
+/// Errors preventing the operation from being started (parsing, validation, not a subscription
+/// operation, etc.) are emitted as a single [`Event::Next`].
+///
+/// Any [`SubscriptionCoordinator`] may be used, like a [`Coordinator`] or a
+/// [`SharedCoordinator`].
+///
+/// The returned stream owns all its arguments, so can be used as a streaming response body.
+///
+/// [`Coordinator`]: crate::Coordinator
+/// [`SharedCoordinator`]: crate::SharedCoordinator
+pub fn subscribe<Coord, CtxT, S, C>(
+    coordinator: Arc<Coord>,
+    request: GraphQLRequest<S>,
+    context: C,
+) -> impl Stream<Item = Event<S>> + Send + 'static
+where
+    Coord:
+        for<'a> SubscriptionCoordinator<'a, CtxT, S, Error = GraphQLError> + Send + Sync + 'static,
+    for<'a> <Coord as SubscriptionCoordinator<'a, CtxT, S>>::Connection: Send,
+    CtxT: Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+    C: Borrow<CtxT> + Send + Sync + 'static,
+{
+    // The operation's stream borrows the `coordinator`, `request` and `context`, so is driven
+    // inside a future owning them, forwarding its events through a channel.
+    let (mut tx, rx) = mpsc::channel(0);
+
+    let driver = async move {
+        let events = match coordinator.subscribe(&request, context.borrow()).await {
+            Ok(conn) => conn
+                .map(|out| Event::Next(GraphQLResponse::from_result(Ok((out.data, out.errors)))))
+                .left_stream(),
+            Err(e) => stream::once(future::ready(Event::Next(GraphQLResponse::from_result(
+                Err(e),
+            ))))
+            .right_stream(),
+        };
+        // Connections of an arbitrary coordinator are not necessarily `Unpin`.
+        let mut events = Box::pin(
+            events
+                .chain(stream::once(future::ready(Event::Complete)))
+                .map(Ok),
+        );
+
+        // Failing to send means the client has gone away, so there is nobody to send the rest to.
+        let _ = tx.send_all(&mut events).await;
+    };
+
+    stream::select(rx.map(Some), driver.into_stream().map(|()| None)).filter_map(future::ready)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{pin::Pin, sync::Arc};
+
+    use futures::{stream, Stream, StreamExt as _};
+    use juniper::{
+        graphql_object, graphql_subscription, http::GraphQLRequest, DefaultScalarValue,
+        EmptyMutation, FieldError, RootNode,
+    };
+
+    use crate::{Coordinator, SharedCoordinator};
+
+    use super::{subscribe, Event};
+
+    struct Query;
+
+    #[graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    type Counter = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+    #[graphql_subscription]
+    impl Subscription {
+        async fn counter(to: i32) -> Counter {
+            Box::pin(stream::iter((1..=to).map(Ok)))
+        }
+    }
+
+    fn coordinator(
+    ) -> Arc<Coordinator<'static, Query, EmptyMutation, Subscription, (), DefaultScalarValue>> {
+        Arc::new(Coordinator::new(RootNode::new(
+            Query,
+            EmptyMutation::new(),
+            Subscription,
+        )))
+    }
+
+    async fn events(query: &str) -> Vec<String> {
+        let req = GraphQLRequest::new(query.into(), None, None);
+        subscribe(coordinator(), req, ())
+            .map(|e: Event| e.to_string())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn streams_events() {
+        assert_eq!(
+            events("subscription { counter(to: 2) }").await,
+            [
+                "event: next\ndata: {\"data\":{\"counter\":1}}\n\n",
+                "event: next\ndata: {\"data\":{\"counter\":2}}\n\n",
+                "event: complete\ndata:\n\n",
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_errors() {
+        let events = events("subscription { unknown }").await;
+
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert!(
+            events[0].starts_with("event: next\ndata: {\"errors\":[{\"message\":"),
+            "{events:?}",
+        );
+        assert_eq!(events[1], "event: complete\ndata:\n\n");
+    }
+
+    #[tokio::test]
+    async fn streams_events_of_shared_coordinator() {
+        let coordinator = Arc::new(SharedCoordinator::new(
+            RootNode::new(Query, EmptyMutation::new(), Subscription),
+            |_: &()| String::new(),
+        ));
+        let req = GraphQLRequest::new("subscription { counter(to: 2) }".into(), None, None);
+
+        assert_eq!(
+            subscribe(coordinator, req, ())
+                .map(|e: Event| e.to_string())
+                .collect::<Vec<_>>()
+                .await,
+            [
+                "event: next\ndata: {\"data\":{\"counter\":1}}\n\n",
+                "event: next\ndata: {\"data\":{\"counter\":2}}\n\n",
+                "event: complete\ndata:\n\n",
+            ],
+        );
+    }
+}
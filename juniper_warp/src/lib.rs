@@ -6,9 +6,12 @@
 use std::{collections::HashMap, str, sync::Arc};
 
 use anyhow::anyhow;
-use futures::{FutureExt as _, TryFutureExt};
+use futures::TryFutureExt as _;
 use juniper::{
-    http::{GraphQLBatchRequest, GraphQLRequest},
+    http::{
+        policy::{self, MediaType, PolicyError},
+        GraphQLBatchRequest, GraphQLRequest,
+    },
     ScalarValue,
 };
 use tokio::task;
@@ -83,65 +86,228 @@ where
     CtxT: Send + Sync + 'static,
     S: ScalarValue + Send + Sync + 'static,
 {
+    graphql_filter(Arc::new(schema), context_extractor)
+}
+
+/// Make a filter for graphql queries/mutations, additionally accepting
+/// [GraphQL multipart requests][1] with file uploads.
+///
+/// The uploaded files are stored according to the provided [`MultipartOptions`]. Replies with
+/// `413 Payload Too Large` if a request exceeds its limits.
+///
+/// See [`make_graphql_filter()`] for the meaning of other arguments.
+///
+/// [`MultipartOptions`]: juniper::http::multipart::MultipartOptions
+/// [1]: https://github.com/jaydenseric/graphql-multipart-request-spec
+#[cfg(feature = "multipart")]
+pub fn make_multipart_graphql_filter<Query, Mutation, Subscription, CtxT, S>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(CtxT,)>,
+    options: juniper::http::multipart::MultipartOptions,
+) -> BoxedFilter<(http::Response<Vec<u8>>,)>
+where
+    Query: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    use juniper::http::multipart;
+
     let schema = Arc::new(schema);
-    let post_json_schema = schema.clone();
-    let post_graphql_schema = schema.clone();
+    let multipart_schema = schema.clone();
+    let options = Arc::new(options);
 
-    let handle_post_json_request = move |context: CtxT, req: GraphQLBatchRequest<S>| {
-        let schema = post_json_schema.clone();
-        async move {
-            let resp = req.execute(&schema, &context).await;
+    let multipart_content_type =
+        warp::header::<String>("content-type").and_then(|ct: String| async move {
+            if multipart::is_multipart(&ct) {
+                Ok(ct)
+            } else {
+                Err(warp::reject())
+            }
+        });
+    let multipart_filter = warp::post()
+        .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
+        .and(multipart_content_type)
+        .and(body::stream())
+        .and_then(
+            move |context: CtxT, accept: Option<String>, content_type: String, body| {
+                execute_multipart_request(
+                    multipart_schema.clone(),
+                    context,
+                    accept,
+                    content_type,
+                    body,
+                    options.clone(),
+                )
+            },
+        );
 
-            Ok::<_, warp::Rejection>(build_response(
-                serde_json::to_vec(&resp)
-                    .map(|json| (json, resp.is_ok()))
-                    .map_err(Into::into),
-            ))
+    multipart_filter
+        .or(graphql_filter(schema, context_extractor))
+        .unify()
+        .boxed()
+}
+
+#[cfg(feature = "multipart")]
+async fn execute_multipart_request<Query, Mutation, Subscription, CtxT, S, B>(
+    schema: Arc<juniper::RootNode<'static, Query, Mutation, Subscription, S>>,
+    context: CtxT,
+    accept: Option<String>,
+    content_type: String,
+    body: impl futures::Stream<Item = Result<B, warp::Error>> + Send + 'static,
+    options: Arc<juniper::http::multipart::MultipartOptions>,
+) -> Result<http::Response<Vec<u8>>, warp::Rejection>
+where
+    Query: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+    B: warp::hyper::body::Buf,
+{
+    use futures::TryStreamExt as _;
+
+    let media_type = match negotiate_media_type(accept.as_deref()) {
+        Ok(media_type) => media_type,
+        Err(e) => return Ok(render_policy_error(e)),
+    };
+    let body = body.map_ok(|mut buf| buf.copy_to_bytes(buf.remaining()));
+    let req = match juniper::http::multipart::parse_multipart_request::<S, _, _, _>(
+        &content_type,
+        body,
+        &options,
+    )
+    .await
+    {
+        Ok(req) => req,
+        Err(e) => {
+            return Ok(http::Response::builder()
+                .status(if e.is_too_large() {
+                    http::StatusCode::PAYLOAD_TOO_LARGE
+                } else {
+                    http::StatusCode::BAD_REQUEST
+                })
+                .body(e.to_string().into_bytes())
+                .expect("response is valid"))
         }
     };
+
+    let resp = req.execute(&schema, &context).await;
+
+    Ok(build_response(
+        serde_json::to_vec(&resp)
+            .map(|json| (json, resp.status_code()))
+            .map_err(Into::into),
+        media_type,
+    ))
+}
+
+fn graphql_filter<Query, Mutation, Subscription, CtxT, S>(
+    schema: Arc<juniper::RootNode<'static, Query, Mutation, Subscription, S>>,
+    context_extractor: BoxedFilter<(CtxT,)>,
+) -> BoxedFilter<(http::Response<Vec<u8>>,)>
+where
+    Query: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    CtxT: Send + Sync + 'static,
+    S: ScalarValue + Send + Sync + 'static,
+{
+    let post_json_schema = schema.clone();
+    let post_graphql_schema = schema.clone();
+
+    let handle_post_json_request =
+        move |context: CtxT, accept: Option<String>, req: GraphQLBatchRequest<S>| {
+            let schema = post_json_schema.clone();
+            async move {
+                let media_type = match negotiate_media_type(accept.as_deref()) {
+                    Ok(media_type) => media_type,
+                    Err(e) => return Ok::<_, warp::Rejection>(render_policy_error(e)),
+                };
+
+                let resp = req.execute(&schema, &context).await;
+
+                Ok(build_response(
+                    serde_json::to_vec(&resp)
+                        .map(|json| (json, resp.status_code()))
+                        .map_err(Into::into),
+                    media_type,
+                ))
+            }
+        };
     let post_json_filter = warp::post()
         .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
         .and(body::json())
         .and_then(handle_post_json_request);
 
-    let handle_post_graphql_request = move |context: CtxT, body: Bytes| {
+    let handle_post_graphql_request = move |context: CtxT, accept: Option<String>, body: Bytes| {
         let schema = post_graphql_schema.clone();
         async move {
-            let query = str::from_utf8(body.as_ref())
-                .map_err(|e| anyhow!("Request body query is not a valid UTF-8 string: {e}"))?;
-            let req = GraphQLRequest::new(query.into(), None, None);
+            let media_type = match negotiate_media_type(accept.as_deref()) {
+                Ok(media_type) => media_type,
+                Err(e) => return Ok::<_, warp::Rejection>(render_policy_error(e)),
+            };
 
-            let resp = req.execute(&schema, &context).await;
+            let res = async {
+                let query = str::from_utf8(body.as_ref())
+                    .map_err(|e| anyhow!("Request body query is not a valid UTF-8 string: {e}"))?;
+                let req = GraphQLRequest::new(query.into(), None, None);
+
+                let resp = req.execute(&schema, &context).await;
+
+                Ok((serde_json::to_vec(&resp)?, resp.status_code()))
+            }
+            .await;
 
-            Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
+            Ok(build_response(res, media_type))
         }
-        .then(|res| async { Ok::<_, warp::Rejection>(build_response(res)) })
     };
     let post_graphql_filter = warp::post()
         .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
         .and(body::bytes())
         .and_then(handle_post_graphql_request);
 
-    let handle_get_request = move |context: CtxT, mut qry: HashMap<String, String>| {
-        let schema = schema.clone();
-        async move {
-            let req = GraphQLRequest::new(
-                qry.remove("query")
-                    .ok_or_else(|| anyhow!("Missing GraphQL query string in query parameters"))?,
-                qry.remove("operation_name"),
-                qry.remove("variables")
-                    .map(|vs| serde_json::from_str(&vs))
-                    .transpose()?,
-            );
-
-            let resp = req.execute(&schema, &context).await;
-
-            Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
-        }
-        .then(|res| async move { Ok::<_, warp::Rejection>(build_response(res)) })
-    };
+    let handle_get_request =
+        move |context: CtxT, accept: Option<String>, qry: HashMap<String, String>| {
+            let schema = schema.clone();
+            async move {
+                let media_type = match negotiate_media_type(accept.as_deref()) {
+                    Ok(media_type) => media_type,
+                    Err(e) => return Ok::<_, warp::Rejection>(render_policy_error(e)),
+                };
+                let req = match parse_get_request(qry) {
+                    Ok(req) => req,
+                    Err(e) => return Ok(build_response(Err(e), media_type)),
+                };
+                let resp = match policy::check_get_request(&req, &schema) {
+                    Ok(req) => req.execute(&schema, &context).await,
+                    Err(e) => return Ok(render_policy_error(e)),
+                };
+
+                Ok(build_response(
+                    serde_json::to_vec(&resp)
+                        .map(|json| (json, resp.status_code()))
+                        .map_err(Into::into),
+                    media_type,
+                ))
+            }
+        };
     let get_filter = warp::get()
         .and(context_extractor)
+        .and(warp::header::optional::<String>("accept"))
         .and(query::query())
         .and_then(handle_get_request);
 
@@ -169,71 +335,91 @@ where
     let post_json_schema = schema.clone();
     let post_graphql_schema = schema.clone();
 
-    let handle_post_json_request = move |context: CtxT, req: GraphQLBatchRequest<S>| {
-        let schema = post_json_schema.clone();
-        async move {
-            let res = task::spawn_blocking(move || {
-                let resp = req.execute_sync(&schema, &context);
-                Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
-            })
-            .await?;
+    let handle_post_json_request =
+        move |context: CtxT, accept: Option<String>, req: GraphQLBatchRequest<S>| {
+            let schema = post_json_schema.clone();
+            async move {
+                let media_type = match negotiate_media_type(accept.as_deref()) {
+                    Ok(media_type) => media_type,
+                    Err(e) => return Ok(render_policy_error(e)),
+                };
+
+                let res = task::spawn_blocking(move || {
+                    let resp = req.execute_sync(&schema, &context);
+                    Ok((serde_json::to_vec(&resp)?, resp.status_code()))
+                })
+                .await?;
 
-            Ok(build_response(res))
-        }
-        .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
-    };
+                Ok(build_response(res, media_type))
+            }
+            .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
+        };
     let post_json_filter = warp::post()
         .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
         .and(body::json())
         .and_then(handle_post_json_request);
 
-    let handle_post_graphql_request = move |context: CtxT, body: Bytes| {
+    let handle_post_graphql_request = move |context: CtxT, accept: Option<String>, body: Bytes| {
         let schema = post_graphql_schema.clone();
         async move {
+            let media_type = match negotiate_media_type(accept.as_deref()) {
+                Ok(media_type) => media_type,
+                Err(e) => return Ok(render_policy_error(e)),
+            };
+
             let res = task::spawn_blocking(move || {
                 let query = str::from_utf8(body.as_ref())
                     .map_err(|e| anyhow!("Request body is not a valid UTF-8 string: {e}"))?;
                 let req = GraphQLRequest::new(query.into(), None, None);
 
                 let resp = req.execute_sync(&schema, &context);
-                Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
+                Ok((serde_json::to_vec(&resp)?, resp.status_code()))
             })
             .await?;
 
-            Ok(build_response(res))
+            Ok(build_response(res, media_type))
         }
         .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
     };
     let post_graphql_filter = warp::post()
         .and(context_extractor.clone())
+        .and(warp::header::optional::<String>("accept"))
         .and(body::bytes())
         .and_then(handle_post_graphql_request);
 
-    let handle_get_request = move |context: CtxT, mut qry: HashMap<String, String>| {
-        let schema = schema.clone();
-        async move {
-            let res = task::spawn_blocking(move || {
-                let req = GraphQLRequest::new(
-                    qry.remove("query").ok_or_else(|| {
-                        anyhow!("Missing GraphQL query string in query parameters")
-                    })?,
-                    qry.remove("operation_name"),
-                    qry.remove("variables")
-                        .map(|vs| serde_json::from_str(&vs))
-                        .transpose()?,
-                );
-
-                let resp = req.execute_sync(&schema, &context);
-                Ok((serde_json::to_vec(&resp)?, resp.is_ok()))
-            })
-            .await?;
-
-            Ok(build_response(res))
-        }
-        .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
-    };
+    let handle_get_request =
+        move |context: CtxT, accept: Option<String>, qry: HashMap<String, String>| {
+            let schema = schema.clone();
+            async move {
+                let media_type = match negotiate_media_type(accept.as_deref()) {
+                    Ok(media_type) => media_type,
+                    Err(e) => return Ok(render_policy_error(e)),
+                };
+
+                task::spawn_blocking(move || {
+                    let req = match parse_get_request(qry) {
+                        Ok(req) => req,
+                        Err(e) => return build_response(Err(e), media_type),
+                    };
+                    let resp = match policy::check_get_request(&req, &schema) {
+                        Ok(req) => req.execute_sync(&schema, &context),
+                        Err(e) => return render_policy_error(e),
+                    };
+                    build_response(
+                        serde_json::to_vec(&resp)
+                            .map(|json| (json, resp.status_code()))
+                            .map_err(Into::into),
+                        media_type,
+                    )
+                })
+                .await
+            }
+            .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e)))
+        };
     let get_filter = warp::get()
         .and(context_extractor)
+        .and(warp::header::optional::<String>("accept"))
         .and(query::query())
         .and_then(handle_get_request);
 
@@ -253,11 +439,42 @@ pub struct JoinError(task::JoinError);
 
 impl warp::reject::Reject for JoinError {}
 
-fn build_response(response: Result<(Vec<u8>, bool), anyhow::Error>) -> http::Response<Vec<u8>> {
+/// Parses a [`GraphQLBatchRequest`] out of the query parameters of a GET request.
+fn parse_get_request<S: ScalarValue>(
+    mut qry: HashMap<String, String>,
+) -> anyhow::Result<GraphQLBatchRequest<S>> {
+    Ok(GraphQLBatchRequest::Single(GraphQLRequest::new(
+        qry.remove("query")
+            .ok_or_else(|| anyhow!("Missing GraphQL query string in query parameters"))?,
+        qry.remove("operation_name"),
+        qry.remove("variables")
+            .map(|vs| serde_json::from_str(&vs))
+            .transpose()?,
+    )))
+}
+
+/// Negotiates the [`MediaType`] of a response out of the `Accept` header of a request.
+fn negotiate_media_type(accept: Option<&str>) -> Result<MediaType, PolicyError> {
+    MediaType::negotiate(accept).ok_or(PolicyError::NotAcceptable)
+}
+
+fn render_policy_error(err: PolicyError) -> http::Response<Vec<u8>> {
+    let mut resp = http::Response::builder().status(err.status_code());
+    if err == PolicyError::NonQueryOverGet {
+        resp = resp.header("allow", "POST");
+    }
+    resp.body(err.to_string().into_bytes())
+        .expect("response is valid")
+}
+
+fn build_response(
+    response: Result<(Vec<u8>, u16), anyhow::Error>,
+    media_type: MediaType,
+) -> http::Response<Vec<u8>> {
     match response {
-        Ok((body, is_ok)) => http::Response::builder()
-            .status(if is_ok { 200 } else { 400 })
-            .header("content-type", "application/json")
+        Ok((body, status_code)) => http::Response::builder()
+            .status(status_code)
+            .header("content-type", media_type.as_str())
             .body(body)
             .expect("response is valid"),
         Err(_) => http::Response::builder()
@@ -345,25 +562,46 @@ fn playground_response(
 /// [1]: https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md
 #[cfg(feature = "subscriptions")]
 pub mod subscriptions {
-    use std::{convert::Infallible, fmt, sync::Arc};
+    use std::{collections::HashMap, convert::Infallible, fmt, sync::Arc};
 
+    use anyhow::anyhow;
     use juniper::{
         futures::{
             future::{self, Either},
             sink::SinkExt,
             stream::StreamExt,
         },
-        GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+        http::GraphQLRequest,
+        GraphQLError, GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+        SubscriptionCoordinator,
+    };
+    use juniper_graphql_ws::{
+        graphql_transport_ws, ArcSchema, ClientMessage, Connection, IncomingMessage, Init, Protocol,
     };
-    use juniper_graphql_ws::{ArcSchema, ClientMessage, Connection, Init};
+    use juniper_subscriptions::sse;
+    use warp::{filters::BoxedFilter, http, hyper::Body, Filter};
 
     struct Message(warp::ws::Message);
 
+    impl IncomingMessage for Message {
+        fn size(&self) -> Option<usize> {
+            Some(self.0.as_bytes().len())
+        }
+    }
+
     impl<S: ScalarValue> TryFrom<Message> for ClientMessage<S> {
-        type Error = serde_json::Error;
+        type Error = Error;
+
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            serde_json::from_slice(msg.0.as_bytes()).map_err(Error::Serde)
+        }
+    }
+
+    impl<S: ScalarValue> TryFrom<Message> for graphql_transport_ws::ClientMessage<S> {
+        type Error = Error;
 
-        fn try_from(msg: Message) -> serde_json::Result<Self> {
-            serde_json::from_slice(msg.0.as_bytes())
+        fn try_from(msg: Message) -> Result<Self, Self::Error> {
+            serde_json::from_slice(msg.0.as_bytes()).map_err(Error::Serde)
         }
     }
 
@@ -373,8 +611,9 @@ pub mod subscriptions {
         /// Errors that can happen in Warp while serving a connection.
         Warp(warp::Error),
 
-        /// Errors that can happen while serializing outgoing messages. Note that errors that occur
-        /// while deserializing incoming messages are handled internally by the protocol.
+        /// Errors that can happen while serializing outgoing messages or deserializing incoming
+        /// ones. Note that the latter are not returned, but reported to the client by the
+        /// protocol.
         Serde(serde_json::Error),
     }
 
@@ -401,7 +640,154 @@ pub mod subscriptions {
         }
     }
 
-    /// Serves the graphql-ws protocol over a WebSocket connection.
+    /// Makes a filter serving subscriptions over [Server-Sent Events][1], for environments where
+    /// WebSockets are not available.
+    ///
+    /// Accepts a GET request with the operation in query parameters, or a POST request with the
+    /// operation in a JSON body, and replies with a `text/event-stream` response, consisting of a
+    /// `next` event per result of the operation and a `complete` event at the end.
+    ///
+    /// Any [`SubscriptionCoordinator`] may serve the subscriptions, like a
+    /// [`juniper_subscriptions::Coordinator`] or a [`juniper_subscriptions::SharedCoordinator`].
+    ///
+    /// The `context_extractor` argument should be a filter that provides the GraphQL context
+    /// required by the schema.
+    ///
+    /// Example:
+    ///
+    /// ```rust,ignore
+    /// let coordinator = Arc::new(juniper_subscriptions::Coordinator::new(schema()));
+    /// let context_extractor = warp::any().map(|| Context).boxed();
+    ///
+    /// let sse_endpoint = warp::path("graphql")
+    ///     .and(warp::path("stream"))
+    ///     .and(make_graphql_sse_filter(coordinator, context_extractor));
+    /// ```
+    ///
+    /// [1]: https://github.com/enisdenjo/graphql-sse/blob/master/PROTOCOL.md
+    pub fn make_graphql_sse_filter<Coord, CtxT, S>(
+        coordinator: Arc<Coord>,
+        context_extractor: BoxedFilter<(CtxT,)>,
+    ) -> BoxedFilter<(http::Response<Body>,)>
+    where
+        Coord: for<'a> SubscriptionCoordinator<'a, CtxT, S, Error = GraphQLError>
+            + Send
+            + Sync
+            + 'static,
+        for<'a> <Coord as SubscriptionCoordinator<'a, CtxT, S>>::Connection: Send,
+        CtxT: Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        let post_coordinator = coordinator.clone();
+        let post_filter = warp::post()
+            .and(context_extractor.clone())
+            .and(warp::body::json())
+            .map(move |context: CtxT, req: GraphQLRequest<S>| {
+                sse_response(post_coordinator.clone(), req, context)
+            });
+
+        let get_filter = warp::get()
+            .and(context_extractor)
+            .and(warp::query::query())
+            .map(move |context: CtxT, mut qry: HashMap<String, String>| {
+                let req = qry
+                    .remove("query")
+                    .ok_or_else(|| anyhow!("Missing GraphQL query string in query parameters"))
+                    .and_then(|query| {
+                        Ok(GraphQLRequest::new(
+                            query,
+                            qry.remove("operation_name"),
+                            qry.remove("variables")
+                                .map(|vs| serde_json::from_str(&vs))
+                                .transpose()?,
+                        ))
+                    });
+
+                match req {
+                    Ok(req) => sse_response(coordinator.clone(), req, context),
+                    Err(e) => http::Response::builder()
+                        .status(http::StatusCode::BAD_REQUEST)
+                        .body(Body::from(e.to_string()))
+                        .expect("response is valid"),
+                }
+            });
+
+        get_filter.or(post_filter).unify().boxed()
+    }
+
+    fn sse_response<Coord, CtxT, S>(
+        coordinator: Arc<Coord>,
+        req: GraphQLRequest<S>,
+        context: CtxT,
+    ) -> http::Response<Body>
+    where
+        Coord: for<'a> SubscriptionCoordinator<'a, CtxT, S, Error = GraphQLError>
+            + Send
+            + Sync
+            + 'static,
+        for<'a> <Coord as SubscriptionCoordinator<'a, CtxT, S>>::Connection: Send,
+        CtxT: Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+    {
+        let events = sse::subscribe(coordinator, req, context)
+            .map(|event| Ok::<_, Infallible>(event.to_string()));
+
+        http::Response::builder()
+            .header("content-type", sse::CONTENT_TYPE)
+            .header("cache-control", "no-cache")
+            .body(Body::wrap_stream(events))
+            .expect("response is valid")
+    }
+
+    /// Serves the provided GraphQL over WebSocket [`Protocol`] over a WebSocket connection.
+    ///
+    /// The `protocol` should be negotiated with [`Protocol::negotiate()`] from the value of the
+    /// `Sec-WebSocket-Protocol` request header, and returned back to the client in the same
+    /// response header:
+    ///
+    /// ```rust,ignore
+    /// warp::ws()
+    ///     .and(warp::header::optional::<String>("sec-websocket-protocol"))
+    ///     .map(move |ws: warp::ws::Ws, protocol: Option<String>| {
+    ///         let protocol = Protocol::negotiate(protocol.as_deref());
+    ///         let root_node = root_node.clone();
+    ///         let reply = ws.on_upgrade(move |websocket| async move {
+    ///             let init = ConnectionConfig::new(Context);
+    ///             if let Err(e) = serve_ws(websocket, protocol, root_node, init).await {
+    ///                 println!("Websocket error: {e}");
+    ///             }
+    ///         });
+    ///         warp::reply::with_header(reply, "sec-websocket-protocol", protocol.as_str())
+    ///     })
+    /// ```
+    ///
+    /// See [`serve_graphql_ws()`] and [`serve_graphql_transport_ws()`] for details.
+    pub async fn serve_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: warp::ws::WebSocket,
+        protocol: Protocol,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        match protocol {
+            Protocol::GraphQLWs => serve_graphql_ws(websocket, root_node, init).await,
+            Protocol::GraphQLTransportWs => {
+                serve_graphql_transport_ws(websocket, root_node, init).await
+            }
+        }
+    }
+
+    /// Serves the legacy graphql-ws protocol over a WebSocket connection.
     ///
     /// The `init` argument is used to provide the context and additional configuration for
     /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
@@ -444,6 +830,302 @@ pub mod subscriptions {
             Either::Right((r, _)) => r,
         }
     }
+
+    /// Serves the graphql-transport-ws protocol over a WebSocket connection.
+    ///
+    /// The `init` argument is used to provide the context and additional configuration for
+    /// connections. This can be a `juniper_graphql_ws::ConnectionConfig` if the context and
+    /// configuration are already known, or it can be a closure that gets executed asynchronously
+    /// when the client sends the ConnectionInit message. Using a closure allows you to perform
+    /// authentication based on the parameters provided by the client.
+    ///
+    /// Protocol violations close the WebSocket connection with the corresponding close code.
+    pub async fn serve_graphql_transport_ws<Query, Mutation, Subscription, CtxT, S, I>(
+        websocket: warp::ws::WebSocket,
+        root_node: Arc<RootNode<'static, Query, Mutation, Subscription, S>>,
+        init: I,
+    ) -> Result<(), Error>
+    where
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        CtxT: Unpin + Send + Sync + 'static,
+        S: ScalarValue + Send + Sync + 'static,
+        I: Init<S, CtxT> + Send,
+    {
+        let (ws_tx, ws_rx) = websocket.split();
+        let (s_tx, s_rx) =
+            graphql_transport_ws::Connection::new(ArcSchema(root_node), init).split();
+
+        let ws_rx = ws_rx.map(|r| r.map(Message));
+        let s_rx = s_rx.map(|output| match output {
+            graphql_transport_ws::Output::Message(msg) => serde_json::to_string(&msg)
+                .map(warp::ws::Message::text)
+                .map_err(Error::Serde),
+            graphql_transport_ws::Output::Close { code, message } => {
+                Ok(warp::ws::Message::close_with(code, message))
+            }
+        });
+
+        match future::select(
+            ws_rx.forward(s_tx.sink_err_into()),
+            s_rx.forward(ws_tx.sink_err_into()),
+        )
+        .await
+        {
+            Either::Left((r, _)) => r.map_err(|e| e.into()),
+            Either::Right((r, _)) => r,
+        }
+    }
+
+    #[cfg(test)]
+    mod sse_tests {
+        use std::{pin::Pin, sync::Arc};
+
+        use juniper::{
+            futures::{stream, Stream},
+            graphql_object, graphql_subscription, EmptyMutation, FieldError, RootNode,
+        };
+        use juniper_subscriptions::{sse, Coordinator, SharedCoordinator};
+        use warp::{
+            filters::BoxedFilter,
+            http,
+            hyper::{body::Bytes, Body},
+            test::request,
+            Filter as _,
+        };
+
+        use super::make_graphql_sse_filter;
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        struct Subscription;
+
+        type Counter = Pin<Box<dyn Stream<Item = Result<i32, FieldError>> + Send>>;
+
+        #[graphql_subscription]
+        impl Subscription {
+            async fn counter(to: i32) -> Counter {
+                Box::pin(stream::iter((1..=to).map(Ok)))
+            }
+        }
+
+        type Schema = RootNode<'static, Query, EmptyMutation, Subscription>;
+
+        fn schema() -> Schema {
+            RootNode::new(Query, EmptyMutation::new(), Subscription)
+        }
+
+        fn filter() -> BoxedFilter<(http::Response<Body>,)> {
+            make_graphql_sse_filter(
+                Arc::new(Coordinator::new(schema())),
+                warp::any().map(|| ()).boxed(),
+            )
+        }
+
+        const EVENTS: &str = "event: next\ndata: {\"data\":{\"counter\":1}}\n\n\
+                              event: next\ndata: {\"data\":{\"counter\":2}}\n\n\
+                              event: complete\ndata:\n\n";
+
+        fn assert_events(response: http::Response<Bytes>, expected: &str) {
+            assert_eq!(response.status(), http::StatusCode::OK);
+            assert_eq!(
+                response.headers().get("content-type").unwrap(),
+                sse::CONTENT_TYPE,
+            );
+            assert_eq!(
+                String::from_utf8(response.body().to_vec()).unwrap(),
+                expected
+            );
+        }
+
+        #[tokio::test]
+        async fn streams_get_request() {
+            let response = request()
+                .method("GET")
+                .path("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D")
+                .reply(&filter())
+                .await;
+
+            assert_events(response, EVENTS);
+        }
+
+        #[tokio::test]
+        async fn streams_post_request() {
+            let response = request()
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(
+                    r#"{
+                        "query": "subscription($to: Int!) { counter(to: $to) }",
+                        "variables": {"to": 2}
+                    }"#,
+                )
+                .reply(&filter())
+                .await;
+
+            assert_events(response, EVENTS);
+        }
+
+        #[tokio::test]
+        async fn streams_error_before_start() {
+            let response = request()
+                .method("POST")
+                .header("content-type", "application/json")
+                .body(r#"{"query": "subscription { unknown }"}"#)
+                .reply(&filter())
+                .await;
+
+            assert_events(
+                response,
+                "event: next\ndata: {\"errors\":[{\"message\":\
+                 \"Unknown field \\\"unknown\\\" on type \\\"Subscription\\\"\",\
+                 \"locations\":[{\"line\":1,\"column\":16}]}]}\n\n\
+                 event: complete\ndata:\n\n",
+            );
+        }
+
+        #[tokio::test]
+        async fn rejects_get_request_without_query() {
+            let response = request().method("GET").path("/").reply(&filter()).await;
+
+            assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        }
+
+        #[tokio::test]
+        async fn streams_via_shared_coordinator() {
+            let filter = make_graphql_sse_filter(
+                Arc::new(SharedCoordinator::new(schema(), |_: &()| String::new())),
+                warp::any().map(|| ()).boxed(),
+            );
+
+            let response = request()
+                .method("GET")
+                .path("/?query=subscription%20%7B%20counter(to%3A%202)%20%7D")
+                .reply(&filter)
+                .await;
+
+            assert_events(response, EVENTS);
+        }
+    }
+
+    #[cfg(test)]
+    mod ws_tests {
+        use std::{sync::Arc, time::Duration};
+
+        use juniper::{
+            http::tests::{
+                run_graphql_transport_ws_test_suite, run_ws_test_suite, WsIntegration,
+                WsIntegrationMessage,
+            },
+            tests::fixtures::starwars::schema::{Database, Query, Subscription},
+            EmptyMutation, LocalBoxFuture, RootNode,
+        };
+        use juniper_graphql_ws::{ConnectionConfig, Protocol};
+        use tokio::time::timeout;
+        use warp::Filter as _;
+
+        use super::{serve_graphql_transport_ws, serve_graphql_ws};
+
+        struct TestWarpWsIntegration(Protocol);
+
+        impl TestWarpWsIntegration {
+            async fn run_async(
+                &self,
+                messages: Vec<WsIntegrationMessage>,
+            ) -> Result<(), anyhow::Error> {
+                let root_node = Arc::new(RootNode::new(
+                    Query,
+                    EmptyMutation::<Database>::new(),
+                    Subscription,
+                ));
+                let protocol = self.0;
+                let filter = warp::ws().map(move |ws: warp::ws::Ws| {
+                    let root_node = root_node.clone();
+                    ws.on_upgrade(move |websocket| async move {
+                        let config = ConnectionConfig::new(Database::new());
+                        let _ = match protocol {
+                            Protocol::GraphQLWs => {
+                                serve_graphql_ws(websocket, root_node, config).await
+                            }
+                            Protocol::GraphQLTransportWs => {
+                                serve_graphql_transport_ws(websocket, root_node, config).await
+                            }
+                        };
+                    })
+                });
+
+                let mut client = warp::test::ws()
+                    .handshake(filter)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+
+                for message in &messages {
+                    match message {
+                        WsIntegrationMessage::Send(body) => client.send_text(body).await,
+                        WsIntegrationMessage::Expect(body, message_timeout) => {
+                            let message =
+                                timeout(Duration::from_millis(*message_timeout), client.recv())
+                                    .await
+                                    .map_err(|_| anyhow::anyhow!("Timed-out waiting for message"))?
+                                    .map_err(|e| anyhow::anyhow!("WS error: {e:?}"))?;
+
+                            let text = message
+                                .to_str()
+                                .map_err(|()| anyhow::anyhow!("Received non-text message"))?;
+
+                            let expected_value = serde_json::from_str::<serde_json::Value>(body)
+                                .map_err(|e| anyhow::anyhow!("Serde error: {e:?}"))?;
+
+                            let value: serde_json::Value = serde_json::from_str(text)
+                                .map_err(|e| anyhow::anyhow!("Serde error: {e:?}"))?;
+
+                            if value != expected_value {
+                                return Err(anyhow::anyhow!(
+                                    "Expected message: {expected_value}. \
+                                     Received message: {value}",
+                                ));
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        impl WsIntegration for TestWarpWsIntegration {
+            fn run(
+                &self,
+                messages: Vec<WsIntegrationMessage>,
+            ) -> LocalBoxFuture<Result<(), anyhow::Error>> {
+                Box::pin(self.run_async(messages))
+            }
+        }
+
+        #[tokio::test]
+        async fn test_warp_ws_integration() {
+            run_ws_test_suite(&TestWarpWsIntegration(Protocol::GraphQLWs)).await;
+        }
+
+        #[tokio::test]
+        async fn test_warp_graphql_transport_ws_integration() {
+            run_graphql_transport_ws_test_suite(&TestWarpWsIntegration(
+                Protocol::GraphQLTransportWs,
+            ))
+            .await;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -591,6 +1273,73 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "multipart")]
+    #[tokio::test]
+    async fn multipart_graphql_filter_works() {
+        use juniper::{
+            graphql_object,
+            http::multipart::{MultipartOptions, Upload},
+            EmptySubscription, Executor, RootNode, ScalarValue,
+        };
+
+        struct Query;
+
+        #[graphql_object]
+        impl Query {
+            fn ping() -> bool {
+                true
+            }
+        }
+
+        struct Mutation;
+
+        #[graphql_object]
+        impl Mutation {
+            fn upload<S: ScalarValue>(executor: &Executor<(), S>, file: Upload) -> Option<i32> {
+                file.file(executor.extensions()).map(|f| f.size() as i32)
+            }
+        }
+
+        let body = "--boundary\r\n\
+                    Content-Disposition: form-data; name=\"operations\"\r\n\r\n\
+                    {\"query\": \"mutation($file: Upload!) { upload(file: $file) }\", \"variables\": {\"file\": null}}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"map\"\r\n\r\n\
+                    {\"0\": [\"variables.file\"]}\r\n\
+                    --boundary\r\n\
+                    Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n\
+                    Hello, world!\r\n\
+                    --boundary--\r\n";
+
+        let filter = |options| {
+            let schema = RootNode::new(Query, Mutation, EmptySubscription::new());
+            warp::path("graphql").and(make_multipart_graphql_filter(
+                schema,
+                warp::any().map(|| ()).boxed(),
+                options,
+            ))
+        };
+        let request = || {
+            request()
+                .method("POST")
+                .path("/graphql")
+                .header("content-type", "multipart/form-data; boundary=boundary")
+                .body(body)
+        };
+
+        let response = request().reply(&filter(MultipartOptions::new())).await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"data":{"upload":13}}"#,
+        );
+
+        let response = request()
+            .reply(&filter(MultipartOptions::new().with_max_file_size(5)))
+            .await;
+        assert_eq!(response.status(), http::StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
     #[tokio::test]
     async fn batch_requests_work() {
         use juniper::{
@@ -744,6 +1493,26 @@ mod tests_http_harness {
                     .body(body),
             )
         }
+
+        fn get_with_accept(&self, url: &str, accept: &str) -> TestResponse {
+            self.make_request(
+                warp::test::request()
+                    .method("GET")
+                    .header("accept", accept)
+                    .path(url),
+            )
+        }
+
+        fn post_json_with_accept(&self, url: &str, body: &str, accept: &str) -> TestResponse {
+            self.make_request(
+                warp::test::request()
+                    .method("POST")
+                    .header("content-type", "application/json; charset=utf-8")
+                    .header("accept", accept)
+                    .path(url)
+                    .body(body),
+            )
+        }
     }
 
     fn make_test_response(resp: http::Response<Vec<u8>>) -> TestResponse {
@@ -753,10 +1522,8 @@ mod tests_http_harness {
             content_type: resp
                 .headers()
                 .get("content-type")
-                .expect("missing content-type header in warp response")
-                .to_str()
-                .expect("invalid content-type string")
-                .into(),
+                .map(|ct| ct.to_str().expect("invalid content-type string").into())
+                .unwrap_or_default(),
         }
     }
 
@@ -0,0 +1,217 @@
+//! Checks that Rust code generated by `juniper::client::ClientGenerator`
+//! compiles and (de)serializes the requests and responses of the operations.
+
+use juniper::{
+    client::{
+        ClientGenerator, GraphQLOperation, OperationResponse, ResponseError, ResponseErrorLocation,
+        ResponsePathSegment,
+    },
+    graphql_interface, graphql_object,
+    http::GraphQLRequest,
+    EmptyMutation, EmptySubscription, FieldError, FieldResult, GraphQLEnum, GraphQLInputObject,
+    GraphQLObject, GraphQLUnion, RootNode,
+};
+
+mod operations {
+    #![allow(dead_code)]
+
+    include!("client_codegen/generated.rs");
+}
+
+use self::operations::{hero_name, search};
+
+const OPERATIONS: &str = include_str!("client_codegen/operations.graphql");
+
+struct Query;
+
+#[graphql_object]
+impl Query {
+    fn hero(episode: Option<Episode>) -> CharacterValue {
+        match episode {
+            Some(Episode::Empire) => luke().into(),
+            _ => r2d2().into(),
+        }
+    }
+
+    fn search(filter: Filter) -> FieldResult<Vec<SearchResult>> {
+        match filter.text.as_deref() {
+            Some("") => Err(FieldError::from("Empty search text")),
+            _ => Ok(vec![
+                SearchResult::Human(luke()),
+                SearchResult::Droid(r2d2()),
+            ]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, GraphQLEnum)]
+enum Episode {
+    NewHope,
+    Empire,
+    Jedi,
+}
+
+#[derive(GraphQLInputObject)]
+struct Filter {
+    text: Option<String>,
+    episodes: Option<Vec<Episode>>,
+}
+
+#[graphql_interface(for = [Human, Droid])]
+trait Character {
+    fn id(&self) -> &str;
+
+    fn name(&self) -> &str;
+}
+
+#[derive(GraphQLObject)]
+#[graphql(impl = CharacterValue)]
+struct Human {
+    id: String,
+    name: String,
+    home_planet: Option<String>,
+}
+
+#[derive(GraphQLObject)]
+#[graphql(impl = CharacterValue)]
+struct Droid {
+    id: String,
+    name: String,
+    primary_function: Option<String>,
+}
+
+#[derive(GraphQLUnion)]
+enum SearchResult {
+    Human(Human),
+    Droid(Droid),
+}
+
+fn luke() -> Human {
+    Human {
+        id: "1000".into(),
+        name: "Luke Skywalker".into(),
+        home_planet: Some("Tatooine".into()),
+    }
+}
+
+fn r2d2() -> Droid {
+    Droid {
+        id: "2001".into(),
+        name: "R2-D2".into(),
+        primary_function: Some("Astromech".into()),
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation, EmptySubscription>;
+
+fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+async fn execute<O: GraphQLOperation>(
+    variables: O::Variables,
+) -> OperationResponse<O::ResponseData> {
+    let request = serde_json::to_value(O::build_request(variables)).unwrap();
+    let request: GraphQLRequest = serde_json::from_value(request).unwrap();
+
+    let response = request.execute(&schema(), &()).await;
+
+    serde_json::from_value(serde_json::to_value(response).unwrap()).unwrap()
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    let schema = schema();
+    let code = ClientGenerator::new(&schema.schema)
+        .derive("PartialEq")
+        .generate(OPERATIONS)
+        .unwrap_or_else(|e| panic!("invalid operations: {e}"));
+
+    assert_eq!(
+        code,
+        include_str!("client_codegen/generated.rs"),
+        "`client_codegen/generated.rs` should be regenerated",
+    );
+}
+
+#[tokio::test]
+async fn deserializes_interface_fragments() {
+    let response = execute::<hero_name::Operation>(hero_name::Variables {
+        episode: Some(operations::Episode::Empire),
+    })
+    .await;
+
+    assert_eq!(
+        response,
+        OperationResponse {
+            data: Some(hero_name::ResponseData {
+                hero: hero_name::Hero::Human(hero_name::HeroOnHuman {
+                    id: "1000".into(),
+                    display_name: "Luke Skywalker".into(),
+                    home_planet: Some("Tatooine".into()),
+                }),
+            }),
+            errors: vec![],
+        },
+    );
+
+    let response = execute::<hero_name::Operation>(hero_name::Variables { episode: None }).await;
+
+    assert_eq!(
+        response.data.unwrap().hero,
+        hero_name::Hero::Droid(hero_name::HeroOnDroid {
+            id: "2001".into(),
+            display_name: "R2-D2".into(),
+            primary_function: Some("Astromech".into()),
+        }),
+    );
+}
+
+#[tokio::test]
+async fn deserializes_union_fragments() {
+    let response = execute::<search::Operation>(search::Variables {
+        filter: operations::Filter {
+            text: Some("a".into()),
+            episodes: Some(vec![
+                operations::Episode::NewHope,
+                operations::Episode::Jedi,
+            ]),
+        },
+    })
+    .await;
+
+    assert_eq!(
+        response.data.unwrap().results,
+        [
+            search::Results::Human(search::ResultsOnHuman {
+                name: "Luke Skywalker".into(),
+            }),
+            search::Results::Droid(search::ResultsOnDroid {
+                name: "R2-D2".into(),
+            }),
+        ],
+    );
+}
+
+#[tokio::test]
+async fn deserializes_errors() {
+    let response = execute::<search::Operation>(search::Variables {
+        filter: operations::Filter {
+            text: Some(String::new()),
+            episodes: None,
+        },
+    })
+    .await;
+
+    assert_eq!(
+        response,
+        OperationResponse {
+            data: None,
+            errors: vec![ResponseError {
+                message: "Empty search text".into(),
+                locations: vec![ResponseErrorLocation { line: 2, column: 3 }],
+                path: vec![ResponsePathSegment::Field("results".into())],
+            }],
+        },
+    );
+}
@@ -0,0 +1,116 @@
+// This file is @generated by `juniper::client::ClientGenerator`.
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ::juniper::serde::Deserialize, ::juniper::serde::Serialize)]
+#[serde(crate = "::juniper::serde")]
+pub enum Episode {
+    #[serde(rename = "NEW_HOPE")]
+    NewHope,
+    #[serde(rename = "EMPIRE")]
+    Empire,
+    #[serde(rename = "JEDI")]
+    Jedi,
+}
+
+#[derive(Clone, Debug, PartialEq, ::juniper::serde::Serialize)]
+#[serde(crate = "::juniper::serde")]
+pub struct Filter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episodes: Option<Vec<Episode>>,
+}
+
+pub mod hero_name {
+    pub struct Operation;
+
+    impl ::juniper::client::GraphQLOperation for Operation {
+        type Variables = Variables;
+        type ResponseData = ResponseData;
+
+        const OPERATION_NAME: &'static str = "HeroName";
+        const DOCUMENT: &'static str = "query HeroName($episode: Episode) {\n  hero(episode: $episode) {\n    id\n    displayName: name\n    ...HeroDetails\n  }\n}\n\nfragment HeroDetails on Character {\n  __typename\n  ... on Human {\n    homePlanet\n  }\n  ... on Droid {\n    primaryFunction\n  }\n}";
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Serialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct Variables {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub episode: Option<super::Episode>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct ResponseData {
+        pub hero: Hero,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde", tag = "__typename")]
+    pub enum Hero {
+        Droid(HeroOnDroid),
+        Human(HeroOnHuman),
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct HeroOnDroid {
+        pub id: String,
+        #[serde(rename = "displayName")]
+        pub display_name: String,
+        #[serde(rename = "primaryFunction")]
+        pub primary_function: Option<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct HeroOnHuman {
+        pub id: String,
+        #[serde(rename = "displayName")]
+        pub display_name: String,
+        #[serde(rename = "homePlanet")]
+        pub home_planet: Option<String>,
+    }
+}
+
+pub mod search {
+    pub struct Operation;
+
+    impl ::juniper::client::GraphQLOperation for Operation {
+        type Variables = Variables;
+        type ResponseData = ResponseData;
+
+        const OPERATION_NAME: &'static str = "Search";
+        const DOCUMENT: &'static str = "query Search($filter: Filter!) {\n  results: search(filter: $filter) {\n    __typename\n    ... on Human {\n      name\n    }\n    ... on Droid {\n      name\n    }\n  }\n}";
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Serialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct Variables {
+        pub filter: super::Filter,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct ResponseData {
+        pub results: Vec<Results>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde", tag = "__typename")]
+    pub enum Results {
+        Droid(ResultsOnDroid),
+        Human(ResultsOnHuman),
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct ResultsOnDroid {
+        pub name: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, ::juniper::serde::Deserialize)]
+    #[serde(crate = "::juniper::serde")]
+    pub struct ResultsOnHuman {
+        pub name: String,
+    }
+}
@@ -795,6 +795,92 @@ mod description_from_graphql_attr {
     }
 }
 
+mod deprecation_from_attr {
+    #![allow(deprecated)]
+
+    use super::*;
+
+    #[derive(GraphQLInputObject)]
+    struct Point2D {
+        x: f64,
+        #[deprecated]
+        y: Option<f64>,
+        #[graphql(deprecated = "Use `x`.", default = 0.0)]
+        z: f64,
+    }
+
+    struct QueryRoot;
+
+    #[graphql_object]
+    impl QueryRoot {
+        fn sum(point: Point2D) -> f64 {
+            point.x + point.y.unwrap_or_default() + point.z
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves() {
+        const DOC: &str = r#"{
+            sum(point: { x: 10, y: 20, z: 30 })
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((graphql_value!({"sum": 60.0}), vec![])),
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_deprecated_input_fields_by_default() {
+        const DOC: &str = r#"{
+            __type(name: "Point2D") {
+                inputFields {
+                    name
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((
+                graphql_value!({"__type": {"inputFields": [{"name": "x"}]}}),
+                vec![],
+            )),
+        );
+    }
+
+    #[tokio::test]
+    async fn deprecates_input_fields() {
+        const DOC: &str = r#"{
+            __type(name: "Point2D") {
+                inputFields(includeDeprecated: true) {
+                    name
+                    isDeprecated
+                    deprecationReason
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((
+                graphql_value!({"__type": {"inputFields": [
+                    {"name": "x", "isDeprecated": false, "deprecationReason": null},
+                    {"name": "y", "isDeprecated": true, "deprecationReason": null},
+                    {"name": "z", "isDeprecated": true, "deprecationReason": "Use `x`."},
+                ]}}),
+                vec![],
+            )),
+        );
+    }
+}
+
 mod renamed_all_fields {
     use super::*;
 
@@ -1180,6 +1180,104 @@ mod default_argument {
     }
 }
 
+mod deprecated_argument {
+    use super::*;
+
+    struct Human;
+
+    #[graphql_object]
+    impl Human {
+        fn id(
+            #[graphql(deprecated)] arg1: Option<i32>,
+            #[graphql(deprecated = "Use `arg1`.", default = 5)] arg2: i32,
+            arg3: Option<bool>,
+        ) -> String {
+            format!("{}|{arg2}|{}", arg1.unwrap_or_default(), arg3.is_some())
+        }
+    }
+
+    struct QueryRoot;
+
+    #[graphql_object]
+    impl QueryRoot {
+        fn human() -> Human {
+            Human
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_deprecated_arguments() {
+        const DOC: &str = r#"{
+            human {
+                id(arg1: 1, arg2: 2)
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((graphql_value!({"human": {"id": "1|2|false"}}), vec![])),
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_deprecated_arguments_by_default() {
+        const DOC: &str = r#"{
+            __type(name: "Human") {
+                fields {
+                    args {
+                        name
+                    }
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((
+                graphql_value!({"__type": {"fields": [{"args": [{"name": "arg3"}]}]}}),
+                vec![],
+            )),
+        );
+    }
+
+    #[tokio::test]
+    async fn deprecates_arguments() {
+        const DOC: &str = r#"{
+            __type(name: "Human") {
+                fields {
+                    args(includeDeprecated: true) {
+                        name
+                        isDeprecated
+                        deprecationReason
+                    }
+                }
+            }
+        }"#;
+
+        let schema = schema(QueryRoot);
+
+        assert_eq!(
+            execute(DOC, None, &schema, &graphql_vars! {}, &()).await,
+            Ok((
+                graphql_value!({"__type": {"fields": [{"args": [
+                    {"name": "arg1", "isDeprecated": true, "deprecationReason": null},
+                    {
+                        "name": "arg2",
+                        "isDeprecated": true,
+                        "deprecationReason": "Use `arg1`.",
+                    },
+                    {"name": "arg3", "isDeprecated": false, "deprecationReason": null},
+                ]}]}}),
+                vec![],
+            )),
+        );
+    }
+}
+
 mod description_from_doc_comment {
     use super::*;
 
@@ -6,7 +6,7 @@ use juniper::{
     graphql_object, graphql_subscription, graphql_vars,
     parser::parse_document_source,
     validation::{validate_input_values, visit_all_rules, ValidatorContext},
-    EmptyMutation, FieldError, OperationType, RootNode,
+    EmptyMutation, Extensions, FieldError, OperationType, RootNode,
 };
 
 pub struct Context;
@@ -65,10 +65,16 @@ async fn query_document_can_be_pre_parsed() {
     let errors = validate_input_values(&graphql_vars! {}, operation, &root_node.schema);
     assert!(errors.is_empty());
 
-    let (_, errors) =
-        execute_validated_query_async(&document, operation, root_node, &graphql_vars! {}, &Context)
-            .await
-            .unwrap();
+    let (_, errors) = execute_validated_query_async(
+        &document,
+        operation,
+        root_node,
+        &graphql_vars! {},
+        &Context,
+        &Extensions::new(),
+    )
+    .await
+    .unwrap();
 
     assert!(errors.len() == 0);
 }
@@ -76,6 +82,7 @@ async fn query_document_can_be_pre_parsed() {
 #[tokio::test]
 async fn subscription_document_can_be_pre_parsed() {
     let root_node = &Schema::new(Query, EmptyMutation::<Context>::new(), Subscription);
+    let extensions = Extensions::new();
 
     let document_source = r#"subscription { users { id } }"#;
     let document = parse_document_source(document_source, &root_node.schema).unwrap();
@@ -89,6 +96,7 @@ async fn subscription_document_can_be_pre_parsed() {
         &root_node,
         &graphql_vars! {},
         &Context,
+        &extensions,
     )
     .map_ok(|(stream, errors)| juniper_subscriptions::Connection::from_stream(stream, errors))
     .await
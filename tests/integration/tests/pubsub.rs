@@ -0,0 +1,136 @@
+//! Checks that subscriptions are fed with messages published by mutations via
+//! `juniper_subscriptions::PubSub` provided in the context.
+
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt as _, TryStreamExt as _};
+use juniper::{
+    execute, graphql_object, graphql_subscription, graphql_value, graphql_vars,
+    resolve_into_stream, FieldError, GraphQLObject, RootNode, Value,
+};
+use juniper_subscriptions::{InMemoryPubSub, PubSub as _, Topic};
+
+const MESSAGES: Topic<Message> = Topic::new("messages");
+
+#[derive(Clone, GraphQLObject)]
+struct Message {
+    room: String,
+    text: String,
+}
+
+#[derive(Default)]
+struct Context {
+    pubsub: InMemoryPubSub,
+}
+
+impl juniper::Context for Context {}
+
+struct Query;
+
+#[graphql_object(context = Context)]
+impl Query {
+    fn subscribers(ctx: &Context) -> i32 {
+        ctx.pubsub.subscribers_count(&MESSAGES) as i32
+    }
+}
+
+struct Mutation;
+
+#[graphql_object(context = Context)]
+impl Mutation {
+    async fn send(room: String, text: String, ctx: &Context) -> i32 {
+        let delivered = ctx
+            .pubsub
+            .publish(&MESSAGES, Message { room, text })
+            .await
+            .unwrap_or_else(|e| match e {});
+        delivered as i32
+    }
+}
+
+type MessageStream = Pin<Box<dyn Stream<Item = Result<Message, FieldError>> + Send>>;
+
+struct Subscription;
+
+#[graphql_subscription(context = Context)]
+impl Subscription {
+    async fn messages(room: String, ctx: &Context) -> MessageStream {
+        let stream = ctx
+            .pubsub
+            .subscribe_filtered(&MESSAGES, Box::new(move |m: &Message| m.room == room))
+            .await
+            .unwrap_or_else(|e| match e {});
+        Box::pin(stream.map_err(FieldError::from))
+    }
+}
+
+type Schema = RootNode<'static, Query, Mutation, Subscription>;
+
+fn schema() -> Schema {
+    Schema::new(Query, Mutation, Subscription)
+}
+
+async fn send(schema: &Schema, ctx: &Context, room: &str, text: &str) -> Value {
+    let (res, errors) = execute(
+        "mutation($room: String!, $text: String!) { send(room: $room, text: $text) }",
+        None,
+        schema,
+        &graphql_vars! {"room": (room), "text": (text)},
+        ctx,
+    )
+    .await
+    .unwrap();
+    assert_eq!(errors, []);
+    res
+}
+
+#[tokio::test]
+async fn mutations_publish_to_subscriptions() {
+    let schema = schema();
+    let ctx = Context::default();
+
+    let (res, errors) = resolve_into_stream(
+        r#"subscription { messages(room: "rust") { text } }"#,
+        None,
+        &schema,
+        &graphql_vars! {},
+        &ctx,
+    )
+    .await
+    .unwrap();
+    assert_eq!(errors, []);
+
+    let mut stream = match res {
+        Value::Object(obj) => match obj.into_iter().next() {
+            Some((_, Value::Scalar(stream))) => stream,
+            _ => panic!("expected `messages` stream"),
+        },
+        _ => panic!("expected `Value::Object`"),
+    };
+
+    assert_eq!(
+        send(&schema, &ctx, "rust", "hello").await,
+        graphql_value!({"send": 1}),
+    );
+    assert_eq!(
+        send(&schema, &ctx, "go", "skipped").await,
+        graphql_value!({"send": 0}),
+    );
+    assert_eq!(
+        send(&schema, &ctx, "rust", "world").await,
+        graphql_value!({"send": 1}),
+    );
+
+    for text in ["hello", "world"] {
+        assert_eq!(
+            stream.next().await.unwrap(),
+            Ok(graphql_value!({ "text": text })),
+        );
+    }
+
+    drop(stream);
+    let (res, _) = execute("{ subscribers }", None, &schema, &graphql_vars! {}, &ctx)
+        .await
+        .unwrap();
+    assert_eq!(res, graphql_value!({"subscribers": 0}));
+}
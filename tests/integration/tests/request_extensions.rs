@@ -0,0 +1,140 @@
+//! Checks that `Extensions` provided with a `GraphQLRequest` are accessible in
+//! resolvers of queries and subscriptions.
+
+use std::pin::Pin;
+
+use futures::{stream, Stream, StreamExt as _};
+use juniper::{
+    graphql_object, graphql_subscription, graphql_value, graphql_vars,
+    http::{self, GraphQLRequest},
+    EmptyMutation, Executor, FieldError, RootNode, ScalarValue, Value,
+};
+
+struct RequestId(String);
+
+fn request_id<S: ScalarValue>(executor: &Executor<'_, '_, (), S>) -> String {
+    executor
+        .extensions()
+        .get::<RequestId>()
+        .map_or_else(|| "none".into(), |id| id.0.clone())
+}
+
+struct Query;
+
+#[graphql_object]
+impl Query {
+    fn request_id<S: ScalarValue>(executor: &Executor<'_, '_, (), S>) -> String {
+        request_id(executor)
+    }
+
+    fn users() -> Vec<User> {
+        vec![User { id: 1 }, User { id: 2 }]
+    }
+}
+
+struct User {
+    id: i32,
+}
+
+#[graphql_object]
+impl User {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn request_id<S: ScalarValue>(executor: &Executor<'_, '_, (), S>) -> String {
+        request_id(executor)
+    }
+}
+
+type UserStream = Pin<Box<dyn Stream<Item = Result<User, FieldError>> + Send>>;
+
+struct Subscription;
+
+#[graphql_subscription]
+impl Subscription {
+    async fn users<S: ScalarValue>(executor: &Executor<'_, '_, (), S>) -> UserStream {
+        let ids = if executor.extensions().contains::<RequestId>() {
+            vec![1, 2]
+        } else {
+            vec![]
+        };
+        Box::pin(stream::iter(ids).map(|id| Ok(User { id })))
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation, Subscription>;
+
+fn schema() -> Schema {
+    Schema::new(Query, EmptyMutation::new(), Subscription)
+}
+
+fn request(query: &str) -> GraphQLRequest {
+    let mut req = GraphQLRequest::new(query.into(), None, None);
+    req.extensions.insert(RequestId("req-1".into()));
+    req
+}
+
+#[tokio::test]
+async fn query_resolvers_access_extensions() {
+    const DOC: &str = r#"{
+        requestId
+        users { id requestId }
+    }"#;
+
+    let expected: Value = graphql_value!({
+        "requestId": "req-1",
+        "users": [
+            {"id": 1, "requestId": "req-1"},
+            {"id": 2, "requestId": "req-1"},
+        ],
+    });
+
+    let res = request(DOC).execute(&schema(), &()).await;
+    assert!(res.is_ok());
+    assert_eq!(
+        serde_json::to_value(&res).unwrap()["data"],
+        serde_json::to_value(&expected).unwrap(),
+    );
+
+    let res = request(DOC).execute_sync(&schema(), &());
+    assert_eq!(
+        serde_json::to_value(&res).unwrap()["data"],
+        serde_json::to_value(&expected).unwrap(),
+    );
+}
+
+#[tokio::test]
+async fn extensions_are_empty_by_default() {
+    let schema = schema();
+
+    let (res, errs) = juniper::execute("{ requestId }", None, &schema, &graphql_vars! {}, &())
+        .await
+        .unwrap();
+
+    assert_eq!(errs, []);
+    assert_eq!(res, graphql_value!({"requestId": "none"}));
+}
+
+#[tokio::test]
+async fn subscription_streams_access_extensions() {
+    let schema = schema();
+    let req = request("subscription { users { id requestId } }");
+
+    let (res, errs) = http::resolve_into_stream(&req, &schema, &()).await.unwrap();
+    assert_eq!(errs, []);
+
+    let stream = match res.into_object().unwrap().into_iter().next() {
+        Some((_, Value::Scalar(stream))) => stream,
+        _ => panic!("expected `users` stream"),
+    };
+    let items = stream.collect::<Vec<_>>().await;
+
+    assert_eq!(
+        items.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+        [
+            graphql_value!({"id": 1, "requestId": "req-1"}),
+            graphql_value!({"id": 2, "requestId": "req-1"}),
+        ],
+    );
+}